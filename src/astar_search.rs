@@ -1,15 +1,16 @@
 use std::cmp::Ordering;
 use std::fmt::Debug;
-use std::collections::BinaryHeap;
-use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashSet};
+use std::hash::BuildHasher;
 use std::marker::PhantomData;
 
-use fnv::FnvHashMap;
 use num_traits::Zero;
 
-use graph::{Graph, AdjacencyGraph, AdjacencyMatrixGraph, VertexListGraph, EdgeDescriptor,
-            VertexDescriptor};
-use path::reverse_path;
+use graph::{Graph, BidirectionalGraph, Directivity, VertexListGraph,
+            EdgeDescriptor, VertexDescriptor};
+use error::GraphError;
+use path::{reverse_path, Path};
+use property_map::{HashPropertyMap, PropertyMap};
 use visitor::{Event, Visitor, DefaultVisitor};
 
 #[derive(Clone, Eq, Debug)]
@@ -50,15 +51,25 @@ where
     }
 }
 
-pub struct Astar<C, T, V>
+/// A* search. The costs of the best paths found to the vertices and their parents on them are
+/// kept in property maps, hash maps unless others are given to `with_property_maps`, and can be
+/// inspected after a search.
+pub struct Astar<C, T, V, D = HashPropertyMap<VertexDescriptor, C>,
+                 P = HashPropertyMap<VertexDescriptor, VertexDescriptor>>
 where
     C: Copy + Debug + Ord + Zero,
     T: Graph,
     V: Visitor<T, Event>,
+    D: PropertyMap<VertexDescriptor, C>,
+    P: PropertyMap<VertexDescriptor, VertexDescriptor>,
 {
     fringe: BinaryHeap<State<C>>,
-    parents: FnvHashMap<VertexDescriptor, (VertexDescriptor, C)>,
+    distances: D,
+    parents: P,
     visitor: V,
+    check_heuristic: bool,
+    // orders the fringe by the heuristic alone, ignoring the cost so far
+    greedy: bool,
     phantom: PhantomData<T>,
 }
 
@@ -72,6 +83,16 @@ where
     }
 }
 
+impl<C, T> Default for Astar<C, T, DefaultVisitor>
+where
+    C: Copy + Debug + Ord + Zero,
+    T: Graph,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<C, T, V> Astar<C, T, V>
 where
     C: Copy + Debug + Ord + Zero,
@@ -79,14 +100,50 @@ where
     V: Visitor<T, Event>,
 {
     pub fn with_visitor(visitor: V) -> Self {
+        Self::with_property_maps(visitor, HashPropertyMap::default(), HashPropertyMap::default())
+    }
+}
+
+impl<C, T, V, D, P> Astar<C, T, V, D, P>
+where
+    C: Copy + Debug + Ord + Zero,
+    T: Graph,
+    V: Visitor<T, Event>,
+    D: PropertyMap<VertexDescriptor, C>,
+    P: PropertyMap<VertexDescriptor, VertexDescriptor>,
+{
+    /// Keeps the costs and the parents of the vertices in `distances` and `parents`, which are
+    /// cleared at the start of every search.
+    pub fn with_property_maps(visitor: V, distances: D, parents: P) -> Self {
         Self {
             fringe: BinaryHeap::new(),
-            parents: FnvHashMap::default(),
-            visitor: visitor,
+            distances,
+            parents,
+            visitor,
+            check_heuristic: false,
+            greedy: false,
             phantom: PhantomData,
         }
     }
 
+    /// When enabled, `run` verifies `h(u) <= cost(u, v) + h(v)` for every examined edge and
+    /// fails with `GraphError::InconsistentHeuristic(u, v)` on the first edge that violates it.
+    /// After reaching a goal it keeps expanding cheaper vertices, so an overestimate that hid a
+    /// shorter path is reported instead of returning the longer one. A start that is itself a
+    /// goal but is only reached afterwards is reported as `InconsistentHeuristic(s, s)`.
+    pub fn check_heuristic(mut self, enabled: bool) -> Self {
+        self.check_heuristic = enabled;
+        self
+    }
+
+    fn evaluate(&self, cost: C, heuristic: C) -> C {
+        if self.greedy {
+            heuristic
+        } else {
+            cost + heuristic
+        }
+    }
+
     pub fn run<'a, F, G, H>(
         &mut self,
         start: &VertexDescriptor,
@@ -94,67 +151,165 @@ where
         heuristic: H,
         is_goal: F,
         graph: &'a T,
-    ) -> Option<Vec<VertexDescriptor>>
+    ) -> Result<Option<Path<C>>, GraphError>
     where
         C: Copy + Debug + Ord + Zero,
         F: Fn(&VertexDescriptor) -> bool,
         G: Fn(&EdgeDescriptor, &T) -> C,
         H: Fn(&VertexDescriptor, &T) -> C,
-        T: AdjacencyGraph<'a> + AdjacencyMatrixGraph + VertexListGraph<'a>,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.run_from_any(Some(*start), edge_cost, heuristic, is_goal, graph)
+    }
+
+    /// Searches from all of `starts` at once, each at no cost, as if from a vertex joined to
+    /// each of them by a free edge. The path returned starts from whichever is closest to the
+    /// goal reached.
+    pub fn run_from_any<'a, I, F, G, H>(
+        &mut self,
+        starts: I,
+        edge_cost: G,
+        heuristic: H,
+        is_goal: F,
+        graph: &'a T,
+    ) -> Result<Option<Path<C>>, GraphError>
+    where
+        I: IntoIterator<Item = VertexDescriptor>,
+        F: Fn(&VertexDescriptor) -> bool,
+        G: Fn(&EdgeDescriptor, &T) -> C,
+        H: Fn(&VertexDescriptor, &T) -> C,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
     {
+        let starts = starts.into_iter().collect::<Vec<_>>();
+        if let Some(&start) = starts.iter().find(|&&s| !graph.contains_vertex(s)) {
+            return Err(GraphError::VertexNotFound(start));
+        }
+
         for vertex in graph.vertices() {
             self.visitor.visit(&Event::InitializeVertex(vertex), graph)
         }
 
-        self.visitor.visit(&Event::DiscoverVertex(*start), graph);
-        self.fringe.push(State {
-            evaluation: heuristic(start, graph),
-            cost: C::zero(),
-            vertex: *start,
-        });
+        self.fringe.clear();
+        self.distances.clear();
+        self.parents.clear();
+        for start in starts {
+            if self.distances.insert(start, C::zero()).is_some() {
+                continue;
+            }
+            self.visitor.visit(&Event::DiscoverVertex(start), graph);
+            self.fringe.push(State {
+                evaluation: heuristic(&start, graph),
+                cost: C::zero(),
+                vertex: start,
+            });
+        }
 
+        let mut found: Option<(Vec<VertexDescriptor>, C)> = None;
         while let Some(State { cost, vertex, .. }) = self.fringe.pop() {
+            if let Some((_, found_cost)) = found {
+                if cost >= found_cost {
+                    continue;
+                }
+                if is_goal(&vertex) {
+                    // a start has no parent, so the overestimate is its own
+                    let parent = self.parents.get(vertex).cloned().unwrap_or(vertex);
+                    return Err(GraphError::InconsistentHeuristic(parent, vertex));
+                }
+            }
             self.visitor.visit(&Event::ExamineVertex(vertex), graph);
-            if is_goal(&vertex) {
-                let parents = self.parents.iter().map(|(&n, &(p, _))| (n, p)).collect();
-                return Some(reverse_path(&parents, vertex));
+            if found.is_none() && is_goal(&vertex) {
+                let path = reverse_path(&self.parents, vertex);
+                if !self.check_heuristic {
+                    return Ok(Some(Path::new(path, cost)));
+                }
+                // Keep expanding anything cheaper than the goal so that a path the heuristic
+                // steered away from is still examined.
+                found = Some((path, cost));
+                continue;
             }
-            for adjacency in graph.adjacent_vertices(vertex) {
-                let edge = graph.edge(vertex, adjacency).unwrap();
+            let in_edges = if T::Directivity::is_directed() {
+                None
+            } else {
+                Some(graph.in_edges(vertex))
+            };
+            for edge in graph.out_edges(vertex).chain(in_edges.into_iter().flatten()) {
+                let adjacency = graph.opposite(edge, vertex).ok_or(
+                    GraphError::EdgeNotFound(edge),
+                )?;
                 self.visitor.visit(&Event::ExamineEdge(edge), graph);
-                let cost_to_adjacency = cost + edge_cost(&edge, graph);
-                if adjacency != *start {
-                    match self.parents.entry(adjacency) {
-                        Entry::Vacant(entry) => {
-                            entry.insert((vertex, cost_to_adjacency));
-                            self.visitor.visit(&Event::EdgeRelaxed(edge), graph);
-                            self.visitor.visit(&Event::DiscoverVertex(adjacency), graph);
-                            self.fringe.push(State {
-                                evaluation: cost_to_adjacency + heuristic(&adjacency, graph),
-                                cost: cost_to_adjacency,
-                                vertex: adjacency,
-                            });
-                        }
-                        Entry::Occupied(mut entry) => {
-                            if entry.get().1 > cost_to_adjacency {
-                                entry.insert((vertex, cost_to_adjacency));
-                                self.visitor.visit(&Event::EdgeRelaxed(edge), graph);
-                                self.visitor.visit(&Event::DiscoverVertex(adjacency), graph);
-                                self.fringe.push(State {
-                                    evaluation: cost_to_adjacency + heuristic(&adjacency, graph),
-                                    cost: cost_to_adjacency,
-                                    vertex: adjacency,
-                                });
-                            } else {
-                                self.visitor.visit(&Event::EdgeNotRelaxed(edge), graph);
-                            }
-                        }
-                    }
+                let cost_of_edge = edge_cost(&edge, graph);
+                if cost_of_edge < C::zero() {
+                    return Err(GraphError::NegativeEdgeCost(edge));
+                }
+                if self.check_heuristic &&
+                    heuristic(&vertex, graph) > cost_of_edge + heuristic(&adjacency, graph)
+                {
+                    return Err(GraphError::InconsistentHeuristic(vertex, adjacency));
                 }
+                let cost_to_adjacency = cost + cost_of_edge;
+                if self.distances.get(adjacency).is_some_and(|&d| d <= cost_to_adjacency) {
+                    self.visitor.visit(&Event::EdgeNotRelaxed(edge), graph);
+                    continue;
+                }
+                self.distances.insert(adjacency, cost_to_adjacency);
+                self.parents.insert(adjacency, vertex);
+                self.visitor.visit(&Event::EdgeRelaxed(edge), graph);
+                self.visitor.visit(&Event::DiscoverVertex(adjacency), graph);
+                let h = heuristic(&adjacency, graph);
+                self.fringe.push(State {
+                    evaluation: self.evaluate(cost_to_adjacency, h),
+                    cost: cost_to_adjacency,
+                    vertex: adjacency,
+                });
             }
             self.visitor.visit(&Event::FinishVertex(vertex), graph);
         }
-        None
+        Ok(found.map(|(path, cost)| Path::new(path, cost)))
+    }
+
+    pub fn run_to_vertex<'a, G, H>(
+        &mut self,
+        start: &VertexDescriptor,
+        goal: &VertexDescriptor,
+        edge_cost: G,
+        heuristic: H,
+        graph: &'a T,
+    ) -> Result<Option<Path<C>>, GraphError>
+    where
+        G: Fn(&EdgeDescriptor, &T) -> C,
+        H: Fn(&VertexDescriptor, &T) -> C,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.run(start, edge_cost, heuristic, |v| v == goal, graph)
+    }
+
+    pub fn run_to_any<'a, S, G, H>(
+        &mut self,
+        start: &VertexDescriptor,
+        goals: &HashSet<VertexDescriptor, S>,
+        edge_cost: G,
+        heuristic: H,
+        graph: &'a T,
+    ) -> Result<Option<Path<C>>, GraphError>
+    where
+        S: BuildHasher,
+        G: Fn(&EdgeDescriptor, &T) -> C,
+        H: Fn(&VertexDescriptor, &T) -> C,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.run(start, edge_cost, heuristic, |v| goals.contains(v), graph)
+    }
+
+    pub fn distances(&self) -> &D {
+        &self.distances
+    }
+
+    pub fn parents(&self) -> &P {
+        &self.parents
     }
 
     pub fn visitor_ref(&self) -> &V {
@@ -162,9 +317,276 @@ where
     }
 }
 
+/// Uniform-cost search: `Astar` with no heuristic, expanding vertices in order of their cost
+/// from the start. It keeps the fringe of the `Astar` it is made from, so a search can switch
+/// between the two without allocating afresh.
+pub struct UniformCost<C, T, V>
+where
+    C: Copy + Debug + Ord + Zero,
+    T: Graph,
+    V: Visitor<T, Event>,
+{
+    astar: Astar<C, T, V>,
+}
+
+impl<C, T> UniformCost<C, T, DefaultVisitor>
+where
+    C: Copy + Debug + Ord + Zero,
+    T: Graph,
+{
+    pub fn new() -> Self {
+        Self::with_visitor(DefaultVisitor)
+    }
+}
+
+impl<C, T> Default for UniformCost<C, T, DefaultVisitor>
+where
+    C: Copy + Debug + Ord + Zero,
+    T: Graph,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C, T, V> From<Astar<C, T, V>> for UniformCost<C, T, V>
+where
+    C: Copy + Debug + Ord + Zero,
+    T: Graph,
+    V: Visitor<T, Event>,
+{
+    fn from(mut astar: Astar<C, T, V>) -> Self {
+        astar.check_heuristic = false;
+        astar.greedy = false;
+        UniformCost { astar }
+    }
+}
+
+impl<C, T, V> UniformCost<C, T, V>
+where
+    C: Copy + Debug + Ord + Zero,
+    T: Graph,
+    V: Visitor<T, Event>,
+{
+    pub fn with_visitor(visitor: V) -> Self {
+        Self::from(Astar::with_visitor(visitor))
+    }
+
+    pub fn into_astar(self) -> Astar<C, T, V> {
+        self.astar
+    }
+
+    pub fn run<'a, F, G>(
+        &mut self,
+        start: &VertexDescriptor,
+        edge_cost: G,
+        is_goal: F,
+        graph: &'a T,
+    ) -> Result<Option<Path<C>>, GraphError>
+    where
+        F: Fn(&VertexDescriptor) -> bool,
+        G: Fn(&EdgeDescriptor, &T) -> C,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.astar.run(start, edge_cost, |_, _| C::zero(), is_goal, graph)
+    }
+
+    pub fn run_from_any<'a, I, F, G>(
+        &mut self,
+        starts: I,
+        edge_cost: G,
+        is_goal: F,
+        graph: &'a T,
+    ) -> Result<Option<Path<C>>, GraphError>
+    where
+        I: IntoIterator<Item = VertexDescriptor>,
+        F: Fn(&VertexDescriptor) -> bool,
+        G: Fn(&EdgeDescriptor, &T) -> C,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.astar.run_from_any(starts, edge_cost, |_, _| C::zero(), is_goal, graph)
+    }
+
+    pub fn run_to_vertex<'a, G>(
+        &mut self,
+        start: &VertexDescriptor,
+        goal: &VertexDescriptor,
+        edge_cost: G,
+        graph: &'a T,
+    ) -> Result<Option<Path<C>>, GraphError>
+    where
+        G: Fn(&EdgeDescriptor, &T) -> C,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.run(start, edge_cost, |v| v == goal, graph)
+    }
+
+    pub fn run_to_any<'a, S, G>(
+        &mut self,
+        start: &VertexDescriptor,
+        goals: &HashSet<VertexDescriptor, S>,
+        edge_cost: G,
+        graph: &'a T,
+    ) -> Result<Option<Path<C>>, GraphError>
+    where
+        S: BuildHasher,
+        G: Fn(&EdgeDescriptor, &T) -> C,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.run(start, edge_cost, |v| goals.contains(v), graph)
+    }
+
+    pub fn visitor_ref(&self) -> &V {
+        self.astar.visitor_ref()
+    }
+}
+
+/// Greedy best-first search: `Astar` ordering its fringe by the heuristic alone, which heads
+/// straight for the goal but may return a path that is not a cheapest one. The cost of the
+/// path returned is still the sum of its edge costs. Like `UniformCost`, it keeps the fringe of
+/// the `Astar` it is made from.
+pub struct GreedyBestFirst<C, T, V>
+where
+    C: Copy + Debug + Ord + Zero,
+    T: Graph,
+    V: Visitor<T, Event>,
+{
+    astar: Astar<C, T, V>,
+}
+
+impl<C, T> GreedyBestFirst<C, T, DefaultVisitor>
+where
+    C: Copy + Debug + Ord + Zero,
+    T: Graph,
+{
+    pub fn new() -> Self {
+        Self::with_visitor(DefaultVisitor)
+    }
+}
+
+impl<C, T> Default for GreedyBestFirst<C, T, DefaultVisitor>
+where
+    C: Copy + Debug + Ord + Zero,
+    T: Graph,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C, T, V> From<Astar<C, T, V>> for GreedyBestFirst<C, T, V>
+where
+    C: Copy + Debug + Ord + Zero,
+    T: Graph,
+    V: Visitor<T, Event>,
+{
+    fn from(mut astar: Astar<C, T, V>) -> Self {
+        astar.check_heuristic = false;
+        astar.greedy = true;
+        GreedyBestFirst { astar }
+    }
+}
+
+impl<C, T, V> GreedyBestFirst<C, T, V>
+where
+    C: Copy + Debug + Ord + Zero,
+    T: Graph,
+    V: Visitor<T, Event>,
+{
+    pub fn with_visitor(visitor: V) -> Self {
+        Self::from(Astar::with_visitor(visitor))
+    }
+
+    pub fn into_astar(mut self) -> Astar<C, T, V> {
+        self.astar.greedy = false;
+        self.astar
+    }
+
+    pub fn run<'a, F, G, H>(
+        &mut self,
+        start: &VertexDescriptor,
+        edge_cost: G,
+        heuristic: H,
+        is_goal: F,
+        graph: &'a T,
+    ) -> Result<Option<Path<C>>, GraphError>
+    where
+        F: Fn(&VertexDescriptor) -> bool,
+        G: Fn(&EdgeDescriptor, &T) -> C,
+        H: Fn(&VertexDescriptor, &T) -> C,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.astar.run(start, edge_cost, heuristic, is_goal, graph)
+    }
+
+    pub fn run_from_any<'a, I, F, G, H>(
+        &mut self,
+        starts: I,
+        edge_cost: G,
+        heuristic: H,
+        is_goal: F,
+        graph: &'a T,
+    ) -> Result<Option<Path<C>>, GraphError>
+    where
+        I: IntoIterator<Item = VertexDescriptor>,
+        F: Fn(&VertexDescriptor) -> bool,
+        G: Fn(&EdgeDescriptor, &T) -> C,
+        H: Fn(&VertexDescriptor, &T) -> C,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.astar.run_from_any(starts, edge_cost, heuristic, is_goal, graph)
+    }
+
+    pub fn run_to_vertex<'a, G, H>(
+        &mut self,
+        start: &VertexDescriptor,
+        goal: &VertexDescriptor,
+        edge_cost: G,
+        heuristic: H,
+        graph: &'a T,
+    ) -> Result<Option<Path<C>>, GraphError>
+    where
+        G: Fn(&EdgeDescriptor, &T) -> C,
+        H: Fn(&VertexDescriptor, &T) -> C,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.run(start, edge_cost, heuristic, |v| v == goal, graph)
+    }
+
+    pub fn run_to_any<'a, S, G, H>(
+        &mut self,
+        start: &VertexDescriptor,
+        goals: &HashSet<VertexDescriptor, S>,
+        edge_cost: G,
+        heuristic: H,
+        graph: &'a T,
+    ) -> Result<Option<Path<C>>, GraphError>
+    where
+        S: BuildHasher,
+        G: Fn(&EdgeDescriptor, &T) -> C,
+        H: Fn(&VertexDescriptor, &T) -> C,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.run(start, edge_cost, heuristic, |v| goals.contains(v), graph)
+    }
+
+    pub fn visitor_ref(&self) -> &V {
+        self.astar.visitor_ref()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{Astar, State};
+    use super::{Astar, GreedyBestFirst, State, UniformCost};
+    use path::Path;
 
     #[test]
     fn state() {
@@ -208,6 +630,7 @@ mod tests {
 
     #[test]
     fn astar_directed() {
+        use std::collections::HashSet;
         use graph::{Directed, Graph, MutableGraph};
         use incidence_list::IncidenceList;
 
@@ -220,13 +643,13 @@ mod tests {
         let v4 = g.add_vertex(("g", 0));
         let v5 = g.add_vertex(("x", 0));
 
-        g.add_edge(v0, v1, 1);
-        g.add_edge(v0, v2, 4);
-        g.add_edge(v1, v2, 2);
-        g.add_edge(v1, v3, 5);
-        g.add_edge(v1, v4, 12);
-        g.add_edge(v2, v3, 2);
-        g.add_edge(v3, v4, 3);
+        g.add_edge(v0, v1, 1).unwrap();
+        g.add_edge(v0, v2, 4).unwrap();
+        g.add_edge(v1, v2, 2).unwrap();
+        g.add_edge(v1, v3, 5).unwrap();
+        g.add_edge(v1, v4, 12).unwrap();
+        g.add_edge(v2, v3, 2).unwrap();
+        g.add_edge(v3, v4, 3).unwrap();
 
         assert_eq!(
             Astar::new().run(
@@ -236,7 +659,7 @@ mod tests {
                 |&v| v == v4,
                 &g,
             ),
-            Some(vec![v0, v1, v2, v3, v4])
+            Ok(Some(Path::new(vec![v0, v1, v2, v3, v4], 8)))
         );
         assert_eq!(
             Astar::new().run(
@@ -246,7 +669,207 @@ mod tests {
                 |&v| v == v5,
                 &g,
             ),
-            None
+            Ok(None)
+        );
+        assert_eq!(
+            Astar::new().run_to_vertex(
+                &v0,
+                &v4,
+                |&e, g| *g.edge_property(e).unwrap(),
+                |&v, g| g.vertex_property(v).unwrap().1,
+                &g,
+            ),
+            Ok(Some(Path::new(vec![v0, v1, v2, v3, v4], 8)))
+        );
+        let goals = [v3, v5].iter().cloned().collect::<HashSet<_>>();
+        assert_eq!(
+            Astar::new().run_to_any(
+                &v0,
+                &goals,
+                |&e, g| *g.edge_property(e).unwrap(),
+                |&v, g| g.vertex_property(v).unwrap().1,
+                &g,
+            ),
+            Ok(Some(Path::new(vec![v0, v1, v2, v3], 5)))
+        );
+        assert_eq!(
+            Astar::new().run_from_any(
+                vec![v5, v0, v2],
+                |&e, g| *g.edge_property(e).unwrap(),
+                |&v, g| g.vertex_property(v).unwrap().1,
+                |&v| v == v4,
+                &g,
+            ),
+            Ok(Some(Path::new(vec![v2, v3, v4], 5)))
+        );
+    }
+
+    #[test]
+    fn astar_inconsistent_heuristic() {
+        use error::GraphError;
+        use graph::{Directed, Graph, MutableGraph};
+        use incidence_list::IncidenceList;
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+
+        let v0 = g.add_vertex(("s", 7));
+        let v1 = g.add_vertex(("a", 6));
+        let v2 = g.add_vertex(("b", 10));
+        let v3 = g.add_vertex(("c", 1));
+        let v4 = g.add_vertex(("g", 0));
+
+        g.add_edge(v0, v1, 1).unwrap();
+        g.add_edge(v0, v2, 4).unwrap();
+        g.add_edge(v1, v2, 2).unwrap();
+        g.add_edge(v1, v3, 5).unwrap();
+        g.add_edge(v2, v3, 2).unwrap();
+        g.add_edge(v3, v4, 3).unwrap();
+
+        assert_eq!(
+            Astar::new().run(
+                &v0,
+                |&e, g| *g.edge_property(e).unwrap(),
+                |&v, g| g.vertex_property(v).unwrap().1,
+                |&v| v == v4,
+                &g,
+            ),
+            Ok(Some(Path::new(vec![v0, v1, v3, v4], 9)))
+        );
+        assert_eq!(
+            Astar::new().check_heuristic(true).run(
+                &v0,
+                |&e, g| *g.edge_property(e).unwrap(),
+                |&v, g| g.vertex_property(v).unwrap().1,
+                |&v| v == v4,
+                &g,
+            ),
+            Err(GraphError::InconsistentHeuristic(v2, v3))
+        );
+    }
+
+    #[test]
+    fn astar_check_heuristic_from_any() {
+        use error::GraphError;
+        use graph::{Directed, Graph, MutableGraph};
+        use incidence_list::IncidenceList;
+
+        // s --1--> g1        g2
+        //
+        // both s and g2 are starts, and g2 is a goal whose heuristic overestimates
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+
+        let s = g.add_vertex(0);
+        let g1 = g.add_vertex(0);
+        let g2 = g.add_vertex(10);
+
+        g.add_edge(s, g1, 1).unwrap();
+
+        let run = |check| {
+            Astar::new().check_heuristic(check).run_from_any(
+                vec![s, g2],
+                |&e, g: &IncidenceList<Directed, i32, i32>| *g.edge_property(e).unwrap(),
+                |&v, g| *g.vertex_property(v).unwrap(),
+                |&v| v == g1 || v == g2,
+                &g,
+            )
+        };
+        assert_eq!(run(false), Ok(Some(Path::new(vec![s, g1], 1))));
+        assert_eq!(run(true), Err(GraphError::InconsistentHeuristic(g2, g2)));
+    }
+
+    #[test]
+    fn astar_with_property_maps() {
+        use graph::{Directed, MutableGraph, VertexListGraph};
+        use incidence_list::IncidenceList;
+        use property_map::{PropertyMap, VecPropertyMap};
+        use visitor::DefaultVisitor;
+
+        // v0 --1--> v1 --1--> v2
+        //  |                  ^
+        //  +--------3---------+
+
+        let mut g = IncidenceList::<Directed, (), u32>::new();
+        let v0 = g.add_vertex(());
+        let v1 = g.add_vertex(());
+        let v2 = g.add_vertex(());
+        g.add_edge(v0, v1, 1).unwrap();
+        g.add_edge(v1, v2, 1).unwrap();
+        g.add_edge(v0, v2, 3).unwrap();
+
+        let bound = g.vertex_bound();
+        let mut astar = Astar::with_property_maps(
+            DefaultVisitor,
+            VecPropertyMap::with_bound(bound),
+            VecPropertyMap::with_bound(bound),
+        );
+        assert_eq!(
+            astar.run_to_vertex(&v0, &v2, |&e, g| g[e], |_, _| 0, &g),
+            Ok(Some(Path::new(vec![v0, v1, v2], 2)))
+        );
+        assert_eq!((astar.distances()[v0], astar.distances()[v2]), (0, 2));
+        assert_eq!((astar.parents()[v2], astar.parents().get(v0)), (v1, None));
+    }
+
+    #[test]
+    fn astar_negative_edge_cost() {
+        use error::GraphError;
+        use graph::{Directed, Graph, MutableGraph};
+        use incidence_list::IncidenceList;
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+
+        let v0 = g.add_vertex(("s", 0));
+        let v1 = g.add_vertex(("a", 0));
+        let v2 = g.add_vertex(("g", 0));
+
+        g.add_edge(v0, v1, 2).unwrap();
+        let e12 = g.add_edge(v1, v2, -1).unwrap();
+
+        assert_eq!(
+            Astar::new().run(
+                &v0,
+                |&e, g| *g.edge_property(e).unwrap(),
+                |&v, g| g.vertex_property(v).unwrap().1,
+                |&v| v == v2,
+                &g,
+            ),
+            Err(GraphError::NegativeEdgeCost(e12))
+        );
+    }
+
+    #[test]
+    fn astar_parallel_edges() {
+        use graph::{Directed, Graph, MutableGraph};
+        use incidence_list::IncidenceList;
+
+        //     9
+        //   ----->
+        // s ----->  a ------> g
+        //     1          1
+        //   ------------->
+        //         3
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+
+        let v0 = g.add_vertex(("s", 0));
+        let v1 = g.add_vertex(("a", 0));
+        let v2 = g.add_vertex(("g", 0));
+
+        g.add_edge(v0, v1, 9).unwrap();
+        g.add_edge(v0, v1, 1).unwrap();
+        g.add_edge(v1, v2, 1).unwrap();
+        g.add_edge(v0, v2, 3).unwrap();
+
+        assert_eq!(
+            Astar::new().run(
+                &v0,
+                |&e, g| *g.edge_property(e).unwrap(),
+                |&v, g| g.vertex_property(v).unwrap().1,
+                |&v| v == v2,
+                &g,
+            ),
+            Ok(Some(Path::new(vec![v0, v1, v2], 2)))
         );
     }
 
@@ -280,19 +903,19 @@ mod tests {
             }
         }
 
-        impl<'a, T> Visitor<T, Event> for MyVisitor
+        impl<T> Visitor<T, Event> for MyVisitor
         where
             T: Graph,
         {
             fn visit(&mut self, e: &Event, _graph: &T) {
-                match e {
-                    &Event::InitializeVertex(v) => self.init.push(v),
-                    &Event::DiscoverVertex(v) => self.discovered.push(v),
-                    &Event::ExamineVertex(v) => self.vertex_examined.push(v),
-                    &Event::ExamineEdge(e) => self.edge_examined.push(e),
-                    &Event::EdgeRelaxed(e) => self.edge_relaxed.push(e),
-                    &Event::EdgeNotRelaxed(e) => self.edge_not_relaxed.push(e),
-                    &Event::FinishVertex(v) => self.finished.push(v),
+                match *e {
+                    Event::InitializeVertex(v) => self.init.push(v),
+                    Event::DiscoverVertex(v) => self.discovered.push(v),
+                    Event::ExamineVertex(v) => self.vertex_examined.push(v),
+                    Event::ExamineEdge(e) => self.edge_examined.push(e),
+                    Event::EdgeRelaxed(e) => self.edge_relaxed.push(e),
+                    Event::EdgeNotRelaxed(e) => self.edge_not_relaxed.push(e),
+                    Event::FinishVertex(v) => self.finished.push(v),
                     _ => (),
                 }
             }
@@ -325,12 +948,12 @@ mod tests {
                 |&v| v == v4,
                 &g,
             ),
-            Some(vec![v0, v1, v2, v3, v4])
+            Ok(Some(Path::new(vec![v0, v1, v2, v3, v4], 8)))
         );
         assert_eq!(astar.visitor_ref().init.len(), 6);
         assert_eq!(
             astar.visitor_ref().discovered,
-            vec![v0, v1, v2, v3, v2, v4, v3, v4]
+            vec![v0, v2, v1, v3, v4, v2, v3, v4]
         );
         assert_eq!(
             astar.visitor_ref().vertex_examined,
@@ -338,11 +961,11 @@ mod tests {
         );
         assert_eq!(
             astar.visitor_ref().edge_examined,
-            vec![e01, e02, e23, e12, e13, e14, e23, e34, e34]
+            vec![e02, e01, e23, e14, e13, e12, e23, e34, e34]
         );
         assert_eq!(
             astar.visitor_ref().edge_relaxed,
-            vec![e01, e02, e23, e12, e14, e23, e34]
+            vec![e02, e01, e23, e14, e12, e23, e34]
         );
         assert_eq!(astar.visitor_ref().edge_not_relaxed, vec![e13, e34]);
         assert_eq!(astar.visitor_ref().finished, vec![v0, v2, v1, v2, v3, v3]);
@@ -376,29 +999,29 @@ mod tests {
         let va = g.add_vertex(("Vaslui", 199));
         let ze = g.add_vertex(("Zerind", 374));
 
-        g.add_edge(ar, ze, 75);
-        g.add_edge(ze, or, 71);
-        g.add_edge(or, si, 151);
-        g.add_edge(ar, si, 140);
-        g.add_edge(ar, ti, 118);
-        g.add_edge(ti, lu, 111);
-        g.add_edge(lu, me, 70);
-        g.add_edge(me, db, 75);
-        g.add_edge(db, cr, 120);
-        g.add_edge(si, fa, 99);
-        g.add_edge(fa, bu, 211);
-        g.add_edge(si, ri, 80);
-        g.add_edge(ri, cr, 146);
-        g.add_edge(ri, pi, 97);
-        g.add_edge(pi, cr, 138);
-        g.add_edge(pi, bu, 101);
-        g.add_edge(bu, gi, 90);
-        g.add_edge(bu, ur, 85);
-        g.add_edge(ur, va, 142);
-        g.add_edge(va, ia, 92);
-        g.add_edge(ia, ne, 87);
-        g.add_edge(ur, hi, 98);
-        g.add_edge(hi, ef, 86);
+        g.add_edge(ar, ze, 75).unwrap();
+        g.add_edge(ze, or, 71).unwrap();
+        g.add_edge(or, si, 151).unwrap();
+        g.add_edge(ar, si, 140).unwrap();
+        g.add_edge(ar, ti, 118).unwrap();
+        g.add_edge(ti, lu, 111).unwrap();
+        g.add_edge(lu, me, 70).unwrap();
+        g.add_edge(me, db, 75).unwrap();
+        g.add_edge(db, cr, 120).unwrap();
+        g.add_edge(si, fa, 99).unwrap();
+        g.add_edge(fa, bu, 211).unwrap();
+        g.add_edge(si, ri, 80).unwrap();
+        g.add_edge(ri, cr, 146).unwrap();
+        g.add_edge(ri, pi, 97).unwrap();
+        g.add_edge(pi, cr, 138).unwrap();
+        g.add_edge(pi, bu, 101).unwrap();
+        g.add_edge(bu, gi, 90).unwrap();
+        g.add_edge(bu, ur, 85).unwrap();
+        g.add_edge(ur, va, 142).unwrap();
+        g.add_edge(va, ia, 92).unwrap();
+        g.add_edge(ia, ne, 87).unwrap();
+        g.add_edge(ur, hi, 98).unwrap();
+        g.add_edge(hi, ef, 86).unwrap();
 
         assert_eq!(
             Astar::new().run(
@@ -408,7 +1031,25 @@ mod tests {
                 |&v| v == bu,
                 &g,
             ),
-            Some(vec![ar, si, ri, pi, bu])
+            Ok(Some(Path::new(vec![ar, si, ri, pi, bu], 418)))
+        );
+
+        // greedy search takes the road through Fagaras, which looks closer but costs more
+        let mut greedy = GreedyBestFirst::new();
+        assert_eq!(
+            greedy.run_to_vertex(
+                &ar,
+                &bu,
+                |&e, g| *g.edge_property(e).unwrap(),
+                |&v, g| g.vertex_property(v).unwrap().1,
+                &g,
+            ),
+            Ok(Some(Path::new(vec![ar, si, fa, bu], 450)))
+        );
+        let mut uniform = UniformCost::from(greedy.into_astar());
+        assert_eq!(
+            uniform.run_to_vertex(&ar, &bu, |&e, g| *g.edge_property(e).unwrap(), &g),
+            Ok(Some(Path::new(vec![ar, si, ri, pi, bu], 418)))
         );
     }
 }
@@ -0,0 +1,248 @@
+use std::collections::hash_map::Entry;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead};
+use std::str::FromStr;
+
+use fnv::FnvHashMap;
+
+use graph::{MutableGraph, VertexDescriptor};
+use incidence_list::IncidenceList;
+
+type Loaded<D, VP, EP> = (IncidenceList<D, VP, EP>, FnvHashMap<String, VertexDescriptor>);
+
+/// The string attributes of a vertex or an edge as read from a text format.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Attributes {
+    values: FnvHashMap<String, String>,
+}
+
+impl Attributes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert<K: Into<String>, S: Into<String>>(&mut self, key: K, value: S) {
+        self.values.insert(key.into(), value.into());
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Parses the value of `key`, which must be present.
+    pub fn parse<T: FromStr>(&self, key: &str) -> Result<T, AttributeError> {
+        let value = self.get(key).ok_or_else(|| AttributeError::Missing(key.to_string()))?;
+        value
+            .parse()
+            .map_err(|_| AttributeError::Invalid(key.to_string(), value.to_string()))
+    }
+
+    /// Parses the value of `key`, or returns `default` if it is absent or empty.
+    pub fn parse_or<T: FromStr>(&self, key: &str, default: T) -> Result<T, AttributeError> {
+        match self.get(key) {
+            Some(value) if !value.is_empty() => self.parse(key),
+            _ => Ok(default),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AttributeError {
+    Missing(String),
+    Invalid(String, String),
+}
+
+impl fmt::Display for AttributeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AttributeError::Missing(ref key) => write!(f, "attribute {} is missing", key),
+            AttributeError::Invalid(ref key, ref value) => {
+                write!(f, "attribute {} has an invalid value {:?}", key, value)
+            }
+        }
+    }
+}
+
+impl Error for AttributeError {}
+
+/// A vertex or edge property that can be built from the attributes of a text format, so that
+/// a loader yields typed properties rather than maps of strings.
+pub trait FromAttributes: Sized {
+    fn from_attributes(attributes: &Attributes) -> Result<Self, AttributeError>;
+}
+
+impl FromAttributes for () {
+    fn from_attributes(_: &Attributes) -> Result<Self, AttributeError> {
+        Ok(())
+    }
+}
+
+impl FromAttributes for Attributes {
+    fn from_attributes(attributes: &Attributes) -> Result<Self, AttributeError> {
+        Ok(attributes.clone())
+    }
+}
+
+fn invalid<E: fmt::Display>(line: usize, error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", line, error))
+}
+
+// Reads a header and the records under it, naming each field after its column.
+fn read_records<R: BufRead>(reader: R) -> io::Result<Vec<(usize, Attributes)>> {
+    let mut lines = reader.lines().enumerate();
+    let header = match lines.next() {
+        Some((_, line)) => line?,
+        None => return Ok(Vec::new()),
+    };
+    let columns = header.split(',').map(|c| c.trim().to_string()).collect::<Vec<_>>();
+    let mut records = Vec::new();
+    for (i, line) in lines {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields = line.split(',').map(str::trim).collect::<Vec<_>>();
+        if fields.len() != columns.len() {
+            let message = format!("expected {} fields, found {}", columns.len(), fields.len());
+            return Err(invalid(i + 1, message));
+        }
+        let mut attributes = Attributes::new();
+        for (column, field) in columns.iter().zip(fields) {
+            attributes.insert(column.as_str(), field);
+        }
+        records.push((i + 1, attributes));
+    }
+    Ok(records)
+}
+
+/// Loads a graph from comma-separated tables with a header line, converting the other columns
+/// of every row into a typed property with `FromAttributes`.
+///
+/// Each row of `vertices`, if given, has the key of a vertex in the `id` column. Each row of
+/// `edges` has the keys of its endpoints in the `source` and `target` columns; a key not in
+/// `vertices` adds a vertex whose property is built from no attributes. Fields are trimmed and
+/// may not be quoted. Returns the graph and the vertex of every key. A malformed row or a
+/// property that cannot be built yields an error of kind `io::ErrorKind::InvalidData`.
+pub fn read_csv<D, VP, EP, R, S>(vertices: Option<R>, edges: S) -> io::Result<Loaded<D, VP, EP>>
+where
+    VP: FromAttributes,
+    EP: FromAttributes,
+    R: BufRead,
+    S: BufRead,
+{
+    let mut graph = IncidenceList::new();
+    let mut keys = FnvHashMap::default();
+
+    if let Some(vertices) = vertices {
+        for (line, mut attributes) in read_records(vertices)? {
+            let key = attributes
+                .values
+                .remove("id")
+                .ok_or_else(|| invalid(line, AttributeError::Missing("id".to_string())))?;
+            let property = VP::from_attributes(&attributes).map_err(|e| invalid(line, e))?;
+            match keys.entry(key) {
+                Entry::Vacant(entry) => {
+                    entry.insert(graph.add_vertex(property));
+                }
+                Entry::Occupied(entry) => {
+                    return Err(invalid(line, format!("duplicate vertex {}", entry.key())));
+                }
+            }
+        }
+    }
+
+    for (line, mut attributes) in read_records(edges)? {
+        let mut endpoint = |column: &str| -> io::Result<VertexDescriptor> {
+            let key = attributes
+                .values
+                .remove(column)
+                .ok_or_else(|| invalid(line, AttributeError::Missing(column.to_string())))?;
+            if let Some(&v) = keys.get(&key) {
+                return Ok(v);
+            }
+            let property = VP::from_attributes(&Attributes::new()).map_err(|e| invalid(line, e))?;
+            let v = graph.add_vertex(property);
+            keys.insert(key, v);
+            Ok(v)
+        };
+        let source = endpoint("source")?;
+        let target = endpoint("target")?;
+        let property = EP::from_attributes(&attributes).map_err(|e| invalid(line, e))?;
+        graph.add_edge(source, target, property).unwrap();
+    }
+
+    Ok((graph, keys))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_csv, AttributeError, Attributes, FromAttributes};
+
+    #[test]
+    fn typed_properties() {
+        use std::io;
+        use graph::{Directed, EdgeListGraph, IncidenceGraph, VertexListGraph};
+        use incidence_list::IncidenceList;
+
+        #[derive(Debug, PartialEq)]
+        struct City {
+            name: String,
+            population: u32,
+        }
+
+        impl FromAttributes for City {
+            fn from_attributes(attributes: &Attributes) -> Result<Self, AttributeError> {
+                Ok(City {
+                    name: attributes.parse("name")?,
+                    population: attributes.parse_or("population", 0)?,
+                })
+            }
+        }
+
+        #[derive(Debug, PartialEq)]
+        struct Road(u32);
+
+        impl FromAttributes for Road {
+            fn from_attributes(attributes: &Attributes) -> Result<Self, AttributeError> {
+                attributes.parse("km").map(Road)
+            }
+        }
+
+        let vertices = "id,name,population\n\
+                        a, Arad, 159074\n\
+                        s, Sibiu,\n";
+        let edges = "source,target,km\n\
+                     a,s,140\n\
+                     \n\
+                     s,a,140\n";
+        let (g, keys): (IncidenceList<Directed, City, Road>, _) =
+            read_csv(Some(vertices.as_bytes()), edges.as_bytes()).unwrap();
+        assert_eq!((g.order(), g.size()), (2, 2));
+        assert_eq!(g[keys["a"]], City { name: "Arad".to_string(), population: 159074 });
+        assert_eq!(g[keys["s"]].population, 0);
+        let e = g.out_edges(keys["a"]).next().unwrap();
+        assert_eq!((g.edge_endpoints(e), &g[e]), (Some((keys["a"], keys["s"])), &Road(140)));
+
+        let bad = "source,target,km\na,s,far\n";
+        let error = read_csv::<Directed, City, Road, &[u8], _>(Some(vertices.as_bytes()),
+                                                               bad.as_bytes()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), "line 2: attribute km has an invalid value \"far\"");
+        let error = read_csv::<Directed, City, Road, &[u8], _>(None, edges.as_bytes())
+            .unwrap_err();
+        assert_eq!(error.to_string(), "line 2: attribute name is missing");
+
+        let (raw, _): (IncidenceList<Directed, (), Attributes>, _) =
+            read_csv(None::<&[u8]>, edges.as_bytes()).unwrap();
+        assert!(raw.edges().all(|e| raw[e].get("km") == Some("140") && raw[e].len() == 1));
+    }
+}
@@ -0,0 +1,384 @@
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+use fnv::FnvHashMap;
+use num_traits::Zero;
+
+use error::GraphError;
+use graph::{Directivity, EdgeDescriptor, EdgeListGraph, Graph, IncidenceGraph, VertexDescriptor,
+            VertexListGraph};
+use property_map::{vertex_index, PropertyMap};
+use visitor::{DefaultVisitor, Event, Visitor};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShortestPathTree<C> {
+    source: VertexDescriptor,
+    distances: FnvHashMap<VertexDescriptor, C>,
+    parents: FnvHashMap<VertexDescriptor, (EdgeDescriptor, VertexDescriptor)>,
+}
+
+impl<C> ShortestPathTree<C>
+where
+    C: Copy,
+{
+    pub fn source(&self) -> VertexDescriptor {
+        self.source
+    }
+
+    /// Returns `None` if `v` is unreachable from the source.
+    pub fn distance(&self, v: VertexDescriptor) -> Option<C> {
+        self.distances.get(&v).cloned()
+    }
+
+    /// The last edge on the shortest path to `v`.
+    pub fn parent(&self, v: VertexDescriptor) -> Option<EdgeDescriptor> {
+        self.parents.get(&v).map(|&(e, _)| e)
+    }
+
+    /// The vertices of a shortest path from the source to `v`, both included.
+    pub fn path(&self, v: VertexDescriptor) -> Option<Vec<VertexDescriptor>> {
+        self.distances.get(&v)?;
+        let mut path = vec![v];
+        let mut w = v;
+        while let Some(&(_, u)) = self.parents.get(&w) {
+            w = u;
+            path.push(w);
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+pub struct BellmanFord<C, T, V>
+where
+    C: Copy + Debug + Ord + Zero,
+    T: Graph,
+    V: Visitor<T, Event>,
+{
+    negative_cycle: Vec<VertexDescriptor>,
+    visitor: V,
+    phantom: PhantomData<(C, T)>,
+}
+
+impl<C, T> BellmanFord<C, T, DefaultVisitor>
+where
+    C: Copy + Debug + Ord + Zero,
+    T: Graph,
+{
+    pub fn new() -> Self {
+        Self::with_visitor(DefaultVisitor)
+    }
+}
+
+impl<C, T> Default for BellmanFord<C, T, DefaultVisitor>
+where
+    C: Copy + Debug + Ord + Zero,
+    T: Graph,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C, T, V> BellmanFord<C, T, V>
+where
+    C: Copy + Debug + Ord + Zero,
+    T: Graph,
+    V: Visitor<T, Event>,
+{
+    pub fn with_visitor(visitor: V) -> Self {
+        Self {
+            negative_cycle: Vec::new(),
+            visitor,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Relaxes every edge of the graph in rounds until no distance drops, at most n - 1 times,
+    /// then checks each edge once more: an edge that would still shorten a path lies on or
+    /// behind a negative cycle reachable from `start`, which fails with
+    /// `GraphError::NegativeCycle` naming a vertex on it. The whole cycle is then available
+    /// from `negative_cycle`. In an undirected graph a single negative edge is such a cycle.
+    pub fn run<'a, G>(
+        &mut self,
+        start: &VertexDescriptor,
+        edge_cost: G,
+        graph: &'a T,
+    ) -> Result<ShortestPathTree<C>, GraphError>
+    where
+        G: Fn(&EdgeDescriptor, &T) -> C,
+        T: EdgeListGraph<'a> + IncidenceGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        if !graph.contains_vertex(*start) {
+            return Err(GraphError::VertexNotFound(*start));
+        }
+        self.negative_cycle.clear();
+        for vertex in graph.vertices() {
+            self.visitor.visit(&Event::InitializeVertex(vertex), graph)
+        }
+
+        let mut arcs = Vec::with_capacity(graph.size());
+        for edge in graph.edges() {
+            let (s, t) = graph.edge_endpoints(edge).ok_or(GraphError::EdgeNotFound(edge))?;
+            let cost = edge_cost(&edge, graph);
+            arcs.push((edge, s, t, cost));
+            if !T::Directivity::is_directed() && s != t {
+                arcs.push((edge, t, s, cost));
+            }
+        }
+
+        let mut distances = FnvHashMap::default();
+        let mut parents = FnvHashMap::default();
+        distances.insert(*start, C::zero());
+        for _ in 1..graph.order() {
+            let mut changed = false;
+            for &(edge, s, t, cost) in &arcs {
+                let d = match distances.get(&s) {
+                    Some(&d) => d + cost,
+                    None => continue,
+                };
+                self.visitor.visit(&Event::ExamineEdge(edge), graph);
+                if distances.get(&t).is_some_and(|&old| d >= old) {
+                    self.visitor.visit(&Event::EdgeNotRelaxed(edge), graph);
+                } else {
+                    distances.insert(t, d);
+                    parents.insert(t, (edge, s));
+                    self.visitor.visit(&Event::EdgeRelaxed(edge), graph);
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        let mut relaxed = None;
+        for &(edge, s, t, cost) in &arcs {
+            let d = match distances.get(&s) {
+                Some(&d) => d + cost,
+                None => continue,
+            };
+            if distances[&t] > d {
+                distances.insert(t, d);
+                parents.insert(t, (edge, s));
+                self.visitor.visit(&Event::EdgeNotMinimized(edge), graph);
+                relaxed = Some(t);
+            } else {
+                self.visitor.visit(&Event::EdgeMinimized(edge), graph);
+            }
+        }
+
+        if let Some(mut v) = relaxed {
+            // n steps back along the parents from a vertex still improving end up on the cycle
+            for _ in 0..graph.order() {
+                v = parents[&v].1;
+            }
+            let mut w = v;
+            loop {
+                self.negative_cycle.push(w);
+                w = parents[&w].1;
+                if w == v {
+                    break;
+                }
+            }
+            self.negative_cycle.reverse();
+            return Err(GraphError::NegativeCycle(v));
+        }
+        Ok(ShortestPathTree {
+            source: *start,
+            distances,
+            parents,
+        })
+    }
+
+    /// The vertices of the negative cycle the last `run` failed on, in the order its edges
+    /// lead, or nothing if it succeeded.
+    pub fn negative_cycle(&self) -> &[VertexDescriptor] {
+        &self.negative_cycle
+    }
+
+    pub fn visitor_ref(&self) -> &V {
+        &self.visitor
+    }
+}
+
+/// The Shortest Path Faster Algorithm: Bellman-Ford relaxing only the edges leaving vertices
+/// whose distance has just dropped, kept in a queue. On sparse graphs that is usually far fewer
+/// relaxations than the n - 1 full rounds. A vertex queued n times lies on or behind a negative
+/// cycle, which fails with `GraphError::NegativeCycle` naming a vertex on it; in an undirected
+/// graph a single negative edge is such a cycle.
+pub fn spfa<'a, G, C, F>(
+    graph: &'a G,
+    source: VertexDescriptor,
+    edge_cost: F,
+) -> Result<ShortestPathTree<C>, GraphError>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+    C: Copy + Debug + Ord + Zero,
+    F: Fn(&EdgeDescriptor, &G) -> C,
+{
+    if !graph.contains_vertex(source) {
+        return Err(GraphError::VertexNotFound(source));
+    }
+    let vertices = graph.vertices().collect::<Vec<_>>();
+    let index = vertex_index(graph, &vertices);
+    let n = vertices.len();
+
+    let mut leaving = vec![Vec::new(); n];
+    for (i, &v) in vertices.iter().enumerate() {
+        for e in graph.out_edges(v) {
+            if let Some(&j) = graph.opposite(e, v).and_then(|t| index.get(t)) {
+                let c = edge_cost(&e, graph);
+                leaving[i].push((j, e, c));
+                if !G::Directivity::is_directed() && i != j {
+                    leaving[j].push((i, e, c));
+                }
+            }
+        }
+    }
+
+    let mut distances = vec![None; n];
+    let mut parents = vec![None; n];
+    let mut queued = vec![false; n];
+    let mut times_queued = vec![0; n];
+    let mut queue = VecDeque::new();
+    let s = index[source];
+    distances[s] = Some(C::zero());
+    queue.push_back(s);
+    queued[s] = true;
+    while let Some(i) = queue.pop_front() {
+        queued[i] = false;
+        let d = distances[i].unwrap();
+        for &(j, e, c) in &leaving[i] {
+            if distances[j].is_some_and(|old| d + c >= old) {
+                continue;
+            }
+            distances[j] = Some(d + c);
+            parents[j] = Some((e, i));
+            if !queued[j] {
+                times_queued[j] += 1;
+                if times_queued[j] >= n {
+                    // n steps back along the parents end up on the cycle once the parents have
+                    // closed it, which they eventually do
+                    let mut k = Some(j);
+                    for _ in 0..n {
+                        k = k.and_then(|k| parents[k]).map(|(_, p)| p);
+                    }
+                    if let Some(k) = k {
+                        return Err(GraphError::NegativeCycle(vertices[k]));
+                    }
+                }
+                queued[j] = true;
+                queue.push_back(j);
+            }
+        }
+    }
+
+    Ok(ShortestPathTree {
+        source,
+        distances: (0..n)
+            .filter_map(|i| distances[i].map(|d| (vertices[i], d)))
+            .collect(),
+        parents: (0..n)
+            .filter_map(|i| parents[i].map(|(e, p)| (vertices[i], (e, vertices[p]))))
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{spfa, BellmanFord};
+
+    #[test]
+    fn bellman_ford() {
+        use error::GraphError;
+        use graph::{Directed, Graph, MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        //        (4)
+        // v0 ----------> v1 --(-2)--> v3
+        //  |             ^
+        // (1)          (-1)
+        //  v             |
+        // v2 ------------+     v4
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+        let vs = (0..5).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        g.add_edge(vs[0], vs[1], 4).unwrap();
+        g.add_edge(vs[0], vs[2], 1).unwrap();
+        let e21 = g.add_edge(vs[2], vs[1], -1).unwrap();
+        g.add_edge(vs[1], vs[3], -2).unwrap();
+        let cost = |e: &_, g: &IncidenceList<Directed, (), i32>| *g.edge_property(*e).unwrap();
+
+        let mut bellman_ford = BellmanFord::new();
+        let tree = bellman_ford.run(&vs[0], cost, &g).unwrap();
+        assert_eq!(tree.distance(vs[1]), Some(0));
+        assert_eq!(tree.distance(vs[3]), Some(-2));
+        assert_eq!(tree.distance(vs[4]), None);
+        assert_eq!(tree.parent(vs[1]), Some(e21));
+        assert_eq!(tree.path(vs[3]), Some(vec![vs[0], vs[2], vs[1], vs[3]]));
+        assert!(bellman_ford.negative_cycle().is_empty());
+
+        // v2 -> v1 -> v3 -> v2 costs -1 - 2 + 2
+        g.add_edge(vs[3], vs[2], 2).unwrap();
+        match bellman_ford.run(&vs[0], cost, &g) {
+            Err(GraphError::NegativeCycle(v)) => assert!(v != vs[0] && v != vs[4]),
+            result => panic!("unexpected {:?}", result),
+        }
+        let mut cycle = bellman_ford.negative_cycle().to_vec();
+        let first = cycle.iter().position(|&v| v == vs[2]).unwrap();
+        cycle.rotate_left(first);
+        assert_eq!(cycle, vec![vs[2], vs[1], vs[3]]);
+
+        // unreachable from v4
+        assert!(bellman_ford.run(&vs[4], cost, &g).is_ok());
+
+        let mut u = IncidenceList::<Undirected, (), _>::new();
+        let us = (0..2).map(|_| u.add_vertex(())).collect::<Vec<_>>();
+        u.add_edge(us[0], us[1], -1).unwrap();
+        let cost = |e: &_, g: &IncidenceList<Undirected, (), i32>| *g.edge_property(*e).unwrap();
+        let mut bellman_ford = BellmanFord::new();
+        assert!(bellman_ford.run(&us[0], cost, &u).is_err());
+        assert_eq!(bellman_ford.negative_cycle().len(), 2);
+    }
+
+    #[test]
+    fn spfa_with_negative_edges() {
+        use error::GraphError;
+        use graph::{Directed, Graph, MutableGraph};
+        use incidence_list::IncidenceList;
+
+        //        (4)
+        // v0 ----------> v1 --(-2)--> v3
+        //  |             ^
+        // (1)          (-1)
+        //  v             |
+        // v2 ------------+     v4
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+        let vs = (0..5).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        g.add_edge(vs[0], vs[1], 4).unwrap();
+        g.add_edge(vs[0], vs[2], 1).unwrap();
+        let e21 = g.add_edge(vs[2], vs[1], -1).unwrap();
+        g.add_edge(vs[1], vs[3], -2).unwrap();
+        let cost = |e: &_, g: &IncidenceList<Directed, (), i32>| *g.edge_property(*e).unwrap();
+
+        let tree = spfa(&g, vs[0], cost).unwrap();
+        assert_eq!(tree.distance(vs[1]), Some(0));
+        assert_eq!(tree.distance(vs[3]), Some(-2));
+        assert_eq!(tree.distance(vs[4]), None);
+        assert_eq!(tree.parent(vs[1]), Some(e21));
+        assert_eq!(tree.path(vs[3]), Some(vec![vs[0], vs[2], vs[1], vs[3]]));
+
+        g.add_edge(vs[3], vs[2], 4).unwrap();
+        assert_eq!(spfa(&g, vs[0], cost).unwrap().distance(vs[2]), Some(1));
+        g.add_edge(vs[3], vs[0], 1).unwrap();
+        match spfa(&g, vs[0], cost) {
+            Err(GraphError::NegativeCycle(v)) => assert!(v != vs[4]),
+            result => panic!("unexpected {:?}", result),
+        }
+    }
+}
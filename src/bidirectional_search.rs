@@ -0,0 +1,256 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::fmt::Debug;
+
+use fnv::FnvHashMap;
+use num_traits::Zero;
+
+use error::GraphError;
+use graph::{BidirectionalGraph, Directivity, EdgeDescriptor, Graph, VertexDescriptor};
+use path::{reverse_path, Path};
+
+// The edges leaving `v` towards the goal when `forward`, or arriving at it otherwise, each with
+// the vertex at its other end. Edges of an undirected graph are followed either way.
+fn steps<'a, G>(
+    graph: &'a G,
+    v: VertexDescriptor,
+    forward: bool,
+) -> Vec<(EdgeDescriptor, VertexDescriptor)>
+where
+    G: BidirectionalGraph<'a>,
+    G::Directivity: Directivity,
+{
+    let edges = if !G::Directivity::is_directed() {
+        graph.out_edges(v).chain(graph.in_edges(v)).collect::<Vec<_>>()
+    } else if forward {
+        graph.out_edges(v).collect()
+    } else {
+        graph.in_edges(v).collect()
+    };
+    edges
+        .into_iter()
+        .filter_map(|e| graph.opposite(e, v).map(|w| (e, w)))
+        .collect()
+}
+
+// Joins the path from the start to `meeting` with the one from `meeting` to the goal.
+fn stitch(
+    forward: &FnvHashMap<VertexDescriptor, VertexDescriptor>,
+    backward: &FnvHashMap<VertexDescriptor, VertexDescriptor>,
+    meeting: VertexDescriptor,
+) -> Vec<VertexDescriptor> {
+    let mut path = reverse_path(forward, meeting);
+    let mut v = meeting;
+    while let Some(&w) = backward.get(&v) {
+        path.push(w);
+        v = w;
+    }
+    path
+}
+
+fn check<G>(graph: &G, start: VertexDescriptor, goal: VertexDescriptor) -> Result<(), GraphError>
+where
+    G: Graph,
+{
+    for &v in &[start, goal] {
+        if !graph.contains_vertex(v) {
+            return Err(GraphError::VertexNotFound(v));
+        }
+    }
+    Ok(())
+}
+
+/// Finds a path with fewest edges from `start` to `goal` by breadth-first search from both
+/// ends at once, following edges backwards from the goal, until the two searches meet. Each
+/// round expands a whole level of whichever side has the smaller frontier. Returns `None` if
+/// the goal is unreachable.
+pub fn bidirectional_bfs<'a, G>(
+    graph: &'a G,
+    start: VertexDescriptor,
+    goal: VertexDescriptor,
+) -> Result<Option<Vec<VertexDescriptor>>, GraphError>
+where
+    G: BidirectionalGraph<'a>,
+    G::Directivity: Directivity,
+{
+    check(graph, start, goal)?;
+    if start == goal {
+        return Ok(Some(vec![start]));
+    }
+
+    // parents are kept pointing back towards the vertex each side started from
+    let mut parents = [FnvHashMap::default(), FnvHashMap::default()];
+    let mut fringes = [VecDeque::new(), VecDeque::new()];
+    let roots = [start, goal];
+    fringes[0].push_back(start);
+    fringes[1].push_back(goal);
+    while !fringes[0].is_empty() && !fringes[1].is_empty() {
+        let side = if fringes[0].len() <= fringes[1].len() { 0 } else { 1 };
+        for _ in 0..fringes[side].len() {
+            let v = fringes[side].pop_front().unwrap();
+            for (_, w) in steps(graph, v, side == 0) {
+                if w == roots[side] || parents[side].contains_key(&w) {
+                    continue;
+                }
+                parents[side].insert(w, v);
+                if w == roots[1 - side] || parents[1 - side].contains_key(&w) {
+                    return Ok(Some(stitch(&parents[0], &parents[1], w)));
+                }
+                fringes[side].push_back(w);
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Finds a cheapest path from `start` to `goal` by running Dijkstra's algorithm from both ends
+/// at once, the backward search following edges into the vertices it settles. Whichever side
+/// has the cheaper vertex to settle goes next, and the search stops once the two next costs
+/// add up to no less than the cheapest path through a vertex reached from both sides. Returns
+/// the path with its cost, or `None` if the goal is unreachable, and fails with
+/// `GraphError::NegativeEdgeCost` on a negative edge.
+pub fn bidirectional_dijkstra<'a, G, C, F>(
+    graph: &'a G,
+    start: VertexDescriptor,
+    goal: VertexDescriptor,
+    edge_cost: F,
+) -> Result<Option<Path<C>>, GraphError>
+where
+    G: BidirectionalGraph<'a>,
+    G::Directivity: Directivity,
+    C: Copy + Debug + Ord + Zero,
+    F: Fn(&EdgeDescriptor, &G) -> C,
+{
+    check(graph, start, goal)?;
+
+    let mut distances = [FnvHashMap::default(), FnvHashMap::default()];
+    let mut parents = [FnvHashMap::default(), FnvHashMap::default()];
+    let mut fringes = [BinaryHeap::new(), BinaryHeap::new()];
+    for (side, &root) in [start, goal].iter().enumerate() {
+        distances[side].insert(root, C::zero());
+        fringes[side].push(Reverse((C::zero(), root)));
+    }
+    let mut best = if start == goal {
+        Some((C::zero(), start))
+    } else {
+        None
+    };
+
+    while let (Some(&Reverse((f, _))), Some(&Reverse((b, _)))) =
+        (fringes[0].peek(), fringes[1].peek())
+    {
+        if best.is_some_and(|(c, _)| f + b >= c) {
+            break;
+        }
+        let side = if f <= b { 0 } else { 1 };
+        let Reverse((cost, v)) = fringes[side].pop().unwrap();
+        if distances[side][&v] < cost {
+            continue;
+        }
+        for (e, w) in steps(graph, v, side == 0) {
+            let step = edge_cost(&e, graph);
+            if step < C::zero() {
+                return Err(GraphError::NegativeEdgeCost(e));
+            }
+            let through = cost + step;
+            if distances[side].get(&w).is_some_and(|&d| d <= through) {
+                continue;
+            }
+            distances[side].insert(w, through);
+            parents[side].insert(w, v);
+            fringes[side].push(Reverse((through, w)));
+            if let Some(&rest) = distances[1 - side].get(&w) {
+                if best.is_none_or(|(c, _)| through + rest < c) {
+                    best = Some((through + rest, w));
+                }
+            }
+        }
+    }
+    Ok(best.map(|(cost, meeting)| Path::new(stitch(&parents[0], &parents[1], meeting), cost)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bidirectional_bfs, bidirectional_dijkstra};
+
+    #[test]
+    fn bidirectional_searches() {
+        use error::GraphError;
+        use graph::{Directed, Graph, MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+        use path::Path;
+
+        // v0 --1--> v1 --1--> v2 --1--> v3 --1--> v4
+        //  |                                       ^
+        //  +------------------10-------------------+
+        //
+        // v5 --1--> v0
+
+        let mut g = IncidenceList::<Directed, (), _>::new();
+        let vs = (0..6).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for i in 0..4 {
+            g.add_edge(vs[i], vs[i + 1], 1).unwrap();
+        }
+        g.add_edge(vs[0], vs[4], 10).unwrap();
+        g.add_edge(vs[5], vs[0], 1).unwrap();
+        let cost = |e: &_, g: &IncidenceList<Directed, (), i32>| *g.edge_property(*e).unwrap();
+
+        assert_eq!(bidirectional_bfs(&g, vs[5], vs[4]), Ok(Some(vec![vs[5], vs[0], vs[4]])));
+        assert_eq!(bidirectional_bfs(&g, vs[1], vs[3]), Ok(Some(vec![vs[1], vs[2], vs[3]])));
+        assert_eq!(bidirectional_bfs(&g, vs[4], vs[0]), Ok(None));
+        assert_eq!(bidirectional_bfs(&g, vs[2], vs[2]), Ok(Some(vec![vs[2]])));
+
+        assert_eq!(
+            bidirectional_dijkstra(&g, vs[5], vs[4], cost),
+            Ok(Some(Path::new(vec![vs[5], vs[0], vs[1], vs[2], vs[3], vs[4]], 5)))
+        );
+        assert_eq!(bidirectional_dijkstra(&g, vs[3], vs[1], cost), Ok(None));
+        assert_eq!(
+            bidirectional_dijkstra(&g, vs[3], vs[3], cost),
+            Ok(Some(Path::new(vec![vs[3]], 0)))
+        );
+
+        // an isolated vertex is reached only from itself, its self-loop notwithstanding
+        let v6 = g.add_vertex(());
+        g.add_edge(v6, v6, 1).unwrap();
+        assert_eq!(bidirectional_bfs(&g, vs[0], v6), Ok(None));
+        assert_eq!(bidirectional_bfs(&g, v6, v6), Ok(Some(vec![v6])));
+        assert_eq!(bidirectional_dijkstra(&g, v6, vs[0], cost), Ok(None));
+        assert_eq!(bidirectional_dijkstra(&g, v6, v6, cost), Ok(Some(Path::new(vec![v6], 0))));
+
+        g.remove_vertex(v6).unwrap();
+        assert_eq!(bidirectional_bfs(&g, vs[0], v6), Err(GraphError::VertexNotFound(v6)));
+        assert_eq!(bidirectional_bfs(&g, v6, vs[0]), Err(GraphError::VertexNotFound(v6)));
+        assert_eq!(
+            bidirectional_dijkstra(&g, v6, vs[0], cost),
+            Err(GraphError::VertexNotFound(v6))
+        );
+        let e = g.add_edge(vs[2], vs[4], -1).unwrap();
+        assert_eq!(
+            bidirectional_dijkstra(&g, vs[0], vs[4], cost),
+            Err(GraphError::NegativeEdgeCost(e))
+        );
+
+        // a 20 x 20 grid, undirected, with unit costs
+        let mut grid = IncidenceList::<Undirected, (), _>::new();
+        let cells = (0..400).map(|_| grid.add_vertex(())).collect::<Vec<_>>();
+        for i in 0..20 {
+            for j in 0..20 {
+                if i + 1 < 20 {
+                    grid.add_edge(cells[i * 20 + j], cells[(i + 1) * 20 + j], 1).unwrap();
+                }
+                if j + 1 < 20 {
+                    grid.add_edge(cells[i * 20 + j], cells[i * 20 + j + 1], 1).unwrap();
+                }
+            }
+        }
+        let path = bidirectional_bfs(&grid, cells[399], cells[0]).unwrap().unwrap();
+        assert_eq!(path.len(), 39);
+        assert_eq!((path[0], path[38]), (cells[399], cells[0]));
+        let cost = |e: &_, g: &IncidenceList<Undirected, (), i32>| *g.edge_property(*e).unwrap();
+        let path = bidirectional_dijkstra(&grid, cells[0], cells[399], cost)
+            .unwrap()
+            .unwrap();
+        assert_eq!((path.vertices().len(), path.cost()), (39, 38));
+    }
+}
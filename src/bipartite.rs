@@ -0,0 +1,204 @@
+use std::collections::VecDeque;
+
+use coloring::symmetric_adjacency;
+use graph::{IncidenceGraph, VertexDescriptor, VertexListGraph};
+
+fn path_to_root(mut i: usize, parents: &[Option<usize>]) -> Vec<usize> {
+    let mut path = vec![i];
+    while let Some(p) = parents[i] {
+        path.push(p);
+        i = p;
+    }
+    path
+}
+
+// Looks for an augmenting path from the free left vertex `root` with Kuhn's depth-first search
+// and flips it if found.
+fn augment(
+    adjacent: &[Vec<usize>],
+    root: usize,
+    visited: &mut [bool],
+    partners: &mut [Option<usize>],
+    owners: &mut [Option<usize>],
+) -> bool {
+    let mut stack = vec![(root, 0)];
+    while let Some(&mut (u, ref mut k)) = stack.last_mut() {
+        let w = match adjacent[u].get(*k) {
+            Some(&w) => w,
+            None => {
+                stack.pop();
+                continue;
+            }
+        };
+        *k += 1;
+        if visited[w] {
+            continue;
+        }
+        visited[w] = true;
+        match owners[w] {
+            Some(p) => stack.push((p, 0)),
+            None => {
+                // every vertex on the stack takes the right vertex its search went through
+                let mut w = w;
+                while let Some((u, _)) = stack.pop() {
+                    let old = partners[u];
+                    partners[u] = Some(w);
+                    owners[w] = Some(u);
+                    match old {
+                        Some(old) => w = old,
+                        None => break,
+                    }
+                }
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Finds a maximum matching of the bipartite graph whose left vertex `i` is adjacent to the
+/// right vertices `adjacent[i]`, all below `right`, by Kuhn's augmenting paths in O(nm). Returns
+/// the partner of every left vertex and the owner of every right vertex, if matched.
+pub(crate) fn kuhn_matching(
+    adjacent: &[Vec<usize>],
+    right: usize,
+) -> (Vec<Option<usize>>, Vec<Option<usize>>) {
+    let mut partners = vec![None; adjacent.len()];
+    let mut owners = vec![None; right];
+    for i in 0..adjacent.len() {
+        let mut visited = vec![false; right];
+        augment(adjacent, i, &mut visited, &mut partners, &mut owners);
+    }
+    (partners, owners)
+}
+
+/// Two-colors the graph breadth-first, ignoring edge direction. Returns the two color classes
+/// if that succeeds, or otherwise the vertices of an odd cycle in the order they are traversed.
+pub fn is_bipartite<'a, G>(
+    graph: &'a G,
+) -> Result<(Vec<VertexDescriptor>, Vec<VertexDescriptor>), Vec<VertexDescriptor>>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+{
+    for v in graph.vertices() {
+        if graph.out_edges(v).any(|e| graph.opposite(e, v) == Some(v)) {
+            return Err(vec![v]);
+        }
+    }
+
+    let (vertices, neighbors) = symmetric_adjacency(graph);
+    let mut sides = vec![None; vertices.len()];
+    let mut parents = vec![None; vertices.len()];
+    let mut fringe = VecDeque::new();
+    for root in 0..vertices.len() {
+        if sides[root].is_some() {
+            continue;
+        }
+        sides[root] = Some(false);
+        fringe.push_back(root);
+        while let Some(i) = fringe.pop_front() {
+            let side = sides[i].unwrap();
+            for &j in &neighbors[i] {
+                match sides[j] {
+                    None => {
+                        sides[j] = Some(!side);
+                        parents[j] = Some(i);
+                        fringe.push_back(j);
+                    }
+                    Some(s) if s == side => {
+                        let mut up = path_to_root(i, &parents);
+                        let mut down = path_to_root(j, &parents);
+                        while up.len() > 1 && down.len() > 1 &&
+                            up[up.len() - 2] == down[down.len() - 2]
+                        {
+                            up.pop();
+                            down.pop();
+                        }
+                        down.pop();
+                        up.extend(down.into_iter().rev());
+                        return Err(up.into_iter().map(|k| vertices[k]).collect());
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+    }
+
+    let (left, right): (Vec<_>, Vec<_>) =
+        (0..vertices.len()).partition(|&i| sides[i] == Some(false));
+    Ok((
+        left.into_iter().map(|i| vertices[i]).collect(),
+        right.into_iter().map(|i| vertices[i]).collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_bipartite, kuhn_matching};
+
+    #[test]
+    fn bipartite() {
+        use graph::{Directed, MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        // v0 --> v1 --> v2
+        //  ^             |
+        //  |             v
+        // v5 <-- v4 <-- v3
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+        let vs = (0..6).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for i in 0..6 {
+            g.add_edge(vs[i], vs[(i + 1) % 6], ()).unwrap();
+        }
+
+        let (left, right) = is_bipartite(&g).unwrap();
+        assert_eq!(left, vec![vs[0], vs[2], vs[4]]);
+        assert_eq!(right, vec![vs[1], vs[3], vs[5]]);
+
+        // adding v0 --> v2 closes the odd cycle v0, v1, v2
+        g.add_edge(vs[0], vs[2], ()).unwrap();
+        let mut cycle = is_bipartite(&g).unwrap_err();
+        cycle.sort();
+        assert_eq!(cycle, vec![vs[0], vs[1], vs[2]]);
+
+        let mut h = IncidenceList::<Directed, _, _>::new();
+        let v = h.add_vertex(());
+        h.add_edge(v, v, ()).unwrap();
+        assert_eq!(is_bipartite(&h), Err(vec![v]));
+
+        // v0 --- v1    v2    v3 --- v4
+        //
+        // every component is colored on its own, starting from the left
+
+        let mut u = IncidenceList::<Undirected, (), ()>::new();
+        assert_eq!(is_bipartite(&u), Ok((vec![], vec![])));
+        let us = (0..5).map(|_| u.add_vertex(())).collect::<Vec<_>>();
+        u.add_edge(us[0], us[1], ()).unwrap();
+        u.add_edge(us[3], us[4], ()).unwrap();
+        assert_eq!(is_bipartite(&u), Ok((vec![us[0], us[2], us[3]], vec![us[1], us[4]])));
+
+        // joining v2 to both v3 and v4 closes a triangle
+        u.add_edge(us[4], us[2], ()).unwrap();
+        u.add_edge(us[2], us[3], ()).unwrap();
+        let mut cycle = is_bipartite(&u).unwrap_err();
+        cycle.sort();
+        assert_eq!(cycle, vec![us[2], us[3], us[4]]);
+    }
+
+    #[test]
+    fn kuhn() {
+        // left 0 -- right 0, 1
+        // left 1 -- right 0
+        // left 2 -- right 0
+        //
+        // left 0 has to give way to left 1, so only one of left 1 and 2 stays unmatched
+
+        let (partners, owners) = kuhn_matching(&[vec![0, 1], vec![0], vec![0]], 3);
+        assert_eq!(partners, vec![Some(1), Some(0), None]);
+        assert_eq!(owners, vec![Some(1), Some(0), None]);
+
+        assert_eq!(kuhn_matching(&[], 2), (vec![], vec![None, None]));
+        assert_eq!(kuhn_matching(&[vec![], vec![]], 0), (vec![None, None], vec![]));
+    }
+}
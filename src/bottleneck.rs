@@ -0,0 +1,312 @@
+use std::collections::{BinaryHeap, VecDeque};
+use std::fmt::Debug;
+use std::mem;
+
+use num_traits::Bounded;
+
+use error::GraphError;
+use graph::{Directivity, EdgeDescriptor, IncidenceGraph, VertexDescriptor, VertexListGraph};
+use path::Path;
+use property_map::{vertex_index, PropertyMap, VecPropertyMap};
+
+/// Finds a path from `source` to `target` whose narrowest edge is as wide as possible, with
+/// the capacity of that edge as its cost, by Dijkstra's algorithm with the width of a path taking the
+/// place of its length: the next vertex settled is the one reached by the widest path so far.
+/// The path from a vertex to itself has no edges and a width of `C::max_value()`. Returns
+/// `Ok(None)` if `target` cannot be reached.
+pub fn widest_path<'a, G, C, F>(
+    graph: &'a G,
+    source: VertexDescriptor,
+    target: VertexDescriptor,
+    edge_capacity: F,
+) -> Result<Option<Path<C>>, GraphError>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+    C: Copy + Debug + Ord + Bounded,
+    F: Fn(&EdgeDescriptor, &G) -> C,
+{
+    for &v in &[source, target] {
+        if !graph.contains_vertex(v) {
+            return Err(GraphError::VertexNotFound(v));
+        }
+    }
+    let vertices = graph.vertices().collect::<Vec<_>>();
+    let index = vertex_index(graph, &vertices);
+    let n = vertices.len();
+
+    let mut leaving = vec![Vec::new(); n];
+    for (i, &v) in vertices.iter().enumerate() {
+        for e in graph.out_edges(v) {
+            if let Some(&j) = graph.opposite(e, v).and_then(|t| index.get(t)) {
+                let c = edge_capacity(&e, graph);
+                leaving[i].push((j, c));
+                if !G::Directivity::is_directed() && i != j {
+                    leaving[j].push((i, c));
+                }
+            }
+        }
+    }
+
+    let (s, t) = (index[source], index[target]);
+    let mut widths = vec![None; n];
+    let mut parents = vec![None; n];
+    let mut settled = vec![false; n];
+    let mut fringe = BinaryHeap::new();
+    widths[s] = Some(C::max_value());
+    fringe.push((C::max_value(), s));
+    while let Some((width, i)) = fringe.pop() {
+        if settled[i] {
+            continue;
+        }
+        settled[i] = true;
+        if i == t {
+            let mut path = vec![vertices[t]];
+            let mut k = t;
+            while let Some(p) = parents[k] {
+                path.push(vertices[p]);
+                k = p;
+            }
+            path.reverse();
+            return Ok(Some(Path::new(path, width)));
+        }
+        for &(j, c) in &leaving[i] {
+            let w = width.min(c);
+            if !settled[j] && widths[j].is_none_or(|old| w > old) {
+                widths[j] = Some(w);
+                parents[j] = Some(i);
+                fringe.push((w, j));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Answers minimax path queries: the least possible cost of the costliest edge on a path
+/// between two vertices, ignoring edge direction. Every minimum spanning tree contains such a
+/// path, so the tree is built once with Kruskal's algorithm and rooted, and each query climbs
+/// to the lowest common ancestor in O(log n) jumps of precomputed length and maximum.
+#[derive(Clone, Debug)]
+pub struct MinimaxPaths<C> {
+    vertices: Vec<VertexDescriptor>,
+    index: VecPropertyMap<VertexDescriptor, usize>,
+    component: Vec<usize>,
+    depth: Vec<usize>,
+    // `jumps[k][i]` is the 2^k-th ancestor of i and the costliest edge on the way there
+    jumps: Vec<Vec<(usize, C)>>,
+}
+
+impl<C> MinimaxPaths<C>
+where
+    C: Copy + Debug + Ord + Bounded,
+{
+    pub fn new<'a, G, F>(graph: &'a G, edge_cost: F) -> Self
+    where
+        G: IncidenceGraph<'a> + VertexListGraph<'a>,
+        F: Fn(&EdgeDescriptor, &G) -> C,
+    {
+        let vertices = graph.vertices().collect::<Vec<_>>();
+        let index = vertex_index(graph, &vertices);
+        let n = vertices.len();
+        let mut edges = Vec::new();
+        for (i, &v) in vertices.iter().enumerate() {
+            for e in graph.out_edges(v) {
+                if let Some(&j) = graph.opposite(e, v).and_then(|t| index.get(t)) {
+                    if i != j {
+                        edges.push((edge_cost(&e, graph), i, j));
+                    }
+                }
+            }
+        }
+        edges.sort();
+
+        let mut roots = (0..n).collect::<Vec<_>>();
+        let mut tree = vec![Vec::new(); n];
+        for (c, i, j) in edges {
+            let (mut a, mut b) = (i, j);
+            while roots[a] != a {
+                roots[a] = roots[roots[a]];
+                a = roots[a];
+            }
+            while roots[b] != b {
+                roots[b] = roots[roots[b]];
+                b = roots[b];
+            }
+            if a != b {
+                roots[a] = b;
+                tree[i].push((j, c));
+                tree[j].push((i, c));
+            }
+        }
+
+        let mut component = vec![usize::MAX; n];
+        let mut depth = vec![0; n];
+        let mut parents = (0..n).map(|i| (i, C::min_value())).collect::<Vec<_>>();
+        for root in 0..n {
+            if component[root] != usize::MAX {
+                continue;
+            }
+            component[root] = root;
+            let mut queue = VecDeque::new();
+            queue.push_back(root);
+            while let Some(i) = queue.pop_front() {
+                for &(j, c) in &tree[i] {
+                    if component[j] == usize::MAX {
+                        component[j] = root;
+                        depth[j] = depth[i] + 1;
+                        parents[j] = (i, c);
+                        queue.push_back(j);
+                    }
+                }
+            }
+        }
+
+        let mut jumps = vec![parents];
+        while 1 << jumps.len() < n {
+            let last = jumps.last().unwrap();
+            let next = last
+                .iter()
+                .map(|&(a, c)| (last[a].0, c.max(last[a].1)))
+                .collect();
+            jumps.push(next);
+        }
+        MinimaxPaths {
+            vertices,
+            index,
+            component,
+            depth,
+            jumps,
+        }
+    }
+
+    // Climbs from i and j to their lowest common ancestor, returning it and the costliest edge
+    // passed.
+    fn meet(&self, mut i: usize, mut j: usize) -> (usize, C) {
+        let mut worst = C::min_value();
+        if self.depth[i] < self.depth[j] {
+            mem::swap(&mut i, &mut j);
+        }
+        for (k, jump) in self.jumps.iter().enumerate().rev() {
+            if self.depth[i] - self.depth[j] >= 1 << k {
+                worst = worst.max(jump[i].1);
+                i = jump[i].0;
+            }
+        }
+        for jump in self.jumps.iter().rev() {
+            if jump[i].0 != jump[j].0 {
+                worst = worst.max(jump[i].1).max(jump[j].1);
+                i = jump[i].0;
+                j = jump[j].0;
+            }
+        }
+        if i != j {
+            worst = worst.max(self.jumps[0][i].1).max(self.jumps[0][j].1);
+            i = self.jumps[0][i].0;
+        }
+        (i, worst)
+    }
+
+    fn indices(&self, u: VertexDescriptor, v: VertexDescriptor) -> Option<(usize, usize)> {
+        let (&i, &j) = (self.index.get(u)?, self.index.get(v)?);
+        if self.component[i] == self.component[j] {
+            Some((i, j))
+        } else {
+            None
+        }
+    }
+
+    /// The least cost of the costliest edge on a path from `u` to `v`, or `C::min_value()`
+    /// when they are the same vertex. Returns `None` if there is no path.
+    pub fn bottleneck(&self, u: VertexDescriptor, v: VertexDescriptor) -> Option<C> {
+        let (i, j) = self.indices(u, v)?;
+        Some(self.meet(i, j).1)
+    }
+
+    /// The vertices of a path from `u` to `v` through the spanning tree attaining the
+    /// bottleneck, both included.
+    pub fn path(&self, u: VertexDescriptor, v: VertexDescriptor) -> Option<Vec<VertexDescriptor>> {
+        let (mut i, mut j) = self.indices(u, v)?;
+        let (top, _) = self.meet(i, j);
+        let mut path = vec![self.vertices[i]];
+        while i != top {
+            i = self.jumps[0][i].0;
+            path.push(self.vertices[i]);
+        }
+        let mut rest = Vec::new();
+        while j != top {
+            rest.push(self.vertices[j]);
+            j = self.jumps[0][j].0;
+        }
+        path.extend(rest.into_iter().rev());
+        Some(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{widest_path, MinimaxPaths};
+
+    #[test]
+    fn widest_paths() {
+        use graph::{Directed, Graph, MutableGraph};
+        use incidence_list::IncidenceList;
+        use path::Path;
+
+        //        4        3
+        //   v0 -----> v1 -----> v3
+        //    |                   ^
+        //  7 |                   | 5
+        //    +------> v2 --------+
+        //                  |
+        //             v4 <-+ 9
+
+        let mut g = IncidenceList::<Directed, (), _>::new();
+        let vs = (0..5).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t, c) in &[(0, 1, 4), (1, 3, 3), (0, 2, 7), (2, 3, 5), (2, 4, 9)] {
+            g.add_edge(vs[s], vs[t], c).unwrap();
+        }
+        let capacity = |e: &_, g: &IncidenceList<Directed, (), i32>| *g.edge_property(*e).unwrap();
+
+        let path = widest_path(&g, vs[0], vs[3], capacity).unwrap().unwrap();
+        assert_eq!(path.vertices(), &[vs[0], vs[2], vs[3]]);
+        assert_eq!(path.cost(), 5);
+
+        let path = widest_path(&g, vs[0], vs[4], capacity).unwrap().unwrap();
+        assert_eq!(path, Path::new(vec![vs[0], vs[2], vs[4]], 7));
+
+        let trivial = widest_path(&g, vs[1], vs[1], capacity).unwrap();
+        assert_eq!(trivial, Some(Path::new(vec![vs[1]], i32::MAX)));
+        assert_eq!(widest_path(&g, vs[3], vs[0], capacity).unwrap(), None);
+    }
+
+    #[test]
+    fn minimax_paths() {
+        use graph::{Graph, MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        //       2         6
+        //  v0 ----- v1 ----- v2
+        //   |        |        |
+        // 5 |      1 |        | 3
+        //   |        |        |
+        //  v3 ----- v4 ----- v5      v6
+        //       8         4
+
+        let mut g = IncidenceList::<Undirected, (), _>::new();
+        let vs = (0..7).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        let edges = [(0, 1, 2), (1, 2, 6), (0, 3, 5), (1, 4, 1), (2, 5, 3), (3, 4, 8), (4, 5, 4)];
+        for &(s, t, c) in &edges {
+            g.add_edge(vs[s], vs[t], c).unwrap();
+        }
+        let cost = |e: &_, g: &IncidenceList<Undirected, (), i32>| *g.edge_property(*e).unwrap();
+
+        let minimax = MinimaxPaths::new(&g, cost);
+        assert_eq!(minimax.bottleneck(vs[0], vs[2]), Some(4));
+        assert_eq!(minimax.path(vs[0], vs[2]).unwrap(), vec![vs[0], vs[1], vs[4], vs[5], vs[2]]);
+        assert_eq!(minimax.bottleneck(vs[3], vs[5]), Some(5));
+        assert_eq!(minimax.path(vs[3], vs[1]).unwrap(), vec![vs[3], vs[0], vs[1]]);
+        assert_eq!(minimax.bottleneck(vs[4], vs[4]), Some(i32::MIN));
+        assert_eq!(minimax.path(vs[4], vs[4]).unwrap(), vec![vs[4]]);
+        assert_eq!(minimax.bottleneck(vs[0], vs[6]), None);
+    }
+}
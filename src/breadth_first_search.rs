@@ -1,20 +1,28 @@
-use std::collections::VecDeque;
-use std::collections::hash_map::Entry;
+use std::collections::{HashSet, VecDeque};
+use std::hash::BuildHasher;
 use std::marker::PhantomData;
 
-use fnv::FnvHashMap;
-
-use graph::{Graph, AdjacencyGraph, AdjacencyMatrixGraph, VertexListGraph, VertexDescriptor};
+use graph::{Graph, BidirectionalGraph, Directivity, VertexListGraph, EdgeDescriptor,
+            VertexDescriptor};
+use error::GraphError;
 use path::reverse_path;
+use property_map::{Color, HashPropertyMap, PropertyMap};
 use visitor::{Event, Visitor, DefaultVisitor};
 
-pub struct Bfs<T, V>
+/// Breadth-first search. The colors of the vertices and their parents in the breadth-first
+/// tree are kept in property maps, hash maps unless others are given to
+/// `with_property_maps`, and can be inspected after a search.
+pub struct Bfs<T, V, C = HashPropertyMap<VertexDescriptor, Color>,
+               P = HashPropertyMap<VertexDescriptor, VertexDescriptor>>
 where
     T: Graph,
     V: Visitor<T, Event>,
+    C: PropertyMap<VertexDescriptor, Color>,
+    P: PropertyMap<VertexDescriptor, VertexDescriptor>,
 {
     fringe: VecDeque<VertexDescriptor>,
-    parents: FnvHashMap<VertexDescriptor, VertexDescriptor>,
+    colors: C,
+    parents: P,
     visitor: V,
     phantom: PhantomData<T>,
 }
@@ -28,59 +36,240 @@ where
     }
 }
 
+impl<T> Default for Bfs<T, DefaultVisitor>
+where
+    T: Graph,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T, V> Bfs<T, V>
 where
     T: Graph,
     V: Visitor<T, Event>,
 {
     pub fn with_visitor(visitor: V) -> Self {
+        Self::with_property_maps(visitor, HashPropertyMap::default(), HashPropertyMap::default())
+    }
+}
+
+impl<T, V, C, P> Bfs<T, V, C, P>
+where
+    T: Graph,
+    V: Visitor<T, Event>,
+    C: PropertyMap<VertexDescriptor, Color>,
+    P: PropertyMap<VertexDescriptor, VertexDescriptor>,
+{
+    /// Keeps the colors and the parents of the vertices in `colors` and `parents`, which are
+    /// cleared at the start of every search.
+    pub fn with_property_maps(visitor: V, colors: C, parents: P) -> Self {
         Self {
             fringe: VecDeque::new(),
-            parents: FnvHashMap::default(),
-            visitor: visitor,
+            colors,
+            parents,
+            visitor,
             phantom: PhantomData,
         }
     }
 
+    fn is_discovered(&self, v: VertexDescriptor) -> bool {
+        self.colors.get(v).is_some_and(|&c| c != Color::White)
+    }
+
     pub fn run<'a, F>(
         &mut self,
         start: &VertexDescriptor,
         is_goal: F,
         graph: &'a T,
-    ) -> Option<Vec<VertexDescriptor>>
+    ) -> Result<Option<Vec<VertexDescriptor>>, GraphError>
     where
         F: Fn(&VertexDescriptor) -> bool,
-        T: AdjacencyGraph<'a> + AdjacencyMatrixGraph + VertexListGraph<'a>,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
     {
+        self.run_from_any(Some(*start), is_goal, graph)
+    }
+
+    /// Searches from all of `starts` at once, as if from a vertex joined to each of them, and
+    /// returns a path from whichever reaches a goal first.
+    pub fn run_from_any<'a, I, F>(
+        &mut self,
+        starts: I,
+        is_goal: F,
+        graph: &'a T,
+    ) -> Result<Option<Vec<VertexDescriptor>>, GraphError>
+    where
+        I: IntoIterator<Item = VertexDescriptor>,
+        F: Fn(&VertexDescriptor) -> bool,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.search(starts, is_goal, |_, _| true, graph)
+    }
+
+    // Follows only the edges to vertices that `is_allowed` accepts.
+    fn search<'a, I, A, F>(
+        &mut self,
+        starts: I,
+        is_goal: F,
+        is_allowed: A,
+        graph: &'a T,
+    ) -> Result<Option<Vec<VertexDescriptor>>, GraphError>
+    where
+        I: IntoIterator<Item = VertexDescriptor>,
+        A: Fn(&EdgeDescriptor, &VertexDescriptor) -> bool,
+        F: Fn(&VertexDescriptor) -> bool,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        let starts = starts.into_iter().collect::<Vec<_>>();
+        if let Some(&start) = starts.iter().find(|&&s| !graph.contains_vertex(s)) {
+            return Err(GraphError::VertexNotFound(start));
+        }
+
         for vertex in graph.vertices() {
             self.visitor.visit(&Event::InitializeVertex(vertex), graph)
         }
 
-        self.visitor.visit(&Event::DiscoverVertex(*start), graph);
-        self.fringe.push_back(*start);
+        self.fringe.clear();
+        self.colors.clear();
+        self.parents.clear();
+        for start in starts {
+            if !self.is_discovered(start) {
+                self.colors.insert(start, Color::Gray);
+                self.visitor.visit(&Event::DiscoverVertex(start), graph);
+                self.fringe.push_back(start);
+            }
+        }
 
         while let Some(vertex) = self.fringe.pop_front() {
             self.visitor.visit(&Event::ExamineVertex(vertex), graph);
             if is_goal(&vertex) {
-                return Some(reverse_path(&self.parents, vertex));
+                return Ok(Some(reverse_path(&self.parents, vertex)));
             }
-            for adjacency in graph.adjacent_vertices(vertex) {
-                let edge = graph.edge(vertex, adjacency).unwrap();
+            let in_edges = if T::Directivity::is_directed() {
+                None
+            } else {
+                Some(graph.in_edges(vertex))
+            };
+            for edge in graph.out_edges(vertex).chain(in_edges.into_iter().flatten()) {
+                let adjacency = graph.opposite(edge, vertex).ok_or(
+                    GraphError::EdgeNotFound(edge),
+                )?;
+                if !is_allowed(&edge, &adjacency) {
+                    continue;
+                }
                 self.visitor.visit(&Event::ExamineEdge(edge), graph);
-                if adjacency != *start {
-                    if let Entry::Vacant(entry) = self.parents.entry(adjacency) {
-                        self.visitor.visit(&Event::TreeEdge(edge), graph);
-                        entry.insert(vertex);
-                        self.visitor.visit(&Event::DiscoverVertex(adjacency), graph);
-                        self.fringe.push_back(adjacency);
-                    } else {
-                        self.visitor.visit(&Event::NonTreeEdge(edge), graph);
-                    }
+                if !self.is_discovered(adjacency) {
+                    self.visitor.visit(&Event::TreeEdge(edge), graph);
+                    self.parents.insert(adjacency, vertex);
+                    self.colors.insert(adjacency, Color::Gray);
+                    self.visitor.visit(&Event::DiscoverVertex(adjacency), graph);
+                    self.fringe.push_back(adjacency);
+                } else {
+                    self.visitor.visit(&Event::NonTreeEdge(edge), graph);
                 }
             }
+            self.colors.insert(vertex, Color::Black);
             self.visitor.visit(&Event::FinishVertex(vertex), graph);
         }
-        None
+        Ok(None)
+    }
+
+    pub fn run_to_vertex<'a>(
+        &mut self,
+        start: &VertexDescriptor,
+        goal: &VertexDescriptor,
+        graph: &'a T,
+    ) -> Result<Option<Vec<VertexDescriptor>>, GraphError>
+    where
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.run(start, |v| v == goal, graph)
+    }
+
+    pub fn run_to_any<'a, S>(
+        &mut self,
+        start: &VertexDescriptor,
+        goals: &HashSet<VertexDescriptor, S>,
+        graph: &'a T,
+    ) -> Result<Option<Vec<VertexDescriptor>>, GraphError>
+    where
+        S: BuildHasher,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.run(start, |v| goals.contains(v), graph)
+    }
+
+    /// Finds a path from `start` to `goal` with the fewest edges that passes through none of
+    /// `vertices` and none of `edges`. There is none if `start` or `goal` is among `vertices`.
+    pub fn run_avoiding<'a, S, R>(
+        &mut self,
+        start: &VertexDescriptor,
+        goal: &VertexDescriptor,
+        vertices: &HashSet<VertexDescriptor, S>,
+        edges: &HashSet<EdgeDescriptor, R>,
+        graph: &'a T,
+    ) -> Result<Option<Vec<VertexDescriptor>>, GraphError>
+    where
+        S: BuildHasher,
+        R: BuildHasher,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        if vertices.contains(start) {
+            return if graph.contains_vertex(*start) {
+                Ok(None)
+            } else {
+                Err(GraphError::VertexNotFound(*start))
+            };
+        }
+        let is_allowed = |e: &EdgeDescriptor, v: &VertexDescriptor| {
+            !edges.contains(e) && !vertices.contains(v)
+        };
+        self.search(Some(*start), |v| v == goal, is_allowed, graph)
+    }
+
+    pub fn has_path<'a>(
+        &mut self,
+        start: &VertexDescriptor,
+        goal: &VertexDescriptor,
+        graph: &'a T,
+    ) -> Result<bool, GraphError>
+    where
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.run_to_vertex(start, goal, graph).map(|path| path.is_some())
+    }
+
+    /// Visits every vertex reachable from `start` and returns the parent of each in the
+    /// breadth-first tree, which is also available from `parents` afterwards.
+    pub fn run_all<'a>(
+        &mut self,
+        start: &VertexDescriptor,
+        graph: &'a T,
+    ) -> Result<&P, GraphError>
+    where
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.run(start, |_| false, graph)?;
+        Ok(&self.parents)
+    }
+
+    /// The colors left by the last search: `Black` for the vertices it finished and `Gray`
+    /// for those it discovered but stopped before finishing.
+    pub fn colors(&self) -> &C {
+        &self.colors
+    }
+
+    pub fn parents(&self) -> &P {
+        &self.parents
     }
 
     pub fn visitor_ref(&self) -> &V {
@@ -94,6 +283,7 @@ mod tests {
 
     #[test]
     fn bfs() {
+        use std::collections::HashSet;
         use graph::{Directed, MutableGraph};
         use incidence_list::IncidenceList;
 
@@ -110,27 +300,137 @@ mod tests {
         let v8 = g.add_vertex("i");
         let v9 = g.add_vertex("j");
 
-        g.add_edge(v0, v1, ());
-        g.add_edge(v0, v4, ());
-        g.add_edge(v1, v5, ());
-        g.add_edge(v2, v0, ());
-        g.add_edge(v2, v4, ());
-        g.add_edge(v4, v1, ());
-        g.add_edge(v4, v3, ());
-        g.add_edge(v4, v6, ());
-        g.add_edge(v5, v4, ());
-        g.add_edge(v5, v4, ());
-        g.add_edge(v6, v7, ());
-        g.add_edge(v7, v3, ());
-        g.add_edge(v7, v9, ());
-        g.add_edge(v8, v7, ());
-        g.add_edge(v9, v8, ());
+        g.add_edge(v0, v1, ()).unwrap();
+        let e04 = g.add_edge(v0, v4, ()).unwrap();
+        g.add_edge(v1, v5, ()).unwrap();
+        g.add_edge(v2, v0, ()).unwrap();
+        g.add_edge(v2, v4, ()).unwrap();
+        g.add_edge(v4, v1, ()).unwrap();
+        g.add_edge(v4, v3, ()).unwrap();
+        g.add_edge(v4, v6, ()).unwrap();
+        g.add_edge(v5, v4, ()).unwrap();
+        g.add_edge(v5, v4, ()).unwrap();
+        g.add_edge(v6, v7, ()).unwrap();
+        g.add_edge(v7, v3, ()).unwrap();
+        g.add_edge(v7, v9, ()).unwrap();
+        g.add_edge(v8, v7, ()).unwrap();
+        g.add_edge(v9, v8, ()).unwrap();
 
         assert_eq!(
             Bfs::new().run(&v0, |&v| v == v9, &g),
-            Some(vec![v0, v4, v6, v7, v9])
+            Ok(Some(vec![v0, v4, v6, v7, v9]))
+        );
+        assert_eq!(Bfs::new().run(&v0, |&v| v == v2, &g), Ok(None));
+
+        assert_eq!(
+            Bfs::new().run_to_vertex(&v0, &v9, &g),
+            Ok(Some(vec![v0, v4, v6, v7, v9]))
+        );
+        let goals = [v2, v3, v9].iter().cloned().collect::<HashSet<_>>();
+        assert_eq!(
+            Bfs::new().run_to_any(&v0, &goals, &g),
+            Ok(Some(vec![v0, v4, v3]))
+        );
+        let (mut vertices, mut edges) = (HashSet::new(), HashSet::new());
+        edges.insert(e04);
+        assert_eq!(
+            Bfs::new().run_avoiding(&v0, &v9, &vertices, &edges, &g),
+            Ok(Some(vec![v0, v1, v5, v4, v6, v7, v9]))
+        );
+        vertices.insert(v5);
+        assert_eq!(Bfs::new().run_avoiding(&v0, &v9, &vertices, &edges, &g), Ok(None));
+        assert_eq!(Bfs::new().has_path(&v0, &v8, &g), Ok(true));
+        assert_eq!(Bfs::new().has_path(&v0, &v2, &g), Ok(false));
+
+        assert_eq!(
+            Bfs::new().run_from_any(vec![v2, v6], |&v| v == v9, &g),
+            Ok(Some(vec![v6, v7, v9]))
+        );
+        assert_eq!(Bfs::new().run_from_any(vec![v3, v9], |&v| v == v0, &g), Ok(None));
+
+        let mut bfs = Bfs::new();
+        let parents = bfs.run_all(&v4, &g).unwrap();
+        assert_eq!(parents.len(), 7);
+        assert_eq!((parents[&v9], parents[&v5], parents.get(&v0)), (v7, v1, None));
+        assert_eq!(bfs.parents()[&v8], v9);
+    }
+
+    #[test]
+    fn bfs_undirected() {
+        use graph::{MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        // v0 --- v1 <--- v2
+        //
+        // edges are stored as drawn but traversed both ways
+
+        let mut g = IncidenceList::<Undirected, (), ()>::new();
+        let v0 = g.add_vertex(());
+        let v1 = g.add_vertex(());
+        let v2 = g.add_vertex(());
+        g.add_edge(v0, v1, ()).unwrap();
+        g.add_edge(v2, v1, ()).unwrap();
+
+        assert_eq!(Bfs::new().run_to_vertex(&v0, &v2, &g), Ok(Some(vec![v0, v1, v2])));
+        assert_eq!(Bfs::new().run_to_vertex(&v2, &v0, &g), Ok(Some(vec![v2, v1, v0])));
+    }
+
+    #[test]
+    fn bfs_colors() {
+        use graph::{Directed, MutableGraph, VertexListGraph};
+        use incidence_list::IncidenceList;
+        use property_map::{Color, PropertyMap, VecPropertyMap};
+        use visitor::DefaultVisitor;
+
+        // v0 --> v1 --> v2 --> v3
+        //        ^      |
+        //        +------+
+
+        let mut g = IncidenceList::<Directed, (), ()>::new();
+        let v0 = g.add_vertex(());
+        let v1 = g.add_vertex(());
+        let v2 = g.add_vertex(());
+        let v3 = g.add_vertex(());
+        g.add_edge(v0, v1, ()).unwrap();
+        g.add_edge(v1, v2, ()).unwrap();
+        g.add_edge(v2, v1, ()).unwrap();
+        g.add_edge(v2, v3, ()).unwrap();
+
+        let mut bfs = Bfs::new();
+        assert_eq!(bfs.run_to_vertex(&v0, &v2, &g), Ok(Some(vec![v0, v1, v2])));
+        assert_eq!(bfs.colors()[&v1], Color::Black);
+        assert_eq!(bfs.colors()[&v2], Color::Gray);
+        assert!(!bfs.colors().contains_key(&v3));
+
+        let mut bfs = Bfs::with_property_maps(
+            DefaultVisitor,
+            VecPropertyMap::with_bound(g.vertex_bound()),
+            VecPropertyMap::new(),
+        );
+        bfs.run_all(&v1, &g).unwrap();
+        assert!([v1, v2, v3].iter().all(|&v| bfs.colors()[v] == Color::Black));
+        assert!(!bfs.colors().contains_key(v0));
+        assert_eq!((bfs.parents()[v3], bfs.parents().get(v1)), (v2, None));
+    }
+
+    #[test]
+    fn bfs_from_removed_vertex() {
+        use error::GraphError;
+        use graph::{Directed, MutableGraph};
+        use incidence_list::IncidenceList;
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+
+        let v0 = g.add_vertex("a");
+        let v1 = g.add_vertex("b");
+        g.add_edge(v0, v1, ()).unwrap();
+
+        assert!(g.remove_vertex(v0).is_some());
+
+        assert_eq!(
+            Bfs::new().run(&v0, |&v| v == v1, &g),
+            Err(GraphError::VertexNotFound(v0))
         );
-        assert_eq!(Bfs::new().run(&v0, |&v| v == v2, &g), None);
     }
 
     #[test]
@@ -168,14 +468,14 @@ mod tests {
             T: IncidenceGraph<'a>,
         {
             fn visit(&mut self, e: &Event, graph: &T) {
-                match e {
-                    &Event::InitializeVertex(v) => self.init.push(v),
-                    &Event::DiscoverVertex(v) => self.discovered.push(v),
-                    &Event::ExamineVertex(v) => self.vertex_examined.push(v),
-                    &Event::ExamineEdge(e) => self.edge_target_examined.push(graph.target(e)),
-                    &Event::TreeEdge(e) => self.tree_edge_target.push(graph.target(e)),
-                    &Event::NonTreeEdge(e) => self.non_tree_edge_target.push(graph.target(e)),
-                    &Event::FinishVertex(v) => self.finished.push(v),
+                match *e {
+                    Event::InitializeVertex(v) => self.init.push(v),
+                    Event::DiscoverVertex(v) => self.discovered.push(v),
+                    Event::ExamineVertex(v) => self.vertex_examined.push(v),
+                    Event::ExamineEdge(e) => self.edge_target_examined.push(graph.target(e)),
+                    Event::TreeEdge(e) => self.tree_edge_target.push(graph.target(e)),
+                    Event::NonTreeEdge(e) => self.non_tree_edge_target.push(graph.target(e)),
+                    Event::FinishVertex(v) => self.finished.push(v),
                     _ => (),
                 }
             }
@@ -194,46 +494,47 @@ mod tests {
         let v8 = g.add_vertex("i");
         let v9 = g.add_vertex("j");
 
-        g.add_edge(v0, v1, ());
-        g.add_edge(v0, v4, ());
-        g.add_edge(v1, v5, ());
-        g.add_edge(v2, v0, ());
-        g.add_edge(v2, v4, ());
-        g.add_edge(v4, v1, ());
-        g.add_edge(v4, v3, ());
-        g.add_edge(v4, v6, ());
-        g.add_edge(v5, v4, ());
-        g.add_edge(v5, v4, ());
-        g.add_edge(v6, v7, ());
-        g.add_edge(v7, v3, ());
-        g.add_edge(v7, v9, ());
-        g.add_edge(v8, v7, ());
-        g.add_edge(v9, v8, ());
+        g.add_edge(v0, v1, ()).unwrap();
+        g.add_edge(v0, v4, ()).unwrap();
+        g.add_edge(v1, v5, ()).unwrap();
+        g.add_edge(v2, v0, ()).unwrap();
+        g.add_edge(v2, v4, ()).unwrap();
+        g.add_edge(v4, v1, ()).unwrap();
+        g.add_edge(v4, v3, ()).unwrap();
+        g.add_edge(v4, v6, ()).unwrap();
+        g.add_edge(v5, v4, ()).unwrap();
+        g.add_edge(v5, v4, ()).unwrap();
+        g.add_edge(v6, v7, ()).unwrap();
+        g.add_edge(v7, v3, ()).unwrap();
+        g.add_edge(v7, v9, ()).unwrap();
+        g.add_edge(v8, v7, ()).unwrap();
+        g.add_edge(v9, v8, ()).unwrap();
 
         let mut bfs = Bfs::with_visitor(MyVisitor::new());
 
         assert_eq!(
             bfs.run(&v0, |&v| v == v9, &g),
-            Some(vec![v0, v4, v6, v7, v9])
+            Ok(Some(vec![v0, v4, v6, v7, v9]))
         );
         assert_eq!(bfs.visitor_ref().init.len(), 10);
         assert_eq!(
             bfs.visitor_ref().discovered,
-            vec![v0, v1, v4, v5, v3, v6, v7, v9]
+            vec![v0, v4, v1, v6, v3, v5, v7, v9]
         );
         assert_eq!(
             bfs.visitor_ref().vertex_examined,
-            vec![v0, v1, v4, v5, v3, v6, v7, v9]
+            vec![v0, v4, v1, v6, v3, v5, v7, v9]
         );
+        // both parallel edges from v5 to v4 are examined
         assert_eq!(
             bfs.visitor_ref().edge_target_examined,
-            vec![v1, v4, v5, v1, v3, v6, v4, v7, v3, v9]
+            vec![v4, v1, v6, v3, v1, v5, v7, v4, v4, v9, v3]
         );
         assert_eq!(
             bfs.visitor_ref().tree_edge_target,
-            vec![v1, v4, v5, v3, v6, v7, v9]
+            vec![v4, v1, v6, v3, v5, v7, v9]
         );
-        assert_eq!(bfs.visitor_ref().non_tree_edge_target, vec![v1, v4, v3]);
-        assert_eq!(bfs.visitor_ref().finished, vec![v0, v1, v4, v5, v3, v6, v7]);
+        assert_eq!(bfs.visitor_ref().non_tree_edge_target, vec![v1, v4, v4, v3]);
+        assert_eq!(bfs.visitor_ref().finished, vec![v0, v4, v1, v6, v3, v5, v7]);
     }
 }
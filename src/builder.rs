@@ -0,0 +1,124 @@
+use std::collections::hash_map::Entry;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use fnv::FnvHashMap;
+
+use graph::{MutableGraph, VertexDescriptor};
+use incidence_list::IncidenceList;
+
+type Built<K, D, VP, EP> = (IncidenceList<D, VP, EP>, FnvHashMap<K, VertexDescriptor>);
+
+pub struct GraphBuilder<K, D, VP, EP> {
+    vertices: Vec<(K, VP)>,
+    edges: Vec<(K, K, EP)>,
+    phantom: PhantomData<D>,
+}
+
+impl<K, D, VP, EP> GraphBuilder<K, D, VP, EP>
+where
+    K: Eq + Hash,
+{
+    pub fn new() -> Self {
+        Self {
+            vertices: Vec::new(),
+            edges: Vec::new(),
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn vertex(mut self, key: K, property: VP) -> Self {
+        self.vertices.push((key, property));
+        self
+    }
+
+    pub fn edge(mut self, source: K, target: K, property: EP) -> Self {
+        self.edges.push((source, target, property));
+        self
+    }
+
+    pub fn build(self) -> Option<Built<K, D, VP, EP>> {
+        let mut graph = IncidenceList::with_order_size(self.vertices.len(), self.edges.len());
+        let mut keys = FnvHashMap::default();
+
+        for (key, property) in self.vertices {
+            match keys.entry(key) {
+                Entry::Vacant(entry) => {
+                    entry.insert(graph.add_vertex(property));
+                }
+                Entry::Occupied(_) => return None,
+            }
+        }
+
+        for (source, target, property) in self.edges {
+            match (keys.get(&source), keys.get(&target)) {
+                (Some(&s), Some(&t)) => {
+                    graph.add_edge(s, t, property).ok()?;
+                }
+                _ => return None,
+            }
+        }
+
+        Some((graph, keys))
+    }
+}
+
+impl<K, D, VP, EP> Default for GraphBuilder<K, D, VP, EP>
+where
+    K: Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GraphBuilder;
+
+    #[test]
+    fn build() {
+        use graph::{Directed, EdgeListGraph, Graph, IncidenceGraph, VertexListGraph};
+
+        let (g, vs) = GraphBuilder::<_, Directed, _, _>::new()
+            .vertex("a", 3)
+            .vertex("b", 5)
+            .vertex("c", 7)
+            .edge("a", "b", "x")
+            .edge("b", "c", "y")
+            .build()
+            .unwrap();
+
+        assert_eq!(g.order(), 3);
+        assert_eq!(g.size(), 2);
+
+        assert_eq!(g.vertex_property(vs["a"]), Some(&3));
+        assert_eq!(g.vertex_property(vs["b"]), Some(&5));
+        assert_eq!(g.vertex_property(vs["c"]), Some(&7));
+
+        let e = g.out_edges(vs["a"]).next().unwrap();
+        assert_eq!(g.target(e), vs["b"]);
+        assert_eq!(g.edge_property(e), Some(&"x"));
+        assert_eq!(g.out_degree(vs["c"]), 0);
+    }
+
+    #[test]
+    fn build_with_invalid_key() {
+        use graph::Directed;
+
+        assert!(
+            GraphBuilder::<_, Directed, _, ()>::new()
+                .vertex("a", 3)
+                .vertex("a", 5)
+                .build()
+                .is_none()
+        );
+        assert!(
+            GraphBuilder::<_, Directed, _, _>::new()
+                .vertex("a", 3)
+                .edge("a", "b", ())
+                .build()
+                .is_none()
+        );
+    }
+}
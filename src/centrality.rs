@@ -0,0 +1,269 @@
+use fnv::FnvHashMap;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use graph::{Directivity, IncidenceGraph, VertexDescriptor, VertexListGraph};
+use property_map::{vertex_index, PropertyMap};
+
+struct Links {
+    vertices: Vec<VertexDescriptor>,
+    // incoming[i] lists the sources of the edges into i, once per edge
+    incoming: Vec<Vec<usize>>,
+    outgoing: Vec<Vec<usize>>,
+}
+
+impl Links {
+    fn new<'a, G>(graph: &'a G) -> Self
+    where
+        G: IncidenceGraph<'a> + VertexListGraph<'a>,
+        G::Directivity: Directivity,
+    {
+        let vertices = graph.vertices().collect::<Vec<_>>();
+        let index = vertex_index(graph, &vertices);
+        let mut incoming = vec![Vec::new(); vertices.len()];
+        let mut outgoing = vec![Vec::new(); vertices.len()];
+        for (i, &v) in vertices.iter().enumerate() {
+            for e in graph.out_edges(v) {
+                if let Some(&j) = graph.opposite(e, v).and_then(|t| index.get(t)) {
+                    incoming[j].push(i);
+                    outgoing[i].push(j);
+                    if !G::Directivity::is_directed() && i != j {
+                        incoming[i].push(j);
+                        outgoing[j].push(i);
+                    }
+                }
+            }
+        }
+        Links {
+            vertices,
+            incoming,
+            outgoing,
+        }
+    }
+
+    fn to_map(&self, scores: Vec<f64>) -> FnvHashMap<VertexDescriptor, f64> {
+        self.vertices.iter().cloned().zip(scores).collect()
+    }
+}
+
+/// Computes one new score per vertex, in parallel with the `rayon` feature.
+#[cfg(feature = "rayon")]
+fn sweep<F>(n: usize, f: F) -> Vec<f64>
+where
+    F: Fn(usize) -> f64 + Sync + Send,
+{
+    (0..n).into_par_iter().map(f).collect()
+}
+
+#[cfg(not(feature = "rayon"))]
+fn sweep<F>(n: usize, f: F) -> Vec<f64>
+where
+    F: Fn(usize) -> f64,
+{
+    (0..n).map(f).collect()
+}
+
+fn distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y).abs()).sum()
+}
+
+fn normalize(scores: &mut [f64], norm: f64) {
+    if norm > 0.0 {
+        for s in scores {
+            *s /= norm;
+        }
+    }
+}
+
+/// Iterates PageRank until the scores move less than `tolerance` in total, or for at most
+/// `max_iterations` rounds. The rank of vertices without outgoing edges is spread evenly over
+/// all vertices. Scores sum to one.
+pub fn pagerank<'a, G>(
+    graph: &'a G,
+    damping: f64,
+    tolerance: f64,
+    max_iterations: usize,
+) -> FnvHashMap<VertexDescriptor, f64>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+{
+    let links = Links::new(graph);
+    let n = links.vertices.len();
+    let mut ranks = vec![1.0 / n as f64; n];
+    for _ in 0..max_iterations {
+        let dangling = (0..n)
+            .filter(|&i| links.outgoing[i].is_empty())
+            .map(|i| ranks[i])
+            .sum::<f64>();
+        let base = (1.0 - damping + damping * dangling) / n as f64;
+        let next = sweep(n, |i| {
+            base +
+                damping *
+                    links.incoming[i]
+                        .iter()
+                        .map(|&j| ranks[j] / links.outgoing[j].len() as f64)
+                        .sum::<f64>()
+        });
+        let change = distance(&ranks, &next);
+        ranks = next;
+        if change < tolerance {
+            break;
+        }
+    }
+    links.to_map(ranks)
+}
+
+/// Iterates `x = alpha * A^T x + beta`, which converges when `alpha` is below the reciprocal of
+/// the largest eigenvalue of the adjacency matrix. Scores have unit Euclidean norm.
+pub fn katz_centrality<'a, G>(
+    graph: &'a G,
+    alpha: f64,
+    beta: f64,
+    tolerance: f64,
+    max_iterations: usize,
+) -> FnvHashMap<VertexDescriptor, f64>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+{
+    let links = Links::new(graph);
+    let n = links.vertices.len();
+    let mut scores = vec![0.0; n];
+    for _ in 0..max_iterations {
+        let next = sweep(n, |i| {
+            alpha * links.incoming[i].iter().map(|&j| scores[j]).sum::<f64>() + beta
+        });
+        let change = distance(&scores, &next);
+        scores = next;
+        if change < tolerance * n as f64 {
+            break;
+        }
+    }
+    let norm = scores.iter().map(|s| s * s).sum::<f64>().sqrt();
+    normalize(&mut scores, norm);
+    links.to_map(scores)
+}
+
+/// Computes hub and authority scores, in that order, each summing to one. A good hub points to
+/// good authorities, and a good authority is pointed to by good hubs.
+pub fn hits<'a, G>(
+    graph: &'a G,
+    tolerance: f64,
+    max_iterations: usize,
+) -> (FnvHashMap<VertexDescriptor, f64>, FnvHashMap<VertexDescriptor, f64>)
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+{
+    let links = Links::new(graph);
+    let n = links.vertices.len();
+    let mut hubs = vec![1.0 / n as f64; n];
+    let mut authorities = vec![0.0; n];
+    for _ in 0..max_iterations {
+        authorities = sweep(n, |i| links.incoming[i].iter().map(|&j| hubs[j]).sum());
+        let norm = authorities.iter().sum();
+        normalize(&mut authorities, norm);
+        let mut next = sweep(n, |i| links.outgoing[i].iter().map(|&j| authorities[j]).sum());
+        let norm = next.iter().sum();
+        normalize(&mut next, norm);
+        let change = distance(&hubs, &next);
+        hubs = next;
+        if change < tolerance {
+            break;
+        }
+    }
+    (links.to_map(hubs), links.to_map(authorities))
+}
+
+#[cfg(test)]
+mod tests {
+    use graph::{Directed, MutableGraph, Undirected, VertexDescriptor};
+    use incidence_list::IncidenceList;
+    use super::{hits, katz_centrality, pagerank};
+
+    // v0 --> v1 <-- v2
+    //  ^     |
+    //  |     v
+    //  +---- v3     v4
+    fn directed() -> (IncidenceList<Directed, (), ()>, Vec<VertexDescriptor>) {
+        let mut g = IncidenceList::new();
+        let vs = (0..5).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t) in &[(0, 1), (2, 1), (1, 3), (3, 0)] {
+            g.add_edge(vs[s], vs[t], ()).unwrap();
+        }
+        (g, vs)
+    }
+
+    // v0 --- v1 --- v3    v4
+    //  |    /
+    //  |   /
+    //  v2
+    fn undirected() -> (IncidenceList<Undirected, (), ()>, Vec<VertexDescriptor>) {
+        let mut g = IncidenceList::new();
+        let vs = (0..5).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t) in &[(0, 1), (2, 1), (0, 2), (1, 3)] {
+            g.add_edge(vs[s], vs[t], ()).unwrap();
+        }
+        (g, vs)
+    }
+
+    #[test]
+    fn pagerank_scores() {
+        let (g, vs) = directed();
+        let ranks = pagerank(&g, 0.85, 1e-10, 100);
+        assert!((ranks.values().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(ranks[&vs[1]] > ranks[&vs[3]]);
+        assert!(ranks[&vs[3]] > ranks[&vs[0]]);
+        assert!(ranks[&vs[0]] > ranks[&vs[2]]);
+        assert!((ranks[&vs[2]] - ranks[&vs[4]]).abs() < 1e-12);
+
+        // every edge is followed both ways, so v0 and v2 rank alike
+        let (g, vs) = undirected();
+        let ranks = pagerank(&g, 0.85, 1e-10, 100);
+        assert!((ranks.values().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(ranks[&vs[1]] > ranks[&vs[0]]);
+        assert!((ranks[&vs[0]] - ranks[&vs[2]]).abs() < 1e-9);
+        assert!(ranks[&vs[0]] > ranks[&vs[4]]);
+
+        assert!(pagerank(&IncidenceList::<Directed, (), ()>::new(), 0.85, 1e-10, 100).is_empty());
+    }
+
+    #[test]
+    fn katz_scores() {
+        let (g, vs) = directed();
+        let katz = katz_centrality(&g, 0.1, 1.0, 1e-10, 1000);
+        assert!((katz.values().map(|s| s * s).sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(katz[&vs[1]] > katz[&vs[3]]);
+        assert!((katz[&vs[2]] - katz[&vs[4]]).abs() < 1e-12);
+
+        let (g, vs) = undirected();
+        let katz = katz_centrality(&g, 0.1, 1.0, 1e-10, 1000);
+        assert!(katz[&vs[1]] > katz[&vs[0]]);
+        assert!((katz[&vs[0]] - katz[&vs[2]]).abs() < 1e-9);
+        assert!(katz[&vs[0]] > katz[&vs[4]]);
+
+        let empty = IncidenceList::<Undirected, (), ()>::new();
+        assert!(katz_centrality(&empty, 0.1, 1.0, 1e-10, 1000).is_empty());
+    }
+
+    #[test]
+    fn hits_scores() {
+        let (g, vs) = directed();
+        let (hubs, authorities) = hits(&g, 1e-10, 100);
+        assert!(authorities[&vs[1]] > authorities[&vs[0]]);
+        assert_eq!(authorities[&vs[2]], 0.0);
+        assert!(hubs[&vs[0]] > hubs[&vs[1]]);
+        assert!((hubs[&vs[0]] - hubs[&vs[2]]).abs() < 1e-12);
+
+        // every edge points both ways, so hubs and authorities coincide
+        let (g, vs) = undirected();
+        let (hubs, authorities) = hits(&g, 1e-10, 100);
+        assert!(vs.iter().all(|v| (hubs[v] - authorities[v]).abs() < 1e-9));
+        assert!(hubs[&vs[1]] > hubs[&vs[0]]);
+        assert_eq!(hubs[&vs[4]], 0.0);
+
+        let (hubs, authorities) = hits(&IncidenceList::<Directed, (), ()>::new(), 1e-10, 100);
+        assert!(hubs.is_empty() && authorities.is_empty());
+    }
+}
@@ -0,0 +1,239 @@
+use std::collections::VecDeque;
+
+use coloring::symmetric_adjacency;
+use graph::{IncidenceGraph, VertexDescriptor, VertexListGraph};
+use property_map::vertex_index;
+
+fn lex_bfs_order(neighbors: &[Vec<usize>]) -> Vec<usize> {
+    let n = neighbors.len();
+    let mut labels = vec![Vec::new(); n];
+    let mut visited = vec![false; n];
+    let mut order = Vec::with_capacity(n);
+    for k in (0..n).rev() {
+        let i = (0..n)
+            .filter(|&i| !visited[i])
+            .max_by(|&a, &b| labels[a].cmp(&labels[b]).then(b.cmp(&a)))
+            .unwrap();
+        visited[i] = true;
+        order.push(i);
+        for &j in &neighbors[i] {
+            if !visited[j] {
+                labels[j].push(k);
+            }
+        }
+    }
+    order
+}
+
+/// Orders the vertices by lexicographic breadth-first search, ignoring edge direction: each
+/// next vertex is one whose already visited neighbors were visited earliest, compared as
+/// sequences. Ties go to the vertex the graph yields first.
+pub fn lex_bfs<'a, G>(graph: &'a G) -> Vec<VertexDescriptor>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+{
+    let (vertices, neighbors) = symmetric_adjacency(graph);
+    lex_bfs_order(&neighbors)
+        .into_iter()
+        .map(|i| vertices[i])
+        .collect()
+}
+
+// The neighbors of each vertex that come after it in `order`, sorted by position.
+fn later_neighbors(neighbors: &[Vec<usize>], order: &[usize]) -> Vec<Vec<usize>> {
+    let mut position = vec![0; order.len()];
+    for (k, &i) in order.iter().enumerate() {
+        position[i] = k;
+    }
+    neighbors
+        .iter()
+        .enumerate()
+        .map(|(i, ns)| {
+            let mut later = ns
+                .iter()
+                .cloned()
+                .filter(|&j| position[j] > position[i])
+                .collect::<Vec<_>>();
+            later.sort_by_key(|&j| position[j]);
+            later
+        })
+        .collect()
+}
+
+// A shortest path from `u` to `w` avoiding `v` and its other neighbors.
+fn chordless_path(neighbors: &[Vec<usize>], v: usize, u: usize, w: usize) -> Option<Vec<usize>> {
+    let mut parents = vec![None; neighbors.len()];
+    let mut blocked = vec![false; neighbors.len()];
+    blocked[v] = true;
+    for &x in &neighbors[v] {
+        blocked[x] = x != u && x != w;
+    }
+    blocked[u] = true;
+    let mut fringe = VecDeque::new();
+    fringe.push_back(u);
+    while let Some(x) = fringe.pop_front() {
+        if x == w {
+            let mut path = vec![w];
+            while let Some(p) = parents[*path.last().unwrap()] {
+                path.push(p);
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for &y in &neighbors[x] {
+            if !blocked[y] {
+                blocked[y] = true;
+                parents[y] = Some(x);
+                fringe.push_back(y);
+            }
+        }
+    }
+    None
+}
+
+/// Tests whether every cycle of four or more vertices has a chord, ignoring edge direction and
+/// self-loops. Returns a perfect elimination ordering if so, in which the neighbors each vertex
+/// has later on form a clique; this is the reverse of a Lex-BFS order. Otherwise returns the
+/// vertices of a chordless cycle in the order they are traversed.
+pub fn is_chordal<'a, G>(graph: &'a G) -> Result<Vec<VertexDescriptor>, Vec<VertexDescriptor>>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+{
+    let (vertices, neighbors) = symmetric_adjacency(graph);
+    let mut order = lex_bfs_order(&neighbors);
+    order.reverse();
+
+    let later = later_neighbors(&neighbors, &order);
+    for &v in &order {
+        // the first later neighbor must be adjacent to all the others
+        let u = match later[v].first() {
+            Some(&u) => u,
+            None => continue,
+        };
+        for &w in &later[v][1..] {
+            if neighbors[u].binary_search(&w).is_err() {
+                let mut cycle = vec![vertices[v]];
+                let path = chordless_path(&neighbors, v, u, w).unwrap();
+                cycle.extend(path.into_iter().map(|i| vertices[i]));
+                return Err(cycle);
+            }
+        }
+    }
+    Ok(order.into_iter().map(|i| vertices[i]).collect())
+}
+
+/// Lists the maximal cliques of a chordal graph, of which there are at most as many as
+/// vertices, from a perfect elimination ordering: each vertex together with its later
+/// neighbors is a clique, and it is maximal unless an earlier vertex has the vertex as its
+/// first later neighbor and exactly one later neighbor more. Returns `None` if the graph is
+/// not chordal.
+pub fn chordal_maximal_cliques<'a, G>(graph: &'a G) -> Option<Vec<Vec<VertexDescriptor>>>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+{
+    let order = is_chordal(graph).ok()?;
+    let (vertices, neighbors) = symmetric_adjacency(graph);
+    let index = vertex_index(graph, &vertices);
+    let order = order.into_iter().map(|v| index[v]).collect::<Vec<_>>();
+    let later = later_neighbors(&neighbors, &order);
+
+    let mut absorbed = vec![false; vertices.len()];
+    for &x in &order {
+        if let Some(&v) = later[x].first() {
+            if later[x].len() == later[v].len() + 1 {
+                absorbed[v] = true;
+            }
+        }
+    }
+    Some(
+        order
+            .into_iter()
+            .filter(|&v| !absorbed[v])
+            .map(|v| {
+                let mut clique = vec![vertices[v]];
+                clique.extend(later[v].iter().map(|&j| vertices[j]));
+                clique
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{chordal_maximal_cliques, is_chordal, lex_bfs};
+
+    #[test]
+    fn chordal_graphs() {
+        use graph::{AdjacencyMatrixGraph, Directed, MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        // v0 --- v1
+        //  | \    |
+        //  |  \   |
+        //  |   \  |
+        // v3 --- v2 --- v4    v5
+        //
+        // plus a self-loop on v4
+
+        let mut g = IncidenceList::<Undirected, (), ()>::new();
+        assert_eq!(lex_bfs(&g), vec![]);
+        assert_eq!(is_chordal(&g), Ok(vec![]));
+        assert_eq!(chordal_maximal_cliques(&g), Some(vec![]));
+
+        let vs = (0..6).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t) in &[(0, 1), (1, 2), (2, 3), (3, 0), (0, 2), (2, 4), (4, 4)] {
+            g.add_edge(vs[s], vs[t], ()).unwrap();
+        }
+        let expected = [0, 1, 2, 3, 4, 5];
+        assert_eq!(lex_bfs(&g), expected.iter().map(|&i| vs[i]).collect::<Vec<_>>());
+
+        let order = is_chordal(&g).unwrap();
+        assert_eq!(order.len(), 6);
+        for (k, &v) in order.iter().enumerate() {
+            let later = order[k + 1..]
+                .iter()
+                .cloned()
+                .filter(|&u| g.edge(v, u).is_some() || g.edge(u, v).is_some())
+                .collect::<Vec<_>>();
+            for &a in &later {
+                for &b in &later {
+                    assert!(a == b || g.edge(a, b).is_some() || g.edge(b, a).is_some());
+                }
+            }
+        }
+
+        let mut cliques = chordal_maximal_cliques(&g).unwrap();
+        for clique in &mut cliques {
+            clique.sort();
+        }
+        cliques.sort();
+        assert_eq!(
+            cliques,
+            vec![
+                vec![vs[0], vs[1], vs[2]],
+                vec![vs[0], vs[2], vs[3]],
+                vec![vs[2], vs[4]],
+                vec![vs[5]],
+            ]
+        );
+
+        // dropping the chord leaves the square chordless
+        g.remove_edge(g.edge(vs[0], vs[2]).unwrap());
+        let mut cycle = is_chordal(&g).unwrap_err();
+        assert_eq!(cycle.len(), 4);
+        cycle.sort();
+        assert_eq!(cycle, vs[..4].to_vec());
+        assert!(chordal_maximal_cliques(&g).is_none());
+
+        // edge directions are ignored, so the square is chordless whichever way its edges point
+        let mut d = IncidenceList::<Directed, (), ()>::new();
+        let ws = (0..4).map(|_| d.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t) in &[(0, 1), (2, 1), (2, 3), (0, 3)] {
+            d.add_edge(ws[s], ws[t], ()).unwrap();
+        }
+        assert_eq!(lex_bfs(&d), vec![ws[0], ws[1], ws[3], ws[2]]);
+        let mut cycle = is_chordal(&d).unwrap_err();
+        cycle.sort();
+        assert_eq!(cycle, ws);
+    }
+}
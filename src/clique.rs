@@ -0,0 +1,211 @@
+use std::time::{Duration, Instant};
+
+use coloring::{degeneracy_order, symmetric_adjacency};
+use graph::{IncidenceGraph, VertexDescriptor, VertexListGraph};
+
+fn intersect(a: &[usize], b: &[usize]) -> Vec<usize> {
+    a.iter().cloned().filter(|i| b.binary_search(i).is_ok()).collect()
+}
+
+struct BronKerbosch<'a, F>
+where
+    F: FnMut(&[usize]),
+{
+    neighbors: &'a [Vec<usize>],
+    report: F,
+    // only cliques larger than this are of interest when pruning
+    largest: Option<usize>,
+    deadline: Option<Instant>,
+}
+
+impl<'a, F> BronKerbosch<'a, F>
+where
+    F: FnMut(&[usize]),
+{
+    /// Returns false once the deadline has passed. The first clique is always reported so that
+    /// an exhausted budget still yields something.
+    fn expand(
+        &mut self,
+        clique: &mut Vec<usize>,
+        candidates: Vec<usize>,
+        excluded: Vec<usize>,
+    ) -> bool {
+        if self.largest != Some(0) && self.deadline.is_some_and(|d| Instant::now() >= d) {
+            return false;
+        }
+        if let Some(largest) = self.largest {
+            if clique.len() + candidates.len() <= largest {
+                return true;
+            }
+        }
+        if candidates.is_empty() {
+            if excluded.is_empty() {
+                (self.report)(clique);
+                self.largest = self.largest.map(|l| l.max(clique.len()));
+            }
+            return true;
+        }
+
+        let neighbors = self.neighbors;
+        let pivot = candidates
+            .iter()
+            .chain(excluded.iter())
+            .cloned()
+            .max_by_key(|&u| {
+                candidates.iter().filter(|v| neighbors[u].binary_search(v).is_ok()).count()
+            })
+            .unwrap();
+
+        let mut candidates = candidates;
+        let mut excluded = excluded;
+        let branches = candidates
+            .iter()
+            .cloned()
+            .filter(|v| neighbors[pivot].binary_search(v).is_err())
+            .collect::<Vec<_>>();
+        for v in branches {
+            clique.push(v);
+            let go_on = self.expand(
+                clique,
+                intersect(&candidates, &neighbors[v]),
+                intersect(&excluded, &neighbors[v]),
+            );
+            clique.pop();
+            if !go_on {
+                return false;
+            }
+            candidates.retain(|&u| u != v);
+            if let Err(k) = excluded.binary_search(&v) {
+                excluded.insert(k, v);
+            }
+        }
+        true
+    }
+
+    fn run(&mut self) {
+        let neighbors = self.neighbors;
+        let order = degeneracy_order(neighbors);
+        let mut position = vec![0; order.len()];
+        for (k, &i) in order.iter().enumerate() {
+            position[i] = k;
+        }
+
+        for &v in &order {
+            let (later, earlier): (Vec<_>, Vec<_>) =
+                neighbors[v].iter().partition(|&&u| position[u] > position[v]);
+            if !self.expand(&mut vec![v], later, earlier) {
+                return;
+            }
+        }
+    }
+}
+
+/// Enumerates every maximal clique, ignoring edge direction, with the Bron–Kerbosch algorithm
+/// using pivoting and a degeneracy ordering of the top level.
+pub fn maximal_cliques<'a, G>(graph: &'a G) -> Vec<Vec<VertexDescriptor>>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+{
+    let (vertices, neighbors) = symmetric_adjacency(graph);
+    let mut cliques = Vec::new();
+    BronKerbosch {
+        neighbors: &neighbors,
+        report: |clique: &[usize]| cliques.push(clique.iter().map(|&i| vertices[i]).collect()),
+        largest: None,
+        deadline: None,
+    }.run();
+    cliques
+}
+
+/// Returns a largest clique, ignoring edge direction. If `budget` runs out before the search
+/// finishes, the largest clique found so far is returned instead.
+pub fn maximum_clique<'a, G>(graph: &'a G, budget: Option<Duration>) -> Vec<VertexDescriptor>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+{
+    let (vertices, neighbors) = symmetric_adjacency(graph);
+    let mut best = Vec::new();
+    BronKerbosch {
+        neighbors: &neighbors,
+        report: |clique: &[usize]| best = clique.to_vec(),
+        largest: Some(0),
+        deadline: budget.map(|b| Instant::now() + b),
+    }.run();
+    best.into_iter().map(|i| vertices[i]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use graph::VertexDescriptor;
+    use super::{maximal_cliques, maximum_clique};
+
+    fn sorted(mut cliques: Vec<Vec<VertexDescriptor>>) -> Vec<Vec<VertexDescriptor>> {
+        for c in &mut cliques {
+            c.sort();
+        }
+        cliques.sort();
+        cliques
+    }
+
+    #[test]
+    fn cliques() {
+        use std::time::Duration;
+        use graph::{Directed, MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        let mut g = IncidenceList::<Undirected, _, _>::new();
+        assert!(maximal_cliques(&g).is_empty());
+        assert!(maximum_clique(&g, None).is_empty());
+
+        // v0 --- v1 --- v4
+        //  | \  / |
+        //  |  \/  |
+        //  |  /\  |
+        //  | /  \ |
+        // v2 --- v3     v5
+
+        let vs = (0..6).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t) in &[(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3), (1, 4)] {
+            g.add_edge(vs[s], vs[t], ()).unwrap();
+        }
+
+        assert_eq!(
+            sorted(maximal_cliques(&g)),
+            vec![
+                vec![vs[0], vs[1], vs[2], vs[3]],
+                vec![vs[1], vs[4]],
+                vec![vs[5]],
+            ]
+        );
+
+        let mut largest = maximum_clique(&g, None);
+        largest.sort();
+        assert_eq!(largest, vec![vs[0], vs[1], vs[2], vs[3]]);
+
+        assert!(!maximum_clique(&g, Some(Duration::from_secs(0))).is_empty());
+
+        // a self-loop does not make a vertex its own neighbour
+        g.add_edge(vs[5], vs[5], ()).unwrap();
+        assert!(sorted(maximal_cliques(&g)).contains(&vec![vs[5]]));
+
+        // v0 <-> v1 <-> v2 --> v3 --+
+        //                      ^    |
+        //                      +----+
+        //
+        // directions and antiparallel edges make no difference either
+
+        let mut d = IncidenceList::<Directed, _, _>::new();
+        let ws = (0..4).map(|_| d.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t) in &[(0, 1), (1, 0), (1, 2), (2, 1), (2, 3), (3, 3)] {
+            d.add_edge(ws[s], ws[t], ()).unwrap();
+        }
+        assert_eq!(
+            sorted(maximal_cliques(&d)),
+            vec![vec![ws[0], ws[1]], vec![ws[1], ws[2]], vec![ws[2], ws[3]]]
+        );
+        d.add_edge(ws[2], ws[0], ()).unwrap();
+        let mut largest = maximum_clique(&d, None);
+        largest.sort();
+        assert_eq!(largest, vec![ws[0], ws[1], ws[2]]);
+    }
+}
@@ -0,0 +1,215 @@
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+
+use fnv::FnvHashMap;
+
+use graph::{IncidenceGraph, VertexDescriptor, VertexListGraph};
+use property_map::{vertex_index, PropertyMap};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ColoringOrder {
+    /// The order in which the graph yields its vertices.
+    Natural,
+    /// Vertices with more neighbors are colored first.
+    LargestFirst,
+    /// The reverse of repeatedly removing a vertex of minimum remaining degree.
+    SmallestLast,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Coloring {
+    colors: FnvHashMap<VertexDescriptor, usize>,
+    count: usize,
+}
+
+impl Coloring {
+    pub fn color(&self, v: VertexDescriptor) -> Option<usize> {
+        self.colors.get(&v).cloned()
+    }
+
+    pub fn colors(&self) -> &FnvHashMap<VertexDescriptor, usize> {
+        &self.colors
+    }
+
+    pub fn color_count(&self) -> usize {
+        self.count
+    }
+}
+
+/// Collects the vertices together with their neighbors regardless of edge direction, leaving
+/// out self-loops and duplicated neighbors from parallel edges.
+pub(crate) fn symmetric_adjacency<'a, G>(graph: &'a G) -> (Vec<VertexDescriptor>, Vec<Vec<usize>>)
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+{
+    let vertices = graph.vertices().collect::<Vec<_>>();
+    let index = vertex_index(graph, &vertices);
+
+    let mut neighbors = vec![Vec::new(); vertices.len()];
+    for (i, &v) in vertices.iter().enumerate() {
+        for e in graph.out_edges(v) {
+            if let Some(&j) = graph.opposite(e, v).and_then(|t| index.get(t)) {
+                if i != j {
+                    neighbors[i].push(j);
+                    neighbors[j].push(i);
+                }
+            }
+        }
+    }
+    for ns in &mut neighbors {
+        ns.sort();
+        ns.dedup();
+    }
+    (vertices, neighbors)
+}
+
+/// Repeatedly removes a vertex of minimum remaining degree and returns the removal order.
+pub(crate) fn degeneracy_order(neighbors: &[Vec<usize>]) -> Vec<usize> {
+    let mut degree = neighbors.iter().map(|ns| ns.len()).collect::<Vec<_>>();
+    let mut remaining = degree
+        .iter()
+        .enumerate()
+        .map(|(i, &d)| (d, i))
+        .collect::<BTreeSet<_>>();
+    let mut order = Vec::with_capacity(neighbors.len());
+    while let Some(&(d, i)) = remaining.iter().next() {
+        remaining.remove(&(d, i));
+        for &j in &neighbors[i] {
+            if remaining.remove(&(degree[j], j)) {
+                degree[j] -= 1;
+                remaining.insert((degree[j], j));
+            }
+        }
+        order.push(i);
+    }
+    order
+}
+
+fn smallest_free_color(i: usize, neighbors: &[Vec<usize>], colors: &[Option<usize>]) -> usize {
+    let mut taken = neighbors[i]
+        .iter()
+        .filter_map(|&j| colors[j])
+        .collect::<Vec<_>>();
+    taken.sort();
+    taken.dedup();
+    taken
+        .iter()
+        .enumerate()
+        .find(|&(c, &t)| c != t)
+        .map_or(taken.len(), |(c, _)| c)
+}
+
+fn to_coloring(vertices: &[VertexDescriptor], colors: &[Option<usize>]) -> Coloring {
+    let colors = vertices
+        .iter()
+        .cloned()
+        .zip(colors.iter().map(|c| c.unwrap()))
+        .collect::<FnvHashMap<_, _>>();
+    let count = colors.values().max().map_or(0, |&c| c + 1);
+    Coloring { colors, count }
+}
+
+/// Colors the vertices one by one in the given order, each with the smallest color not used by
+/// any of its neighbors. Edge direction is ignored.
+pub fn greedy_coloring<'a, G>(graph: &'a G, order: ColoringOrder) -> Coloring
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+{
+    let (vertices, neighbors) = symmetric_adjacency(graph);
+    let order = match order {
+        ColoringOrder::Natural => (0..vertices.len()).collect(),
+        ColoringOrder::LargestFirst => {
+            let mut order = (0..vertices.len()).collect::<Vec<_>>();
+            order.sort_by_key(|&i| Reverse(neighbors[i].len()));
+            order
+        }
+        ColoringOrder::SmallestLast => {
+            let mut order = degeneracy_order(&neighbors);
+            order.reverse();
+            order
+        }
+    };
+
+    let mut colors = vec![None; vertices.len()];
+    for i in order {
+        colors[i] = Some(smallest_free_color(i, &neighbors, &colors));
+    }
+    to_coloring(&vertices, &colors)
+}
+
+/// Colors next the vertex whose neighbors already use the most distinct colors, breaking ties
+/// by the number of uncolored neighbors. Edge direction is ignored.
+pub fn dsatur_coloring<'a, G>(graph: &'a G) -> Coloring
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+{
+    let (vertices, neighbors) = symmetric_adjacency(graph);
+    let mut colors = vec![None; vertices.len()];
+    let mut saturation = vec![Vec::<usize>::new(); vertices.len()];
+    for _ in 0..vertices.len() {
+        let i = (0..vertices.len())
+            .filter(|&i| colors[i].is_none())
+            .max_by_key(|&i| {
+                let uncolored = neighbors[i].iter().filter(|&&j| colors[j].is_none()).count();
+                (saturation[i].len(), uncolored, Reverse(i))
+            })
+            .unwrap();
+        let c = smallest_free_color(i, &neighbors, &colors);
+        colors[i] = Some(c);
+        for &j in &neighbors[i] {
+            if let Err(k) = saturation[j].binary_search(&c) {
+                saturation[j].insert(k, c);
+            }
+        }
+    }
+    to_coloring(&vertices, &colors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dsatur_coloring, greedy_coloring, Coloring, ColoringOrder};
+
+    #[test]
+    fn coloring() {
+        use graph::{EdgeListGraph, IncidenceGraph, MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        // crown graph on 8 vertices: a_i --- b_j for i != j
+        //
+        // a0   a1   a2   a3
+        //  \\ // \\ // \\ //
+        // b0   b1   b2   b3
+
+        let mut g = IncidenceList::<Undirected, _, _>::new();
+        let mut vs = Vec::new();
+        for i in 0..4 {
+            vs.push((g.add_vertex(()), g.add_vertex(())));
+            for j in 0..i {
+                g.add_edge(vs[i].0, vs[j].1, ()).unwrap();
+                g.add_edge(vs[j].0, vs[i].1, ()).unwrap();
+            }
+        }
+
+        let proper = |c: &Coloring| {
+            g.edges().all(|e| {
+                let (s, t) = g.edge_endpoints(e).unwrap();
+                c.color(s) != c.color(t)
+            })
+        };
+
+        let natural = greedy_coloring(&g, ColoringOrder::Natural);
+        assert!(proper(&natural));
+        assert_eq!(natural.color_count(), 4);
+
+        for &order in &[ColoringOrder::LargestFirst, ColoringOrder::SmallestLast] {
+            let c = greedy_coloring(&g, order);
+            assert!(proper(&c));
+            assert!(c.color_count() <= 4);
+        }
+
+        let dsatur = dsatur_coloring(&g);
+        assert!(proper(&dsatur));
+        assert_eq!(dsatur.color_count(), 2);
+        assert_eq!(dsatur.colors().len(), 8);
+    }
+}
@@ -0,0 +1,153 @@
+use std::marker::PhantomData;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use error::GraphError;
+use graph::{EdgeDescriptor, Generation, MutableGraph, VertexDescriptor};
+use incidence_list::IncidenceList;
+
+const DEFAULT_SHARDS: usize = 16;
+
+// (key, source key, target key, property)
+type EdgeShard<EP> = Mutex<Vec<(usize, usize, usize, EP)>>;
+
+/// Collects vertices and edges from many threads at once, for loading large graphs in
+/// parallel. Elements go to one of several independently locked shards, so threads rarely
+/// contend. The descriptors handed out are those of the `IncidenceList` built by `freeze`.
+pub struct ConcurrentGraph<D, VP, EP> {
+    vertex_count: AtomicUsize,
+    edge_count: AtomicUsize,
+    vertices: Vec<Mutex<Vec<(usize, VP)>>>,
+    edges: Vec<EdgeShard<EP>>,
+    phantom: PhantomData<D>,
+}
+
+impl<D, VP, EP> ConcurrentGraph<D, VP, EP> {
+    pub fn new() -> Self {
+        Self::with_shards(DEFAULT_SHARDS)
+    }
+
+    pub fn with_shards(shards: usize) -> Self {
+        let shards = shards.max(1);
+        Self {
+            vertex_count: AtomicUsize::new(0),
+            edge_count: AtomicUsize::new(0),
+            vertices: (0..shards).map(|_| Mutex::new(Vec::new())).collect(),
+            edges: (0..shards).map(|_| Mutex::new(Vec::new())).collect(),
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn add_vertex(&self, property: VP) -> VertexDescriptor {
+        let k = self.vertex_count.fetch_add(1, Ordering::Relaxed);
+        let shard = &self.vertices[k % self.vertices.len()];
+        shard.lock().unwrap().push((k, property));
+        VertexDescriptor::with_generation(k, Generation::default())
+    }
+
+    /// Fails with `GraphError::VertexNotFound` if an endpoint was not returned by `add_vertex`
+    /// of this graph.
+    pub fn add_edge(
+        &self,
+        source: VertexDescriptor,
+        target: VertexDescriptor,
+        property: EP,
+    ) -> Result<EdgeDescriptor, GraphError> {
+        for &v in &[source, target] {
+            if usize::from(v) >= self.vertex_count.load(Ordering::Relaxed) {
+                return Err(GraphError::VertexNotFound(v));
+            }
+        }
+        let k = self.edge_count.fetch_add(1, Ordering::Relaxed);
+        let shard = &self.edges[k % self.edges.len()];
+        shard
+            .lock()
+            .unwrap()
+            .push((k, usize::from(source), usize::from(target), property));
+        Ok(EdgeDescriptor::with_generation(k, Generation::default()))
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.vertex_count.load(Ordering::Relaxed)
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edge_count.load(Ordering::Relaxed)
+    }
+
+    /// Builds an `IncidenceList` in which every descriptor returned so far refers to the
+    /// element it was returned for.
+    pub fn freeze(self) -> IncidenceList<D, VP, EP> {
+        let mut vertices = self.vertices
+            .into_iter()
+            .flat_map(|shard| shard.into_inner().unwrap())
+            .collect::<Vec<_>>();
+        vertices.sort_by_key(|&(k, _)| k);
+        let mut edges = self.edges
+            .into_iter()
+            .flat_map(|shard| shard.into_inner().unwrap())
+            .collect::<Vec<_>>();
+        edges.sort_by_key(|&(k, _, _, _)| k);
+
+        let mut graph = IncidenceList::new();
+        let descriptors = vertices
+            .into_iter()
+            .map(|(_, property)| graph.add_vertex(property))
+            .collect::<Vec<_>>();
+        for (_, source, target, property) in edges {
+            graph
+                .add_edge(descriptors[source], descriptors[target], property)
+                .unwrap();
+        }
+        graph
+    }
+}
+
+impl<D, VP, EP> Default for ConcurrentGraph<D, VP, EP> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConcurrentGraph;
+
+    #[test]
+    fn concurrent_construction() {
+        use std::sync::Arc;
+        use std::thread;
+        use graph::{Directed, Graph, IncidenceGraph, VertexListGraph};
+
+        let g = Arc::new(ConcurrentGraph::<Directed, _, _>::with_shards(4));
+        let root = g.add_vertex(0);
+        let handles = (1..9)
+            .map(|t| {
+                let g = Arc::clone(&g);
+                thread::spawn(move || {
+                    (0..100)
+                        .map(|i| {
+                            let v = g.add_vertex(t * 1000 + i);
+                            (v, g.add_edge(root, v, t).unwrap())
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+        let added = handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(g.vertex_count(), 801);
+        assert_eq!(g.edge_count(), 800);
+
+        let g = Arc::try_unwrap(g).ok().unwrap().freeze();
+        assert_eq!(g.vertices().count(), 801);
+        assert_eq!(g.vertex_property(root), Some(&0));
+        for (v, e) in added {
+            let t = g.vertex_property(v).unwrap() / 1000;
+            assert_eq!(g.edge_endpoints(e), Some((root, v)));
+            assert_eq!(g.edge_property(e), Some(&t));
+        }
+    }
+}
@@ -0,0 +1,279 @@
+use std::collections::VecDeque;
+
+use graph::{Directivity, IncidenceGraph, VertexListGraph};
+use property_map::{vertex_index, PropertyMap};
+
+// A flow network with integer capacities, each arc stored next to its reverse so that `arc ^ 1`
+// is the residual counterpart.
+pub(crate) struct Network {
+    heads: Vec<usize>,
+    capacities: Vec<usize>,
+    leaving: Vec<Vec<usize>>,
+}
+
+impl Network {
+    pub(crate) fn new(nodes: usize) -> Self {
+        Network {
+            heads: Vec::new(),
+            capacities: Vec::new(),
+            leaving: vec![Vec::new(); nodes],
+        }
+    }
+
+    pub(crate) fn add_arc(&mut self, from: usize, to: usize, capacity: usize) {
+        self.leaving[from].push(self.heads.len());
+        self.heads.push(to);
+        self.capacities.push(capacity);
+        self.leaving[to].push(self.heads.len());
+        self.heads.push(from);
+        self.capacities.push(0);
+    }
+
+    // Augments along shortest paths as Edmonds and Karp do, stopping once the flow reaches
+    // `limit`, and returns the flow. Consumes the capacities.
+    pub(crate) fn max_flow(&mut self, source: usize, sink: usize, limit: usize) -> usize {
+        let mut flow = 0;
+        while flow < limit {
+            let mut via = vec![None; self.leaving.len()];
+            let mut queue = VecDeque::new();
+            queue.push_back(source);
+            while let Some(u) = queue.pop_front() {
+                for &a in &self.leaving[u] {
+                    let w = self.heads[a];
+                    if self.capacities[a] > 0 && w != source && via[w].is_none() {
+                        via[w] = Some(a);
+                        queue.push_back(w);
+                    }
+                }
+            }
+            if via[sink].is_none() {
+                break;
+            }
+            let mut amount = limit - flow;
+            let mut w = sink;
+            while let Some(a) = via[w] {
+                amount = amount.min(self.capacities[a]);
+                w = self.heads[a ^ 1];
+            }
+            let mut w = sink;
+            while let Some(a) = via[w] {
+                self.capacities[a] -= amount;
+                self.capacities[a ^ 1] += amount;
+                w = self.heads[a ^ 1];
+            }
+            flow += amount;
+        }
+        flow
+    }
+
+    // The nodes still reachable from `source` through arcs with capacity left, which after
+    // `max_flow` are the source side of a minimum cut.
+    pub(crate) fn source_side(&self, source: usize) -> Vec<bool> {
+        let mut reached = vec![false; self.leaving.len()];
+        reached[source] = true;
+        let mut stack = vec![source];
+        while let Some(u) = stack.pop() {
+            for &a in &self.leaving[u] {
+                let w = self.heads[a];
+                if self.capacities[a] > 0 && !reached[w] {
+                    reached[w] = true;
+                    stack.push(w);
+                }
+            }
+        }
+        reached
+    }
+}
+
+// The arcs between distinct vertices, both ways round for an undirected graph.
+fn arcs<'a, G>(graph: &'a G) -> (usize, Vec<(usize, usize)>)
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+{
+    let vertices = graph.vertices().collect::<Vec<_>>();
+    let index = vertex_index(graph, &vertices);
+    let mut arcs = Vec::new();
+    for (i, &v) in vertices.iter().enumerate() {
+        for e in graph.out_edges(v) {
+            if let Some(&j) = graph.opposite(e, v).and_then(|t| index.get(t)) {
+                if i != j {
+                    arcs.push((i, j));
+                    if !G::Directivity::is_directed() {
+                        arcs.push((j, i));
+                    }
+                }
+            }
+        }
+    }
+    (vertices.len(), arcs)
+}
+
+/// The least number of edges whose removal disconnects the graph, or leaves a directed graph
+/// not strongly connected: the smallest maximum flow from one fixed vertex to any other and
+/// back, with every edge of unit capacity. Zero for a graph of fewer than two vertices.
+pub fn edge_connectivity<'a, G>(graph: &'a G) -> usize
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+{
+    let (n, arcs) = arcs(graph);
+    if n < 2 {
+        return 0;
+    }
+    let mut best = arcs.len();
+    for t in 1..n {
+        for &(s, t) in &[(0, t), (t, 0)] {
+            let mut network = Network::new(n);
+            for &(a, b) in &arcs {
+                network.add_arc(a, b, 1);
+            }
+            best = best.min(network.max_flow(s, t, best));
+        }
+    }
+    best
+}
+
+/// The least number of vertices whose removal disconnects the graph, or leaves a directed
+/// graph not strongly connected, and n - 1 for a complete graph on n vertices. Each vertex is
+/// split into an entry and an exit joined by an arc of unit capacity, and the answer is the
+/// smallest maximum flow between non-adjacent vertices; following Even, only sources among
+/// the first κ + 1 vertices need to be tried.
+pub fn vertex_connectivity<'a, G>(graph: &'a G) -> usize
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+{
+    let (n, arcs) = arcs(graph);
+    if n < 2 {
+        return 0;
+    }
+    let mut adjacent = vec![vec![false; n]; n];
+    for &(a, b) in &arcs {
+        adjacent[a][b] = true;
+    }
+
+    let mut best = n - 1;
+    let mut s = 0;
+    while s < n && s <= best {
+        for t in (0..n).filter(|&t| t != s) {
+            for &(from, to) in &[(s, t), (t, s)] {
+                if adjacent[from][to] {
+                    continue;
+                }
+                // vertex v enters at 2v and leaves from 2v + 1
+                let mut network = Network::new(2 * n);
+                for v in 0..n {
+                    network.add_arc(2 * v, 2 * v + 1, if v == from || v == to { n } else { 1 });
+                }
+                for &(a, b) in &arcs {
+                    network.add_arc(2 * a + 1, 2 * b, n);
+                }
+                best = best.min(network.max_flow(2 * from + 1, 2 * to, best));
+            }
+        }
+        s += 1;
+    }
+    best
+}
+
+/// Whether at least `k` vertices must be removed to disconnect the graph, and it has more
+/// than `k` vertices.
+pub fn is_k_connected<'a, G>(graph: &'a G, k: usize) -> bool
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+{
+    k == 0 || vertex_connectivity(graph) >= k
+}
+
+/// Whether at least `k` edges must be removed to disconnect the graph.
+pub fn is_k_edge_connected<'a, G>(graph: &'a G, k: usize) -> bool
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+{
+    k == 0 || edge_connectivity(graph) >= k
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{edge_connectivity, is_k_connected, is_k_edge_connected, vertex_connectivity};
+
+    #[test]
+    fn connectivity() {
+        use graph::{Directed, MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        let empty = IncidenceList::<Undirected, (), ()>::new();
+        assert_eq!((vertex_connectivity(&empty), edge_connectivity(&empty)), (0, 0));
+        assert!(is_k_connected(&empty, 0) && !is_k_connected(&empty, 1));
+        assert!(is_k_edge_connected(&empty, 0));
+
+        // two triangles sharing the cut vertex v2
+        //
+        // v0         v3
+        //  | \     / |
+        //  |  v2 --  |
+        //  | /     \ |
+        // v1         v4
+
+        let mut g = IncidenceList::<Undirected, (), ()>::new();
+        let vs = (0..5).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t) in &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 2)] {
+            g.add_edge(vs[s], vs[t], ()).unwrap();
+        }
+        assert_eq!(vertex_connectivity(&g), 1);
+        assert_eq!(edge_connectivity(&g), 2);
+
+        // joining v1 and v4 leaves no cut vertex
+        g.add_edge(vs[1], vs[4], ()).unwrap();
+        assert_eq!(vertex_connectivity(&g), 2);
+        assert_eq!(edge_connectivity(&g), 2);
+        assert!(is_k_connected(&g, 2));
+        assert!(!is_k_connected(&g, 3));
+        assert!(is_k_edge_connected(&g, 2));
+
+        // a self-loop cuts nothing, an isolated vertex is cut off already
+        g.add_edge(vs[0], vs[0], ()).unwrap();
+        assert_eq!((vertex_connectivity(&g), edge_connectivity(&g)), (2, 2));
+        g.add_vertex(());
+        assert_eq!((vertex_connectivity(&g), edge_connectivity(&g)), (0, 0));
+        assert!(!is_k_connected(&g, 1) && !is_k_edge_connected(&g, 1));
+
+        let mut k4 = IncidenceList::<Undirected, (), ()>::new();
+        let ks = (0..4).map(|_| k4.add_vertex(())).collect::<Vec<_>>();
+        for i in 0..4 {
+            for j in 0..i {
+                k4.add_edge(ks[i], ks[j], ()).unwrap();
+            }
+        }
+        assert_eq!(vertex_connectivity(&k4), 3);
+        assert_eq!(edge_connectivity(&k4), 3);
+        assert!(!is_k_connected(&k4, 4));
+
+        // a directed cycle is strongly connected, but only just; undirected it takes two cuts
+        let mut d = IncidenceList::<Directed, (), ()>::new();
+        let mut u = IncidenceList::<Undirected, (), ()>::new();
+        let ds = (0..4).map(|_| d.add_vertex(())).collect::<Vec<_>>();
+        let us = (0..4).map(|_| u.add_vertex(())).collect::<Vec<_>>();
+        for i in 0..4 {
+            d.add_edge(ds[i], ds[(i + 1) % 4], ()).unwrap();
+            u.add_edge(us[i], us[(i + 1) % 4], ()).unwrap();
+        }
+        assert_eq!(vertex_connectivity(&d), 1);
+        assert_eq!(edge_connectivity(&d), 1);
+        assert_eq!((vertex_connectivity(&u), edge_connectivity(&u)), (2, 2));
+        d.add_vertex(());
+        assert_eq!(edge_connectivity(&d), 0);
+
+        // broken open, the cycle is a path that cannot be walked back
+        let mut d = IncidenceList::<Directed, (), ()>::new();
+        let ds = (0..3).map(|_| d.add_vertex(())).collect::<Vec<_>>();
+        d.add_edge(ds[0], ds[1], ()).unwrap();
+        d.add_edge(ds[1], ds[2], ()).unwrap();
+        assert_eq!((vertex_connectivity(&d), edge_connectivity(&d)), (0, 0));
+        u.remove_vertex(us[3]).unwrap();
+        assert_eq!((vertex_connectivity(&u), edge_connectivity(&u)), (1, 1));
+    }
+}
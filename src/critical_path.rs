@@ -0,0 +1,157 @@
+use std::fmt::Debug;
+use std::ops::Sub;
+
+use fnv::FnvHashMap;
+use num_traits::Zero;
+
+use error::GraphError;
+use graph::{EdgeDescriptor, IncidenceGraph, VertexDescriptor, VertexListGraph};
+use topological_sort::topological_sort;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CriticalPath<C> {
+    path: Vec<VertexDescriptor>,
+    length: C,
+    earliest: FnvHashMap<VertexDescriptor, C>,
+    latest: FnvHashMap<VertexDescriptor, C>,
+}
+
+impl<C> CriticalPath<C>
+where
+    C: Copy + Sub<Output = C>,
+{
+    pub fn path(&self) -> &[VertexDescriptor] {
+        &self.path
+    }
+
+    pub fn length(&self) -> C {
+        self.length
+    }
+
+    /// The earliest time `v` can start.
+    pub fn earliest(&self, v: VertexDescriptor) -> Option<C> {
+        self.earliest.get(&v).cloned()
+    }
+
+    /// The latest time `v` can start without delaying the whole.
+    pub fn latest(&self, v: VertexDescriptor) -> Option<C> {
+        self.latest.get(&v).cloned()
+    }
+
+    pub fn slack(&self, v: VertexDescriptor) -> Option<C> {
+        Some(self.latest(v)? - self.earliest(v)?)
+    }
+}
+
+/// Schedules a DAG of tasks taking `vertex_duration` each, where an edge from u to v means v
+/// starts at least `edge_duration` after u finishes. The critical path is a longest chain of
+/// tasks; its vertices have no slack. Fails with `GraphError::Cyclic` on a cyclic graph.
+pub fn critical_path<'a, G, C, F, H>(
+    graph: &'a G,
+    vertex_duration: F,
+    edge_duration: H,
+) -> Result<CriticalPath<C>, GraphError>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    C: Copy + Debug + Ord + Zero + Sub<Output = C>,
+    F: Fn(&VertexDescriptor, &G) -> C,
+    H: Fn(&EdgeDescriptor, &G) -> C,
+{
+    let order = topological_sort(graph)?;
+    let duration = order
+        .iter()
+        .map(|v| (*v, vertex_duration(v, graph)))
+        .collect::<FnvHashMap<_, _>>();
+
+    let mut earliest = order
+        .iter()
+        .map(|&v| (v, C::zero()))
+        .collect::<FnvHashMap<_, _>>();
+    let mut parents = FnvHashMap::default();
+    for &u in &order {
+        let finish = earliest[&u] + duration[&u];
+        for e in graph.out_edges(u) {
+            if let Some(v) = graph.opposite(e, u) {
+                let start = finish + edge_duration(&e, graph);
+                if start > earliest[&v] {
+                    earliest.insert(v, start);
+                    parents.insert(v, u);
+                }
+            }
+        }
+    }
+
+    let last = order.iter().cloned().max_by_key(|v| earliest[v] + duration[v]);
+    let length = last.map_or(C::zero(), |v| earliest[&v] + duration[&v]);
+
+    let mut latest = FnvHashMap::default();
+    for &u in order.iter().rev() {
+        let mut finish = length;
+        for e in graph.out_edges(u) {
+            if let Some(v) = graph.opposite(e, u) {
+                let deadline = latest[&v] - edge_duration(&e, graph);
+                if deadline < finish {
+                    finish = deadline;
+                }
+            }
+        }
+        latest.insert(u, finish - duration[&u]);
+    }
+
+    let mut path = Vec::new();
+    let mut current = last;
+    while let Some(v) = current {
+        path.push(v);
+        current = parents.get(&v).cloned();
+    }
+    path.reverse();
+
+    Ok(CriticalPath {
+        path,
+        length,
+        earliest,
+        latest,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::critical_path;
+
+    #[test]
+    fn critical_path_and_slack() {
+        use graph::{Directed, Graph, MutableGraph};
+        use incidence_list::IncidenceList;
+
+        //      +--> b(2) --+
+        //      |           v
+        // a(3) +--> c(4) --> d(1)
+        //                     ^
+        // e(1) ---(2)---------+
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+        let a = g.add_vertex(3);
+        let b = g.add_vertex(2);
+        let c = g.add_vertex(4);
+        let d = g.add_vertex(1);
+        let e = g.add_vertex(1);
+        g.add_edge(a, b, 0).unwrap();
+        g.add_edge(a, c, 0).unwrap();
+        g.add_edge(b, d, 0).unwrap();
+        g.add_edge(c, d, 0).unwrap();
+        g.add_edge(e, d, 2).unwrap();
+
+        let cp = critical_path(
+            &g,
+            |v, g| *g.vertex_property(*v).unwrap(),
+            |e, g| *g.edge_property(*e).unwrap(),
+        ).unwrap();
+        assert_eq!(cp.path(), &[a, c, d]);
+        assert_eq!(cp.length(), 8);
+        assert_eq!(cp.earliest(d), Some(7));
+        assert_eq!(cp.latest(b), Some(5));
+        assert_eq!(cp.slack(b), Some(2));
+        assert_eq!(cp.slack(c), Some(0));
+        assert_eq!(cp.slack(e), Some(4));
+    }
+}
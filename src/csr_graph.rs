@@ -0,0 +1,461 @@
+use std::convert::TryInto;
+use std::io::{self, Write};
+use std::iter::Map;
+use std::marker::PhantomData;
+use std::ops::Range;
+
+#[cfg(feature = "memmap2")]
+use std::fs::File;
+#[cfg(feature = "memmap2")]
+use std::path::Path;
+
+#[cfg(feature = "memmap2")]
+use memmap2::Mmap;
+
+use graph::{AdjacencyGraph, AdjacencyMatrixGraph, BidirectionalGraph, Direction, Directivity,
+            EdgeDescriptor, EdgeListGraph, Generation, Graph, IncidenceGraph, VertexDescriptor,
+            VertexListGraph};
+use property_map::vertex_index;
+
+const MAGIC: &[u8; 8] = b"GRPHCSR1";
+const DIRECTED: u64 = 1;
+// magic, flags, order, size
+const HEADER_WORDS: usize = 4;
+
+fn vertex_descriptor(k: usize) -> VertexDescriptor {
+    VertexDescriptor::with_generation(k, Generation::default())
+}
+
+fn edge_descriptor(k: usize) -> EdgeDescriptor {
+    EdgeDescriptor::with_generation(k, Generation::default())
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// A read-only graph in compressed sparse row form, used straight from its binary encoding so
+/// that a large static graph is ready as soon as its bytes are, for instance once a file is
+/// memory-mapped. The bytes can be anything that derefs to a slice: a `Vec<u8>`, a borrowed
+/// slice or, with the `memmap2` feature, a mapped file opened by `open`.
+///
+/// The encoding is a sequence of little-endian 64-bit words: a header, the offsets of the
+/// out-edges and in-edges of every vertex, the source and target of every edge, and the edges
+/// entering each vertex. Edges are numbered in order of their source, so the out-edges of a
+/// vertex are a range of edge numbers. Vertices and edges are numbered from zero, which makes
+/// their properties easy to keep alongside in a `VecPropertyMap` or a file of their own; the
+/// graph itself has none. `from_bytes` checks the header and the length only, so corrupt
+/// contents may cause a panic later on.
+pub struct CsrGraph<D, B> {
+    bytes: B,
+    order: usize,
+    size: usize,
+    phantom: PhantomData<D>,
+}
+
+impl<D, B> CsrGraph<D, B>
+where
+    D: Directivity,
+    B: AsRef<[u8]>,
+{
+    /// Fails with an error of kind `io::ErrorKind::InvalidData` if the bytes are not the
+    /// encoding of a graph of this directivity.
+    pub fn from_bytes(bytes: B) -> io::Result<Self> {
+        let (order, size, flags) = {
+            let b = bytes.as_ref();
+            if b.len() < HEADER_WORDS * 8 || &b[..8] != MAGIC {
+                return Err(invalid("not a CSR graph"));
+            }
+            (word(b, 2), word(b, 3), word(b, 1) as u64)
+        };
+        if (flags & DIRECTED != 0) != D::is_directed() {
+            return Err(invalid("directivity does not match"));
+        }
+        let words = order
+            .checked_add(1)
+            .and_then(|n| n.checked_mul(2))
+            .and_then(|n| size.checked_mul(3).and_then(|m| n.checked_add(m)))
+            .and_then(|n| n.checked_add(HEADER_WORDS));
+        if words.and_then(|w| w.checked_mul(8)) != Some(bytes.as_ref().len()) {
+            return Err(invalid("length does not match the header"));
+        }
+        Ok(CsrGraph {
+            bytes,
+            order,
+            size,
+            phantom: PhantomData,
+        })
+    }
+}
+
+#[cfg(feature = "memmap2")]
+impl<D> CsrGraph<D, Mmap>
+where
+    D: Directivity,
+{
+    /// Maps a file written by `write_csr` into memory.
+    ///
+    /// # Safety
+    ///
+    /// The graph reads the file as a plain slice of bytes, which nothing stops another handle
+    /// or process from changing or truncating while it is mapped. The caller must make sure
+    /// that the file is left alone until the graph is dropped, for example by keeping it in a
+    /// directory only this program writes to.
+    pub unsafe fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: the file is opened read-only, so the mapping is never written through here,
+        // and the caller of `open` guarantees that nothing else modifies or truncates the file
+        // while the map, owned by the returned graph, is alive. A file that is merely corrupt
+        // is caught by the checks of `from_bytes` or, failing those, by bounds-checked reads.
+        let map = unsafe { Mmap::map(&file)? };
+        Self::from_bytes(map)
+    }
+}
+
+impl<D, B> CsrGraph<D, B> {
+    pub fn into_inner(self) -> B {
+        self.bytes
+    }
+}
+
+impl<D, B> CsrGraph<D, B>
+where
+    B: AsRef<[u8]>,
+{
+    fn word(&self, i: usize) -> usize {
+        word(self.bytes.as_ref(), i)
+    }
+
+    fn out_offsets(&self) -> usize {
+        HEADER_WORDS
+    }
+
+    fn in_offsets(&self) -> usize {
+        HEADER_WORDS + self.order + 1
+    }
+
+    fn sources(&self) -> usize {
+        HEADER_WORDS + 2 * (self.order + 1)
+    }
+
+    fn targets(&self) -> usize {
+        self.sources() + self.size
+    }
+
+    fn in_edge_list(&self) -> usize {
+        self.targets() + self.size
+    }
+
+    fn incidences<'a>(&'a self, d: VertexDescriptor, direction: Direction) -> CsrIncidences<'a> {
+        let k = usize::from(d);
+        if k >= self.order {
+            return CsrIncidences {
+                bytes: self.bytes.as_ref(),
+                list: None,
+                range: 0..0,
+            };
+        }
+        let offsets = match direction {
+            Direction::Outgoing => self.out_offsets(),
+            Direction::Incoming => self.in_offsets(),
+        };
+        CsrIncidences {
+            bytes: self.bytes.as_ref(),
+            list: match direction {
+                Direction::Outgoing => None,
+                Direction::Incoming => Some(self.in_edge_list()),
+            },
+            range: self.word(offsets + k)..self.word(offsets + k + 1),
+        }
+    }
+}
+
+fn word(bytes: &[u8], i: usize) -> usize {
+    u64::from_le_bytes(bytes[i * 8..i * 8 + 8].try_into().unwrap()) as usize
+}
+
+/// Writes `graph` in the encoding read by `CsrGraph::from_bytes`. Vertices are numbered in the
+/// order `vertices` lists them, and the descriptors of the vertices and edges are returned in
+/// the order of their numbers, so that their properties can be written out to match.
+pub fn write_csr<'a, G, W>(
+    graph: &'a G,
+    writer: &mut W,
+) -> io::Result<(Vec<VertexDescriptor>, Vec<EdgeDescriptor>)>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+    W: Write,
+{
+    let vertices = graph.vertices().collect::<Vec<_>>();
+    let index = vertex_index(graph, &vertices);
+
+    let mut edges = Vec::new();
+    let mut ends = Vec::new();
+    let mut out_offsets = vec![0];
+    for &v in &vertices {
+        for e in graph.out_edges(v) {
+            edges.push(e);
+            ends.push((index[v], index[graph.target(e)]));
+        }
+        out_offsets.push(edges.len());
+    }
+    let mut incoming = vec![Vec::new(); vertices.len()];
+    for (k, &(_, t)) in ends.iter().enumerate() {
+        incoming[t].push(k);
+    }
+    let mut in_offsets = vec![0];
+    for list in &incoming {
+        in_offsets.push(in_offsets.last().unwrap() + list.len());
+    }
+
+    let flags = if G::Directivity::is_directed() { DIRECTED } else { 0 };
+    let mut words = vec![flags as usize, vertices.len(), edges.len()];
+    words.extend(out_offsets);
+    words.extend(in_offsets);
+    words.extend(ends.iter().map(|&(s, _)| s));
+    words.extend(ends.iter().map(|&(_, t)| t));
+    words.extend(incoming.into_iter().flatten());
+
+    writer.write_all(MAGIC)?;
+    for w in words {
+        writer.write_all(&(w as u64).to_le_bytes())?;
+    }
+    Ok((vertices, edges))
+}
+
+/// The edges leaving or entering a vertex of a `CsrGraph`.
+pub struct CsrIncidences<'a> {
+    bytes: &'a [u8],
+    // where the edge numbers are listed, or `None` if the range holds the numbers themselves
+    list: Option<usize>,
+    range: Range<usize>,
+}
+
+impl<'a> Iterator for CsrIncidences<'a> {
+    type Item = EdgeDescriptor;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let i = self.range.next()?;
+        Some(edge_descriptor(match self.list {
+            Some(list) => word(self.bytes, list + i),
+            None => i,
+        }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl<D, B> Graph for CsrGraph<D, B>
+where
+    B: AsRef<[u8]>,
+{
+    type Directivity = D;
+    type VertexProperty = ();
+    type EdgeProperty = ();
+
+    fn vertex_property(&self, d: VertexDescriptor) -> Option<&Self::VertexProperty> {
+        if usize::from(d) < self.order {
+            Some(&())
+        } else {
+            None
+        }
+    }
+
+    fn edge_property(&self, d: EdgeDescriptor) -> Option<&Self::EdgeProperty> {
+        if usize::from(d) < self.size {
+            Some(&())
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, D, B> IncidenceGraph<'a> for CsrGraph<D, B>
+where
+    B: AsRef<[u8]> + 'a,
+{
+    type Incidences = CsrIncidences<'a>;
+
+    fn out_degree(&self, d: VertexDescriptor) -> usize {
+        self.incidences(d, Direction::Outgoing).range.len()
+    }
+
+    fn out_edges(&'a self, d: VertexDescriptor) -> Self::Incidences {
+        self.incidences(d, Direction::Outgoing)
+    }
+
+    fn source(&self, d: EdgeDescriptor) -> VertexDescriptor {
+        self.edge_endpoints(d).unwrap().0
+    }
+
+    fn target(&self, d: EdgeDescriptor) -> VertexDescriptor {
+        self.edge_endpoints(d).unwrap().1
+    }
+
+    fn edge_endpoints(&self, d: EdgeDescriptor) -> Option<(VertexDescriptor, VertexDescriptor)> {
+        let k = usize::from(d);
+        if k >= self.size {
+            return None;
+        }
+        let s = self.word(self.sources() + k);
+        let t = self.word(self.targets() + k);
+        Some((vertex_descriptor(s), vertex_descriptor(t)))
+    }
+}
+
+impl<'a, D, B> BidirectionalGraph<'a> for CsrGraph<D, B>
+where
+    B: AsRef<[u8]> + 'a,
+    D: 'a,
+{
+    type Neighbors = Box<dyn Iterator<Item = VertexDescriptor> + 'a>;
+
+    fn degree(&self, d: VertexDescriptor) -> usize {
+        self.out_degree(d) + self.in_degree(d)
+    }
+
+    fn in_degree(&self, d: VertexDescriptor) -> usize {
+        self.incidences(d, Direction::Incoming).range.len()
+    }
+
+    fn in_edges(&'a self, d: VertexDescriptor) -> Self::Incidences {
+        self.incidences(d, Direction::Incoming)
+    }
+
+    fn neighbors_directed(&'a self, d: VertexDescriptor, direction: Direction) -> Self::Neighbors {
+        Box::new(self.incidences(d, direction).map(move |e| match direction {
+            Direction::Outgoing => self.target(e),
+            Direction::Incoming => self.source(e),
+        }))
+    }
+}
+
+impl<'a, D, B> AdjacencyGraph<'a> for CsrGraph<D, B>
+where
+    B: AsRef<[u8]> + 'a,
+    D: Directivity + 'a,
+{
+    type Adjacencies = Box<dyn Iterator<Item = VertexDescriptor> + 'a>;
+
+    fn adjacent_vertices(&'a self, d: VertexDescriptor) -> Self::Adjacencies {
+        let successors = self.neighbors_directed(d, Direction::Outgoing);
+        if D::is_directed() {
+            successors
+        } else {
+            Box::new(successors.chain(self.neighbors_directed(d, Direction::Incoming)))
+        }
+    }
+}
+
+impl<'a, D, B> VertexListGraph<'a> for CsrGraph<D, B>
+where
+    B: AsRef<[u8]>,
+{
+    type Vertices = Map<Range<usize>, fn(usize) -> VertexDescriptor>;
+
+    fn order(&self) -> usize {
+        self.order
+    }
+
+    fn vertex_bound(&self) -> usize {
+        self.order
+    }
+
+    fn vertices(&'a self) -> Self::Vertices {
+        (0..self.order).map(vertex_descriptor as fn(usize) -> VertexDescriptor)
+    }
+}
+
+impl<'a, D, B> EdgeListGraph<'a> for CsrGraph<D, B>
+where
+    B: AsRef<[u8]>,
+{
+    type Edges = Map<Range<usize>, fn(usize) -> EdgeDescriptor>;
+
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn edge_bound(&self) -> usize {
+        self.size
+    }
+
+    fn edges(&'a self) -> Self::Edges {
+        (0..self.size).map(edge_descriptor as fn(usize) -> EdgeDescriptor)
+    }
+}
+
+impl<D, B> AdjacencyMatrixGraph for CsrGraph<D, B>
+where
+    B: AsRef<[u8]>,
+    D: Directivity,
+{
+    fn edge(&self, source: VertexDescriptor, target: VertexDescriptor) -> Option<EdgeDescriptor> {
+        self.incidences(source, Direction::Outgoing)
+            .find(|&e| self.target(e) == target)
+            .or_else(|| if D::is_directed() {
+                None
+            } else {
+                self.incidences(target, Direction::Outgoing)
+                    .find(|&e| self.target(e) == source)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{write_csr, CsrGraph};
+
+    #[test]
+    fn csr_graph() {
+        use std::io;
+        use graph::{AdjacencyMatrixGraph, BidirectionalGraph, Directed, EdgeListGraph,
+                    IncidenceGraph, MutableGraph, Undirected, VertexListGraph};
+        use incidence_list::IncidenceList;
+
+        // v0 ---> v1 ---> v2
+        //  ^       |
+        //  +-------+      v3
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+        let vs = (0..4).map(|i| g.add_vertex(i)).collect::<Vec<_>>();
+        g.add_edge(vs[0], vs[1], "a").unwrap();
+        g.add_edge(vs[1], vs[2], "b").unwrap();
+        g.add_edge(vs[1], vs[0], "c").unwrap();
+
+        let mut bytes = Vec::new();
+        let (vertices, edges) = write_csr(&g, &mut bytes).unwrap();
+        assert_eq!(vertices, vs);
+        let csr = CsrGraph::<Directed, _>::from_bytes(&bytes[..]).unwrap();
+        assert_eq!((csr.order(), csr.size()), (4, 3));
+        let w = csr.vertices().collect::<Vec<_>>();
+        for e in csr.edges() {
+            let (s, t) = g.edge_endpoints(edges[usize::from(e)]).unwrap();
+            let (s, t) = (vs.iter().position(|&v| v == s), vs.iter().position(|&v| v == t));
+            assert_eq!(csr.edge_endpoints(e), Some((w[s.unwrap()], w[t.unwrap()])));
+        }
+        assert_eq!((csr.out_degree(w[1]), csr.in_degree(w[0]), csr.degree(w[3])), (2, 1, 0));
+        assert_eq!(csr.in_edges(w[2]).map(|e| csr.source(e)).collect::<Vec<_>>(), vec![w[1]]);
+        assert!(csr.edge(w[1], w[0]).is_some() && csr.edge(w[2], w[1]).is_none());
+
+        let error = CsrGraph::<Undirected, _>::from_bytes(&bytes[..]).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        let error = CsrGraph::<Directed, _>::from_bytes(&bytes[..40]).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        #[cfg(feature = "memmap2")]
+        {
+            use std::{env, fs, process};
+
+            let path = env::temp_dir().join(format!("graph-csr-{}.bin", process::id()));
+            fs::write(&path, &bytes).unwrap();
+            // SAFETY: the file was written just above under a name of this process and is only
+            // removed once the mapped graph is no longer used
+            let mapped = unsafe { CsrGraph::<Directed, _>::open(&path).unwrap() };
+            assert_eq!(mapped.out_edges(w[1]).count(), 2);
+            fs::remove_file(&path).unwrap();
+        }
+    }
+}
@@ -0,0 +1,373 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::fmt::Debug;
+
+use num_traits::Zero;
+
+use graph::{BidirectionalGraph, Directivity, EdgeDescriptor, IncidenceGraph, VertexDescriptor,
+            VertexListGraph};
+use property_map::{vertex_index, Color, PropertyMap, VecPropertyMap};
+
+struct Frame {
+    vertex: VertexDescriptor,
+    edges: Vec<EdgeDescriptor>,
+    next: usize,
+    // the tree edge this vertex was discovered through
+    parent: Option<EdgeDescriptor>,
+}
+
+fn incident_edges<'a, G>(graph: &'a G, v: VertexDescriptor) -> Vec<EdgeDescriptor>
+where
+    G: BidirectionalGraph<'a>,
+    G::Directivity: Directivity,
+{
+    let mut edges = graph.out_edges(v).collect::<Vec<_>>();
+    if !G::Directivity::is_directed() {
+        edges.extend(graph.in_edges(v).filter(|&e| graph.opposite(e, v) != Some(v)));
+    }
+    edges
+}
+
+/// Searches depth-first with white, gray and black vertices and stops at the first back edge,
+/// that is an edge reaching a gray vertex other than through the tree edge just taken. Returns
+/// the vertices of the cycle it closes and the edges between them, the k-th edge leading from
+/// the k-th vertex to the next and the last one back to the first.
+pub fn find_cycle<'a, G>(graph: &'a G) -> Option<(Vec<VertexDescriptor>, Vec<EdgeDescriptor>)>
+where
+    G: BidirectionalGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+{
+    let roots = graph.vertices().collect::<Vec<_>>();
+    let index = vertex_index(graph, &roots);
+    let mut colors = VecPropertyMap::<usize, Color>::with_bound(roots.len());
+    for &root in &roots {
+        if colors.contains_key(index[root]) {
+            continue;
+        }
+        colors.insert(index[root], Color::Gray);
+        let mut stack = vec![
+            Frame {
+                vertex: root,
+                edges: incident_edges(graph, root),
+                next: 0,
+                parent: None,
+            },
+        ];
+        while !stack.is_empty() {
+            let top = stack.len() - 1;
+            let (vertex, edge) = {
+                let frame = &mut stack[top];
+                if frame.next == frame.edges.len() {
+                    colors.insert(index[frame.vertex], Color::Black);
+                    stack.pop();
+                    continue;
+                }
+                frame.next += 1;
+                (frame.vertex, frame.edges[frame.next - 1])
+            };
+            if stack[top].parent == Some(edge) {
+                continue;
+            }
+            let target = match graph.opposite(edge, vertex) {
+                Some(target) => target,
+                None => continue,
+            };
+            match colors.get(index[target]).cloned() {
+                None | Some(Color::White) => {
+                    colors.insert(index[target], Color::Gray);
+                    stack.push(Frame {
+                        vertex: target,
+                        edges: incident_edges(graph, target),
+                        next: 0,
+                        parent: Some(edge),
+                    });
+                }
+                Some(Color::Gray) => {
+                    // the gray vertices are exactly those on the stack
+                    let from = stack.iter().position(|f| f.vertex == target).unwrap();
+                    let vertices = stack[from..].iter().map(|f| f.vertex).collect();
+                    let mut edges = stack[from + 1..]
+                        .iter()
+                        .map(|f| f.parent.unwrap())
+                        .collect::<Vec<_>>();
+                    edges.push(edge);
+                    return Some((vertices, edges));
+                }
+                _ => {}
+            }
+        }
+    }
+    None
+}
+
+struct Undirected {
+    edges: Vec<(EdgeDescriptor, usize, usize)>,
+    // incident[i] lists (neighbor, edge index) once per incident edge, twice for a self-loop
+    incident: Vec<Vec<(usize, usize)>>,
+}
+
+impl Undirected {
+    fn new<'a, G>(graph: &'a G) -> Self
+    where
+        G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    {
+        let vertices = graph.vertices().collect::<Vec<_>>();
+        let index = vertex_index(graph, &vertices);
+        let mut edges = Vec::new();
+        let mut incident = vec![Vec::new(); vertices.len()];
+        for (i, &v) in vertices.iter().enumerate() {
+            for e in graph.out_edges(v) {
+                if let Some(&j) = graph.opposite(e, v).and_then(|t| index.get(t)) {
+                    incident[i].push((j, edges.len()));
+                    incident[j].push((i, edges.len()));
+                    edges.push((e, i, j));
+                }
+            }
+        }
+        Undirected { edges, incident }
+    }
+}
+
+/// Returns the number of edges in a shortest cycle, ignoring edge direction, or `None` for a
+/// forest. A self-loop is a cycle of length one and two parallel edges one of length two.
+pub fn girth<'a, G>(graph: &'a G) -> Option<usize>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+{
+    let g = Undirected::new(graph);
+    if g.edges.iter().any(|&(_, i, j)| i == j) {
+        return Some(1);
+    }
+
+    let mut shortest = None;
+    for root in 0..g.incident.len() {
+        let mut depth = vec![None; g.incident.len()];
+        let mut via = vec![None; g.incident.len()];
+        depth[root] = Some(0);
+        let mut fringe = VecDeque::new();
+        fringe.push_back(root);
+        while let Some(u) = fringe.pop_front() {
+            let du = depth[u].unwrap();
+            if shortest.is_some_and(|s| 2 * du + 1 >= s) {
+                break;
+            }
+            for &(w, k) in &g.incident[u] {
+                if via[u] == Some(k) {
+                    continue;
+                }
+                match depth[w] {
+                    None => {
+                        depth[w] = Some(du + 1);
+                        via[w] = Some(k);
+                        fringe.push_back(w);
+                    }
+                    Some(dw) => {
+                        let length = du + dw + 1;
+                        if shortest.is_none_or(|s| length < s) {
+                            shortest = Some(length);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    shortest
+}
+
+/// Finds a set of cycles of minimum total cost from which every cycle of the graph, ignoring
+/// edge direction, is a sum modulo two, using the candidate cycles of Horton: a shortest path
+/// tree from each vertex closed by each edge, tried cheapest first and kept when independent
+/// of those kept before. Costs must not be negative. Each cycle is given as its set of edges.
+pub fn minimum_cycle_basis<'a, G, C, F>(graph: &'a G, edge_cost: F) -> Vec<Vec<EdgeDescriptor>>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    C: Copy + Debug + Ord + Zero,
+    F: Fn(&EdgeDescriptor, &G) -> C,
+{
+    let g = Undirected::new(graph);
+    let n = g.incident.len();
+    let m = g.edges.len();
+    let costs = g.edges.iter().map(|&(e, _, _)| edge_cost(&e, graph)).collect::<Vec<_>>();
+    let words = m.div_ceil(64);
+
+    let mut candidates = Vec::new();
+    for root in 0..n {
+        let mut distance = vec![None; n];
+        let mut via: Vec<Option<usize>> = vec![None; n];
+        let mut fringe = BinaryHeap::new();
+        distance[root] = Some(C::zero());
+        fringe.push(Reverse((C::zero(), root)));
+        while let Some(Reverse((d, u))) = fringe.pop() {
+            if distance[u].is_some_and(|best| d > best) {
+                continue;
+            }
+            for &(w, k) in &g.incident[u] {
+                let next = d + costs[k];
+                if distance[w].is_none_or(|best| next < best) {
+                    distance[w] = Some(next);
+                    via[w] = Some(k);
+                    fringe.push(Reverse((next, w)));
+                }
+            }
+        }
+
+        // the tree path from the root to each vertex, as edges and vertices
+        let path = |mut v: usize| {
+            let mut edges = Vec::new();
+            let mut vertices = vec![v];
+            while let Some(k) = via[v] {
+                let (_, a, b) = g.edges[k];
+                v = if a == v { b } else { a };
+                edges.push(k);
+                vertices.push(v);
+            }
+            (edges, vertices)
+        };
+        for (k, &(_, a, b)) in g.edges.iter().enumerate() {
+            if distance[a].is_none() || via[a] == Some(k) || via[b] == Some(k) {
+                continue;
+            }
+            let (mut edges, to_a) = path(a);
+            let (to_b_edges, to_b) = path(b);
+            // the two paths may only meet at the root
+            if a != b && to_a.iter().filter(|v| to_b.contains(v)).count() > 1 {
+                continue;
+            }
+            edges.extend(to_b_edges);
+            edges.push(k);
+            let cost = edges.iter().fold(C::zero(), |c, &k| c + costs[k]);
+            candidates.push((cost, edges));
+        }
+    }
+    candidates.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.len().cmp(&b.1.len())));
+
+    // Gaussian elimination over GF(2), each row kept reduced by its lowest set bit
+    let mut rows: Vec<(usize, Vec<u64>)> = Vec::new();
+    let mut basis = Vec::new();
+    for (_, edges) in candidates {
+        let mut bits = vec![0u64; words];
+        for &k in &edges {
+            bits[k / 64] ^= 1 << (k % 64);
+        }
+        for &(pivot, ref row) in &rows {
+            if bits[pivot / 64] & (1 << (pivot % 64)) != 0 {
+                for (b, r) in bits.iter_mut().zip(row) {
+                    *b ^= *r;
+                }
+            }
+        }
+        if let Some(w) = bits.iter().position(|&b| b != 0) {
+            let pivot = w * 64 + bits[w].trailing_zeros() as usize;
+            rows.push((pivot, bits));
+            basis.push(edges.into_iter().map(|k| g.edges[k].0).collect());
+        }
+    }
+    basis
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_cycle, girth, minimum_cycle_basis};
+
+    #[test]
+    fn find_cycle_in_directed_graph() {
+        use graph::{Directed, MutableGraph};
+        use incidence_list::IncidenceList;
+
+        // v0 --> v1 --> v2 --> v3
+        //        ^             |
+        //        +-------------+
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+        let vs = (0..4).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        let e01 = g.add_edge(vs[0], vs[1], ()).unwrap();
+        let e12 = g.add_edge(vs[1], vs[2], ()).unwrap();
+        let e23 = g.add_edge(vs[2], vs[3], ()).unwrap();
+        let e31 = g.add_edge(vs[3], vs[1], ()).unwrap();
+
+        assert_eq!(
+            find_cycle(&g),
+            Some((vec![vs[1], vs[2], vs[3]], vec![e12, e23, e31]))
+        );
+
+        g.remove_edge(e31);
+        assert_eq!(find_cycle(&g), None);
+        let e00 = g.add_edge(vs[0], vs[0], ()).unwrap();
+        assert_eq!(find_cycle(&g), Some((vec![vs[0]], vec![e00])));
+        g.remove_edge(e00);
+        g.remove_edge(e01);
+        assert_eq!(find_cycle(&g), None);
+    }
+
+    #[test]
+    fn find_cycle_in_undirected_graph() {
+        use graph::{MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        // v0 --- v1 --- v2
+        //         \     /
+        //          \   /
+        //           v3
+
+        let mut g = IncidenceList::<Undirected, _, _>::new();
+        let vs = (0..4).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        g.add_edge(vs[0], vs[1], ()).unwrap();
+        let e12 = g.add_edge(vs[1], vs[2], ()).unwrap();
+        g.add_edge(vs[2], vs[3], ()).unwrap();
+        g.add_edge(vs[3], vs[1], ()).unwrap();
+
+        let (vertices, edges) = find_cycle(&g).unwrap();
+        assert_eq!(vertices.len(), 3);
+        assert_eq!(edges.len(), 3);
+        assert!(!vertices.contains(&vs[0]));
+
+        // a tree has no cycle, but two parallel edges form one
+        g.remove_edge(e12);
+        assert_eq!(find_cycle(&g), None);
+        let e01 = g.add_edge(vs[1], vs[0], ()).unwrap();
+        let (vertices, edges) = find_cycle(&g).unwrap();
+        assert_eq!(vertices.len(), 2);
+        assert!(edges.contains(&e01));
+    }
+
+    #[test]
+    fn girth_and_cycle_basis() {
+        use graph::{Graph, MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        // v0 --(1)-- v1 --(1)-- v2
+        //  |          |          |
+        // (1)        (1)        (1)
+        //  |          |          |
+        // v3 --(1)-- v4 --(1)-- v5
+        //  \                    /
+        //   +-------(1)--------+
+
+        let mut g = IncidenceList::<Undirected, _, _>::new();
+        let vs = (0..6).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t) in &[(0, 1), (1, 2), (0, 3), (1, 4), (2, 5), (3, 4), (4, 5), (3, 5)] {
+            g.add_edge(vs[s], vs[t], 1).unwrap();
+        }
+        let cost = |e: &_, g: &IncidenceList<Undirected, (), u32>| *g.edge_property(*e).unwrap();
+
+        assert_eq!(girth(&g), Some(3));
+        let basis = minimum_cycle_basis(&g, cost);
+        let mut lengths = basis.iter().map(|c| c.len()).collect::<Vec<_>>();
+        lengths.sort();
+        assert_eq!(lengths, vec![3, 4, 4]);
+
+        let e = g.add_edge(vs[5], vs[2], 1).unwrap();
+        assert_eq!(girth(&g), Some(2));
+        assert!(minimum_cycle_basis(&g, cost).iter().any(|c| c.len() == 2 && c.contains(&e)));
+        g.add_edge(vs[0], vs[0], 1).unwrap();
+        assert_eq!(girth(&g), Some(1));
+        assert_eq!(minimum_cycle_basis(&g, cost).len(), 5);
+
+        let mut tree = IncidenceList::<Undirected, (), u32>::new();
+        let a = tree.add_vertex(());
+        let b = tree.add_vertex(());
+        tree.add_edge(a, b, 1).unwrap();
+        assert_eq!(girth(&tree), None);
+        assert!(minimum_cycle_basis(&tree, cost).is_empty());
+    }
+}
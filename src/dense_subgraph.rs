@@ -0,0 +1,136 @@
+use coloring::{degeneracy_order, symmetric_adjacency};
+use connectivity::Network;
+use graph::{IncidenceGraph, VertexDescriptor, VertexListGraph};
+
+/// Finds a set of vertices inducing as many edges per vertex as possible, with the algorithm
+/// of Goldberg: a minimum cut in a network built for a guess g separates a subgraph denser
+/// than g if there is one, so the density is found by binary search. Densities of different
+/// subgraphs differ by at least 1 / (n (n - 1)), which bounds the search. Edge direction,
+/// self-loops and parallel edges are ignored. Returns no vertices for a graph without edges.
+pub fn densest_subgraph<'a, G>(graph: &'a G) -> (Vec<VertexDescriptor>, f64)
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+{
+    let (vertices, neighbors) = symmetric_adjacency(graph);
+    let n = vertices.len();
+    let m = neighbors.iter().map(|ns| ns.len()).sum::<usize>() / 2;
+    if m == 0 {
+        return (Vec::new(), 0.0);
+    }
+
+    // a guess of k / scale is checked with every capacity multiplied by scale
+    let scale = n * (n - 1);
+    let (source, sink) = (n, n + 1);
+    let denser = |k: usize| {
+        let mut network = Network::new(n + 2);
+        for (i, ns) in neighbors.iter().enumerate() {
+            network.add_arc(source, i, m * scale);
+            network.add_arc(i, sink, m * scale + 2 * k - ns.len() * scale);
+            for &j in ns {
+                network.add_arc(i, j, scale);
+            }
+        }
+        network.max_flow(source, sink, usize::MAX);
+        let side = network.source_side(source);
+        (0..n).filter(|&i| side[i]).collect::<Vec<_>>()
+    };
+
+    // some subgraph is denser than low / scale, and none denser than high / scale
+    let (mut low, mut high) = (0, m * scale);
+    let mut best = denser(low);
+    while high - low > 1 {
+        let mid = low + (high - low) / 2;
+        let set = denser(mid);
+        if set.is_empty() {
+            high = mid;
+        } else {
+            low = mid;
+            best = set;
+        }
+    }
+    induced(&vertices, &neighbors, best)
+}
+
+fn induced(
+    vertices: &[VertexDescriptor],
+    neighbors: &[Vec<usize>],
+    set: Vec<usize>,
+) -> (Vec<VertexDescriptor>, f64) {
+    let mut inside = vec![false; vertices.len()];
+    for &i in &set {
+        inside[i] = true;
+    }
+    let edges = set
+        .iter()
+        .map(|&i| neighbors[i].iter().filter(|&&j| inside[j]).count())
+        .sum::<usize>() / 2;
+    let density = edges as f64 / set.len() as f64;
+    (set.into_iter().map(|i| vertices[i]).collect(), density)
+}
+
+/// Approximates the densest subgraph within a factor of two, as Charikar showed, by peeling:
+/// vertices of fewest remaining neighbors are removed one at a time, and the densest of the
+/// subgraphs passed through is returned. Takes O(m log n) time against the many maximum flows
+/// of `densest_subgraph`. Edge direction, self-loops and parallel edges are ignored.
+pub fn approximate_densest_subgraph<'a, G>(graph: &'a G) -> (Vec<VertexDescriptor>, f64)
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+{
+    let (vertices, neighbors) = symmetric_adjacency(graph);
+    let mut order = degeneracy_order(&neighbors);
+    if neighbors.iter().all(|ns| ns.is_empty()) {
+        return (Vec::new(), 0.0);
+    }
+
+    // add the vertices back in reverse, counting the edges each brings along
+    order.reverse();
+    let mut added = vec![false; vertices.len()];
+    let mut edges = 0;
+    let mut best = (0.0, 0);
+    for (k, &i) in order.iter().enumerate() {
+        added[i] = true;
+        edges += neighbors[i].iter().filter(|&&j| added[j]).count();
+        let density = edges as f64 / (k + 1) as f64;
+        if density > best.0 {
+            best = (density, k + 1);
+        }
+    }
+    order.truncate(best.1);
+    induced(&vertices, &neighbors, order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{approximate_densest_subgraph, densest_subgraph};
+
+    #[test]
+    fn densest_subgraphs() {
+        use graph::{MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        // a clique on v0 - v3 with a path v3 - v4 - v5 - v6 hanging off it, and a star around
+        // v7 of which none is worth taking
+
+        let mut g = IncidenceList::<Undirected, (), ()>::new();
+        let vs = (0..12).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for i in 0..4 {
+            for j in 0..i {
+                g.add_edge(vs[i], vs[j], ()).unwrap();
+            }
+        }
+        for &(s, t) in &[(3, 4), (4, 5), (5, 6), (7, 8), (7, 9), (7, 10), (7, 11)] {
+            g.add_edge(vs[s], vs[t], ()).unwrap();
+        }
+
+        let (mut set, density) = densest_subgraph(&g);
+        set.sort();
+        assert_eq!(set, vs[..4].to_vec());
+        assert_eq!(density, 1.5);
+
+        let (_, approximate) = approximate_densest_subgraph(&g);
+        assert!(approximate * 2.0 >= density && approximate <= density);
+
+        let empty = IncidenceList::<Undirected, (), ()>::new();
+        assert_eq!(densest_subgraph(&empty), (Vec::new(), 0.0));
+    }
+}
@@ -1,19 +1,27 @@
-use std::collections::hash_map::Entry;
+use std::collections::HashSet;
+use std::hash::BuildHasher;
 use std::marker::PhantomData;
 
-use fnv::FnvHashMap;
-
-use graph::{Graph, AdjacencyGraph, AdjacencyMatrixGraph, VertexListGraph, VertexDescriptor};
+use graph::{Graph, BidirectionalGraph, Directivity, VertexListGraph, VertexDescriptor};
+use error::GraphError;
 use path::reverse_path;
+use property_map::{Color, HashPropertyMap, PropertyMap};
 use visitor::{Event, Visitor, DefaultVisitor};
 
-pub struct Dfs<T, V>
+/// Depth-first search. The colors of the vertices and their parents in the depth-first tree
+/// are kept in property maps, hash maps unless others are given to `with_property_maps`, and
+/// can be inspected after a search.
+pub struct Dfs<T, V, C = HashPropertyMap<VertexDescriptor, Color>,
+               P = HashPropertyMap<VertexDescriptor, VertexDescriptor>>
 where
     T: Graph,
     V: Visitor<T, Event>,
+    C: PropertyMap<VertexDescriptor, Color>,
+    P: PropertyMap<VertexDescriptor, VertexDescriptor>,
 {
     fringe: Vec<VertexDescriptor>,
-    parents: FnvHashMap<VertexDescriptor, VertexDescriptor>,
+    colors: C,
+    parents: P,
     visitor: V,
     phantom: PhantomData<T>,
 }
@@ -27,59 +35,190 @@ where
     }
 }
 
+impl<T> Default for Dfs<T, DefaultVisitor>
+where
+    T: Graph,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T, V> Dfs<T, V>
 where
     T: Graph,
     V: Visitor<T, Event>,
 {
     pub fn with_visitor(visitor: V) -> Self {
+        Self::with_property_maps(visitor, HashPropertyMap::default(), HashPropertyMap::default())
+    }
+}
+
+impl<T, V, C, P> Dfs<T, V, C, P>
+where
+    T: Graph,
+    V: Visitor<T, Event>,
+    C: PropertyMap<VertexDescriptor, Color>,
+    P: PropertyMap<VertexDescriptor, VertexDescriptor>,
+{
+    /// Keeps the colors and the parents of the vertices in `colors` and `parents`, which are
+    /// cleared at the start of every search.
+    pub fn with_property_maps(visitor: V, colors: C, parents: P) -> Self {
         Self {
             fringe: Vec::new(),
-            parents: FnvHashMap::default(),
-            visitor: visitor,
+            colors,
+            parents,
+            visitor,
             phantom: PhantomData,
         }
     }
 
+    fn is_discovered(&self, v: VertexDescriptor) -> bool {
+        self.colors.get(v).is_some_and(|&c| c != Color::White)
+    }
+
     pub fn run<'a, F>(
         &mut self,
         start: &VertexDescriptor,
         is_goal: F,
         graph: &'a T,
-    ) -> Option<Vec<VertexDescriptor>>
+    ) -> Result<Option<Vec<VertexDescriptor>>, GraphError>
+    where
+        F: Fn(&VertexDescriptor) -> bool,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.run_from_any(Some(*start), is_goal, graph)
+    }
+
+    /// Searches from all of `starts` at once, as if from a vertex joined to each of them, and
+    /// returns a path from whichever reaches a goal first.
+    pub fn run_from_any<'a, I, F>(
+        &mut self,
+        starts: I,
+        is_goal: F,
+        graph: &'a T,
+    ) -> Result<Option<Vec<VertexDescriptor>>, GraphError>
     where
+        I: IntoIterator<Item = VertexDescriptor>,
         F: Fn(&VertexDescriptor) -> bool,
-        T: AdjacencyGraph<'a> + AdjacencyMatrixGraph + VertexListGraph<'a>,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
     {
+        let starts = starts.into_iter().collect::<Vec<_>>();
+        if let Some(&start) = starts.iter().find(|&&s| !graph.contains_vertex(s)) {
+            return Err(GraphError::VertexNotFound(start));
+        }
+
         for vertex in graph.vertices() {
             self.visitor.visit(&Event::InitializeVertex(vertex), graph)
         }
 
-        self.visitor.visit(&Event::DiscoverVertex(*start), graph);
-        self.fringe.push(*start);
+        self.fringe.clear();
+        self.colors.clear();
+        self.parents.clear();
+        for start in starts {
+            if !self.is_discovered(start) {
+                self.colors.insert(start, Color::Gray);
+                self.visitor.visit(&Event::DiscoverVertex(start), graph);
+                self.fringe.push(start);
+            }
+        }
 
         while let Some(vertex) = self.fringe.pop() {
             self.visitor.visit(&Event::ExamineVertex(vertex), graph);
             if is_goal(&vertex) {
-                return Some(reverse_path(&self.parents, vertex));
+                return Ok(Some(reverse_path(&self.parents, vertex)));
             }
-            for adjacency in graph.adjacent_vertices(vertex) {
-                let edge = graph.edge(vertex, adjacency).unwrap();
+            let in_edges = if T::Directivity::is_directed() {
+                None
+            } else {
+                Some(graph.in_edges(vertex))
+            };
+            for edge in graph.out_edges(vertex).chain(in_edges.into_iter().flatten()) {
+                let adjacency = graph.opposite(edge, vertex).ok_or(
+                    GraphError::EdgeNotFound(edge),
+                )?;
                 self.visitor.visit(&Event::ExamineEdge(edge), graph);
-                if adjacency != *start {
-                    if let Entry::Vacant(entry) = self.parents.entry(adjacency) {
-                        self.visitor.visit(&Event::TreeEdge(edge), graph);
-                        entry.insert(vertex);
-                        self.visitor.visit(&Event::DiscoverVertex(adjacency), graph);
-                        self.fringe.push(adjacency);
-                    } else {
-                        self.visitor.visit(&Event::NonTreeEdge(edge), graph);
-                    }
+                if !self.is_discovered(adjacency) {
+                    self.visitor.visit(&Event::TreeEdge(edge), graph);
+                    self.parents.insert(adjacency, vertex);
+                    self.colors.insert(adjacency, Color::Gray);
+                    self.visitor.visit(&Event::DiscoverVertex(adjacency), graph);
+                    self.fringe.push(adjacency);
+                } else {
+                    self.visitor.visit(&Event::NonTreeEdge(edge), graph);
                 }
             }
+            self.colors.insert(vertex, Color::Black);
             self.visitor.visit(&Event::FinishVertex(vertex), graph);
         }
-        None
+        Ok(None)
+    }
+
+    pub fn run_to_vertex<'a>(
+        &mut self,
+        start: &VertexDescriptor,
+        goal: &VertexDescriptor,
+        graph: &'a T,
+    ) -> Result<Option<Vec<VertexDescriptor>>, GraphError>
+    where
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.run(start, |v| v == goal, graph)
+    }
+
+    pub fn run_to_any<'a, S>(
+        &mut self,
+        start: &VertexDescriptor,
+        goals: &HashSet<VertexDescriptor, S>,
+        graph: &'a T,
+    ) -> Result<Option<Vec<VertexDescriptor>>, GraphError>
+    where
+        S: BuildHasher,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.run(start, |v| goals.contains(v), graph)
+    }
+
+    pub fn has_path<'a>(
+        &mut self,
+        start: &VertexDescriptor,
+        goal: &VertexDescriptor,
+        graph: &'a T,
+    ) -> Result<bool, GraphError>
+    where
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.run_to_vertex(start, goal, graph).map(|path| path.is_some())
+    }
+
+    /// Visits every vertex reachable from `start` and returns the parent of each in the
+    /// depth-first tree, which is also available from `parents` afterwards.
+    pub fn run_all<'a>(
+        &mut self,
+        start: &VertexDescriptor,
+        graph: &'a T,
+    ) -> Result<&P, GraphError>
+    where
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.run(start, |_| false, graph)?;
+        Ok(&self.parents)
+    }
+
+    /// The colors left by the last search: `Black` for the vertices it finished and `Gray`
+    /// for those it discovered but stopped before finishing.
+    pub fn colors(&self) -> &C {
+        &self.colors
+    }
+
+    pub fn parents(&self) -> &P {
+        &self.parents
     }
 
     pub fn visitor_ref(&self) -> &V {
@@ -95,6 +234,7 @@ mod tests {
     fn dfs() {
         use graph::{Directed, MutableGraph};
         use incidence_list::IncidenceList;
+        use property_map::Color;
 
         let mut g = IncidenceList::<Directed, _, _>::new();
 
@@ -109,27 +249,42 @@ mod tests {
         let v8 = g.add_vertex("i");
         let v9 = g.add_vertex("j");
 
-        g.add_edge(v0, v1, ());
-        g.add_edge(v0, v4, ());
-        g.add_edge(v1, v5, ());
-        g.add_edge(v2, v0, ());
-        g.add_edge(v2, v4, ());
-        g.add_edge(v4, v1, ());
-        g.add_edge(v4, v3, ());
-        g.add_edge(v4, v6, ());
-        g.add_edge(v5, v4, ());
-        g.add_edge(v5, v4, ());
-        g.add_edge(v6, v7, ());
-        g.add_edge(v7, v3, ());
-        g.add_edge(v7, v9, ());
-        g.add_edge(v8, v7, ());
-        g.add_edge(v9, v8, ());
+        g.add_edge(v0, v1, ()).unwrap();
+        g.add_edge(v0, v4, ()).unwrap();
+        g.add_edge(v1, v5, ()).unwrap();
+        g.add_edge(v2, v0, ()).unwrap();
+        g.add_edge(v2, v4, ()).unwrap();
+        g.add_edge(v4, v1, ()).unwrap();
+        g.add_edge(v4, v3, ()).unwrap();
+        g.add_edge(v4, v6, ()).unwrap();
+        g.add_edge(v5, v4, ()).unwrap();
+        g.add_edge(v5, v4, ()).unwrap();
+        g.add_edge(v6, v7, ()).unwrap();
+        g.add_edge(v7, v3, ()).unwrap();
+        g.add_edge(v7, v9, ()).unwrap();
+        g.add_edge(v8, v7, ()).unwrap();
+        g.add_edge(v9, v8, ()).unwrap();
 
         assert_eq!(
             Dfs::new().run(&v0, |&v| v == v9, &g),
-            Some(vec![v0, v4, v6, v7, v9])
+            Ok(Some(vec![v0, v4, v6, v7, v9]))
+        );
+        assert_eq!(Dfs::new().run(&v0, |&v| v == v2, &g), Ok(None));
+
+        assert_eq!(Dfs::new().run_to_vertex(&v0, &v9, &g), Dfs::new().run(&v0, |&v| v == v9, &g));
+        assert_eq!(Dfs::new().has_path(&v0, &v8, &g), Ok(true));
+        assert_eq!(Dfs::new().has_path(&v0, &v2, &g), Ok(false));
+
+        assert_eq!(
+            Dfs::new().run_from_any(vec![v3, v9], |&v| v == v7, &g),
+            Ok(Some(vec![v9, v8, v7]))
         );
-        assert_eq!(Dfs::new().run(&v0, |&v| v == v2, &g), None);
+
+        let mut dfs = Dfs::new();
+        assert_eq!(dfs.run_all(&v7, &g).unwrap().len(), 3);
+        assert!(!dfs.parents().contains_key(&v2));
+        assert_eq!(dfs.colors().len(), 4);
+        assert!(dfs.colors().values().all(|&c| c == Color::Black));
     }
 
     #[test]
@@ -167,14 +322,14 @@ mod tests {
             T: IncidenceGraph<'a>,
         {
             fn visit(&mut self, e: &Event, graph: &T) {
-                match e {
-                    &Event::InitializeVertex(v) => self.init.push(v),
-                    &Event::DiscoverVertex(v) => self.discovered.push(v),
-                    &Event::ExamineVertex(v) => self.vertex_examined.push(v),
-                    &Event::ExamineEdge(e) => self.edge_target_examined.push(graph.target(e)),
-                    &Event::TreeEdge(e) => self.tree_edge_target.push(graph.target(e)),
-                    &Event::NonTreeEdge(e) => self.non_tree_edge_target.push(graph.target(e)),
-                    &Event::FinishVertex(v) => self.finished.push(v),
+                match *e {
+                    Event::InitializeVertex(v) => self.init.push(v),
+                    Event::DiscoverVertex(v) => self.discovered.push(v),
+                    Event::ExamineVertex(v) => self.vertex_examined.push(v),
+                    Event::ExamineEdge(e) => self.edge_target_examined.push(graph.target(e)),
+                    Event::TreeEdge(e) => self.tree_edge_target.push(graph.target(e)),
+                    Event::NonTreeEdge(e) => self.non_tree_edge_target.push(graph.target(e)),
+                    Event::FinishVertex(v) => self.finished.push(v),
                     _ => (),
                 }
             }
@@ -193,43 +348,47 @@ mod tests {
         let v8 = g.add_vertex("i");
         let v9 = g.add_vertex("j");
 
-        g.add_edge(v0, v1, ());
-        g.add_edge(v0, v4, ());
-        g.add_edge(v1, v5, ());
-        g.add_edge(v2, v0, ());
-        g.add_edge(v2, v4, ());
-        g.add_edge(v4, v1, ());
-        g.add_edge(v4, v3, ());
-        g.add_edge(v4, v6, ());
-        g.add_edge(v5, v4, ());
-        g.add_edge(v5, v4, ());
-        g.add_edge(v6, v7, ());
-        g.add_edge(v7, v3, ());
-        g.add_edge(v7, v9, ());
-        g.add_edge(v8, v7, ());
-        g.add_edge(v9, v8, ());
+        g.add_edge(v0, v1, ()).unwrap();
+        g.add_edge(v0, v4, ()).unwrap();
+        g.add_edge(v1, v5, ()).unwrap();
+        g.add_edge(v2, v0, ()).unwrap();
+        g.add_edge(v2, v4, ()).unwrap();
+        g.add_edge(v4, v1, ()).unwrap();
+        g.add_edge(v4, v3, ()).unwrap();
+        g.add_edge(v4, v6, ()).unwrap();
+        g.add_edge(v5, v4, ()).unwrap();
+        g.add_edge(v5, v4, ()).unwrap();
+        g.add_edge(v6, v7, ()).unwrap();
+        g.add_edge(v7, v3, ()).unwrap();
+        g.add_edge(v7, v9, ()).unwrap();
+        g.add_edge(v8, v7, ()).unwrap();
+        g.add_edge(v9, v8, ()).unwrap();
 
         let mut dfs = Dfs::with_visitor(MyVisitor::new());
 
         assert_eq!(
             dfs.run(&v0, |&v| v == v9, &g),
-            Some(vec![v0, v4, v6, v7, v9])
+            Ok(Some(vec![v0, v4, v6, v7, v9]))
         );
         assert_eq!(dfs.visitor_ref().init.len(), 10);
         assert_eq!(
             dfs.visitor_ref().discovered,
-            vec![v0, v1, v4, v3, v6, v7, v9]
+            vec![v0, v4, v1, v5, v6, v3, v7, v9]
+        );
+        assert_eq!(
+            dfs.visitor_ref().vertex_examined,
+            vec![v0, v1, v5, v4, v3, v6, v7, v9]
         );
-        assert_eq!(dfs.visitor_ref().vertex_examined, vec![v0, v4, v6, v7, v9]);
+        // both parallel edges from v5 to v4 are examined
         assert_eq!(
             dfs.visitor_ref().edge_target_examined,
-            vec![v1, v4, v1, v3, v6, v7, v3, v9]
+            vec![v4, v1, v5, v4, v4, v6, v3, v1, v7, v9, v3]
         );
         assert_eq!(
             dfs.visitor_ref().tree_edge_target,
-            vec![v1, v4, v3, v6, v7, v9]
+            vec![v4, v1, v5, v6, v3, v7, v9]
         );
-        assert_eq!(dfs.visitor_ref().non_tree_edge_target, vec![v1, v3]);
-        assert_eq!(dfs.visitor_ref().finished, vec![v0, v4, v6, v7]);
+        assert_eq!(dfs.visitor_ref().non_tree_edge_target, vec![v4, v4, v1, v3]);
+        assert_eq!(dfs.visitor_ref().finished, vec![v0, v1, v5, v4, v3, v6, v7]);
     }
 }
@@ -0,0 +1,466 @@
+use std::cmp::Ordering;
+use std::fmt::Debug;
+use std::collections::{BinaryHeap, HashSet};
+use std::hash::BuildHasher;
+use std::marker::PhantomData;
+
+use num_traits::Zero;
+
+use graph::{Graph, BidirectionalGraph, Directivity, VertexListGraph,
+            EdgeDescriptor, VertexDescriptor};
+use error::GraphError;
+use path::{reverse_path, Path};
+use property_map::{HashPropertyMap, PropertyMap};
+use visitor::{Event, Visitor, DefaultVisitor};
+
+#[derive(Clone, Eq, Debug)]
+struct State<C>
+where
+    C: Ord,
+{
+    cost: C,
+    vertex: VertexDescriptor,
+}
+
+impl<C> PartialEq for State<C>
+where
+    C: Ord,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.cost == other.cost
+    }
+}
+
+impl<C> PartialOrd for State<C>
+where
+    C: Ord,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<C> Ord for State<C>
+where
+    C: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.cmp(&self.cost)
+    }
+}
+
+/// Dijkstra's algorithm. The distances of the vertices from the start and their parents on
+/// shortest paths are kept in property maps, hash maps unless others are given to
+/// `with_property_maps`, and can be inspected after a search.
+pub struct Dijkstra<C, T, V, D = HashPropertyMap<VertexDescriptor, C>,
+                    P = HashPropertyMap<VertexDescriptor, VertexDescriptor>>
+where
+    C: Copy + Debug + Ord + Zero,
+    T: Graph,
+    V: Visitor<T, Event>,
+    D: PropertyMap<VertexDescriptor, C>,
+    P: PropertyMap<VertexDescriptor, VertexDescriptor>,
+{
+    fringe: BinaryHeap<State<C>>,
+    distances: D,
+    parents: P,
+    visitor: V,
+    phantom: PhantomData<T>,
+}
+
+impl<C, T> Dijkstra<C, T, DefaultVisitor>
+where
+    C: Copy + Debug + Ord + Zero,
+    T: Graph,
+{
+    pub fn new() -> Self {
+        Self::with_visitor(DefaultVisitor)
+    }
+}
+
+impl<C, T> Default for Dijkstra<C, T, DefaultVisitor>
+where
+    C: Copy + Debug + Ord + Zero,
+    T: Graph,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C, T, V> Dijkstra<C, T, V>
+where
+    C: Copy + Debug + Ord + Zero,
+    T: Graph,
+    V: Visitor<T, Event>,
+{
+    pub fn with_visitor(visitor: V) -> Self {
+        Self::with_property_maps(visitor, HashPropertyMap::default(), HashPropertyMap::default())
+    }
+}
+
+impl<C, T, V, D, P> Dijkstra<C, T, V, D, P>
+where
+    C: Copy + Debug + Ord + Zero,
+    T: Graph,
+    V: Visitor<T, Event>,
+    D: PropertyMap<VertexDescriptor, C>,
+    P: PropertyMap<VertexDescriptor, VertexDescriptor>,
+{
+    /// Keeps the distances and the parents of the vertices in `distances` and `parents`, which
+    /// are cleared at the start of every search.
+    pub fn with_property_maps(visitor: V, distances: D, parents: P) -> Self {
+        Self {
+            fringe: BinaryHeap::new(),
+            distances,
+            parents,
+            visitor,
+            phantom: PhantomData,
+        }
+    }
+
+    // Settles vertices in order of distance until one satisfies `is_goal`, to which a path is
+    // returned, or none are left, following only the edges to vertices that `is_allowed`
+    // accepts.
+    fn search<'a, A, F, G>(
+        &mut self,
+        start: &VertexDescriptor,
+        edge_cost: G,
+        is_goal: F,
+        is_allowed: A,
+        graph: &'a T,
+    ) -> Result<Option<Path<C>>, GraphError>
+    where
+        A: Fn(&EdgeDescriptor, &VertexDescriptor) -> bool,
+        F: Fn(&VertexDescriptor) -> bool,
+        G: Fn(&EdgeDescriptor, &T) -> C,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        if !graph.contains_vertex(*start) {
+            return Err(GraphError::VertexNotFound(*start));
+        }
+        self.fringe.clear();
+        self.distances.clear();
+        self.parents.clear();
+
+        for vertex in graph.vertices() {
+            self.visitor.visit(&Event::InitializeVertex(vertex), graph)
+        }
+
+        self.visitor.visit(&Event::DiscoverVertex(*start), graph);
+        self.distances.insert(*start, C::zero());
+        self.fringe.push(State {
+            cost: C::zero(),
+            vertex: *start,
+        });
+
+        while let Some(State { cost, vertex }) = self.fringe.pop() {
+            if self.distances.get(vertex).is_some_and(|&d| d < cost) {
+                continue;
+            }
+            self.visitor.visit(&Event::ExamineVertex(vertex), graph);
+            if is_goal(&vertex) {
+                return Ok(Some(Path::new(reverse_path(&self.parents, vertex), cost)));
+            }
+            let in_edges = if T::Directivity::is_directed() {
+                None
+            } else {
+                Some(graph.in_edges(vertex))
+            };
+            for edge in graph.out_edges(vertex).chain(in_edges.into_iter().flatten()) {
+                let adjacency = graph.opposite(edge, vertex).ok_or(
+                    GraphError::EdgeNotFound(edge),
+                )?;
+                if !is_allowed(&edge, &adjacency) {
+                    continue;
+                }
+                self.visitor.visit(&Event::ExamineEdge(edge), graph);
+                let cost_of_edge = edge_cost(&edge, graph);
+                if cost_of_edge < C::zero() {
+                    return Err(GraphError::NegativeEdgeCost(edge));
+                }
+                let cost_to_adjacency = cost + cost_of_edge;
+                if self.distances.get(adjacency).is_some_and(|&d| d <= cost_to_adjacency) {
+                    self.visitor.visit(&Event::EdgeNotRelaxed(edge), graph);
+                    continue;
+                }
+                self.distances.insert(adjacency, cost_to_adjacency);
+                self.parents.insert(adjacency, vertex);
+                self.visitor.visit(&Event::EdgeRelaxed(edge), graph);
+                self.visitor.visit(&Event::DiscoverVertex(adjacency), graph);
+                self.fringe.push(State {
+                    cost: cost_to_adjacency,
+                    vertex: adjacency,
+                });
+            }
+            self.visitor.visit(&Event::FinishVertex(vertex), graph);
+        }
+        Ok(None)
+    }
+
+    pub fn run<'a, F, G>(
+        &mut self,
+        start: &VertexDescriptor,
+        edge_cost: G,
+        is_goal: F,
+        graph: &'a T,
+    ) -> Result<Option<Path<C>>, GraphError>
+    where
+        F: Fn(&VertexDescriptor) -> bool,
+        G: Fn(&EdgeDescriptor, &T) -> C,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.search(start, edge_cost, is_goal, |_, _| true, graph)
+    }
+
+    pub fn run_to_vertex<'a, G>(
+        &mut self,
+        start: &VertexDescriptor,
+        goal: &VertexDescriptor,
+        edge_cost: G,
+        graph: &'a T,
+    ) -> Result<Option<Path<C>>, GraphError>
+    where
+        G: Fn(&EdgeDescriptor, &T) -> C,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.run(start, edge_cost, |v| v == goal, graph)
+    }
+
+    pub fn run_to_any<'a, S, G>(
+        &mut self,
+        start: &VertexDescriptor,
+        goals: &HashSet<VertexDescriptor, S>,
+        edge_cost: G,
+        graph: &'a T,
+    ) -> Result<Option<Path<C>>, GraphError>
+    where
+        S: BuildHasher,
+        G: Fn(&EdgeDescriptor, &T) -> C,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.run(start, edge_cost, |v| goals.contains(v), graph)
+    }
+
+    /// Finds a shortest path from `start` to `goal` that passes through none of `vertices` and
+    /// none of `edges`, as if they had been removed from the graph. There is none if `start` or
+    /// `goal` is among `vertices`.
+    pub fn run_avoiding<'a, S, R, G>(
+        &mut self,
+        start: &VertexDescriptor,
+        goal: &VertexDescriptor,
+        vertices: &HashSet<VertexDescriptor, S>,
+        edges: &HashSet<EdgeDescriptor, R>,
+        edge_cost: G,
+        graph: &'a T,
+    ) -> Result<Option<Path<C>>, GraphError>
+    where
+        S: BuildHasher,
+        R: BuildHasher,
+        G: Fn(&EdgeDescriptor, &T) -> C,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        if vertices.contains(start) {
+            return if graph.contains_vertex(*start) {
+                Ok(None)
+            } else {
+                Err(GraphError::VertexNotFound(*start))
+            };
+        }
+        let is_allowed = |e: &EdgeDescriptor, v: &VertexDescriptor| {
+            !edges.contains(e) && !vertices.contains(v)
+        };
+        self.search(start, edge_cost, |v| v == goal, is_allowed, graph)
+    }
+
+    /// Computes the distance from `start` to every vertex reachable from it. The parent of each
+    /// on a shortest path is available from `parents` afterwards.
+    pub fn run_all<'a, G>(
+        &mut self,
+        start: &VertexDescriptor,
+        edge_cost: G,
+        graph: &'a T,
+    ) -> Result<&D, GraphError>
+    where
+        G: Fn(&EdgeDescriptor, &T) -> C,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.search(start, edge_cost, |_| false, |_, _| true, graph)?;
+        Ok(&self.distances)
+    }
+
+    pub fn distances(&self) -> &D {
+        &self.distances
+    }
+
+    pub fn parents(&self) -> &P {
+        &self.parents
+    }
+
+    pub fn visitor_ref(&self) -> &V {
+        &self.visitor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Dijkstra;
+
+    #[test]
+    fn dijkstra_directed() {
+        use std::collections::HashSet;
+        use graph::{Directed, Graph, MutableGraph};
+        use incidence_list::IncidenceList;
+        use path::Path;
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+
+        let v0 = g.add_vertex("s");
+        let v1 = g.add_vertex("a");
+        let v2 = g.add_vertex("b");
+        let v3 = g.add_vertex("c");
+        let v4 = g.add_vertex("g");
+        let v5 = g.add_vertex("x");
+
+        g.add_edge(v0, v1, 1).unwrap();
+        g.add_edge(v0, v2, 4).unwrap();
+        g.add_edge(v1, v2, 2).unwrap();
+        let e13 = g.add_edge(v1, v3, 5).unwrap();
+        g.add_edge(v1, v4, 12).unwrap();
+        g.add_edge(v2, v3, 2).unwrap();
+        g.add_edge(v3, v4, 3).unwrap();
+
+        assert_eq!(
+            Dijkstra::new().run(&v0, |&e, g| *g.edge_property(e).unwrap(), |&v| v == v4, &g),
+            Ok(Some(Path::new(vec![v0, v1, v2, v3, v4], 8)))
+        );
+        assert_eq!(
+            Dijkstra::new().run(&v0, |&e, g| *g.edge_property(e).unwrap(), |&v| v == v5, &g),
+            Ok(None)
+        );
+        assert_eq!(
+            Dijkstra::new().run_to_vertex(&v0, &v4, |&e, g| *g.edge_property(e).unwrap(), &g),
+            Ok(Some(Path::new(vec![v0, v1, v2, v3, v4], 8)))
+        );
+        let goals = [v3, v5].iter().cloned().collect::<HashSet<_>>();
+        assert_eq!(
+            Dijkstra::new().run_to_any(&v0, &goals, |&e, g| *g.edge_property(e).unwrap(), &g),
+            Ok(Some(Path::new(vec![v0, v1, v2, v3], 5)))
+        );
+        let (mut vertices, mut edges) = (HashSet::new(), HashSet::new());
+        vertices.insert(v2);
+        assert_eq!(
+            Dijkstra::new().run_avoiding(&v0, &v4, &vertices, &edges, |&e, g| g[e], &g),
+            Ok(Some(Path::new(vec![v0, v1, v3, v4], 9)))
+        );
+        edges.insert(e13);
+        assert_eq!(
+            Dijkstra::new().run_avoiding(&v0, &v4, &vertices, &edges, |&e, g| g[e], &g),
+            Ok(Some(Path::new(vec![v0, v1, v4], 13)))
+        );
+        vertices.insert(v4);
+        assert_eq!(
+            Dijkstra::new().run_avoiding(&v0, &v4, &vertices, &edges, |&e, g| g[e], &g),
+            Ok(None)
+        );
+
+        let mut dijkstra = Dijkstra::new();
+        {
+            let distances = dijkstra
+                .run_all(&v1, |&e, g| *g.edge_property(e).unwrap(), &g)
+                .unwrap();
+            assert_eq!(distances.len(), 4);
+            assert_eq!(distances[&v1], 0);
+            assert_eq!(distances[&v3], 4);
+            assert_eq!(distances[&v4], 7);
+            assert!(!distances.contains_key(&v0));
+        }
+        assert_eq!(dijkstra.parents()[&v4], v3);
+    }
+
+    #[test]
+    fn dijkstra_with_property_maps() {
+        use graph::{Directed, MutableGraph, VertexListGraph};
+        use incidence_list::IncidenceList;
+        use path::Path;
+        use property_map::{PropertyMap, VecPropertyMap};
+        use visitor::DefaultVisitor;
+
+        // v0 --1--> v1 --2--> v2    v3
+
+        let mut g = IncidenceList::<Directed, (), u32>::new();
+        let v0 = g.add_vertex(());
+        let v1 = g.add_vertex(());
+        let v2 = g.add_vertex(());
+        let v3 = g.add_vertex(());
+        g.add_edge(v0, v1, 1).unwrap();
+        g.add_edge(v1, v2, 2).unwrap();
+
+        let bound = g.vertex_bound();
+        let mut dijkstra = Dijkstra::with_property_maps(
+            DefaultVisitor,
+            VecPropertyMap::with_bound(bound),
+            VecPropertyMap::with_bound(bound),
+        );
+        assert_eq!(
+            dijkstra.run_to_vertex(&v0, &v2, |&e, g| g[e], &g),
+            Ok(Some(Path::new(vec![v0, v1, v2], 3)))
+        );
+        assert_eq!((dijkstra.distances()[v2], dijkstra.parents()[v2]), (3, v1));
+        assert!(!dijkstra.distances().contains_key(v3));
+
+        // the maps are cleared by the next search
+        dijkstra.run_all(&v1, |&e, g| g[e], &g).unwrap();
+        assert_eq!(dijkstra.distances().get(v0), None);
+        assert_eq!(dijkstra.distances()[v2], 2);
+    }
+
+    #[test]
+    fn dijkstra_undirected_with_visitor() {
+        use error::GraphError;
+        use graph::{Graph, MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+        use path::Path;
+        use visitor::{Event, Visitor};
+
+        struct Examined(Vec<usize>);
+
+        impl<G: Graph> Visitor<G, Event> for Examined {
+            fn visit(&mut self, e: &Event, _g: &G) {
+                if let Event::ExamineVertex(v) = *e {
+                    self.0.push(v.into());
+                }
+            }
+        }
+
+        // v0 --1-- v1 --1-- v2
+        //  |                 |
+        //  +--------5--------+
+
+        let mut g = IncidenceList::<Undirected, (), _>::new();
+        let v0 = g.add_vertex(());
+        let v1 = g.add_vertex(());
+        let v2 = g.add_vertex(());
+        g.add_edge(v0, v1, 1).unwrap();
+        g.add_edge(v1, v2, 1).unwrap();
+        let e = g.add_edge(v2, v0, 5).unwrap();
+
+        let mut dijkstra = Dijkstra::with_visitor(Examined(Vec::new()));
+        assert_eq!(
+            dijkstra.run_to_vertex(&v2, &v0, |&e, g| *g.edge_property(e).unwrap(), &g),
+            Ok(Some(Path::new(vec![v2, v1, v0], 2)))
+        );
+        assert_eq!(dijkstra.visitor_ref().0, vec![2, 1, 0]);
+
+        assert_eq!(
+            Dijkstra::new().run_to_vertex(&v0, &v2, |&x, _| if x == e { -1 } else { 1 }, &g),
+            Err(GraphError::NegativeEdgeCost(e))
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use std::cmp::Reverse;
+
+use coloring::symmetric_adjacency;
+use graph::{IncidenceGraph, VertexDescriptor, VertexListGraph};
+
+/// Picks vertices one at a time, each time the one dominating the most vertices not yet
+/// dominated, which is within a factor of ln(n) + 1 of a minimum dominating set. Edge direction
+/// is ignored.
+pub fn greedy_dominating_set<'a, G>(graph: &'a G) -> Vec<VertexDescriptor>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+{
+    let (vertices, neighbors) = symmetric_adjacency(graph);
+    let mut dominated = vec![false; vertices.len()];
+    let mut remaining = vertices.len();
+    let mut chosen = Vec::new();
+    while remaining > 0 {
+        let gain = |i: usize| {
+            neighbors[i].iter().filter(|&&j| !dominated[j]).count() + !dominated[i] as usize
+        };
+        let i = (0..vertices.len()).max_by_key(|&i| (gain(i), Reverse(i))).unwrap();
+        for &j in neighbors[i].iter().chain(Some(&i)) {
+            if !dominated[j] {
+                dominated[j] = true;
+                remaining -= 1;
+            }
+        }
+        chosen.push(vertices[i]);
+    }
+    chosen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::greedy_dominating_set;
+
+    #[test]
+    fn dominating_set() {
+        use graph::{MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        //       v1          v5
+        //       |           |
+        // v2 -- v0 -- v4 -- v6 -- v8
+        //       |           |
+        //       v3          v7     v9
+
+        let mut g = IncidenceList::<Undirected, _, _>::new();
+        let vs = (0..10).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t) in &[(0, 1), (0, 2), (0, 3), (0, 4), (4, 6), (6, 5), (6, 7), (6, 8)] {
+            g.add_edge(vs[s], vs[t], ()).unwrap();
+        }
+
+        assert_eq!(greedy_dominating_set(&g), vec![vs[0], vs[6], vs[9]]);
+    }
+
+    #[test]
+    fn dominating_set_empty() {
+        use graph::Undirected;
+        use incidence_list::IncidenceList;
+
+        let g = IncidenceList::<Undirected, (), ()>::new();
+        assert!(greedy_dominating_set(&g).is_empty());
+    }
+
+    #[test]
+    fn dominating_set_directed() {
+        use graph::{Directed, MutableGraph};
+        use incidence_list::IncidenceList;
+
+        // v1 --> v0 <-- v2
+        //        ^
+        //        |
+        // v4 --> v3
+        //
+        // v0 dominates its predecessors although no edge leaves it
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+        let vs = (0..5).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t) in &[(1, 0), (2, 0), (3, 0), (4, 3)] {
+            g.add_edge(vs[s], vs[t], ()).unwrap();
+        }
+
+        assert_eq!(greedy_dominating_set(&g), vec![vs[0], vs[3]]);
+    }
+}
@@ -0,0 +1,383 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::fmt::Debug;
+
+use fnv::{FnvHashMap, FnvHashSet};
+use num_traits::Zero;
+
+use error::GraphError;
+use graph::{Directivity, EdgeDescriptor, IncidenceGraph, VertexDescriptor, VertexListGraph};
+
+/// A shortest path tree from a fixed source that is repaired, rather than rebuilt, as edges
+/// are inserted, removed or change cost. It keeps its own copy of the edges and their costs,
+/// so the graph itself can be changed freely as long as every change is reported here too.
+/// A cheaper edge is propagated from its target as in Dijkstra's algorithm; a dearer or removed
+/// tree edge detaches the subtree below it, which is then reattached from the rest of the tree
+/// in the manner of Ramalingam and Reps. Either way only the vertices whose distance changes
+/// are touched. Costs must not be negative.
+#[derive(Clone, Debug)]
+pub struct DynamicShortestPaths<C> {
+    source: VertexDescriptor,
+    directed: bool,
+    edges: FnvHashMap<EdgeDescriptor, (VertexDescriptor, VertexDescriptor, C)>,
+    leaving: FnvHashMap<VertexDescriptor, Vec<EdgeDescriptor>>,
+    entering: FnvHashMap<VertexDescriptor, Vec<EdgeDescriptor>>,
+    distances: FnvHashMap<VertexDescriptor, C>,
+    parents: FnvHashMap<VertexDescriptor, EdgeDescriptor>,
+}
+
+impl<C> DynamicShortestPaths<C>
+where
+    C: Copy + Debug + Ord + Zero,
+{
+    pub fn new<'a, G, F>(
+        graph: &'a G,
+        source: VertexDescriptor,
+        edge_cost: F,
+    ) -> Result<Self, GraphError>
+    where
+        G: IncidenceGraph<'a> + VertexListGraph<'a>,
+        G::Directivity: Directivity,
+        F: Fn(&EdgeDescriptor, &G) -> C,
+    {
+        if !graph.contains_vertex(source) {
+            return Err(GraphError::VertexNotFound(source));
+        }
+        let mut paths = DynamicShortestPaths {
+            source,
+            directed: G::Directivity::is_directed(),
+            edges: FnvHashMap::default(),
+            leaving: FnvHashMap::default(),
+            entering: FnvHashMap::default(),
+            distances: FnvHashMap::default(),
+            parents: FnvHashMap::default(),
+        };
+        for v in graph.vertices() {
+            for e in graph.out_edges(v) {
+                if let Some(t) = graph.opposite(e, v) {
+                    paths.link(e, v, t, edge_cost(&e, graph))?;
+                }
+            }
+        }
+        paths.distances.insert(source, C::zero());
+        paths.propagate(vec![source]);
+        Ok(paths)
+    }
+
+    pub fn source(&self) -> VertexDescriptor {
+        self.source
+    }
+
+    /// The length of a shortest path from the source, or `None` if `v` cannot be reached.
+    pub fn distance(&self, v: VertexDescriptor) -> Option<C> {
+        self.distances.get(&v).cloned()
+    }
+
+    /// The last edge on the shortest path to `v`.
+    pub fn parent(&self, v: VertexDescriptor) -> Option<EdgeDescriptor> {
+        self.parents.get(&v).cloned()
+    }
+
+    /// The vertices of a shortest path from the source to `v`, both included.
+    pub fn path(&self, v: VertexDescriptor) -> Option<Vec<VertexDescriptor>> {
+        self.distances.get(&v)?;
+        let mut path = vec![v];
+        let mut w = v;
+        while let Some(&e) = self.parents.get(&w) {
+            w = self.tail(e, w);
+            path.push(w);
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Records an edge added to the graph from `source` to `target`.
+    pub fn insert_edge(
+        &mut self,
+        e: EdgeDescriptor,
+        source: VertexDescriptor,
+        target: VertexDescriptor,
+        cost: C,
+    ) -> Result<(), GraphError> {
+        self.link(e, source, target, cost)?;
+        let mut improved = self.relax(e, source).into_iter().collect::<Vec<_>>();
+        if !self.directed {
+            improved.extend(self.relax(e, target));
+        }
+        self.propagate(improved);
+        Ok(())
+    }
+
+    /// Records an edge removed from the graph.
+    pub fn remove_edge(&mut self, e: EdgeDescriptor) -> Result<(), GraphError> {
+        let (s, t, _) = self.edges.remove(&e).ok_or(GraphError::EdgeNotFound(e))?;
+        for end in &[s, t] {
+            if let Some(list) = self.leaving.get_mut(end) {
+                list.retain(|&f| f != e);
+            }
+            if let Some(list) = self.entering.get_mut(end) {
+                list.retain(|&f| f != e);
+            }
+        }
+        self.detach(e, s, t);
+        Ok(())
+    }
+
+    /// Records a change of the cost of an edge.
+    pub fn set_edge_cost(&mut self, e: EdgeDescriptor, cost: C) -> Result<(), GraphError> {
+        if cost < C::zero() {
+            return Err(GraphError::NegativeEdgeCost(e));
+        }
+        let (s, t, old) = *self.edges.get(&e).ok_or(GraphError::EdgeNotFound(e))?;
+        self.edges.insert(e, (s, t, cost));
+        if cost > old {
+            self.detach(e, s, t);
+        } else if cost < old {
+            let mut improved = self.relax(e, s).into_iter().collect::<Vec<_>>();
+            if !self.directed {
+                improved.extend(self.relax(e, t));
+            }
+            self.propagate(improved);
+        }
+        Ok(())
+    }
+
+    fn link(
+        &mut self,
+        e: EdgeDescriptor,
+        s: VertexDescriptor,
+        t: VertexDescriptor,
+        cost: C,
+    ) -> Result<(), GraphError> {
+        if cost < C::zero() {
+            return Err(GraphError::NegativeEdgeCost(e));
+        }
+        self.edges.insert(e, (s, t, cost));
+        self.leaving.entry(s).or_default().push(e);
+        self.entering.entry(t).or_default().push(e);
+        if !self.directed && s != t {
+            self.leaving.entry(t).or_default().push(e);
+            self.entering.entry(s).or_default().push(e);
+        }
+        Ok(())
+    }
+
+    // The end `e` leads to when followed from `v`.
+    fn head(&self, e: EdgeDescriptor, v: VertexDescriptor) -> VertexDescriptor {
+        let (s, t, _) = self.edges[&e];
+        if s == v {
+            t
+        } else {
+            s
+        }
+    }
+
+    // The end `e` comes from when it leads to `v`.
+    fn tail(&self, e: EdgeDescriptor, v: VertexDescriptor) -> VertexDescriptor {
+        let (s, t, _) = self.edges[&e];
+        if t == v {
+            s
+        } else {
+            t
+        }
+    }
+
+    // Shortens the path to the head of `e` through `from` if that is cheaper.
+    fn relax(&mut self, e: EdgeDescriptor, from: VertexDescriptor) -> Option<VertexDescriptor> {
+        let d = *self.distances.get(&from)?;
+        let to = self.head(e, from);
+        let through = d + self.edges[&e].2;
+        if self.distances.get(&to).is_none_or(|&old| through < old) {
+            self.distances.insert(to, through);
+            self.parents.insert(to, e);
+            return Some(to);
+        }
+        None
+    }
+
+    // Runs Dijkstra's algorithm onwards from vertices whose distance has just dropped.
+    fn propagate<I>(&mut self, improved: I)
+    where
+        I: IntoIterator<Item = VertexDescriptor>,
+    {
+        let mut fringe = improved
+            .into_iter()
+            .map(|v| Reverse((self.distances[&v], v)))
+            .collect::<BinaryHeap<_>>();
+        while let Some(Reverse((d, v))) = fringe.pop() {
+            if self.distances.get(&v) != Some(&d) {
+                continue;
+            }
+            let leaving = self.leaving.get(&v).cloned().unwrap_or_default();
+            for e in leaving {
+                if let Some(w) = self.relax(e, v) {
+                    fringe.push(Reverse((self.distances[&w], w)));
+                }
+            }
+        }
+    }
+
+    // If `e` is a tree edge, forgets the distances of the subtree below it and reattaches the
+    // subtree through the cheapest edges entering it from outside.
+    fn detach(&mut self, e: EdgeDescriptor, s: VertexDescriptor, t: VertexDescriptor) {
+        let root = if self.parents.get(&t) == Some(&e) {
+            t
+        } else if self.parents.get(&s) == Some(&e) {
+            s
+        } else {
+            return;
+        };
+        let mut subtree = FnvHashSet::default();
+        let mut queue = VecDeque::new();
+        subtree.insert(root);
+        queue.push_back(root);
+        while let Some(v) = queue.pop_front() {
+            for &f in self.leaving.get(&v).into_iter().flatten() {
+                let w = self.head(f, v);
+                if self.parents.get(&w) == Some(&f) && subtree.insert(w) {
+                    queue.push_back(w);
+                }
+            }
+        }
+        for v in &subtree {
+            self.distances.remove(v);
+            self.parents.remove(v);
+        }
+
+        let mut improved = Vec::new();
+        for &v in &subtree {
+            let entering = self.entering.get(&v).cloned().unwrap_or_default();
+            for f in entering {
+                let u = self.tail(f, v);
+                if !subtree.contains(&u) && self.relax(f, u).is_some() {
+                    improved.push(v);
+                }
+            }
+        }
+        self.propagate(improved);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DynamicShortestPaths;
+
+    #[test]
+    fn maintain_shortest_paths() {
+        use graph::{Directed, Graph, MutableGraph, VertexListGraph};
+        use incidence_list::IncidenceList;
+
+        //        (4)
+        // v0 ----------> v1 --(1)--> v3
+        //  |             ^            ^
+        // (1)           (1)           |
+        //  v             |           (5)
+        // v2 ------------+            |
+        //  +--------------------------+
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+        let vs = (0..4).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        let e01 = g.add_edge(vs[0], vs[1], 4).unwrap();
+        let e02 = g.add_edge(vs[0], vs[2], 1).unwrap();
+        let e21 = g.add_edge(vs[2], vs[1], 1).unwrap();
+        g.add_edge(vs[1], vs[3], 1).unwrap();
+        g.add_edge(vs[2], vs[3], 5).unwrap();
+        let cost = |e: &_, g: &IncidenceList<Directed, (), u32>| *g.edge_property(*e).unwrap();
+
+        let mut paths = DynamicShortestPaths::new(&g, vs[0], cost).unwrap();
+        assert_eq!(paths.distance(vs[3]), Some(3));
+        assert_eq!(paths.path(vs[3]), Some(vec![vs[0], vs[2], vs[1], vs[3]]));
+
+        let agrees = |paths: &DynamicShortestPaths<u32>, g: &IncidenceList<Directed, (), u32>| {
+            let fresh = DynamicShortestPaths::new(g, vs[0], cost).unwrap();
+            g.vertices().all(|v| paths.distance(v) == fresh.distance(v))
+        };
+
+        *g.edge_property_mut(e21).unwrap() = 10;
+        paths.set_edge_cost(e21, 10).unwrap();
+        assert!(agrees(&paths, &g));
+        assert_eq!(paths.path(vs[3]), Some(vec![vs[0], vs[1], vs[3]]));
+
+        g.remove_edge(e01);
+        paths.remove_edge(e01).unwrap();
+        assert!(agrees(&paths, &g));
+        assert_eq!(paths.distance(vs[3]), Some(6));
+
+        g.remove_edge(e02);
+        paths.remove_edge(e02).unwrap();
+        assert!(agrees(&paths, &g));
+        assert_eq!(paths.distance(vs[1]), None);
+
+        let e = g.add_edge(vs[0], vs[2], 0).unwrap();
+        paths.insert_edge(e, vs[0], vs[2], 0).unwrap();
+        assert!(agrees(&paths, &g));
+        assert_eq!(paths.distance(vs[3]), Some(5));
+
+        *g.edge_property_mut(e21).unwrap() = 1;
+        paths.set_edge_cost(e21, 1).unwrap();
+        assert!(agrees(&paths, &g));
+        assert_eq!(paths.distance(vs[3]), Some(2));
+        assert!(paths.remove_edge(e01).is_err());
+    }
+
+    #[test]
+    fn dynamic_shortest_paths_empty() {
+        use error::GraphError;
+        use graph::{Directed, Graph, MutableGraph};
+        use incidence_list::IncidenceList;
+
+        let cost = |e: &_, g: &IncidenceList<Directed, (), u32>| *g.edge_property(*e).unwrap();
+        let mut g = IncidenceList::<Directed, (), u32>::new();
+        let v = g.add_vertex(());
+        let empty = IncidenceList::<Directed, (), u32>::new();
+        assert_eq!(
+            DynamicShortestPaths::new(&empty, v, cost).err(),
+            Some(GraphError::VertexNotFound(v))
+        );
+
+        let paths = DynamicShortestPaths::new(&g, v, cost).unwrap();
+        assert_eq!(paths.source(), v);
+        assert_eq!(paths.distance(v), Some(0));
+        assert_eq!(paths.parent(v), None);
+        assert_eq!(paths.path(v), Some(vec![v]));
+    }
+
+    #[test]
+    fn dynamic_shortest_paths_undirected() {
+        use error::GraphError;
+        use graph::{Graph, MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        // v0 --(1)-- v1 --(1)-- v2    v3
+        //
+        // edges are added towards v0, and v3 stays out of reach
+
+        let mut g = IncidenceList::<Undirected, _, _>::new();
+        let vs = (0..4).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        let e10 = g.add_edge(vs[1], vs[0], 1).unwrap();
+        let e21 = g.add_edge(vs[2], vs[1], 1).unwrap();
+        let cost = |e: &_, g: &IncidenceList<Undirected, (), u32>| *g.edge_property(*e).unwrap();
+
+        let mut paths = DynamicShortestPaths::new(&g, vs[0], cost).unwrap();
+        assert_eq!(paths.path(vs[2]), Some(vec![vs[0], vs[1], vs[2]]));
+        assert_eq!((paths.parent(vs[1]), paths.parent(vs[2])), (Some(e10), Some(e21)));
+        assert_eq!(paths.distance(vs[3]), None);
+        assert_eq!(paths.path(vs[3]), None);
+
+        paths.remove_edge(e10).unwrap();
+        assert_eq!((paths.distance(vs[1]), paths.distance(vs[2])), (None, None));
+
+        let e20 = g.add_edge(vs[2], vs[0], 5).unwrap();
+        paths.insert_edge(e20, vs[2], vs[0], 5).unwrap();
+        assert_eq!(paths.path(vs[1]), Some(vec![vs[0], vs[2], vs[1]]));
+        assert_eq!(paths.distance(vs[1]), Some(6));
+
+        assert_eq!(paths.set_edge_cost(e20, 2), Ok(()));
+        assert_eq!(paths.distance(vs[1]), Some(3));
+        assert_eq!(paths.distance(vs[3]), None);
+
+        // an edge already removed is unknown
+        assert_eq!(paths.remove_edge(e10), Err(GraphError::EdgeNotFound(e10)));
+        assert_eq!(paths.set_edge_cost(e10, 1), Err(GraphError::EdgeNotFound(e10)));
+    }
+}
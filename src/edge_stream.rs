@@ -0,0 +1,192 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Lines};
+use std::path::{Path, PathBuf};
+
+/// An edge list kept on disk and read afresh on every pass, for graphs too large to load.
+///
+/// The file holds one edge per line as the numbers of its source and target vertices,
+/// separated by whitespace; anything after them on the line is ignored, as are blank lines and
+/// lines starting with `#` or `%`. Vertices are numbered from zero. The algorithms make
+/// sequential passes over the file and keep memory proportional to the largest vertex number,
+/// never to the number of edges.
+#[derive(Clone, Debug)]
+pub struct EdgeStream {
+    path: PathBuf,
+}
+
+impl EdgeStream {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        EdgeStream { path: path.as_ref().to_path_buf() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Starts a pass over the edges. A line that cannot be read as an edge yields an error of
+    /// kind `io::ErrorKind::InvalidData`.
+    pub fn edges(&self) -> io::Result<StreamedEdges> {
+        Ok(StreamedEdges {
+            lines: BufReader::new(File::open(&self.path)?).lines(),
+            line: 0,
+        })
+    }
+
+    /// Counts the edges leaving and entering each vertex in one pass. A self-loop counts once
+    /// each way.
+    pub fn degrees(&self) -> io::Result<Degrees> {
+        let mut degrees = Degrees {
+            out_degrees: Vec::new(),
+            in_degrees: Vec::new(),
+        };
+        for edge in self.edges()? {
+            let (s, t) = edge?;
+            let bound = s.max(t) + 1;
+            if degrees.out_degrees.len() < bound {
+                degrees.out_degrees.resize(bound, 0);
+                degrees.in_degrees.resize(bound, 0);
+            }
+            degrees.out_degrees[s] += 1;
+            degrees.in_degrees[t] += 1;
+        }
+        Ok(degrees)
+    }
+
+    /// Labels every vertex with the smallest vertex number in its connected component, edges
+    /// being taken as undirected, in one pass with a union-find forest. Vertex numbers below
+    /// the largest one that never appear form components of their own.
+    pub fn connected_components(&self) -> io::Result<Vec<usize>> {
+        let mut parents = Vec::new();
+        for edge in self.edges()? {
+            let (s, t) = edge?;
+            let bound = s.max(t) + 1;
+            if parents.len() < bound {
+                let len = parents.len();
+                parents.extend(len..bound);
+            }
+            let (s, t) = (find(&mut parents, s), find(&mut parents, t));
+            // the smaller root wins, so each root is the least vertex of its tree
+            if s < t {
+                parents[t] = s;
+            } else {
+                parents[s] = t;
+            }
+        }
+        for v in 0..parents.len() {
+            let root = find(&mut parents, v);
+            parents[v] = root;
+        }
+        Ok(parents)
+    }
+}
+
+fn find(parents: &mut [usize], mut v: usize) -> usize {
+    while parents[v] != v {
+        parents[v] = parents[parents[v]];
+        v = parents[v];
+    }
+    v
+}
+
+/// A pass over the edges of an `EdgeStream`, as pairs of vertex numbers.
+pub struct StreamedEdges {
+    lines: Lines<BufReader<File>>,
+    line: usize,
+}
+
+impl Iterator for StreamedEdges {
+    type Item = io::Result<(usize, usize)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e)),
+            };
+            self.line += 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with('%') {
+                continue;
+            }
+            let mut fields = line.split_whitespace().map(|f| f.parse::<usize>());
+            return Some(match (fields.next(), fields.next()) {
+                (Some(Ok(s)), Some(Ok(t))) => Ok((s, t)),
+                _ => Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: expected two vertex numbers", self.line),
+                )),
+            });
+        }
+    }
+}
+
+/// The degrees of the vertices of an `EdgeStream`, indexed by vertex number.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Degrees {
+    out_degrees: Vec<usize>,
+    in_degrees: Vec<usize>,
+}
+
+impl Degrees {
+    /// One more than the largest vertex number seen.
+    pub fn vertex_bound(&self) -> usize {
+        self.out_degrees.len()
+    }
+
+    pub fn out_degree(&self, v: usize) -> usize {
+        self.out_degrees.get(v).cloned().unwrap_or(0)
+    }
+
+    pub fn in_degree(&self, v: usize) -> usize {
+        self.in_degrees.get(v).cloned().unwrap_or(0)
+    }
+
+    pub fn degree(&self, v: usize) -> usize {
+        self.out_degree(v) + self.in_degree(v)
+    }
+
+    pub fn out_degrees(&self) -> &[usize] {
+        &self.out_degrees
+    }
+
+    pub fn in_degrees(&self) -> &[usize] {
+        &self.in_degrees
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EdgeStream;
+
+    #[test]
+    fn edge_stream() {
+        use std::env;
+        use std::fs;
+        use std::io;
+        use std::process;
+
+        // 0 --> 1 --> 2    3 <-- 5    4    6 (self-loop)
+        //       ^     |
+        //       +-----+
+
+        let path = env::temp_dir().join(format!("graph-edge-stream-{}.txt", process::id()));
+        fs::write(&path, "# source target\n0 1\n1 2 0.5\n\n2 1\n5 3\n6 6\n").unwrap();
+        let stream = EdgeStream::new(&path);
+
+        assert_eq!(stream.edges().unwrap().count(), 5);
+        let degrees = stream.degrees().unwrap();
+        assert_eq!(degrees.vertex_bound(), 7);
+        assert_eq!(degrees.out_degrees(), &[1, 1, 1, 0, 0, 1, 1]);
+        assert_eq!(degrees.in_degrees(), &[0, 2, 1, 1, 0, 0, 1]);
+        assert_eq!((degrees.degree(1), degrees.degree(6), degrees.degree(9)), (3, 2, 0));
+        assert_eq!(stream.connected_components().unwrap(), vec![0, 0, 0, 3, 4, 3, 6]);
+
+        fs::write(&path, "0 1\n1 x\n").unwrap();
+        let error = stream.degrees().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(error.to_string().contains("line 2"));
+
+        fs::remove_file(&path).unwrap();
+        assert_eq!(stream.connected_components().unwrap_err().kind(), io::ErrorKind::NotFound);
+    }
+}
@@ -0,0 +1,490 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fmt::Debug;
+
+use fnv::FnvHashMap;
+use num_traits::Zero;
+
+use graph::{Directivity, EdgeDescriptor, Graph, IncidenceGraph, VertexDescriptor,
+            VertexListGraph};
+use property_map::{vertex_index, PropertyMap};
+
+pub trait EditCosts<G0, G1>
+where
+    G0: Graph,
+    G1: Graph,
+{
+    type Cost: Copy + Debug + Ord + Zero;
+
+    fn vertex_substitution(&self, p0: &G0::VertexProperty, p1: &G1::VertexProperty) -> Self::Cost;
+    fn vertex_deletion(&self, p0: &G0::VertexProperty) -> Self::Cost;
+    fn vertex_insertion(&self, p1: &G1::VertexProperty) -> Self::Cost;
+    fn edge_substitution(&self, p0: &G0::EdgeProperty, p1: &G1::EdgeProperty) -> Self::Cost;
+    fn edge_deletion(&self, p0: &G0::EdgeProperty) -> Self::Cost;
+    fn edge_insertion(&self, p1: &G1::EdgeProperty) -> Self::Cost;
+}
+
+/// Charges one for every insertion, deletion and substitution between unequal properties.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UnitCosts;
+
+impl<G0, G1> EditCosts<G0, G1> for UnitCosts
+where
+    G0: Graph,
+    G1: Graph<VertexProperty = G0::VertexProperty, EdgeProperty = G0::EdgeProperty>,
+    G0::VertexProperty: PartialEq,
+    G0::EdgeProperty: PartialEq,
+{
+    type Cost = usize;
+
+    fn vertex_substitution(&self, p0: &G0::VertexProperty, p1: &G1::VertexProperty) -> usize {
+        if p0 == p1 { 0 } else { 1 }
+    }
+
+    fn vertex_deletion(&self, _: &G0::VertexProperty) -> usize {
+        1
+    }
+
+    fn vertex_insertion(&self, _: &G1::VertexProperty) -> usize {
+        1
+    }
+
+    fn edge_substitution(&self, p0: &G0::EdgeProperty, p1: &G1::EdgeProperty) -> usize {
+        if p0 == p1 { 0 } else { 1 }
+    }
+
+    fn edge_deletion(&self, _: &G0::EdgeProperty) -> usize {
+        1
+    }
+
+    fn edge_insertion(&self, _: &G1::EdgeProperty) -> usize {
+        1
+    }
+}
+
+pub type EditPath = Vec<(Option<VertexDescriptor>, Option<VertexDescriptor>)>;
+
+struct Operand<'a, G>
+where
+    G: 'a,
+{
+    graph: &'a G,
+    directed: bool,
+    vertices: Vec<VertexDescriptor>,
+    edges: FnvHashMap<(usize, usize), Vec<EdgeDescriptor>>,
+}
+
+impl<'a, G> Operand<'a, G>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+{
+    fn new(graph: &'a G) -> Self {
+        let directed = G::Directivity::is_directed();
+        let vertices = graph.vertices().collect::<Vec<_>>();
+        let index = vertex_index(graph, &vertices);
+        let mut edges = FnvHashMap::default();
+        for (i, &v) in vertices.iter().enumerate() {
+            for e in graph.out_edges(v) {
+                if let Some(&j) = graph.opposite(e, v).and_then(|t| index.get(t)) {
+                    let key = if directed || i <= j { (i, j) } else { (j, i) };
+                    edges.entry(key).or_insert_with(Vec::new).push(e);
+                }
+            }
+        }
+        Operand {
+            graph,
+            directed,
+            vertices,
+            edges,
+        }
+    }
+
+    fn edges_between(&self, i: usize, j: usize) -> &[EdgeDescriptor] {
+        let key = if self.directed || i <= j { (i, j) } else { (j, i) };
+        self.edges.get(&key).map_or(&[], |es| es.as_slice())
+    }
+
+    fn vertex_property(&self, i: usize) -> &G::VertexProperty {
+        self.graph.vertex_property(self.vertices[i]).unwrap()
+    }
+
+    fn edge_property(&self, e: EdgeDescriptor) -> &G::EdgeProperty {
+        self.graph.edge_property(e).unwrap()
+    }
+}
+
+// The least cost of matching `a` items against `b` items, each item of either side matched to
+// one of the other side or dropped, by dynamic programming over the subsets of the `b` side.
+fn assignment<C, S, D, I>(a: usize, b: usize, substitution: S, deletion: D, insertion: I) -> C
+where
+    C: Copy + Debug + Ord + Zero,
+    S: Fn(usize, usize) -> C,
+    D: Fn(usize) -> C,
+    I: Fn(usize) -> C,
+{
+    // best[set]: cheapest cost of the items of `a` so far, substituted into exactly `set`
+    let full = 1 << b;
+    let mut best = vec![None; full];
+    best[0] = Some(C::zero());
+    for i in 0..a {
+        let mut next: Vec<Option<C>> = vec![None; full];
+        for (set, &c) in best.iter().enumerate() {
+            let c = match c {
+                Some(c) => c,
+                None => continue,
+            };
+            let mut relax = |to: usize, c: C| {
+                if next[to].is_none_or(|old| c < old) {
+                    next[to] = Some(c);
+                }
+            };
+            relax(set, c + deletion(i));
+            for j in (0..b).filter(|&j| set & (1 << j) == 0) {
+                relax(set | (1 << j), c + substitution(i, j));
+            }
+        }
+        best = next;
+    }
+    best.iter()
+        .enumerate()
+        .filter_map(|(set, c)| {
+            c.map(|c| {
+                (0..b)
+                    .filter(|&j| set & (1 << j) == 0)
+                    .fold(c, |c, j| c + insertion(j))
+            })
+        })
+        .min()
+        .unwrap()
+}
+
+#[derive(Clone)]
+struct State<C> {
+    estimate: C,
+    cost: C,
+    mapping: Vec<Option<usize>>,
+    used: Vec<bool>,
+    complete: bool,
+}
+
+impl<C: Ord> PartialEq for State<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.estimate == other.estimate
+    }
+}
+
+impl<C: Ord> Eq for State<C> {}
+
+impl<C: Ord> PartialOrd for State<C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<C: Ord> Ord for State<C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.cmp(&self.estimate)
+    }
+}
+
+struct Search<'a, 'b, 'c, G0, G1, E>
+where
+    G0: 'a,
+    G1: 'b,
+    E: 'c,
+{
+    first: Operand<'a, G0>,
+    second: Operand<'b, G1>,
+    costs: &'c E,
+}
+
+impl<'a, 'b, 'c, G0, G1, E> Search<'a, 'b, 'c, G0, G1, E>
+where
+    G0: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G0::Directivity: Directivity,
+    G1: IncidenceGraph<'b> + VertexListGraph<'b>,
+    G1::Directivity: Directivity,
+    E: EditCosts<G0, G1>,
+{
+    // Pairs up the parallel edges of a mapped vertex pair at the least cost, trying every way
+    // of substituting them into the edges on the shorter side, whose number is seldom more
+    // than a few.
+    fn pair_cost(&self, es0: &[EdgeDescriptor], es1: &[EdgeDescriptor]) -> E::Cost {
+        let substitution = |i: usize, j: usize| {
+            self.costs.edge_substitution(
+                self.first.edge_property(es0[i]),
+                self.second.edge_property(es1[j]),
+            )
+        };
+        let deletion = |i: usize| self.costs.edge_deletion(self.first.edge_property(es0[i]));
+        let insertion = |j: usize| self.costs.edge_insertion(self.second.edge_property(es1[j]));
+        if es1.len() <= es0.len() {
+            assignment(es0.len(), es1.len(), substitution, deletion, insertion)
+        } else {
+            assignment(es1.len(), es0.len(), |j, i| substitution(i, j), insertion, deletion)
+        }
+    }
+
+    fn root(&self) -> State<E::Cost> {
+        let mut state = State {
+            estimate: E::Cost::zero(),
+            cost: E::Cost::zero(),
+            mapping: Vec::new(),
+            used: vec![false; self.second.vertices.len()],
+            complete: false,
+        };
+        state.estimate = self.heuristic(&state);
+        state
+    }
+
+    fn heuristic(&self, state: &State<E::Cost>) -> E::Cost {
+        if state.complete {
+            return E::Cost::zero();
+        }
+        let free = (0..self.second.vertices.len())
+            .filter(|&j| !state.used[j])
+            .collect::<Vec<_>>();
+        let remaining = self.first.vertices.len() - state.mapping.len();
+
+        let mut estimate = E::Cost::zero();
+        for i in state.mapping.len()..self.first.vertices.len() {
+            let p0 = self.first.vertex_property(i);
+            let deletion = self.costs.vertex_deletion(p0);
+            estimate = estimate +
+                free.iter()
+                    .map(|&j| {
+                        self.costs.vertex_substitution(p0, self.second.vertex_property(j))
+                    })
+                    .fold(deletion, |a, b| if b < a { b } else { a });
+        }
+        if free.len() > remaining {
+            let mut insertions = free.iter()
+                .map(|&j| self.costs.vertex_insertion(self.second.vertex_property(j)))
+                .collect::<Vec<_>>();
+            insertions.sort();
+            for &c in insertions.iter().take(free.len() - remaining) {
+                estimate = estimate + c;
+            }
+        }
+        estimate
+    }
+
+    fn successors(&self, state: &State<E::Cost>) -> Vec<State<E::Cost>> {
+        let k = state.mapping.len();
+        if k == self.first.vertices.len() {
+            if state.complete {
+                return Vec::new();
+            }
+            let mut next = state.clone();
+            next.complete = true;
+            for (j, &used) in state.used.iter().enumerate() {
+                if !used {
+                    next.cost = next.cost +
+                        self.costs.vertex_insertion(self.second.vertex_property(j));
+                }
+            }
+            for (&(a, b), es1) in &self.second.edges {
+                if !state.used[a] || !state.used[b] {
+                    next.cost = next.cost + self.pair_cost(&[], es1);
+                }
+            }
+            next.estimate = next.cost;
+            return vec![next];
+        }
+
+        let p0 = self.first.vertex_property(k);
+        let targets = (0..self.second.vertices.len())
+            .filter(|&j| !state.used[j])
+            .map(Some)
+            .chain(Some(None));
+        targets
+            .map(|target| {
+                let mut next = state.clone();
+                next.mapping.push(target);
+                next.cost = next.cost +
+                    match target {
+                        Some(j) => {
+                            next.used[j] = true;
+                            self.costs.vertex_substitution(p0, self.second.vertex_property(j))
+                        }
+                        None => self.costs.vertex_deletion(p0),
+                    };
+
+                for i in 0..k + 1 {
+                    let mut pairs = vec![(k, i)];
+                    if self.first.directed && i != k {
+                        pairs.push((i, k));
+                    }
+                    for (s, t) in pairs {
+                        let es0 = self.first.edges_between(s, t);
+                        let es1 = match (next.mapping[s], next.mapping[t]) {
+                            (Some(s1), Some(t1)) => self.second.edges_between(s1, t1),
+                            _ => &[],
+                        };
+                        next.cost = next.cost + self.pair_cost(es0, es1);
+                    }
+                }
+
+                next.estimate = next.cost + self.heuristic(&next);
+                next
+            })
+            .collect()
+    }
+
+    fn path(&self, state: &State<E::Cost>) -> EditPath {
+        let mut path = state
+            .mapping
+            .iter()
+            .enumerate()
+            .map(|(i, &j)| (Some(self.first.vertices[i]), j.map(|j| self.second.vertices[j])))
+            .collect::<Vec<_>>();
+        for (j, &used) in state.used.iter().enumerate() {
+            if !used {
+                path.push((None, Some(self.second.vertices[j])));
+            }
+        }
+        path
+    }
+}
+
+pub fn graph_edit_distance<'a, 'b, 'c, G0, G1, E>(
+    g0: &'a G0,
+    g1: &'b G1,
+    costs: &'c E,
+) -> (E::Cost, EditPath)
+where
+    G0: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G0::Directivity: Directivity,
+    G1: IncidenceGraph<'b> + VertexListGraph<'b>,
+    G1::Directivity: Directivity,
+    E: EditCosts<G0, G1>,
+{
+    let search = Search {
+        first: Operand::new(g0),
+        second: Operand::new(g1),
+        costs,
+    };
+
+    let mut fringe = BinaryHeap::new();
+    fringe.push(search.root());
+    while let Some(state) = fringe.pop() {
+        if state.complete {
+            return (state.cost, search.path(&state));
+        }
+        fringe.extend(search.successors(&state));
+    }
+    unreachable!("the search space always contains a complete edit path")
+}
+
+/// Bounds the edit distance from above with a beam search that keeps only the `beam_width`
+/// most promising partial mappings at each step. Wider beams are slower and closer to exact.
+pub fn approximate_graph_edit_distance<'a, 'b, 'c, G0, G1, E>(
+    g0: &'a G0,
+    g1: &'b G1,
+    costs: &'c E,
+    beam_width: usize,
+) -> (E::Cost, EditPath)
+where
+    G0: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G0::Directivity: Directivity,
+    G1: IncidenceGraph<'b> + VertexListGraph<'b>,
+    G1::Directivity: Directivity,
+    E: EditCosts<G0, G1>,
+{
+    let search = Search {
+        first: Operand::new(g0),
+        second: Operand::new(g1),
+        costs,
+    };
+
+    let mut beam = vec![search.root()];
+    for _ in 0..search.first.vertices.len() + 1 {
+        let mut next = beam.iter()
+            .flat_map(|state| search.successors(state))
+            .collect::<Vec<_>>();
+        next.sort_by_key(|state| state.estimate);
+        next.truncate(beam_width.max(1));
+        beam = next;
+    }
+
+    let best = beam.iter().min_by(|a, b| a.cost.cmp(&b.cost)).unwrap();
+    (best.cost, search.path(best))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{approximate_graph_edit_distance, graph_edit_distance, UnitCosts};
+
+    #[test]
+    fn edit_distance() {
+        use graph::{MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        // g0:              g1:
+        // a --- b --- c    x --- y
+        //                   \   /
+        //                     z --- w
+
+        let mut g0 = IncidenceList::<Undirected, _, _>::new();
+        let a = g0.add_vertex("a");
+        let b = g0.add_vertex("b");
+        let c = g0.add_vertex("c");
+        g0.add_edge(a, b, ()).unwrap();
+        g0.add_edge(b, c, ()).unwrap();
+
+        let mut g1 = IncidenceList::<Undirected, _, _>::new();
+        let x = g1.add_vertex("a");
+        let y = g1.add_vertex("b");
+        let z = g1.add_vertex("c");
+        let w = g1.add_vertex("d");
+        g1.add_edge(x, y, ()).unwrap();
+        g1.add_edge(y, z, ()).unwrap();
+        g1.add_edge(z, x, ()).unwrap();
+        g1.add_edge(z, w, ()).unwrap();
+
+        assert_eq!(graph_edit_distance(&g0, &g0, &UnitCosts).0, 0);
+
+        let (cost, path) = graph_edit_distance(&g0, &g1, &UnitCosts);
+        assert_eq!(cost, 3);
+        assert!(path.contains(&(Some(a), Some(x))));
+        assert!(path.contains(&(Some(b), Some(y))));
+        assert!(path.contains(&(Some(c), Some(z))));
+        assert!(path.contains(&(None, Some(w))));
+
+        assert_eq!(graph_edit_distance(&g1, &g0, &UnitCosts).0, 3);
+        assert_eq!(approximate_graph_edit_distance(&g0, &g1, &UnitCosts, 100).0, 3);
+        assert!(approximate_graph_edit_distance(&g0, &g1, &UnitCosts, 1).0 >= 3);
+    }
+
+    #[test]
+    fn edit_distance_of_multigraphs() {
+        use graph::{Directed, MutableGraph};
+        use incidence_list::IncidenceList;
+
+        // g0:                g1:
+        // a ==1, 2, 3==> b   x ==3, 1==> y
+        //
+        // the parallel edges are stored in a different order, so pairing them up in that
+        // order would substitute both edges of g1
+
+        let mut g0 = IncidenceList::<Directed, _, _>::new();
+        let a = g0.add_vertex("a");
+        let b = g0.add_vertex("b");
+        for &p in &[1, 2, 3] {
+            g0.add_edge(a, b, p).unwrap();
+        }
+
+        let mut g1 = IncidenceList::<Directed, _, _>::new();
+        let x = g1.add_vertex("a");
+        let y = g1.add_vertex("b");
+        for &p in &[3, 1] {
+            g1.add_edge(x, y, p).unwrap();
+        }
+
+        let (cost, path) = graph_edit_distance(&g0, &g1, &UnitCosts);
+        assert_eq!(cost, 1);
+        assert!(path.contains(&(Some(a), Some(x))) && path.contains(&(Some(b), Some(y))));
+        assert_eq!(graph_edit_distance(&g1, &g0, &UnitCosts).0, 1);
+    }
+}
@@ -0,0 +1,43 @@
+use std::error::Error;
+use std::fmt;
+
+use graph::{EdgeDescriptor, VertexDescriptor};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GraphError {
+    VertexNotFound(VertexDescriptor),
+    EdgeNotFound(EdgeDescriptor),
+    NotAdjacent(VertexDescriptor, VertexDescriptor),
+    InconsistentHeuristic(VertexDescriptor, VertexDescriptor),
+    NegativeEdgeCost(EdgeDescriptor),
+    CorruptedVertex(VertexDescriptor),
+    CorruptedEdge(EdgeDescriptor),
+    Cyclic(VertexDescriptor),
+    NegativeCycle(VertexDescriptor),
+    Undirected,
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            GraphError::VertexNotFound(d) => write!(f, "vertex {} not found", d),
+            GraphError::EdgeNotFound(d) => write!(f, "edge {} not found", d),
+            GraphError::NotAdjacent(s, t) => write!(f, "no edge from {} to {}", s, t),
+            GraphError::InconsistentHeuristic(s, t) => {
+                write!(f, "heuristic is inconsistent on edge from {} to {}", s, t)
+            }
+            GraphError::NegativeEdgeCost(d) => write!(
+                f,
+                "edge {} has a negative cost; use Bellman-Ford for negative weights",
+                d
+            ),
+            GraphError::CorruptedVertex(d) => write!(f, "incidence lists of {} are corrupted", d),
+            GraphError::CorruptedEdge(d) => write!(f, "links of {} are corrupted", d),
+            GraphError::Cyclic(d) => write!(f, "graph has a cycle through {}", d),
+            GraphError::NegativeCycle(d) => write!(f, "graph has a negative cycle through {}", d),
+            GraphError::Undirected => write!(f, "graph must be directed"),
+        }
+    }
+}
+
+impl Error for GraphError {}
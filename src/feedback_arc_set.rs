@@ -0,0 +1,138 @@
+use error::GraphError;
+use graph::{Directivity, EdgeDescriptor, IncidenceGraph, VertexListGraph};
+use property_map::{vertex_index, PropertyMap};
+
+/// Orders the vertices with the greedy heuristic of Eades, Lin and Smyth and returns the edges
+/// pointing backwards in that order, self-loops included. Removing them leaves the graph
+/// acyclic. Fails with `GraphError::Undirected` on an undirected graph.
+pub fn feedback_arc_set<'a, G>(graph: &'a G) -> Result<Vec<EdgeDescriptor>, GraphError>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+{
+    if !G::Directivity::is_directed() {
+        return Err(GraphError::Undirected);
+    }
+    let vertices = graph.vertices().collect::<Vec<_>>();
+    let index = vertex_index(graph, &vertices);
+
+    let mut arcs = Vec::new();
+    let mut successors = vec![Vec::new(); vertices.len()];
+    let mut predecessors = vec![Vec::new(); vertices.len()];
+    for (i, &v) in vertices.iter().enumerate() {
+        for e in graph.out_edges(v) {
+            if let Some(&j) = graph.opposite(e, v).and_then(|t| index.get(t)) {
+                arcs.push((e, i, j));
+                if i != j {
+                    successors[i].push(j);
+                    predecessors[j].push(i);
+                }
+            }
+        }
+    }
+
+    let mut out_degree = successors.iter().map(|s| s.len() as isize).collect::<Vec<_>>();
+    let mut in_degree = predecessors.iter().map(|p| p.len() as isize).collect::<Vec<_>>();
+    let mut removed = vec![false; vertices.len()];
+    let mut head = Vec::new();
+    let mut tail = Vec::new();
+    for _ in 0..vertices.len() {
+        let remaining = (0..vertices.len()).filter(|&i| !removed[i]);
+        let i = match remaining.clone().find(|&i| out_degree[i] == 0) {
+            Some(sink) => {
+                tail.push(sink);
+                sink
+            }
+            None => {
+                let i = remaining
+                    .clone()
+                    .find(|&i| in_degree[i] == 0)
+                    .or_else(|| remaining.max_by_key(|&i| out_degree[i] - in_degree[i]))
+                    .unwrap();
+                head.push(i);
+                i
+            }
+        };
+        removed[i] = true;
+        for &j in &successors[i] {
+            in_degree[j] -= 1;
+        }
+        for &j in &predecessors[i] {
+            out_degree[j] -= 1;
+        }
+    }
+
+    let mut position = vec![0; vertices.len()];
+    for (k, &i) in head.iter().chain(tail.iter().rev()).enumerate() {
+        position[i] = k;
+    }
+    Ok(arcs
+        .into_iter()
+        .filter(|&(_, i, j)| position[i] >= position[j])
+        .map(|(e, _, _)| e)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::feedback_arc_set;
+
+    #[test]
+    fn feedback_arcs() {
+        use graph::{Directed, IncidenceGraph, MutableGraph};
+        use incidence_list::IncidenceList;
+        use topological_sort::topological_sort;
+
+        //    +-----------------+
+        //    v                 |
+        // v0 --> v1 --> v2 --> v3
+        //        ^      |
+        //        +------+
+        //
+        // plus a self-loop on v0
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+        assert!(feedback_arc_set(&g).unwrap().is_empty());
+        let vs = (0..4).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        g.add_edge(vs[0], vs[1], ()).unwrap();
+        let e12 = g.add_edge(vs[1], vs[2], ()).unwrap();
+        g.add_edge(vs[2], vs[3], ()).unwrap();
+        g.add_edge(vs[3], vs[0], ()).unwrap();
+        g.add_edge(vs[2], vs[1], ()).unwrap();
+        let e00 = g.add_edge(vs[0], vs[0], ()).unwrap();
+
+        let mut arcs = feedback_arc_set(&g).unwrap();
+        arcs.sort();
+        assert_eq!(arcs, vec![e12, e00]);
+
+        for e in arcs {
+            g.remove_edge(e);
+        }
+        assert!(feedback_arc_set(&g).unwrap().is_empty());
+
+        // v4 <-> v5    v6 --> v7    v8
+        //
+        // beside them, a two-cycle loses one edge, and nothing of a path or a lone vertex
+        let ws = (0..5).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t) in &[(0, 1), (1, 0), (2, 3)] {
+            g.add_edge(ws[s], ws[t], ()).unwrap();
+        }
+        let arcs = feedback_arc_set(&g).unwrap();
+        assert_eq!(arcs.len(), 1);
+        assert!(g.source(arcs[0]) == ws[0] || g.source(arcs[0]) == ws[1]);
+        g.remove_edge(arcs[0]);
+        assert!(topological_sort(&g).is_ok());
+    }
+
+    #[test]
+    fn feedback_arcs_undirected() {
+        use error::GraphError;
+        use graph::{MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        let mut g = IncidenceList::<Undirected, _, _>::new();
+        let vs = (0..2).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        g.add_edge(vs[0], vs[1], ()).unwrap();
+        assert_eq!(feedback_arc_set(&g), Err(GraphError::Undirected));
+    }
+}
@@ -0,0 +1,334 @@
+use std::cmp::Ordering;
+use std::fmt::Debug;
+
+use num_traits::Zero;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use error::GraphError;
+use graph::{Directivity, EdgeDescriptor, EdgeListGraph, IncidenceGraph, VertexDescriptor,
+            VertexListGraph};
+use property_map::{vertex_index, PropertyMap, VecPropertyMap};
+
+/// Rows of pivots processed together; their rows are kept aside while the rest is updated.
+const BLOCK_SIZE: usize = 64;
+
+pub struct AllPairsShortestPaths<C> {
+    vertices: Vec<VertexDescriptor>,
+    index: VecPropertyMap<VertexDescriptor, usize>,
+    distances: Vec<Option<C>>,
+    // the vertex before the last on each shortest path, empty unless paths were asked for
+    parents: Vec<Option<usize>>,
+}
+
+impl<C> AllPairsShortestPaths<C>
+where
+    C: Copy,
+{
+    /// Returns `None` if `v` is unreachable from `u` or either is unknown.
+    pub fn distance(&self, u: VertexDescriptor, v: VertexDescriptor) -> Option<C> {
+        let i = *self.index.get(u)?;
+        let j = *self.index.get(v)?;
+        self.distances[i * self.vertices.len() + j]
+    }
+
+    /// Returns the vertex preceding `v` on a shortest path from `u`, or `None` if there is no
+    /// such path, `u` is `v`, or the paths were not kept.
+    pub fn predecessor(
+        &self,
+        u: VertexDescriptor,
+        v: VertexDescriptor,
+    ) -> Option<VertexDescriptor> {
+        let i = *self.index.get(u)?;
+        let j = *self.index.get(v)?;
+        let p = (*self.parents.get(i * self.vertices.len() + j)?)?;
+        Some(self.vertices[p])
+    }
+
+    /// Returns the vertices of a shortest path from `u` to `v`, both included. Returns `None`
+    /// if there is no such path or the paths were not kept by `floyd_warshall_with_paths`.
+    pub fn path(
+        &self,
+        u: VertexDescriptor,
+        v: VertexDescriptor,
+    ) -> Option<Vec<VertexDescriptor>> {
+        if self.parents.is_empty() {
+            return None;
+        }
+        let i = *self.index.get(u)?;
+        let mut j = *self.index.get(v)?;
+        let n = self.vertices.len();
+        self.distances[i * n + j]?;
+        let mut path = vec![self.vertices[j]];
+        while j != i {
+            j = self.parents[i * n + j]?;
+            path.push(self.vertices[j]);
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+fn relax<C>(
+    row: &mut [Option<C>],
+    parents: &mut [Option<usize>],
+    pivots: &[Option<C>],
+    pivot_parents: &[Option<usize>],
+    first: usize,
+    n: usize,
+) where
+    C: Copy + Debug + Ord + Zero,
+{
+    for (p, pivot) in pivots.chunks(n).enumerate() {
+        let through = match row[first + p] {
+            Some(c) => c,
+            None => continue,
+        };
+        for (j, &step) in pivot.iter().enumerate() {
+            if let Some(step) = step {
+                if row[j].is_none_or(|old| through + step < old) {
+                    row[j] = Some(through + step);
+                    if !parents.is_empty() {
+                        parents[j] = pivot_parents[p * n + j];
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "rayon")]
+fn relax_rows<C>(
+    distances: &mut [Option<C>],
+    parents: &mut [Option<usize>],
+    pivots: &[Option<C>],
+    pivot_parents: &[Option<usize>],
+    first: usize,
+    n: usize,
+) where
+    C: Copy + Debug + Ord + Zero + Send + Sync,
+{
+    if parents.is_empty() {
+        distances
+            .par_chunks_mut(n)
+            .for_each(|row| relax(row, &mut [], pivots, pivot_parents, first, n));
+    } else {
+        distances
+            .par_chunks_mut(n)
+            .zip(parents.par_chunks_mut(n))
+            .for_each(|(row, parents)| relax(row, parents, pivots, pivot_parents, first, n));
+    }
+}
+
+#[cfg(not(feature = "rayon"))]
+fn relax_rows<C>(
+    distances: &mut [Option<C>],
+    parents: &mut [Option<usize>],
+    pivots: &[Option<C>],
+    pivot_parents: &[Option<usize>],
+    first: usize,
+    n: usize,
+) where
+    C: Copy + Debug + Ord + Zero + Send + Sync,
+{
+    for (i, row) in distances.chunks_mut(n).enumerate() {
+        let parents = parents.get_mut(i * n..(i + 1) * n).unwrap_or(&mut []);
+        relax(row, parents, pivots, pivot_parents, first, n);
+    }
+}
+
+// Splits the rows of a matrix into those above row `first`, rows `first` to `last`, and those
+// below, any of which may be empty if the matrix is.
+fn split_rows<T>(
+    matrix: &mut [T],
+    first: usize,
+    last: usize,
+    n: usize,
+) -> (&mut [T], &mut [T], &mut [T]) {
+    if matrix.is_empty() {
+        return (&mut [], &mut [], &mut []);
+    }
+    let (above, rest) = matrix.split_at_mut(first * n);
+    let (block, below) = rest.split_at_mut((last - first) * n);
+    (above, block, below)
+}
+
+/// Computes the distances between all pairs of vertices. Pivots are taken in blocks: the rows
+/// of a block are brought up to date first, and then every other row is relaxed through the
+/// whole block at once, in parallel with the `rayon` feature. Negative costs are allowed, but a
+/// negative cycle fails with `GraphError::NegativeCycle`.
+pub fn floyd_warshall<'a, G, C, F>(
+    graph: &'a G,
+    edge_cost: F,
+) -> Result<AllPairsShortestPaths<C>, GraphError>
+where
+    G: EdgeListGraph<'a> + IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+    C: Copy + Debug + Ord + Zero + Send + Sync,
+    F: Fn(&EdgeDescriptor, &G) -> C,
+{
+    all_pairs(graph, edge_cost, false)
+}
+
+/// Like `floyd_warshall`, but also keeps a predecessor matrix from which `path` and
+/// `predecessor` reconstruct the shortest paths, at the cost of a second n × n matrix.
+pub fn floyd_warshall_with_paths<'a, G, C, F>(
+    graph: &'a G,
+    edge_cost: F,
+) -> Result<AllPairsShortestPaths<C>, GraphError>
+where
+    G: EdgeListGraph<'a> + IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+    C: Copy + Debug + Ord + Zero + Send + Sync,
+    F: Fn(&EdgeDescriptor, &G) -> C,
+{
+    all_pairs(graph, edge_cost, true)
+}
+
+fn all_pairs<'a, G, C, F>(
+    graph: &'a G,
+    edge_cost: F,
+    with_paths: bool,
+) -> Result<AllPairsShortestPaths<C>, GraphError>
+where
+    G: EdgeListGraph<'a> + IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+    C: Copy + Debug + Ord + Zero + Send + Sync,
+    F: Fn(&EdgeDescriptor, &G) -> C,
+{
+    let vertices = graph.vertices().collect::<Vec<_>>();
+    let index = vertex_index(graph, &vertices);
+    let n = vertices.len();
+
+    let mut distances = vec![None; n * n];
+    let mut parents = if with_paths { vec![None; n * n] } else { Vec::new() };
+    for i in 0..n {
+        distances[i * n + i] = Some(C::zero());
+    }
+    for e in graph.edges() {
+        let (i, j) = match graph.edge_endpoints(e) {
+            Some((s, t)) => match (index.get(s), index.get(t)) {
+                (Some(&i), Some(&j)) => (i, j),
+                _ => continue,
+            },
+            None => continue,
+        };
+        let c = edge_cost(&e, graph);
+        let mut pairs = vec![(i, j)];
+        if !G::Directivity::is_directed() {
+            pairs.push((j, i));
+        }
+        for (s, t) in pairs {
+            if distances[s * n + t].is_none_or(|old| c < old) {
+                distances[s * n + t] = Some(c);
+                if with_paths {
+                    parents[s * n + t] = Some(s);
+                }
+            }
+        }
+    }
+
+    for first in (0..n).step_by(BLOCK_SIZE) {
+        let last = (first + BLOCK_SIZE).min(n);
+        for k in first..last {
+            let (above, pivot, below) = split_rows(&mut distances, k, k + 1, n);
+            let (parents_above, pivot_parents, parents_below) =
+                split_rows(&mut parents, k, k + 1, n);
+            for i in first..last {
+                let (rows, parent_rows, r) = match i.cmp(&k) {
+                    Ordering::Less => (&mut above[..], &mut parents_above[..], i),
+                    Ordering::Equal => continue,
+                    Ordering::Greater => (&mut below[..], &mut parents_below[..], i - k - 1),
+                };
+                let row_parents = parent_rows.get_mut(r * n..(r + 1) * n).unwrap_or(&mut []);
+                relax(&mut rows[r * n..(r + 1) * n], row_parents, pivot, pivot_parents, k, n);
+            }
+        }
+
+        let pivots = distances[first * n..last * n].to_vec();
+        let pivot_parents = parents.get(first * n..last * n).unwrap_or(&[]).to_vec();
+        let (above, _, below) = split_rows(&mut distances, first, last, n);
+        let (parents_above, _, parents_below) = split_rows(&mut parents, first, last, n);
+        relax_rows(above, parents_above, &pivots, &pivot_parents, first, n);
+        relax_rows(below, parents_below, &pivots, &pivot_parents, first, n);
+    }
+
+    if let Some(i) = (0..n).find(|&i| distances[i * n + i].is_some_and(|c| c < C::zero())) {
+        return Err(GraphError::NegativeCycle(vertices[i]));
+    }
+    Ok(AllPairsShortestPaths {
+        vertices,
+        index,
+        distances,
+        parents,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{floyd_warshall, floyd_warshall_with_paths};
+
+    #[test]
+    fn all_pairs_shortest_paths() {
+        use error::GraphError;
+        use graph::{Directed, Graph, MutableGraph};
+        use incidence_list::IncidenceList;
+
+        // a ring of 150 vertices with unit edges, plus one chord
+        // v0 --(5)--> v100
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+        let vs = (0..150).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for i in 0..150 {
+            g.add_edge(vs[i], vs[(i + 1) % 150], 1).unwrap();
+        }
+        g.add_edge(vs[0], vs[100], 5).unwrap();
+        let cost = |e: &_, g: &IncidenceList<Directed, (), i32>| *g.edge_property(*e).unwrap();
+
+        let apsp = floyd_warshall(&g, cost).unwrap();
+        assert_eq!(apsp.distance(vs[0], vs[0]), Some(0));
+        assert_eq!(apsp.distance(vs[0], vs[99]), Some(99));
+        assert_eq!(apsp.distance(vs[0], vs[101]), Some(6));
+        assert_eq!(apsp.distance(vs[120], vs[110]), Some(45));
+        assert_eq!(apsp.distance(vs[1], vs[0]), Some(149));
+        assert_eq!(apsp.path(vs[0], vs[101]), None);
+
+        let apsp = floyd_warshall_with_paths(&g, cost).unwrap();
+        assert_eq!(apsp.distance(vs[0], vs[101]), Some(6));
+        assert_eq!(apsp.path(vs[0], vs[101]), Some(vec![vs[0], vs[100], vs[101]]));
+        assert_eq!(apsp.predecessor(vs[0], vs[100]), Some(vs[0]));
+        assert_eq!(apsp.path(vs[5], vs[5]), Some(vec![vs[5]]));
+        assert_eq!(apsp.path(vs[120], vs[110]).map(|p| p.len()), Some(42));
+        assert_eq!(apsp.path(vs[1], vs[0]).unwrap()[148..], [vs[149], vs[0]]);
+
+        let e = g.add_edge(vs[1], vs[0], -1).unwrap();
+        assert!(floyd_warshall(&g, cost).is_ok());
+        *g.edge_property_mut(e).unwrap() = -2;
+        assert!(matches!(floyd_warshall(&g, cost), Err(GraphError::NegativeCycle(_))));
+    }
+
+    #[test]
+    fn undirected_paths() {
+        use graph::{Graph, MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        // v0 --1-- v1 --1-- v2    v3
+        //  |                 |
+        //  +--------3--------+
+
+        let mut g = IncidenceList::<Undirected, (), _>::new();
+        let vs = (0..4).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        g.add_edge(vs[0], vs[1], 1).unwrap();
+        g.add_edge(vs[1], vs[2], 1).unwrap();
+        g.add_edge(vs[2], vs[0], 3).unwrap();
+        let cost = |e: &_, g: &IncidenceList<Undirected, (), i32>| *g.edge_property(*e).unwrap();
+
+        let apsp = floyd_warshall_with_paths(&g, cost).unwrap();
+        assert_eq!(apsp.distance(vs[2], vs[0]), Some(2));
+        assert_eq!(apsp.path(vs[2], vs[0]), Some(vec![vs[2], vs[1], vs[0]]));
+        assert_eq!(apsp.path(vs[0], vs[2]), Some(vec![vs[0], vs[1], vs[2]]));
+        assert_eq!(apsp.distance(vs[0], vs[3]), None);
+        assert_eq!(apsp.path(vs[0], vs[3]), None);
+        assert_eq!(apsp.predecessor(vs[0], vs[3]), None);
+    }
+}
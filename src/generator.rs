@@ -0,0 +1,127 @@
+use fnv::FnvHashSet;
+use rand::Rng;
+
+use graph::{Directivity, MutableGraph, VertexDescriptor};
+use incidence_list::IncidenceList;
+
+fn add_vertices<D, VP, EP, R, FV>(
+    graph: &mut IncidenceList<D, VP, EP>,
+    order: usize,
+    rng: &mut R,
+    mut vertex_property: FV,
+) -> Vec<VertexDescriptor>
+where
+    R: Rng,
+    FV: FnMut(&mut R) -> VP,
+{
+    (0..order)
+        .map(|_| {
+            let property = vertex_property(rng);
+            graph.add_vertex(property)
+        })
+        .collect()
+}
+
+/// Generates a random graph in the G(n, p) model: `order` vertices, and every edge between two
+/// distinct vertices present with probability `p`, in both directions separately if the graph
+/// is directed. The properties of the vertices and edges are drawn by `vertex_property` and
+/// `edge_property` from `rng`, so that a weighted graph comes out ready to search, and the
+/// same seed always gives the same graph.
+pub fn gnp_random_graph<D, VP, EP, R, FV, FE>(
+    order: usize,
+    p: f64,
+    rng: &mut R,
+    vertex_property: FV,
+    mut edge_property: FE,
+) -> IncidenceList<D, VP, EP>
+where
+    D: Directivity,
+    R: Rng,
+    FV: FnMut(&mut R) -> VP,
+    FE: FnMut(&mut R) -> EP,
+{
+    let mut graph = IncidenceList::new();
+    let vertices = add_vertices(&mut graph, order, rng, vertex_property);
+    for i in 0..order {
+        let targets = if D::is_directed() { 0 } else { i + 1 };
+        for j in targets..order {
+            if i != j && rng.gen_bool(p) {
+                let property = edge_property(rng);
+                graph.add_edge(vertices[i], vertices[j], property).unwrap();
+            }
+        }
+    }
+    graph
+}
+
+/// Generates a random graph in the G(n, m) model: `order` vertices and `size` edges between
+/// distinct vertices, chosen uniformly among the graphs with no parallel edges. The edges are
+/// drawn by rejection, which suits sparse graphs. Properties are drawn as in
+/// `gnp_random_graph`. Panics if there are fewer than `size` possible edges.
+pub fn gnm_random_graph<D, VP, EP, R, FV, FE>(
+    order: usize,
+    size: usize,
+    rng: &mut R,
+    vertex_property: FV,
+    mut edge_property: FE,
+) -> IncidenceList<D, VP, EP>
+where
+    D: Directivity,
+    R: Rng,
+    FV: FnMut(&mut R) -> VP,
+    FE: FnMut(&mut R) -> EP,
+{
+    let pairs = order * order.saturating_sub(1);
+    let pairs = if D::is_directed() { pairs } else { pairs / 2 };
+    assert!(size <= pairs, "too many edges for {} vertices", order);
+
+    let mut graph = IncidenceList::new();
+    let vertices = add_vertices(&mut graph, order, rng, vertex_property);
+    let mut chosen = FnvHashSet::default();
+    while chosen.len() < size {
+        let (i, j) = (rng.gen_range(0..order), rng.gen_range(0..order));
+        let pair = if D::is_directed() { (i, j) } else { (i.min(j), i.max(j)) };
+        if i != j && chosen.insert(pair) {
+            let property = edge_property(rng);
+            graph.add_edge(vertices[pair.0], vertices[pair.1], property).unwrap();
+        }
+    }
+    graph
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{gnm_random_graph, gnp_random_graph};
+
+    #[test]
+    fn random_graphs() {
+        use rand::SeedableRng;
+        use rand::rngs::StdRng;
+        use rand::Rng;
+        use graph::{BidirectionalGraph, Directed, EdgeListGraph, Graph, IncidenceGraph, Undirected,
+                    VertexListGraph};
+        use incidence_list::IncidenceList;
+
+        let mut rng = StdRng::seed_from_u64(1);
+        let g: IncidenceList<Directed, u32, u32> =
+            gnp_random_graph(50, 0.1, &mut rng, |r| r.gen_range(0..10), |r| r.gen_range(1..=9));
+        assert_eq!(g.order(), 50);
+        assert!(g.size() > 100 && g.size() < 400);
+        assert!(g.vertices().all(|v| g[v] < 10));
+        assert!(g.edges().all(|e| (1..=9).contains(&g[e]) && !g.is_self_loop(e)));
+
+        let mut again = StdRng::seed_from_u64(1);
+        let h: IncidenceList<Directed, u32, u32> =
+            gnp_random_graph(50, 0.1, &mut again, |r| r.gen_range(0..10), |r| r.gen_range(1..=9));
+        assert_eq!(h.edges().map(|e| h[e]).sum::<u32>(), g.edges().map(|e| g[e]).sum::<u32>());
+
+        let complete: IncidenceList<Undirected, (), ()> =
+            gnm_random_graph(6, 15, &mut rng, |_| (), |_| ());
+        assert_eq!(complete.size(), 15);
+        assert!(complete.vertices().all(|v| complete.degree(v) == 5));
+        let sparse: IncidenceList<Directed, (), f64> =
+            gnm_random_graph(100, 250, &mut rng, |_| (), |r| r.gen::<f64>());
+        assert_eq!((sparse.order(), sparse.size()), (100, 250));
+        assert!(sparse.edges().all(|e| sparse.edge_property(e).is_some_and(|&w| w < 1.0)));
+    }
+}
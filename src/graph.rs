@@ -1,34 +1,109 @@
+use std::fmt;
+use std::num::NonZeroUsize;
+
+use error::GraphError;
+
+#[cfg(test)]
 pub trait FromUsize {
     fn from_usize(v: usize) -> Self;
 }
 
-#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub struct VertexDescriptor(usize);
+/// Tags a descriptor with the number of times its slot has been reused, so that a descriptor
+/// kept across a removal no longer matches the element that took its place. Without the
+/// `generational` feature this is zero-sized and every descriptor compares as current.
+#[cfg(feature = "generational")]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Generation(u32);
+
+#[cfg(not(feature = "generational"))]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Generation(());
+
+impl Generation {
+    #[cfg(feature = "generational")]
+    pub fn next(self) -> Self {
+        Generation(self.0.wrapping_add(1))
+    }
+
+    #[cfg(not(feature = "generational"))]
+    pub fn next(self) -> Self {
+        self
+    }
+}
+
+#[derive(Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct VertexDescriptor(NonZeroUsize, Generation);
+
+impl VertexDescriptor {
+    pub(crate) fn with_generation(v: usize, generation: Generation) -> Self {
+        VertexDescriptor(NonZeroUsize::new(v + 1).unwrap(), generation)
+    }
+
+    pub fn generation(&self) -> Generation {
+        self.1
+    }
+}
 
 impl From<VertexDescriptor> for usize {
     fn from(v: VertexDescriptor) -> Self {
-        v.0
+        v.0.get() - 1
     }
 }
 
+#[cfg(test)]
 impl FromUsize for VertexDescriptor {
     fn from_usize(v: usize) -> Self {
-        VertexDescriptor(v)
+        VertexDescriptor::with_generation(v, Generation::default())
     }
 }
 
-#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub struct EdgeDescriptor(usize);
+impl fmt::Debug for VertexDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("VertexDescriptor").field(&usize::from(*self)).finish()
+    }
+}
+
+impl fmt::Display for VertexDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "v{}", usize::from(*self))
+    }
+}
+
+#[derive(Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct EdgeDescriptor(NonZeroUsize, Generation);
+
+impl EdgeDescriptor {
+    pub(crate) fn with_generation(v: usize, generation: Generation) -> Self {
+        EdgeDescriptor(NonZeroUsize::new(v + 1).unwrap(), generation)
+    }
+
+    pub fn generation(&self) -> Generation {
+        self.1
+    }
+}
 
 impl From<EdgeDescriptor> for usize {
     fn from(v: EdgeDescriptor) -> Self {
-        v.0
+        v.0.get() - 1
     }
 }
 
+#[cfg(test)]
 impl FromUsize for EdgeDescriptor {
     fn from_usize(v: usize) -> Self {
-        EdgeDescriptor(v)
+        EdgeDescriptor::with_generation(v, Generation::default())
+    }
+}
+
+impl fmt::Debug for EdgeDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("EdgeDescriptor").field(&usize::from(*self)).finish()
+    }
+}
+
+impl fmt::Display for EdgeDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "e{}", usize::from(*self))
     }
 }
 
@@ -39,6 +114,14 @@ pub trait Graph {
 
     fn vertex_property(&self, d: VertexDescriptor) -> Option<&Self::VertexProperty>;
     fn edge_property(&self, d: EdgeDescriptor) -> Option<&Self::EdgeProperty>;
+
+    fn contains_vertex(&self, d: VertexDescriptor) -> bool {
+        self.vertex_property(d).is_some()
+    }
+
+    fn contains_edge(&self, d: EdgeDescriptor) -> bool {
+        self.edge_property(d).is_some()
+    }
 }
 
 pub trait IncidenceGraph<'a>: Graph {
@@ -48,12 +131,47 @@ pub trait IncidenceGraph<'a>: Graph {
     fn out_edges(&'a self, d: VertexDescriptor) -> Self::Incidences;
     fn source(&self, d: EdgeDescriptor) -> VertexDescriptor;
     fn target(&self, d: EdgeDescriptor) -> VertexDescriptor;
+    fn edge_endpoints(&self, d: EdgeDescriptor) -> Option<(VertexDescriptor, VertexDescriptor)>;
+
+    fn try_source(&self, d: EdgeDescriptor) -> Option<VertexDescriptor> {
+        self.edge_endpoints(d).map(|(s, _)| s)
+    }
+
+    fn try_target(&self, d: EdgeDescriptor) -> Option<VertexDescriptor> {
+        self.edge_endpoints(d).map(|(_, t)| t)
+    }
+
+    fn opposite(&self, e: EdgeDescriptor, v: VertexDescriptor) -> Option<VertexDescriptor> {
+        self.edge_endpoints(e).and_then(|(s, t)| if s == v {
+            Some(t)
+        } else if t == v {
+            Some(s)
+        } else {
+            None
+        })
+    }
+
+    fn is_self_loop(&self, d: EdgeDescriptor) -> bool {
+        self.edge_endpoints(d).is_some_and(|(s, t)| s == t)
+    }
 }
 
 pub trait BidirectionalGraph<'a>: IncidenceGraph<'a> {
+    type Neighbors: Iterator<Item = VertexDescriptor>;
+
+    /// Returns the number of edges incident to `d`. A self-loop is counted twice, once as an
+    /// out-edge and once as an in-edge, on directed and undirected graphs alike.
     fn degree(&self, d: VertexDescriptor) -> usize;
     fn in_degree(&self, d: VertexDescriptor) -> usize;
     fn in_edges(&'a self, d: VertexDescriptor) -> Self::Incidences;
+    fn neighbors_directed(&'a self, d: VertexDescriptor, direction: Direction) -> Self::Neighbors;
+
+    fn edges_directed(&'a self, d: VertexDescriptor, direction: Direction) -> Self::Incidences {
+        match direction {
+            Direction::Outgoing => self.out_edges(d),
+            Direction::Incoming => self.in_edges(d),
+        }
+    }
 }
 
 pub trait AdjacencyGraph<'a>: Graph {
@@ -66,6 +184,7 @@ pub trait VertexListGraph<'a>: Graph {
     type Vertices: Iterator<Item = VertexDescriptor>;
 
     fn order(&self) -> usize;
+    fn vertex_bound(&self) -> usize;
     fn vertices(&'a self) -> Self::Vertices;
 }
 
@@ -73,6 +192,7 @@ pub trait EdgeListGraph<'a>: Graph {
     type Edges: Iterator<Item = EdgeDescriptor>;
 
     fn size(&self) -> usize;
+    fn edge_bound(&self) -> usize;
     fn edges(&'a self) -> Self::Edges;
 }
 
@@ -87,13 +207,34 @@ pub trait MutableGraph: Graph {
         source: VertexDescriptor,
         target: VertexDescriptor,
         property: Self::EdgeProperty,
-    ) -> Option<EdgeDescriptor>;
+    ) -> Result<EdgeDescriptor, GraphError>;
     fn remove_vertex(&mut self, d: VertexDescriptor) -> Option<Self::VertexProperty>;
     fn remove_edge(&mut self, d: EdgeDescriptor) -> Option<Self::EdgeProperty>;
+    fn retain_vertices<F>(&mut self, f: F)
+    where
+        F: FnMut(&Self, VertexDescriptor) -> bool;
+    fn retain_edges<F>(&mut self, f: F)
+    where
+        F: FnMut(&Self, EdgeDescriptor) -> bool;
     fn vertex_property_mut(&mut self, d: VertexDescriptor) -> Option<&mut Self::VertexProperty>;
     fn edge_property_mut(&mut self, d: EdgeDescriptor) -> Option<&mut Self::EdgeProperty>;
 }
 
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Direction {
+    Outgoing,
+    Incoming,
+}
+
+impl Direction {
+    pub fn opposite(self) -> Self {
+        match self {
+            Direction::Outgoing => Direction::Incoming,
+            Direction::Incoming => Direction::Outgoing,
+        }
+    }
+}
+
 pub trait Directivity {
     fn is_directed() -> bool;
 }
@@ -115,3 +256,29 @@ impl Directivity for Undirected {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn descriptor() {
+        use std::mem::size_of;
+        use super::{EdgeDescriptor, FromUsize, VertexDescriptor};
+
+        if cfg!(not(feature = "generational")) {
+            assert_eq!(size_of::<Option<VertexDescriptor>>(), size_of::<usize>());
+            assert_eq!(size_of::<Option<EdgeDescriptor>>(), size_of::<usize>());
+        }
+
+        let v = VertexDescriptor::from_usize(0);
+        assert_eq!(usize::from(v), 0);
+        assert_eq!(format!("{:?}", v), "VertexDescriptor(0)");
+        assert_eq!(format!("{}", v), "v0");
+
+        let e = EdgeDescriptor::from_usize(42);
+        assert_eq!(usize::from(e), 42);
+        assert_eq!(format!("{:?}", e), "EdgeDescriptor(42)");
+        assert_eq!(format!("{}", e), "e42");
+
+        assert!(VertexDescriptor::from_usize(1) < VertexDescriptor::from_usize(2));
+    }
+}
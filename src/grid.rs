@@ -0,0 +1,339 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::f64::consts::SQRT_2;
+
+use fnv::FnvHashMap;
+
+use ordered_float::OrderedFloat;
+
+type Cell = (isize, isize);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Connectivity {
+    /// Moves to the cells left, right, above and below.
+    Four,
+    /// Also moves diagonally, at a cost of √2, where neither cell beside the move is blocked.
+    Eight,
+}
+
+/// A rectangle of cells addressed by `(x, y)`, some of them blocked, to find paths through
+/// without building a graph of it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Grid {
+    width: usize,
+    height: usize,
+    blocked: Vec<bool>,
+}
+
+impl Grid {
+    pub fn new(width: usize, height: usize) -> Self {
+        Grid {
+            width,
+            height,
+            blocked: vec![false; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Cells outside the grid count as blocked.
+    pub fn is_blocked(&self, x: usize, y: usize) -> bool {
+        x >= self.width || y >= self.height || self.blocked[y * self.width + x]
+    }
+
+    pub fn set_blocked(&mut self, x: usize, y: usize, blocked: bool) {
+        assert!(x < self.width && y < self.height, "cell out of the grid");
+        self.blocked[y * self.width + x] = blocked;
+    }
+
+    fn open(&self, (x, y): Cell) -> bool {
+        x >= 0 && y >= 0 && !self.is_blocked(x as usize, y as usize)
+    }
+
+    fn neighbors(&self, (x, y): Cell, connectivity: Connectivity) -> Vec<(Cell, f64)> {
+        let mut cells = Vec::new();
+        for &(dx, dy) in &[(1, 0), (-1, 0), (0, 1), (0, -1)] {
+            if self.open((x + dx, y + dy)) {
+                cells.push(((x + dx, y + dy), 1.0));
+            }
+        }
+        if connectivity == Connectivity::Eight {
+            for &(dx, dy) in &[(1, 1), (1, -1), (-1, 1), (-1, -1)] {
+                if self.open((x + dx, y)) && self.open((x, y + dy)) && self.open((x + dx, y + dy))
+                {
+                    cells.push(((x + dx, y + dy), SQRT_2));
+                }
+            }
+        }
+        cells
+    }
+
+    // Follows a direction from `cell` until reaching the goal or a cell where the optimal
+    // paths may turn, a jump point, and returns it; `None` if a blocked cell comes first.
+    fn jump(&self, cell: Cell, (dx, dy): Cell, goal: Cell) -> Option<Cell> {
+        let (mut x, mut y) = cell;
+        loop {
+            if !self.open((x, y)) {
+                return None;
+            }
+            if (x, y) == goal {
+                return Some((x, y));
+            }
+            let forced = if dx != 0 && dy != 0 {
+                self.jump((x + dx, y), (dx, 0), goal).is_some()
+                    || self.jump((x, y + dy), (0, dy), goal).is_some()
+            } else if dx != 0 {
+                (self.open((x, y - 1)) && !self.open((x - dx, y - 1)))
+                    || (self.open((x, y + 1)) && !self.open((x - dx, y + 1)))
+            } else {
+                (self.open((x - 1, y)) && !self.open((x - 1, y - dy)))
+                    || (self.open((x + 1, y)) && !self.open((x + 1, y - dy)))
+            };
+            if forced {
+                return Some((x, y));
+            }
+            if !self.open((x + dx, y)) || !self.open((x, y + dy)) {
+                return None;
+            }
+            x += dx;
+            y += dy;
+        }
+    }
+
+    // The directions worth following from `cell` when entered from `parent`, with the others
+    // pruned by symmetry.
+    fn directions(&self, (x, y): Cell, parent: Cell) -> Vec<Cell> {
+        let (dx, dy) = ((x - parent.0).signum(), (y - parent.1).signum());
+        let mut directions = Vec::new();
+        if dx != 0 && dy != 0 {
+            let vertical = self.open((x, y + dy));
+            let horizontal = self.open((x + dx, y));
+            if vertical {
+                directions.push((0, dy));
+            }
+            if horizontal {
+                directions.push((dx, 0));
+            }
+            if vertical && horizontal {
+                directions.push((dx, dy));
+            }
+        } else if dx != 0 {
+            let ahead = self.open((x + dx, y));
+            for &side in &[1, -1] {
+                if self.open((x, y + side)) {
+                    if ahead {
+                        directions.push((dx, side));
+                    }
+                    directions.push((0, side));
+                }
+            }
+            if ahead {
+                directions.push((dx, 0));
+            }
+        } else {
+            let ahead = self.open((x, y + dy));
+            for &side in &[1, -1] {
+                if self.open((x + side, y)) {
+                    if ahead {
+                        directions.push((side, dy));
+                    }
+                    directions.push((side, 0));
+                }
+            }
+            if ahead {
+                directions.push((0, dy));
+            }
+        }
+        directions
+    }
+
+    fn search<F, H>(
+        &self,
+        start: Cell,
+        goal: Cell,
+        successors: F,
+        heuristic: H,
+    ) -> Option<Vec<Cell>>
+    where
+        F: Fn(Cell, Option<Cell>) -> Vec<(Cell, f64)>,
+        H: Fn(Cell) -> f64,
+    {
+        let mut best = FnvHashMap::default();
+        let mut parents: FnvHashMap<Cell, Cell> = FnvHashMap::default();
+        let mut fringe = BinaryHeap::new();
+        best.insert(start, 0.0);
+        fringe.push(Reverse((OrderedFloat(heuristic(start)), start)));
+        while let Some(Reverse((OrderedFloat(estimate), cell))) = fringe.pop() {
+            let cost = best[&cell];
+            if estimate > cost + heuristic(cell) {
+                continue;
+            }
+            if cell == goal {
+                let mut path = vec![goal];
+                while let Some(&parent) = parents.get(path.last().unwrap()) {
+                    path.push(parent);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for (next, step) in successors(cell, parents.get(&cell).cloned()) {
+                let through = cost + step;
+                if best.get(&next).is_none_or(|&old| through < old) {
+                    best.insert(next, through);
+                    parents.insert(next, cell);
+                    fringe.push(Reverse((OrderedFloat(through + heuristic(next)), next)));
+                }
+            }
+        }
+        None
+    }
+
+    /// Finds a shortest path of cells from `start` to `goal`, both included, or `None` if the
+    /// goal cannot be reached. With eight-way moves the search jumps over the cells the many
+    /// symmetric shortest paths of an open grid have in common, as in Jump Point Search by
+    /// Harabor and Grastien, so it expands far fewer cells than plain A*.
+    pub fn find_path(
+        &self,
+        start: (usize, usize),
+        goal: (usize, usize),
+        connectivity: Connectivity,
+    ) -> Option<Vec<(usize, usize)>> {
+        if self.is_blocked(start.0, start.1) || self.is_blocked(goal.0, goal.1) {
+            return None;
+        }
+        let start = (start.0 as isize, start.1 as isize);
+        let goal = (goal.0 as isize, goal.1 as isize);
+        let path = match connectivity {
+            Connectivity::Four => self.search(
+                start,
+                goal,
+                |cell, _| self.neighbors(cell, connectivity),
+                |(x, y)| ((goal.0 - x).abs() + (goal.1 - y).abs()) as f64,
+            ),
+            Connectivity::Eight => {
+                let jump_points = self.search(
+                    start,
+                    goal,
+                    |cell, parent| {
+                        let directions = match parent {
+                            Some(parent) => self.directions(cell, parent),
+                            None => self
+                                .neighbors(cell, connectivity)
+                                .into_iter()
+                                .map(|((x, y), _)| (x - cell.0, y - cell.1))
+                                .collect(),
+                        };
+                        directions
+                            .into_iter()
+                            .filter_map(|d| self.jump((cell.0 + d.0, cell.1 + d.1), d, goal))
+                            .map(|next| (next, octile(cell, next)))
+                            .collect()
+                    },
+                    |cell| octile(cell, goal),
+                )?;
+                // fill in the straight and diagonal runs between jump points
+                let mut path = vec![start];
+                for &(x, y) in &jump_points[1..] {
+                    let mut last = *path.last().unwrap();
+                    let (dx, dy) = ((x - last.0).signum(), (y - last.1).signum());
+                    while last != (x, y) {
+                        last = (last.0 + dx, last.1 + dy);
+                        path.push(last);
+                    }
+                }
+                Some(path)
+            }
+        };
+        path.map(|cells| {
+            cells
+                .into_iter()
+                .map(|(x, y)| (x as usize, y as usize))
+                .collect()
+        })
+    }
+}
+
+fn octile(a: Cell, b: Cell) -> f64 {
+    let (dx, dy) = ((a.0 - b.0).abs() as f64, (a.1 - b.1).abs() as f64);
+    dx.max(dy) + (SQRT_2 - 1.0) * dx.min(dy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{octile, Connectivity, Grid};
+
+    #[test]
+    fn grid_paths() {
+        // . . . . . .
+        // . # # # # .
+        // . . . . # .
+        // # # # . # .
+        // . . . . . .
+
+        let mut grid = Grid::new(6, 5);
+        for &(x, y) in &[(1, 1), (2, 1), (3, 1), (4, 1), (4, 2), (0, 3), (1, 3), (2, 3), (4, 3)] {
+            grid.set_blocked(x, y, true);
+        }
+
+        let path = grid.find_path((0, 2), (0, 4), Connectivity::Four).unwrap();
+        assert_eq!(path.len(), 9);
+        assert_eq!(path[0], (0, 2));
+        assert_eq!(path[8], (0, 4));
+        assert!(path.windows(2).all(|w| {
+            let (dx, dy) = (w[0].0 as isize - w[1].0 as isize, w[0].1 as isize - w[1].1 as isize);
+            dx.abs() + dy.abs() == 1 && !grid.is_blocked(w[1].0, w[1].1)
+        }));
+
+        // diagonal moves may not cut the corners of (2, 3), so there is no shortcut through (3, 3)
+        let path = grid.find_path((0, 2), (0, 4), Connectivity::Eight).unwrap();
+        let expected = [(0, 2), (1, 2), (2, 2), (3, 2), (3, 3), (3, 4), (2, 4), (1, 4), (0, 4)];
+        assert_eq!(path, expected.to_vec());
+        grid.set_blocked(3, 3, true);
+        assert!(grid.find_path((0, 2), (0, 4), Connectivity::Eight).is_some());
+        grid.set_blocked(5, 3, true);
+        assert!(grid.find_path((0, 2), (0, 4), Connectivity::Four).is_none());
+        assert!(grid.find_path((0, 2), (0, 4), Connectivity::Eight).is_none());
+
+        // jump point search agrees with plain A* on the length of shortest paths
+        let mut seed = 12345u32;
+        for _ in 0..50 {
+            let mut grid = Grid::new(12, 9);
+            for y in 0..9 {
+                for x in 0..12 {
+                    seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                    grid.set_blocked(x, y, (seed >> 16) % 10 < 3);
+                }
+            }
+            grid.set_blocked(0, 0, false);
+            grid.set_blocked(11, 8, false);
+            let length = |path: &[(usize, usize)]| {
+                let cell = |&(x, y): &(usize, usize)| (x as isize, y as isize);
+                path.windows(2)
+                    .map(|w| octile(cell(&w[0]), cell(&w[1])))
+                    .sum::<f64>()
+            };
+            let plain = grid.search(
+                (0, 0),
+                (11, 8),
+                |cell, _| grid.neighbors(cell, Connectivity::Eight),
+                |cell| octile(cell, (11, 8)),
+            );
+            let jumped = grid.find_path((0, 0), (11, 8), Connectivity::Eight);
+            assert_eq!(plain.is_some(), jumped.is_some());
+            if let (Some(plain), Some(jumped)) = (plain, jumped) {
+                let plain = plain
+                    .into_iter()
+                    .map(|(x, y)| (x as usize, y as usize))
+                    .collect::<Vec<_>>();
+                assert!((length(&plain) - length(&jumped)).abs() < 1e-9);
+                assert!(jumped.windows(2).all(|w| !grid.is_blocked(w[1].0, w[1].1)));
+            }
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use std::collections::VecDeque;
+
+use graph::{Directivity, IncidenceGraph, VertexDescriptor, VertexListGraph};
+use property_map::{vertex_index, PropertyMap};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HamiltonianSearch {
+    /// Backtracks over every choice, so finds an answer whenever there is one.
+    Exhaustive,
+    /// Follows the rule of Warnsdorff from each start without backtracking. Fast, but may miss
+    /// an answer that exists.
+    Heuristic,
+}
+
+struct Search {
+    successors: Vec<Vec<usize>>,
+    predecessors: Vec<Vec<usize>>,
+    visited: Vec<bool>,
+    path: Vec<usize>,
+    cycle: bool,
+}
+
+impl Search {
+    fn new<'a, G>(graph: &'a G, cycle: bool) -> (Vec<VertexDescriptor>, Self)
+    where
+        G: IncidenceGraph<'a> + VertexListGraph<'a>,
+        G::Directivity: Directivity,
+    {
+        let vertices = graph.vertices().collect::<Vec<_>>();
+        let index = vertex_index(graph, &vertices);
+
+        let mut successors = vec![Vec::new(); vertices.len()];
+        let mut predecessors = vec![Vec::new(); vertices.len()];
+        for (i, &v) in vertices.iter().enumerate() {
+            for e in graph.out_edges(v) {
+                if let Some(&j) = graph.opposite(e, v).and_then(|t| index.get(t)) {
+                    if i != j {
+                        successors[i].push(j);
+                        predecessors[j].push(i);
+                        if !G::Directivity::is_directed() {
+                            successors[j].push(i);
+                            predecessors[i].push(j);
+                        }
+                    }
+                }
+            }
+        }
+        for list in successors.iter_mut().chain(predecessors.iter_mut()) {
+            list.sort();
+            list.dedup();
+        }
+
+        let search = Search {
+            visited: vec![false; vertices.len()],
+            path: Vec::with_capacity(vertices.len()),
+            successors,
+            predecessors,
+            cycle,
+        };
+        (vertices, search)
+    }
+
+    fn onward(&self, i: usize) -> usize {
+        self.successors[i].iter().filter(|&&j| !self.visited[j]).count()
+    }
+
+    // Every unvisited vertex needs a way in from the unvisited ones or the end of the path and,
+    // but for the one finishing a path, a way out to the unvisited ones or back to the start of
+    // a cycle. All of them must also be reachable from the end of the path.
+    fn feasible(&self) -> bool {
+        let last = *self.path.last().unwrap();
+        let open = |j: usize| !self.visited[j];
+        let mut dead_ends = 0;
+        for i in (0..self.visited.len()).filter(|&i| open(i)) {
+            if !self.predecessors[i].iter().any(|&j| open(j) || j == last) {
+                return false;
+            }
+            if !self.successors[i].iter().any(|&j| open(j) || (self.cycle && j == self.path[0])) {
+                dead_ends += 1;
+            }
+        }
+        if dead_ends > if self.cycle { 0 } else { 1 } {
+            return false;
+        }
+
+        let mut seen = self.visited.clone();
+        let mut reached = self.path.len();
+        let mut fringe = VecDeque::new();
+        fringe.push_back(last);
+        while let Some(i) = fringe.pop_front() {
+            for &j in &self.successors[i] {
+                if !seen[j] {
+                    seen[j] = true;
+                    reached += 1;
+                    fringe.push_back(j);
+                }
+            }
+        }
+        reached == self.visited.len()
+    }
+
+    fn extend(&mut self, search: HamiltonianSearch) -> bool {
+        let last = *self.path.last().unwrap();
+        if self.path.len() == self.visited.len() {
+            return !self.cycle || self.successors[last].contains(&self.path[0]);
+        }
+        if search == HamiltonianSearch::Exhaustive && !self.feasible() {
+            return false;
+        }
+
+        let mut candidates = self.successors[last]
+            .iter()
+            .cloned()
+            .filter(|&j| !self.visited[j])
+            .collect::<Vec<_>>();
+        candidates.sort_by_key(|&j| self.onward(j));
+        if search == HamiltonianSearch::Heuristic {
+            candidates.truncate(1);
+        }
+        for j in candidates {
+            self.visited[j] = true;
+            self.path.push(j);
+            if self.extend(search) {
+                return true;
+            }
+            self.path.pop();
+            self.visited[j] = false;
+        }
+        false
+    }
+
+    fn start(&mut self, i: usize, search: HamiltonianSearch) -> bool {
+        self.visited[i] = true;
+        self.path.push(i);
+        if self.extend(search) {
+            return true;
+        }
+        self.path.pop();
+        self.visited[i] = false;
+        false
+    }
+}
+
+fn hamiltonian<'a, G>(
+    graph: &'a G,
+    cycle: bool,
+    search: HamiltonianSearch,
+) -> Option<Vec<VertexDescriptor>>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+{
+    let (vertices, mut state) = Search::new(graph, cycle);
+    if vertices.len() <= 1 {
+        return Some(vertices);
+    }
+
+    // a cycle may as well start anywhere, while a path is most likely to start at a vertex that
+    // is hard to enter and must start at one that cannot be entered at all
+    let mut starts = (0..vertices.len()).collect::<Vec<_>>();
+    if cycle && search == HamiltonianSearch::Exhaustive {
+        starts.truncate(1);
+    } else if !cycle {
+        starts.sort_by_key(|&i| state.predecessors[i].len());
+        if state.predecessors[starts[0]].is_empty() {
+            starts.truncate(1);
+        }
+    }
+    for i in starts {
+        if state.start(i, search) {
+            return Some(state.path.into_iter().map(|i| vertices[i]).collect());
+        }
+    }
+    None
+}
+
+/// Finds a path visiting every vertex exactly once, following edge directions in a directed
+/// graph, by depth-first backtracking that extends the path towards the vertex with the fewest
+/// onward choices first and abandons it as soon as the rest of the graph can no longer be
+/// covered. The search is exponential in the worst case, so meant for small to medium graphs.
+pub fn hamiltonian_path<'a, G>(
+    graph: &'a G,
+    search: HamiltonianSearch,
+) -> Option<Vec<VertexDescriptor>>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+{
+    hamiltonian(graph, false, search)
+}
+
+/// Like `hamiltonian_path` but also requires an edge from the last vertex back to the first,
+/// which is not repeated at the end.
+pub fn hamiltonian_cycle<'a, G>(
+    graph: &'a G,
+    search: HamiltonianSearch,
+) -> Option<Vec<VertexDescriptor>>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+{
+    hamiltonian(graph, true, search)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hamiltonian_cycle, hamiltonian_path, HamiltonianSearch};
+
+    #[test]
+    fn hamiltonian_paths_and_cycles() {
+        use graph::{AdjacencyMatrixGraph, Directed, MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        // v0 --- v1 --- v2
+        //  |      |      |
+        // v3 --- v4 --- v5
+
+        let mut g = IncidenceList::<Undirected, _, _>::new();
+        let vs = (0..6).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t) in &[(0, 1), (1, 2), (0, 3), (1, 4), (2, 5), (3, 4), (4, 5)] {
+            g.add_edge(vs[s], vs[t], ()).unwrap();
+        }
+        let is_walk = |g: &IncidenceList<Undirected, (), ()>, order: &[_]| {
+            order.windows(2).all(|w| g.edge(w[0], w[1]).is_some())
+        };
+
+        for &search in &[HamiltonianSearch::Exhaustive, HamiltonianSearch::Heuristic] {
+            let cycle = hamiltonian_cycle(&g, search).unwrap();
+            let mut sorted = cycle.clone();
+            sorted.sort();
+            assert_eq!(sorted, vs);
+            assert!(is_walk(&g, &cycle));
+            assert!(g.edge(cycle[5], cycle[0]).is_some());
+        }
+
+        // without v2 --- v5 the only paths run between v2 and v5, and there is no cycle
+        g.remove_edge(g.edge(vs[2], vs[5]).unwrap());
+        assert_eq!(hamiltonian_cycle(&g, HamiltonianSearch::Exhaustive), None);
+        let path = hamiltonian_path(&g, HamiltonianSearch::Exhaustive).unwrap();
+        assert_eq!(path.len(), 6);
+        assert!(is_walk(&g, &path));
+        assert!(path[0] == vs[2] || path[0] == vs[5]);
+
+        // an isolated vertex leaves neither, a self-loop on it being no way in or out
+        let x = g.add_vertex(());
+        g.add_edge(x, x, ()).unwrap();
+        for &search in &[HamiltonianSearch::Exhaustive, HamiltonianSearch::Heuristic] {
+            assert_eq!(hamiltonian_path(&g, search), None);
+            assert_eq!(hamiltonian_cycle(&g, search), None);
+        }
+
+        // v0 --> v1 --> v2 <-- v3
+        let mut d = IncidenceList::<Directed, _, _>::new();
+        let ws = (0..4).map(|_| d.add_vertex(())).collect::<Vec<_>>();
+        d.add_edge(ws[0], ws[1], ()).unwrap();
+        d.add_edge(ws[1], ws[2], ()).unwrap();
+        d.add_edge(ws[3], ws[2], ()).unwrap();
+        assert_eq!(hamiltonian_path(&d, HamiltonianSearch::Exhaustive), None);
+        d.add_edge(ws[2], ws[3], ()).unwrap();
+        assert_eq!(hamiltonian_path(&d, HamiltonianSearch::Exhaustive), Some(ws.clone()));
+        assert_eq!(hamiltonian_cycle(&d, HamiltonianSearch::Exhaustive), None);
+        d.add_edge(ws[3], ws[0], ()).unwrap();
+        assert_eq!(hamiltonian_cycle(&d, HamiltonianSearch::Heuristic), Some(ws));
+
+        // the empty graph and a lone vertex are their own paths and cycles
+        let mut t = IncidenceList::<Directed, (), ()>::new();
+        assert_eq!(hamiltonian_cycle(&t, HamiltonianSearch::Exhaustive), Some(vec![]));
+        let v = t.add_vertex(());
+        assert_eq!(hamiltonian_path(&t, HamiltonianSearch::Heuristic), Some(vec![v]));
+        assert_eq!(hamiltonian_cycle(&t, HamiltonianSearch::Exhaustive), Some(vec![v]));
+    }
+}
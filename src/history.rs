@@ -0,0 +1,316 @@
+use std::mem;
+
+use fnv::FnvHashMap;
+
+use error::GraphError;
+use graph::{BidirectionalGraph, EdgeDescriptor, Graph, IncidenceGraph, MutableGraph,
+            VertexDescriptor};
+use incidence_list::IncidenceList;
+
+enum Operation<VP, EP> {
+    AddVertex(VertexDescriptor, VP),
+    AddEdge(EdgeDescriptor, VertexDescriptor, VertexDescriptor, EP),
+    RemoveVertex(VertexDescriptor),
+    RemoveEdge(EdgeDescriptor),
+    SetVertex(VertexDescriptor, VP),
+    SetEdge(EdgeDescriptor, EP),
+}
+
+/// An `IncidenceList` whose mutations are journaled so that they can be undone and redone.
+///
+/// A vertex or edge that is removed and then restored by `undo` or `redo` is added anew, so
+/// with the `generational` feature its descriptor changes. The history keeps answering to the
+/// descriptor first handed out, and `vertex` and `edge` translate it to the one in `graph`.
+/// Without the feature the restored descriptors are the original ones.
+pub struct History<D, VP, EP> {
+    graph: IncidenceList<D, VP, EP>,
+    undo: Vec<Vec<Operation<VP, EP>>>,
+    redo: Vec<Vec<Operation<VP, EP>>>,
+    // descriptors first handed out to their current ones and back, where they differ
+    vertices: (FnvHashMap<VertexDescriptor, VertexDescriptor>,
+               FnvHashMap<VertexDescriptor, VertexDescriptor>),
+    edges: (FnvHashMap<EdgeDescriptor, EdgeDescriptor>,
+            FnvHashMap<EdgeDescriptor, EdgeDescriptor>),
+}
+
+impl<D, VP, EP> History<D, VP, EP> {
+    pub fn new(graph: IncidenceList<D, VP, EP>) -> Self {
+        History {
+            graph,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            vertices: (FnvHashMap::default(), FnvHashMap::default()),
+            edges: (FnvHashMap::default(), FnvHashMap::default()),
+        }
+    }
+
+    pub fn graph(&self) -> &IncidenceList<D, VP, EP> {
+        &self.graph
+    }
+
+    pub fn into_inner(self) -> IncidenceList<D, VP, EP> {
+        self.graph
+    }
+
+    /// Returns the descriptor in `graph` of a vertex known by the descriptor it was first
+    /// given.
+    pub fn vertex(&self, d: VertexDescriptor) -> VertexDescriptor {
+        self.vertices.0.get(&d).cloned().unwrap_or(d)
+    }
+
+    /// Returns the descriptor in `graph` of an edge known by the descriptor it was first given.
+    pub fn edge(&self, d: EdgeDescriptor) -> EdgeDescriptor {
+        self.edges.0.get(&d).cloned().unwrap_or(d)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Forgets every change made so far; they can no longer be undone.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    pub fn add_vertex(&mut self, property: VP) -> VertexDescriptor {
+        let v = self.graph.add_vertex(property);
+        self.record(vec![Operation::RemoveVertex(v)]);
+        v
+    }
+
+    pub fn add_edge(
+        &mut self,
+        source: VertexDescriptor,
+        target: VertexDescriptor,
+        property: EP,
+    ) -> Result<EdgeDescriptor, GraphError> {
+        let e = self.graph.add_edge(self.vertex(source), self.vertex(target), property)?;
+        self.record(vec![Operation::RemoveEdge(e)]);
+        Ok(e)
+    }
+
+    /// Removes a vertex along with its edges, all of which `undo` restores.
+    pub fn remove_vertex(&mut self, d: VertexDescriptor) -> Result<(), GraphError> {
+        let inverse = self.apply(Operation::RemoveVertex(d))?;
+        self.record(inverse);
+        Ok(())
+    }
+
+    pub fn remove_edge(&mut self, d: EdgeDescriptor) -> Result<(), GraphError> {
+        let inverse = self.apply(Operation::RemoveEdge(d))?;
+        self.record(inverse);
+        Ok(())
+    }
+
+    pub fn set_vertex_property(
+        &mut self,
+        d: VertexDescriptor,
+        property: VP,
+    ) -> Result<(), GraphError> {
+        let inverse = self.apply(Operation::SetVertex(d, property))?;
+        self.record(inverse);
+        Ok(())
+    }
+
+    pub fn set_edge_property(&mut self, d: EdgeDescriptor, property: EP) -> Result<(), GraphError> {
+        let inverse = self.apply(Operation::SetEdge(d, property))?;
+        self.record(inverse);
+        Ok(())
+    }
+
+    /// Reverts the latest change not yet undone. Returns `false` if there is none.
+    pub fn undo(&mut self) -> bool {
+        match self.undo.pop() {
+            Some(operations) => {
+                let inverse = self.replay(operations);
+                self.redo.push(inverse);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Makes again the latest change undone. Returns `false` if there is none, which is also
+    /// the case once a new change has been made after undoing.
+    pub fn redo(&mut self) -> bool {
+        match self.redo.pop() {
+            Some(operations) => {
+                let inverse = self.replay(operations);
+                self.undo.push(inverse);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn record(&mut self, inverse: Vec<Operation<VP, EP>>) {
+        self.undo.push(inverse);
+        self.redo.clear();
+    }
+
+    fn replay(&mut self, operations: Vec<Operation<VP, EP>>) -> Vec<Operation<VP, EP>> {
+        let mut inverses = Vec::new();
+        for operation in operations {
+            inverses.push(self.apply(operation).expect("history out of step with the graph"));
+        }
+        inverses.into_iter().rev().flatten().collect()
+    }
+
+    fn bind_vertex(&mut self, first: VertexDescriptor, current: Option<VertexDescriptor>) {
+        if let Some(old) = self.vertices.0.remove(&first) {
+            self.vertices.1.remove(&old);
+        }
+        if let Some(current) = current.filter(|&c| c != first) {
+            self.vertices.0.insert(first, current);
+            self.vertices.1.insert(current, first);
+        }
+    }
+
+    fn bind_edge(&mut self, first: EdgeDescriptor, current: Option<EdgeDescriptor>) {
+        if let Some(old) = self.edges.0.remove(&first) {
+            self.edges.1.remove(&old);
+        }
+        if let Some(current) = current.filter(|&c| c != first) {
+            self.edges.0.insert(first, current);
+            self.edges.1.insert(current, first);
+        }
+    }
+
+    fn first_vertex(&self, d: VertexDescriptor) -> VertexDescriptor {
+        self.vertices.1.get(&d).cloned().unwrap_or(d)
+    }
+
+    fn first_edge(&self, d: EdgeDescriptor) -> EdgeDescriptor {
+        self.edges.1.get(&d).cloned().unwrap_or(d)
+    }
+
+    // Applies an operation to descriptors first handed out, and returns the operations that
+    // revert it, in the order they are to be applied.
+    fn apply(
+        &mut self,
+        operation: Operation<VP, EP>,
+    ) -> Result<Vec<Operation<VP, EP>>, GraphError> {
+        match operation {
+            Operation::AddVertex(first, property) => {
+                let v = self.graph.add_vertex(property);
+                self.bind_vertex(first, Some(v));
+                Ok(vec![Operation::RemoveVertex(first)])
+            }
+            Operation::AddEdge(first, source, target, property) => {
+                let e = self.graph.add_edge(self.vertex(source), self.vertex(target), property)?;
+                self.bind_edge(first, Some(e));
+                Ok(vec![Operation::RemoveEdge(first)])
+            }
+            Operation::RemoveVertex(first) => {
+                let v = self.vertex(first);
+                if !self.graph.contains_vertex(v) {
+                    return Err(GraphError::VertexNotFound(first));
+                }
+                let mut edges = self.graph
+                    .out_edges(v)
+                    .chain(self.graph.in_edges(v))
+                    .collect::<Vec<_>>();
+                edges.sort();
+                edges.dedup();
+                // the edges are restored in reverse so that each takes back the slot it freed
+                let mut inverse = Vec::new();
+                for e in edges {
+                    inverse.extend(self.apply(Operation::RemoveEdge(self.first_edge(e)))?);
+                }
+                inverse.reverse();
+                let property = self.graph
+                    .remove_vertex(v)
+                    .ok_or(GraphError::VertexNotFound(first))?;
+                self.bind_vertex(first, None);
+                inverse.insert(0, Operation::AddVertex(first, property));
+                Ok(inverse)
+            }
+            Operation::RemoveEdge(first) => {
+                let e = self.edge(first);
+                let (s, t) = self.graph.edge_endpoints(e).ok_or(GraphError::EdgeNotFound(first))?;
+                let property = self.graph.remove_edge(e).ok_or(GraphError::EdgeNotFound(first))?;
+                self.bind_edge(first, None);
+                let (s, t) = (self.first_vertex(s), self.first_vertex(t));
+                Ok(vec![Operation::AddEdge(first, s, t, property)])
+            }
+            Operation::SetVertex(first, property) => {
+                let v = self.vertex(first);
+                let slot = self.graph
+                    .vertex_property_mut(v)
+                    .ok_or(GraphError::VertexNotFound(first))?;
+                Ok(vec![Operation::SetVertex(first, mem::replace(slot, property))])
+            }
+            Operation::SetEdge(first, property) => {
+                let e = self.edge(first);
+                let slot = self.graph
+                    .edge_property_mut(e)
+                    .ok_or(GraphError::EdgeNotFound(first))?;
+                Ok(vec![Operation::SetEdge(first, mem::replace(slot, property))])
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::History;
+
+    #[test]
+    fn undo_redo() {
+        use error::GraphError;
+        use graph::{Directed, EdgeListGraph, Graph, IncidenceGraph, MutableGraph,
+                    VertexListGraph};
+        use incidence_list::IncidenceList;
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+        let v0 = g.add_vertex("a");
+        let v1 = g.add_vertex("b");
+        g.add_edge(v0, v1, 1).unwrap();
+
+        let mut h = History::new(g);
+        let v2 = h.add_vertex("c");
+        let e12 = h.add_edge(v1, v2, 2).unwrap();
+        let e20 = h.add_edge(v2, v0, 3).unwrap();
+        h.set_vertex_property(v0, "z").unwrap();
+        h.remove_vertex(v2).unwrap();
+        assert_eq!((h.graph().order(), h.graph().size()), (2, 1));
+        assert_eq!(h.remove_vertex(v2), Err(GraphError::VertexNotFound(v2)));
+
+        // the removed vertex comes back with both its edges and their properties
+        assert!(h.undo());
+        assert_eq!((h.graph().order(), h.graph().size()), (3, 3));
+        let g = h.graph();
+        assert_eq!(g.vertex_property(h.vertex(v2)), Some(&"c"));
+        assert_eq!(g.edge_property(h.edge(e20)), Some(&3));
+        assert_eq!(g.edge_endpoints(h.edge(e12)), Some((h.vertex(v1), h.vertex(v2))));
+
+        assert!(h.undo());
+        assert_eq!(h.graph()[v0], "a");
+        h.undo();
+        h.undo();
+        assert_eq!((h.graph().order(), h.graph().size()), (3, 1));
+        assert!(h.undo());
+        assert!(!h.undo());
+        assert_eq!((h.graph().order(), h.graph().size()), (2, 1));
+
+        while h.redo() {}
+        assert_eq!((h.graph().order(), h.graph().size()), (2, 1));
+        assert_eq!(h.graph()[v0], "z");
+        h.undo();
+        h.remove_edge(e12).unwrap();
+        assert!(!h.can_redo());
+        assert_eq!(h.graph().size(), 2);
+        h.undo();
+        assert_eq!(h.graph().edge_property(h.edge(e12)), Some(&2));
+        assert!(h.can_undo());
+
+        h.set_edge_property(e20, 4).unwrap();
+        let g = h.into_inner();
+        assert_eq!((g.order(), g.size()), (3, 3));
+    }
+}
@@ -0,0 +1,337 @@
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::hash::BuildHasher;
+use std::marker::PhantomData;
+
+use num_traits::Zero;
+
+use graph::{Graph, BidirectionalGraph, Directivity, VertexListGraph,
+            EdgeDescriptor, VertexDescriptor};
+use error::GraphError;
+use path::Path;
+use visitor::{Event, Visitor, DefaultVisitor};
+
+struct Frame<C> {
+    vertex: VertexDescriptor,
+    cost: C,
+    edges: Vec<EdgeDescriptor>,
+    next: usize,
+}
+
+enum Outcome<C> {
+    Found(Path<C>),
+    Exceeded(C),
+    Exhausted,
+}
+
+/// Iterative deepening A*: a depth-first search cut off where the cost so far plus the
+/// heuristic exceeds a bound, repeated with the bound raised to the least value that exceeded
+/// it until a goal is reached. Only the current path is stored, so memory stays proportional
+/// to its length, at the price of examining vertices again in every iteration and of
+/// re-exploring vertices reachable along several paths. With an admissible heuristic the path
+/// found is a cheapest one. Each iteration starts with `Event::StartVertex`.
+pub struct IdaStar<C, T, V>
+where
+    C: Copy + Debug + Ord + Zero,
+    T: Graph,
+    V: Visitor<T, Event>,
+{
+    path: Vec<Frame<C>>,
+    visitor: V,
+    phantom: PhantomData<T>,
+}
+
+impl<C, T> IdaStar<C, T, DefaultVisitor>
+where
+    C: Copy + Debug + Ord + Zero,
+    T: Graph,
+{
+    pub fn new() -> Self {
+        Self::with_visitor(DefaultVisitor)
+    }
+}
+
+impl<C, T> Default for IdaStar<C, T, DefaultVisitor>
+where
+    C: Copy + Debug + Ord + Zero,
+    T: Graph,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C, T, V> IdaStar<C, T, V>
+where
+    C: Copy + Debug + Ord + Zero,
+    T: Graph,
+    V: Visitor<T, Event>,
+{
+    pub fn with_visitor(visitor: V) -> Self {
+        Self {
+            path: Vec::new(),
+            visitor,
+            phantom: PhantomData,
+        }
+    }
+
+    fn edges<'a>(graph: &'a T, vertex: VertexDescriptor) -> Vec<EdgeDescriptor>
+    where
+        T: BidirectionalGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        let in_edges = if T::Directivity::is_directed() {
+            None
+        } else {
+            Some(graph.in_edges(vertex))
+        };
+        graph.out_edges(vertex).chain(in_edges.into_iter().flatten()).collect()
+    }
+
+    fn found(&self, vertex: VertexDescriptor, cost: C) -> Outcome<C> {
+        let mut vertices = self.path.iter().map(|f| f.vertex).collect::<Vec<_>>();
+        vertices.push(vertex);
+        Outcome::Found(Path::new(vertices, cost))
+    }
+
+    // One depth-first pass below `bound`.
+    fn search<'a, F, G, H>(
+        &mut self,
+        start: &VertexDescriptor,
+        bound: C,
+        edge_cost: &G,
+        heuristic: &H,
+        is_goal: &F,
+        graph: &'a T,
+    ) -> Result<Outcome<C>, GraphError>
+    where
+        F: Fn(&VertexDescriptor) -> bool,
+        G: Fn(&EdgeDescriptor, &T) -> C,
+        H: Fn(&VertexDescriptor, &T) -> C,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.path.clear();
+        self.visitor.visit(&Event::StartVertex(*start), graph);
+        self.visitor.visit(&Event::DiscoverVertex(*start), graph);
+        self.visitor.visit(&Event::ExamineVertex(*start), graph);
+        if is_goal(start) {
+            return Ok(self.found(*start, C::zero()));
+        }
+        self.path.push(Frame {
+            vertex: *start,
+            cost: C::zero(),
+            edges: Self::edges(graph, *start),
+            next: 0,
+        });
+
+        let mut exceeded: Option<C> = None;
+        while let Some(frame) = self.path.last_mut() {
+            if frame.next == frame.edges.len() {
+                self.visitor.visit(&Event::FinishVertex(frame.vertex), graph);
+                self.path.pop();
+                continue;
+            }
+            let edge = frame.edges[frame.next];
+            frame.next += 1;
+            let (vertex, cost) = (frame.vertex, frame.cost);
+
+            let adjacency = graph.opposite(edge, vertex).ok_or(
+                GraphError::EdgeNotFound(edge),
+            )?;
+            self.visitor.visit(&Event::ExamineEdge(edge), graph);
+            let cost_of_edge = edge_cost(&edge, graph);
+            if cost_of_edge < C::zero() {
+                return Err(GraphError::NegativeEdgeCost(edge));
+            }
+            let cost_to_adjacency = cost + cost_of_edge;
+            let evaluation = cost_to_adjacency + heuristic(&adjacency, graph);
+            if evaluation > bound {
+                if exceeded.is_none_or(|e| evaluation < e) {
+                    exceeded = Some(evaluation);
+                }
+                self.visitor.visit(&Event::EdgeNotRelaxed(edge), graph);
+                continue;
+            }
+            if self.path.iter().any(|f| f.vertex == adjacency) {
+                self.visitor.visit(&Event::EdgeNotRelaxed(edge), graph);
+                continue;
+            }
+
+            self.visitor.visit(&Event::EdgeRelaxed(edge), graph);
+            self.visitor.visit(&Event::DiscoverVertex(adjacency), graph);
+            self.visitor.visit(&Event::ExamineVertex(adjacency), graph);
+            if is_goal(&adjacency) {
+                return Ok(self.found(adjacency, cost_to_adjacency));
+            }
+            self.path.push(Frame {
+                vertex: adjacency,
+                cost: cost_to_adjacency,
+                edges: Self::edges(graph, adjacency),
+                next: 0,
+            });
+        }
+        Ok(exceeded.map_or(Outcome::Exhausted, Outcome::Exceeded))
+    }
+
+    pub fn run<'a, F, G, H>(
+        &mut self,
+        start: &VertexDescriptor,
+        edge_cost: G,
+        heuristic: H,
+        is_goal: F,
+        graph: &'a T,
+    ) -> Result<Option<Path<C>>, GraphError>
+    where
+        F: Fn(&VertexDescriptor) -> bool,
+        G: Fn(&EdgeDescriptor, &T) -> C,
+        H: Fn(&VertexDescriptor, &T) -> C,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        if !graph.contains_vertex(*start) {
+            return Err(GraphError::VertexNotFound(*start));
+        }
+
+        for vertex in graph.vertices() {
+            self.visitor.visit(&Event::InitializeVertex(vertex), graph)
+        }
+
+        let mut bound = heuristic(start, graph);
+        loop {
+            match self.search(start, bound, &edge_cost, &heuristic, &is_goal, graph)? {
+                Outcome::Found(path) => return Ok(Some(path)),
+                Outcome::Exceeded(next) => bound = next,
+                Outcome::Exhausted => return Ok(None),
+            }
+        }
+    }
+
+    pub fn run_to_vertex<'a, G, H>(
+        &mut self,
+        start: &VertexDescriptor,
+        goal: &VertexDescriptor,
+        edge_cost: G,
+        heuristic: H,
+        graph: &'a T,
+    ) -> Result<Option<Path<C>>, GraphError>
+    where
+        G: Fn(&EdgeDescriptor, &T) -> C,
+        H: Fn(&VertexDescriptor, &T) -> C,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.run(start, edge_cost, heuristic, |v| v == goal, graph)
+    }
+
+    pub fn run_to_any<'a, S, G, H>(
+        &mut self,
+        start: &VertexDescriptor,
+        goals: &HashSet<VertexDescriptor, S>,
+        edge_cost: G,
+        heuristic: H,
+        graph: &'a T,
+    ) -> Result<Option<Path<C>>, GraphError>
+    where
+        S: BuildHasher,
+        G: Fn(&EdgeDescriptor, &T) -> C,
+        H: Fn(&VertexDescriptor, &T) -> C,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.run(start, edge_cost, heuristic, |v| goals.contains(v), graph)
+    }
+
+    pub fn visitor_ref(&self) -> &V {
+        &self.visitor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IdaStar;
+    use path::Path;
+
+    #[test]
+    fn ida_star_directed() {
+        use std::collections::HashSet;
+        use error::GraphError;
+        use graph::{Directed, Graph, MutableGraph};
+        use incidence_list::IncidenceList;
+        use visitor::{Event, Visitor};
+
+        struct Iterations(usize);
+
+        impl<G: Graph> Visitor<G, Event> for Iterations {
+            fn visit(&mut self, e: &Event, _g: &G) {
+                if let Event::StartVertex(_) = *e {
+                    self.0 += 1;
+                }
+            }
+        }
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+
+        let v0 = g.add_vertex(("s", 7));
+        let v1 = g.add_vertex(("a", 6));
+        let v2 = g.add_vertex(("b", 2));
+        let v3 = g.add_vertex(("c", 1));
+        let v4 = g.add_vertex(("g", 0));
+        let v5 = g.add_vertex(("x", 0));
+
+        g.add_edge(v0, v1, 1).unwrap();
+        g.add_edge(v0, v2, 4).unwrap();
+        g.add_edge(v1, v2, 2).unwrap();
+        g.add_edge(v1, v3, 5).unwrap();
+        g.add_edge(v1, v4, 12).unwrap();
+        g.add_edge(v2, v3, 2).unwrap();
+        g.add_edge(v3, v4, 3).unwrap();
+        g.add_edge(v3, v0, 1).unwrap();
+
+        let mut ida = IdaStar::with_visitor(Iterations(0));
+        assert_eq!(
+            ida.run(
+                &v0,
+                |&e, g| *g.edge_property(e).unwrap(),
+                |&v, g| g.vertex_property(v).unwrap().1,
+                |&v| v == v4,
+                &g,
+            ),
+            Ok(Some(Path::new(vec![v0, v1, v2, v3, v4], 8)))
+        );
+        assert_eq!(ida.visitor_ref().0, 2);
+        assert_eq!(
+            IdaStar::new().run_to_vertex(
+                &v0,
+                &v5,
+                |&e, g| *g.edge_property(e).unwrap(),
+                |&v, g| g.vertex_property(v).unwrap().1,
+                &g,
+            ),
+            Ok(None)
+        );
+        let goals = [v3, v5].iter().cloned().collect::<HashSet<_>>();
+        assert_eq!(
+            IdaStar::new().run_to_any(
+                &v0,
+                &goals,
+                |&e, g| *g.edge_property(e).unwrap(),
+                |_, _| 0,
+                &g,
+            ),
+            Ok(Some(Path::new(vec![v0, v1, v2, v3], 5)))
+        );
+
+        let e = g.add_edge(v0, v5, -1).unwrap();
+        assert_eq!(
+            IdaStar::new().run_to_vertex(
+                &v0,
+                &v4,
+                |&e, g| *g.edge_property(e).unwrap(),
+                |_, _| 0,
+                &g,
+            ),
+            Err(GraphError::NegativeEdgeCost(e))
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, VecDeque};
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use fnv::FnvHashMap;
+use num_traits::Zero;
+
+// Interns states so that the search bookkeeping deals in indices and each state is stored once.
+struct States<S> {
+    states: Vec<S>,
+    index: FnvHashMap<S, usize>,
+}
+
+impl<S> States<S>
+where
+    S: Clone + Eq + Hash,
+{
+    fn new() -> Self {
+        States {
+            states: Vec::new(),
+            index: FnvHashMap::default(),
+        }
+    }
+
+    // Returns the index of `state` and whether it was seen for the first time.
+    fn intern(&mut self, state: S) -> (usize, bool) {
+        match self.index.entry(state) {
+            Entry::Occupied(entry) => (*entry.get(), false),
+            Entry::Vacant(entry) => {
+                self.states.push(entry.key().clone());
+                (*entry.insert(self.states.len() - 1), true)
+            }
+        }
+    }
+
+    fn path(&self, parents: &[usize], mut i: usize) -> Vec<S> {
+        let mut path = vec![self.states[i].clone()];
+        while parents[i] != i {
+            i = parents[i];
+            path.push(self.states[i].clone());
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Searches a state space that is never stored as a graph: `successors` yields the states one
+/// step away from a state together with the cost of that step, and states are told apart by
+/// hashing. Otherwise behaves like `Astar`, so with an admissible and consistent `heuristic`
+/// the path returned, from `start` to the first goal reached, is a cheapest one. Returns the
+/// path and its cost, or `None` if no goal is reachable; an infinite space without a reachable
+/// goal is searched forever.
+pub fn implicit_astar<S, C, I, F, H, G>(
+    start: S,
+    mut successors: F,
+    heuristic: H,
+    is_goal: G,
+) -> Option<(Vec<S>, C)>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Debug + Ord + Zero,
+    I: IntoIterator<Item = (S, C)>,
+    F: FnMut(&S) -> I,
+    H: Fn(&S) -> C,
+    G: Fn(&S) -> bool,
+{
+    let mut states = States::new();
+    let mut parents = Vec::new();
+    let mut costs = Vec::new();
+    let mut closed = Vec::new();
+    let mut fringe = BinaryHeap::new();
+
+    let (i, _) = states.intern(start);
+    parents.push(i);
+    costs.push(C::zero());
+    closed.push(false);
+    fringe.push(Reverse((heuristic(&states.states[i]), i)));
+    while let Some(Reverse((_, i))) = fringe.pop() {
+        if closed[i] {
+            continue;
+        }
+        closed[i] = true;
+        if is_goal(&states.states[i]) {
+            return Some((states.path(&parents, i), costs[i]));
+        }
+
+        let next = successors(&states.states[i]);
+        for (state, step) in next {
+            let cost = costs[i] + step;
+            let (j, new) = states.intern(state);
+            if new {
+                parents.push(i);
+                costs.push(cost);
+                closed.push(false);
+            } else if closed[j] || cost >= costs[j] {
+                continue;
+            } else {
+                parents[j] = i;
+                costs[j] = cost;
+            }
+            fringe.push(Reverse((cost + heuristic(&states.states[j]), j)));
+        }
+    }
+    None
+}
+
+/// Breadth-first counterpart of `implicit_astar` for state spaces where every step costs the
+/// same: returns a path from `start` to the nearest goal in the fewest steps, or `None`.
+pub fn implicit_bfs<S, I, F, G>(start: S, mut successors: F, is_goal: G) -> Option<Vec<S>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+    F: FnMut(&S) -> I,
+    G: Fn(&S) -> bool,
+{
+    let mut states = States::new();
+    let mut parents = Vec::new();
+    let mut fringe = VecDeque::new();
+
+    let (i, _) = states.intern(start);
+    parents.push(i);
+    fringe.push_back(i);
+    while let Some(i) = fringe.pop_front() {
+        if is_goal(&states.states[i]) {
+            return Some(states.path(&parents, i));
+        }
+        let next = successors(&states.states[i]);
+        for state in next {
+            let (j, new) = states.intern(state);
+            if new {
+                parents.push(i);
+                fringe.push_back(j);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{implicit_astar, implicit_bfs};
+
+    #[test]
+    fn search_implicit_state_spaces() {
+        // from n one step leads to n + 1 and another to 2n; 1, 2, 4, 5, 10 is the only
+        // way to reach 10 in four steps
+        let steps = |&n: &u32| vec![n + 1, 2 * n];
+        assert_eq!(implicit_bfs(1, steps, |&n| n == 10), Some(vec![1, 2, 4, 5, 10]));
+        assert_eq!(implicit_bfs(1, |&n: &u32| vec![(n + 1) % 7], |&n| n == 9), None);
+
+        // doubling is expensive, so walking is cheaper for small numbers
+        let weighted = |&n: &u32| {
+            vec![(n + 1, 1), (2 * n, 3)]
+                .into_iter()
+                .filter(|&(m, _)| m <= 20)
+        };
+        let (path, cost) = implicit_astar(1, weighted, |_| 0, |&n| n == 10).unwrap();
+        assert_eq!(cost, 7);
+        assert_eq!(path, vec![1, 2, 3, 4, 5, 10]);
+
+        // on an unbounded grid, a Manhattan distance heuristic keeps the search near the line
+        let mut expanded = 0;
+        let grid = |&(x, y): &(i32, i32)| {
+            expanded += 1;
+            vec![((x + 1, y), 1), ((x - 1, y), 1), ((x, y + 1), 1), ((x, y - 1), 1)]
+        };
+        let manhattan = |&(x, y): &(i32, i32)| (x - 20).abs() + (y - 5).abs();
+        let (path, cost) = implicit_astar((0, 0), grid, manhattan, |&p| p == (20, 5)).unwrap();
+        assert_eq!(cost, 25);
+        assert_eq!(path.len(), 26);
+        assert!(expanded < 200);
+    }
+}
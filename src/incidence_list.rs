@@ -1,21 +1,32 @@
+use std::fmt::{self, Debug};
+use std::iter::FromIterator;
 use std::marker::PhantomData;
-use std::ops::Deref;
+use std::mem;
+use std::ops::{Deref, Index, IndexMut};
+use std::vec;
+use fnv::{FnvHashMap, FnvHashSet};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 use slab::{self, Slab};
 
-use graph::{AdjacencyGraph, AdjacencyMatrixGraph, BidirectionalGraph, EdgeDescriptor,
-            EdgeListGraph, Directivity, FromUsize, Graph, IncidenceGraph, MutableGraph,
+use error::GraphError;
+use graph::{AdjacencyGraph, AdjacencyMatrixGraph, BidirectionalGraph, Direction, EdgeDescriptor,
+            EdgeListGraph, Directivity, Generation, Graph, IncidenceGraph, MutableGraph,
             VertexDescriptor, VertexListGraph};
 
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct IncidenceList<D, VP, EP> {
     vertices: Slab<Vertex<VP>>,
     edges: Slab<Edge<EP>>,
+    vertex_generations: Vec<Generation>,
+    edge_generations: Vec<Generation>,
     phantom: PhantomData<D>,
 }
 
 #[derive(Clone, Debug, Hash)]
 pub struct Vertex<VP> {
     incidence: (Option<EdgeDescriptor>, VP, Option<EdgeDescriptor>),
+    generation: Generation,
 }
 
 impl<VP> Deref for Vertex<VP> {
@@ -30,6 +41,7 @@ impl<VP> Deref for Vertex<VP> {
 pub struct Edge<EP> {
     incidence: (Option<VertexDescriptor>, EP, Option<VertexDescriptor>),
     next: (Option<EdgeDescriptor>, Option<EdgeDescriptor>),
+    generation: Generation,
 }
 
 impl<EP> Deref for Edge<EP> {
@@ -45,6 +57,8 @@ impl<D, VP, EP> IncidenceList<D, VP, EP> {
         Self {
             vertices: Slab::new(),
             edges: Slab::new(),
+            vertex_generations: Vec::new(),
+            edge_generations: Vec::new(),
             phantom: PhantomData,
         }
     }
@@ -53,6 +67,8 @@ impl<D, VP, EP> IncidenceList<D, VP, EP> {
         Self {
             vertices: Slab::with_capacity(order),
             edges: Slab::new(),
+            vertex_generations: Vec::new(),
+            edge_generations: Vec::new(),
             phantom: PhantomData,
         }
     }
@@ -61,6 +77,8 @@ impl<D, VP, EP> IncidenceList<D, VP, EP> {
         Self {
             vertices: Slab::with_capacity(order),
             edges: Slab::with_capacity(size),
+            vertex_generations: Vec::new(),
+            edge_generations: Vec::new(),
             phantom: PhantomData,
         }
     }
@@ -69,9 +87,459 @@ impl<D, VP, EP> IncidenceList<D, VP, EP> {
         Self {
             vertices: Slab::new(),
             edges: Slab::with_capacity(size),
+            vertex_generations: Vec::new(),
+            edge_generations: Vec::new(),
             phantom: PhantomData,
         }
     }
+
+    pub fn clear(&mut self) {
+        self.clear_edges();
+        for (k, v) in self.vertices.iter() {
+            retire(&mut self.vertex_generations, k, v.generation);
+        }
+        self.vertices.clear();
+    }
+
+    pub fn clear_edges(&mut self) {
+        for (k, e) in self.edges.iter() {
+            retire(&mut self.edge_generations, k, e.generation);
+        }
+        self.edges.clear();
+        for (_, v) in self.vertices.iter_mut() {
+            v.incidence.0 = None;
+            v.incidence.2 = None;
+        }
+    }
+
+    pub fn self_loops<'a>(&'a self) -> SelfLoops<'a, EP> {
+        SelfLoops { iter: self.edge_references() }
+    }
+
+    pub fn remove_self_loops(&mut self) {
+        self.retain_edges(|g, e| !g.is_self_loop(e));
+    }
+
+    pub fn vertex_references<'a>(&'a self) -> VertexReferences<'a, VP> {
+        VertexReferences { iter: self.vertices.iter() }
+    }
+
+    pub fn edge_references<'a>(&'a self) -> EdgeReferences<'a, EP> {
+        EdgeReferences { iter: self.edges.iter() }
+    }
+
+    pub fn vertex_properties<'a>(&'a self) -> VertexProperties<'a, VP> {
+        VertexProperties { iter: self.vertices.iter() }
+    }
+
+    pub fn edge_properties<'a>(&'a self) -> EdgeProperties<'a, EP> {
+        EdgeProperties { iter: self.edges.iter() }
+    }
+
+    /// Checks that every edge is linked exactly once into the outgoing list of its source and
+    /// the incoming list of its target, that the lists only hold live edges, and that the
+    /// generation tables agree with the stored elements.
+    pub fn validate(&self) -> Result<(), GraphError> {
+        for (k, v) in self.vertices.iter() {
+            let vd = VertexDescriptor::with_generation(k, v.generation);
+            if generation_at(&self.vertex_generations, k) != v.generation {
+                return Err(GraphError::CorruptedVertex(vd));
+            }
+        }
+
+        for &direction in &[Direction::Outgoing, Direction::Incoming] {
+            let mut seen = FnvHashSet::default();
+            for (k, v) in self.vertices.iter() {
+                let vd = VertexDescriptor::with_generation(k, v.generation);
+                let mut current = match direction {
+                    Direction::Outgoing => v.2,
+                    Direction::Incoming => v.0,
+                };
+                while let Some(ed) = current {
+                    let e = self.edge_entry(ed).ok_or(GraphError::CorruptedVertex(vd))?;
+                    let endpoint = match direction {
+                        Direction::Outgoing => e.0,
+                        Direction::Incoming => e.2,
+                    };
+                    if endpoint != Some(vd) || !seen.insert(ed) {
+                        return Err(GraphError::CorruptedEdge(ed));
+                    }
+                    current = match direction {
+                        Direction::Outgoing => e.next.1,
+                        Direction::Incoming => e.next.0,
+                    };
+                }
+            }
+
+            for (k, e) in self.edges.iter() {
+                let ed = EdgeDescriptor::with_generation(k, e.generation);
+                if generation_at(&self.edge_generations, k) != e.generation ||
+                    !seen.contains(&ed)
+                {
+                    return Err(GraphError::CorruptedEdge(ed));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Renumbers the vertices into `0..order()` in the order listed, followed by those left
+    /// out in their current order, and the edges into `0..size()` by source in that same
+    /// order, so that dense arrays indexed by descriptor waste no space after removals.
+    /// Incidence lists keep their order. Every old descriptor goes stale; the returned
+    /// relabeling maps them to the new ones.
+    pub fn relabel(&mut self, order: &[VertexDescriptor]) -> Result<Relabeling, GraphError> {
+        if let Some(&vd) = order.iter().find(|&&vd| self.vertex(vd).is_none()) {
+            return Err(GraphError::VertexNotFound(vd));
+        }
+        let current = self.vertices
+            .iter()
+            .map(|(k, v)| VertexDescriptor::with_generation(k, v.generation))
+            .collect::<Vec<_>>();
+        let mut seen = FnvHashSet::default();
+        let vds = order
+            .iter()
+            .chain(&current)
+            .cloned()
+            .filter(|&vd| seen.insert(vd))
+            .collect::<Vec<_>>();
+        let outgoing = vds.iter()
+            .map(|&vd| self.incident_edges(vd, Direction::Outgoing))
+            .collect::<Vec<_>>();
+        let incoming = vds.iter()
+            .map(|&vd| self.incident_edges(vd, Direction::Incoming))
+            .collect::<Vec<_>>();
+
+        let (n, m) = (self.vertices.len(), self.edges.len());
+        let mut old_vertices = mem::replace(&mut self.vertices, Slab::with_capacity(n));
+        let mut old_edges = mem::replace(&mut self.edges, Slab::with_capacity(m));
+        for (k, v) in old_vertices.iter() {
+            retire(&mut self.vertex_generations, k, v.generation);
+        }
+        for (k, e) in old_edges.iter() {
+            retire(&mut self.edge_generations, k, e.generation);
+        }
+
+        let mut relabeling = Relabeling::default();
+        for &vd in &vds {
+            let Vertex { incidence: (_, vp, _), .. } = old_vertices.remove(vd.into());
+            relabeling.vertices.insert(vd, self.add_vertex(vp));
+        }
+        for &ed in outgoing.iter().flatten() {
+            let Edge { incidence: (s, ep, t), .. } = old_edges.remove(ed.into());
+            let entry = self.edges.vacant_entry();
+            let generation = generation_at(&self.edge_generations, entry.key());
+            relabeling.edges.insert(ed, EdgeDescriptor::with_generation(entry.key(), generation));
+            let (s, t) = (s.map(|s| relabeling.vertices[&s]), t.map(|t| relabeling.vertices[&t]));
+            entry.insert(Edge {
+                incidence: (s, ep, t),
+                next: (None, None),
+                generation,
+            });
+        }
+        for (i, vd) in vds.into_iter().enumerate() {
+            let vd = relabeling.vertices[&vd];
+            let eds = outgoing[i].iter().map(|ed| relabeling.edges[ed]).collect::<Vec<_>>();
+            self.link_edges(vd, Direction::Outgoing, &eds);
+            let eds = incoming[i].iter().map(|ed| relabeling.edges[ed]).collect::<Vec<_>>();
+            self.link_edges(vd, Direction::Incoming, &eds);
+        }
+        Ok(relabeling)
+    }
+
+    /// Relabels the vertices keeping their current order.
+    pub fn compact(&mut self) -> Relabeling {
+        self.relabel(&[]).unwrap()
+    }
+}
+
+/// Maps the descriptors a graph had before `IncidenceList::relabel` to the ones it has after.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Relabeling {
+    vertices: FnvHashMap<VertexDescriptor, VertexDescriptor>,
+    edges: FnvHashMap<EdgeDescriptor, EdgeDescriptor>,
+}
+
+impl Relabeling {
+    pub fn vertex(&self, old: VertexDescriptor) -> Option<VertexDescriptor> {
+        self.vertices.get(&old).cloned()
+    }
+
+    pub fn edge(&self, old: EdgeDescriptor) -> Option<EdgeDescriptor> {
+        self.edges.get(&old).cloned()
+    }
+
+    pub fn vertices(&self) -> &FnvHashMap<VertexDescriptor, VertexDescriptor> {
+        &self.vertices
+    }
+
+    pub fn edges(&self) -> &FnvHashMap<EdgeDescriptor, EdgeDescriptor> {
+        &self.edges
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Externals<'a, VP>
+where
+    VP: 'a,
+{
+    iter: slab::Iter<'a, Vertex<VP>>,
+    direction: Direction,
+    directed: bool,
+}
+
+impl<'a, VP> Iterator for Externals<'a, VP> {
+    type Item = VertexDescriptor;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (k, v) in &mut self.iter {
+            let head = match self.direction {
+                Direction::Outgoing => v.2,
+                Direction::Incoming => v.0,
+            };
+            if head.is_none() && (self.directed || (v.0.is_none() && v.2.is_none())) {
+                return Some(VertexDescriptor::with_generation(k, v.generation));
+            }
+        }
+        None
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct VertexReferences<'a, VP>
+where
+    VP: 'a,
+{
+    iter: slab::Iter<'a, Vertex<VP>>,
+}
+
+impl<'a, VP> Iterator for VertexReferences<'a, VP> {
+    type Item = (VertexDescriptor, &'a VP);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(
+            |(k, v)| (VertexDescriptor::with_generation(k, v.generation), &v.1),
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct EdgeReferences<'a, EP>
+where
+    EP: 'a,
+{
+    iter: slab::Iter<'a, Edge<EP>>,
+}
+
+impl<'a, EP> Iterator for EdgeReferences<'a, EP> {
+    type Item = (EdgeDescriptor, VertexDescriptor, VertexDescriptor, &'a EP);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (k, e) in &mut self.iter {
+            if let (Some(s), ref ep, Some(t)) = *e.deref() {
+                return Some((EdgeDescriptor::with_generation(k, e.generation), s, t, ep));
+            }
+        }
+        None
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SelfLoops<'a, EP>
+where
+    EP: 'a,
+{
+    iter: EdgeReferences<'a, EP>,
+}
+
+impl<'a, EP> Iterator for SelfLoops<'a, EP> {
+    type Item = EdgeDescriptor;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.find(|&(_, s, t, _)| s == t).map(|(ed, _, _, _)| ed)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct VertexProperties<'a, VP>
+where
+    VP: 'a,
+{
+    iter: slab::Iter<'a, Vertex<VP>>,
+}
+
+impl<'a, VP> Iterator for VertexProperties<'a, VP> {
+    type Item = &'a VP;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(_, v)| &v.1)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct EdgeProperties<'a, EP>
+where
+    EP: 'a,
+{
+    iter: slab::Iter<'a, Edge<EP>>,
+}
+
+impl<'a, EP> Iterator for EdgeProperties<'a, EP> {
+    type Item = &'a EP;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(_, e)| &e.1)
+    }
+}
+
+impl<D, VP, EP> IncidenceList<D, VP, EP>
+where
+    D: Directivity,
+{
+    /// Returns the vertices with no edges in `direction`. An undirected graph has no
+    /// direction, so only the vertices with no incident edges at all are returned for it.
+    pub fn externals<'a>(&'a self, direction: Direction) -> Externals<'a, VP> {
+        Externals {
+            iter: self.vertices.iter(),
+            direction,
+            directed: D::is_directed(),
+        }
+    }
+
+    pub fn sources<'a>(&'a self) -> Externals<'a, VP> {
+        self.externals(Direction::Incoming)
+    }
+
+    pub fn sinks<'a>(&'a self) -> Externals<'a, VP> {
+        self.externals(Direction::Outgoing)
+    }
+}
+
+impl<D, VP, EP> Default for IncidenceList<D, VP, EP> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D, VP, EP> IncidenceList<D, VP, EP>
+where
+    VP: Default,
+{
+    /// Builds a graph from `(source, target, property)` triples as `try_extend` does.
+    pub fn from_edges<I>(iter: I) -> Result<Self, GraphError>
+    where
+        I: IntoIterator<Item = (VertexDescriptor, VertexDescriptor, EP)>,
+    {
+        let mut g = Self::new();
+        g.try_extend(iter)?;
+        Ok(g)
+    }
+
+    /// Adds an edge for each of the `(source, target, property)` triples. Before each edge is
+    /// added, vertices with the default property are added until the slots of both endpoints
+    /// are taken, so that a graph can be built from the indices of its vertices alone.
+    ///
+    /// Fails with `GraphError::VertexNotFound` at the first endpoint that still does not
+    /// name a vertex, one whose slot was reused after the vertex was removed; the edges
+    /// before it are kept.
+    pub fn try_extend<I>(&mut self, iter: I) -> Result<(), GraphError>
+    where
+        I: IntoIterator<Item = (VertexDescriptor, VertexDescriptor, EP)>,
+    {
+        for (source, target, property) in iter {
+            while !self.vertices.contains(source.into()) {
+                self.add_vertex(VP::default());
+            }
+            while !self.vertices.contains(target.into()) {
+                self.add_vertex(VP::default());
+            }
+            self.add_edge(source, target, property)?;
+        }
+        Ok(())
+    }
+}
+
+/// Adds the edges as `IncidenceList::try_extend` does, padding the graph with vertices of the
+/// default property. Panics where `try_extend` would fail, on an endpoint naming a removed
+/// vertex.
+impl<D, VP, EP> Extend<(VertexDescriptor, VertexDescriptor, EP)> for IncidenceList<D, VP, EP>
+where
+    VP: Default,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (VertexDescriptor, VertexDescriptor, EP)>,
+    {
+        if let Err(e) = self.try_extend(iter) {
+            panic!("{}", e);
+        }
+    }
+}
+
+/// Builds a graph as `IncidenceList::from_edges` does. Panics where `from_edges` would fail.
+impl<D, VP, EP> FromIterator<(VertexDescriptor, VertexDescriptor, EP)> for IncidenceList<D, VP, EP>
+where
+    VP: Default,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (VertexDescriptor, VertexDescriptor, EP)>,
+    {
+        let mut g = Self::new();
+        g.extend(iter);
+        g
+    }
+}
+
+impl<D, VP, EP> IncidenceList<D, VP, EP>
+where
+    VP: Debug,
+    EP: Debug,
+{
+    fn fmt_vertices(&self, f: &mut fmt::Formatter, separator: &str) -> fmt::Result {
+        for (i, (k, v)) in self.vertices.iter().enumerate() {
+            if i > 0 {
+                f.write_str(separator)?;
+            }
+
+            let vd = VertexDescriptor::with_generation(k, v.generation);
+            write!(f, "{}({:?})", vd, v.1)?;
+
+            let mut eds = self.out_edges(vd).collect::<Vec<_>>();
+            eds.sort();
+            for (j, ed) in eds.into_iter().enumerate() {
+                let &(_, ref ep, t) = self.edges[ed.into()].deref();
+                write!(f, "{}", if j == 0 { " -> " } else { ", " })?;
+                match t {
+                    Some(t) => write!(f, "{} [{:?}]", t, ep)?,
+                    None => write!(f, "? [{:?}]", ep)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<D, VP, EP> fmt::Display for IncidenceList<D, VP, EP>
+where
+    VP: Debug,
+    EP: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_vertices(f, "\n")
+    }
+}
+
+impl<D, VP, EP> Debug for IncidenceList<D, VP, EP>
+where
+    VP: Debug,
+    EP: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("IncidenceList { ")?;
+        self.fmt_vertices(f, "; ")?;
+        f.write_str(" }")
+    }
 }
 
 impl<D, VP, EP> Graph for IncidenceList<D, VP, EP> {
@@ -80,16 +548,47 @@ impl<D, VP, EP> Graph for IncidenceList<D, VP, EP> {
     type EdgeProperty = EP;
 
     fn vertex_property(&self, d: VertexDescriptor) -> Option<&Self::VertexProperty> {
-        self.vertices.get(d.into()).and_then(|&Vertex {
-             incidence: (_, ref vp, _),
-         }| Some(vp))
+        self.vertex(d).map(|v| &v.1)
     }
 
     fn edge_property(&self, d: EdgeDescriptor) -> Option<&Self::EdgeProperty> {
-        self.edges.get(d.into()).and_then(|&Edge {
-             incidence: (_, ref ep, _),
-             next: _,
-         }| Some(ep))
+        self.edge_entry(d).map(|e| &e.1)
+    }
+
+    fn contains_vertex(&self, d: VertexDescriptor) -> bool {
+        self.vertex(d).is_some()
+    }
+
+    fn contains_edge(&self, d: EdgeDescriptor) -> bool {
+        self.edge_entry(d).is_some()
+    }
+}
+
+impl<D, VP, EP> Index<VertexDescriptor> for IncidenceList<D, VP, EP> {
+    type Output = VP;
+
+    fn index(&self, d: VertexDescriptor) -> &Self::Output {
+        &self.vertex_at(d).incidence.1
+    }
+}
+
+impl<D, VP, EP> IndexMut<VertexDescriptor> for IncidenceList<D, VP, EP> {
+    fn index_mut(&mut self, d: VertexDescriptor) -> &mut Self::Output {
+        &mut self.vertex_at_mut(d).incidence.1
+    }
+}
+
+impl<D, VP, EP> Index<EdgeDescriptor> for IncidenceList<D, VP, EP> {
+    type Output = EP;
+
+    fn index(&self, d: EdgeDescriptor) -> &Self::Output {
+        &self.edge_at(d).incidence.1
+    }
+}
+
+impl<D, VP, EP> IndexMut<EdgeDescriptor> for IncidenceList<D, VP, EP> {
+    fn index_mut(&mut self, d: EdgeDescriptor) -> &mut Self::Output {
+        &mut self.edge_at_mut(d).incidence.1
     }
 }
 
@@ -106,31 +605,32 @@ where
     }
 
     fn out_edges(&'a self, d: VertexDescriptor) -> Self::Incidences {
-        let &(_, _, oe) = self.vertices[d.into()].deref();
+        let &(_, _, oe) = self.vertex_at(d).deref();
         IncidentEdges {
             graph: self,
             current_edge_descriptor: oe,
-            kind: EdgeKind::Outgoing,
+            direction: Direction::Outgoing,
         }
     }
 
     fn source(&self, d: EdgeDescriptor) -> VertexDescriptor {
-        let &(s, _, _) = self.edges[d.into()].deref();
+        let &(s, _, _) = self.edge_at(d).deref();
         assert!(s.is_some());
         s.unwrap()
     }
 
     fn target(&self, d: EdgeDescriptor) -> VertexDescriptor {
-        let &(_, _, t) = self.edges[d.into()].deref();
+        let &(_, _, t) = self.edge_at(d).deref();
         assert!(t.is_some());
         t.unwrap()
     }
-}
 
-#[derive(Clone, Debug, Hash)]
-pub enum EdgeKind {
-    Outgoing,
-    Incoming,
+    fn edge_endpoints(&self, d: EdgeDescriptor) -> Option<(VertexDescriptor, VertexDescriptor)> {
+        self.edge_entry(d).and_then(|e| match (e.0, e.2) {
+            (Some(s), Some(t)) => Some((s, t)),
+            _ => None,
+        })
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -142,7 +642,7 @@ where
 {
     graph: &'a IncidenceList<D, VP, EP>,
     current_edge_descriptor: Option<EdgeDescriptor>,
-    kind: EdgeKind,
+    direction: Direction,
 }
 
 impl<'a, D, VP, EP> Iterator for IncidentEdges<'a, D, VP, EP> {
@@ -152,16 +652,13 @@ impl<'a, D, VP, EP> Iterator for IncidentEdges<'a, D, VP, EP> {
         match self.current_edge_descriptor {
             None => None,
             Some(ed) => {
-                self.graph.edges.get(ed.into()).and_then(|e| {
-                    let &Edge {
-                        incidence: _,
-                        next: (ie, oe),
-                    } = e;
-                    match self.kind {
-                        EdgeKind::Outgoing => self.current_edge_descriptor = oe,
-                        EdgeKind::Incoming => self.current_edge_descriptor = ie,
+                self.graph.edges.get(ed.into()).map(|e| {
+                    let (ie, oe) = e.next;
+                    match self.direction {
+                        Direction::Outgoing => self.current_edge_descriptor = oe,
+                        Direction::Incoming => self.current_edge_descriptor = ie,
                     }
-                    Some(ed)
+                    ed
                 })
             }
         }
@@ -174,62 +671,114 @@ where
     VP: 'a,
     EP: 'a,
 {
+    type Neighbors = IncidentVertices<'a, D, VP, EP>;
+
     fn degree(&self, d: VertexDescriptor) -> usize {
-        self.out_edges(d.clone()).chain(self.in_edges(d)).fold(
+        self.out_edges(d).chain(self.in_edges(d)).fold(
             0,
             |acc, _| {
                 acc + 1
             },
         )
     }
+
     fn in_degree(&self, d: VertexDescriptor) -> usize {
         self.in_edges(d).fold(0, |acc, _| acc + 1)
     }
 
     fn in_edges(&'a self, d: VertexDescriptor) -> Self::Incidences {
-        let &(ie, _, _) = self.vertices[d.into()].deref();
+        let &(ie, _, _) = self.vertex_at(d).deref();
         IncidentEdges {
             graph: self,
             current_edge_descriptor: ie,
-            kind: EdgeKind::Incoming,
+            direction: Direction::Incoming,
+        }
+    }
+
+    fn neighbors_directed(&'a self, d: VertexDescriptor, direction: Direction) -> Self::Neighbors {
+        let &(ie, _, oe) = self.vertex_at(d).deref();
+        IncidentVertices {
+            graph: self,
+            current_edge_descriptor: match direction {
+                Direction::Outgoing => oe,
+                Direction::Incoming => ie,
+            },
+            direction,
         }
     }
 }
 
 impl<'a, D, VP, EP> AdjacencyGraph<'a> for IncidenceList<D, VP, EP>
 where
-    D: Directivity,
+    D: Directivity + 'a,
+    VP: 'a,
+    EP: 'a,
 {
-    type Adjacencies = Box<Iterator<Item = VertexDescriptor> + 'a>;
+    type Adjacencies = AdjacentVertices<'a, D, VP, EP>;
 
     fn adjacent_vertices(&'a self, d: VertexDescriptor) -> Self::Adjacencies {
-        let &(ie, _, oe) = self.vertices[d.into()].deref();
+        let &(ie, _, oe) = self.vertex_at(d).deref();
         let successors = IncidentVertices {
             graph: self,
             current_edge_descriptor: oe,
-            kind: VertexKind::Successor,
+            direction: Direction::Outgoing,
         };
-        let predecessors = IncidentVertices {
-            graph: self,
-            current_edge_descriptor: ie,
-            kind: VertexKind::Predecessor,
-        };
-
-        let mut vs = if D::is_directed() {
-            successors.collect::<Vec<_>>()
+        let predecessors = if D::is_directed() {
+            None
         } else {
-            successors.chain(predecessors).collect::<Vec<_>>()
+            Some(IncidentVertices {
+                graph: self,
+                current_edge_descriptor: ie,
+                direction: Direction::Incoming,
+            })
         };
+        AdjacentVertices {
+            successors,
+            predecessors,
+        }
+    }
+}
+
+impl<D, VP, EP> IncidenceList<D, VP, EP>
+where
+    D: Directivity,
+{
+    /// Returns the vertices adjacent to `d` in ascending order, each of them once however many
+    /// edges join it to `d`.
+    pub fn unique_adjacent_vertices(
+        &self,
+        d: VertexDescriptor,
+    ) -> vec::IntoIter<VertexDescriptor> {
+        let mut vs = self.adjacent_vertices(d).collect::<Vec<_>>();
         vs.sort();
         vs.dedup();
-        Box::new(vs.into_iter())
+        vs.into_iter()
     }
 }
 
-#[derive(Clone, Debug, Hash)]
-pub enum VertexKind {
-    Predecessor,
-    Successor,
+/// The vertices adjacent to a vertex, found lazily by walking its incidence lists. A vertex is
+/// yielded once for every edge joining it, and a self-loop twice in an undirected graph; see
+/// `IncidenceList::unique_adjacent_vertices` for each of them once.
+#[derive(Clone, Debug)]
+pub struct AdjacentVertices<'a, D, VP, EP>
+where
+    D: 'a,
+    VP: 'a,
+    EP: 'a,
+{
+    successors: IncidentVertices<'a, D, VP, EP>,
+    predecessors: Option<IncidentVertices<'a, D, VP, EP>>,
+}
+
+impl<'a, D, VP, EP> Iterator for AdjacentVertices<'a, D, VP, EP> {
+    type Item = VertexDescriptor;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.successors.next() {
+            Some(v) => Some(v),
+            None => self.predecessors.as_mut().and_then(Iterator::next),
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -241,7 +790,7 @@ where
 {
     graph: &'a IncidenceList<D, VP, EP>,
     current_edge_descriptor: Option<EdgeDescriptor>,
-    kind: VertexKind,
+    direction: Direction,
 }
 
 impl<'a, D, VP, EP> Iterator for IncidentVertices<'a, D, VP, EP> {
@@ -252,16 +801,14 @@ impl<'a, D, VP, EP> Iterator for IncidentVertices<'a, D, VP, EP> {
             None => None,
             Some(ed) => {
                 self.graph.edges.get(ed.into()).and_then(|e| {
-                    let &Edge {
-                        incidence: (s, _, t),
-                        next: (ie, oe),
-                    } = e;
-                    match self.kind {
-                        VertexKind::Predecessor => {
+                    let &(s, _, t) = e.deref();
+                    let (ie, oe) = e.next;
+                    match self.direction {
+                        Direction::Incoming => {
                             self.current_edge_descriptor = ie;
                             s
                         }
-                        VertexKind::Successor => {
+                        Direction::Outgoing => {
                             self.current_edge_descriptor = oe;
                             t
                         }
@@ -285,9 +832,13 @@ where
         self.vertices.len()
     }
 
+    fn vertex_bound(&self) -> usize {
+        self.vertices.iter().next_back().map_or(0, |(k, _)| k + 1)
+    }
+
     fn vertices(&'a self) -> Self::Vertices {
         self.vertices.iter().map(
-            |(k, _)| VertexDescriptor::from_usize(k),
+            |(k, v)| VertexDescriptor::with_generation(k, v.generation),
         )
     }
 }
@@ -302,9 +853,13 @@ where
         self.edges.len()
     }
 
+    fn edge_bound(&self) -> usize {
+        self.edges.iter().next_back().map_or(0, |(k, _)| k + 1)
+    }
+
     fn edges(&'a self) -> Self::Edges {
         self.edges.iter().map(
-            |(k, _)| EdgeDescriptor::from_usize(k),
+            |(k, e)| EdgeDescriptor::with_generation(k, e.generation),
         )
     }
 }
@@ -313,27 +868,221 @@ impl<D, VP, EP> AdjacencyMatrixGraph for IncidenceList<D, VP, EP>
 where
     D: Directivity,
 {
+    // Walks the incidence lists of `source`, so that a lookup takes O(degree) time.
     fn edge(&self, source: VertexDescriptor, target: VertexDescriptor) -> Option<EdgeDescriptor> {
-        self.edges
+        let &(ie, _, oe) = self.vertex(source)?.deref();
+        let endpoints = |d: &EdgeDescriptor| {
+            let &(s, _, t) = self.edge_at(*d).deref();
+            (s, t)
+        };
+        let mut outgoing = IncidentEdges {
+            graph: self,
+            current_edge_descriptor: oe,
+            direction: Direction::Outgoing,
+        };
+        let found = outgoing.find(|d| endpoints(d).1 == Some(target));
+        if found.is_some() || D::is_directed() {
+            return found;
+        }
+        let mut incoming = IncidentEdges {
+            graph: self,
+            current_edge_descriptor: ie,
+            direction: Direction::Incoming,
+        };
+        incoming.find(|d| endpoints(d).0 == Some(target))
+    }
+}
+
+impl<D, VP, EP> IncidenceList<D, VP, EP>
+where
+    D: Directivity,
+{
+    pub fn update_edge(
+        &mut self,
+        source: VertexDescriptor,
+        target: VertexDescriptor,
+        property: EP,
+    ) -> Result<EdgeDescriptor, GraphError> {
+        match self.edge(source, target) {
+            Some(ed) => {
+                self.edges[ed.into()].incidence.1 = property;
+                Ok(ed)
+            }
+            None => self.add_edge(source, target, property),
+        }
+    }
+}
+
+fn generation_at(generations: &[Generation], k: usize) -> Generation {
+    generations.get(k).cloned().unwrap_or_default()
+}
+
+fn retire(generations: &mut Vec<Generation>, k: usize, generation: Generation) {
+    if generations.len() <= k {
+        generations.resize(k + 1, Generation::default());
+    }
+    generations[k] = generation.next();
+}
+
+impl<D, VP, EP> IncidenceList<D, VP, EP> {
+    fn vertex(&self, d: VertexDescriptor) -> Option<&Vertex<VP>> {
+        self.vertices.get(d.into()).filter(
+            |v| v.generation == d.generation(),
+        )
+    }
+
+    fn vertex_mut(&mut self, d: VertexDescriptor) -> Option<&mut Vertex<VP>> {
+        self.vertices.get_mut(d.into()).filter(
+            |v| v.generation == d.generation(),
+        )
+    }
+
+    fn edge_entry(&self, d: EdgeDescriptor) -> Option<&Edge<EP>> {
+        self.edges.get(d.into()).filter(
+            |e| e.generation == d.generation(),
+        )
+    }
+
+    fn edge_entry_mut(&mut self, d: EdgeDescriptor) -> Option<&mut Edge<EP>> {
+        self.edges.get_mut(d.into()).filter(
+            |e| e.generation == d.generation(),
+        )
+    }
+
+    fn vertex_at(&self, d: VertexDescriptor) -> &Vertex<VP> {
+        match self.vertex(d) {
+            Some(v) => v,
+            None => panic!("invalid vertex descriptor {}", d),
+        }
+    }
+
+    fn vertex_at_mut(&mut self, d: VertexDescriptor) -> &mut Vertex<VP> {
+        match self.vertex_mut(d) {
+            Some(v) => v,
+            None => panic!("invalid vertex descriptor {}", d),
+        }
+    }
+
+    fn edge_at(&self, d: EdgeDescriptor) -> &Edge<EP> {
+        match self.edge_entry(d) {
+            Some(e) => e,
+            None => panic!("invalid edge descriptor {}", d),
+        }
+    }
+
+    fn edge_at_mut(&mut self, d: EdgeDescriptor) -> &mut Edge<EP> {
+        match self.edge_entry_mut(d) {
+            Some(e) => e,
+            None => panic!("invalid edge descriptor {}", d),
+        }
+    }
+
+    fn take_vertex(&mut self, d: VertexDescriptor) -> Vertex<VP> {
+        let v = self.vertices.remove(d.into());
+        retire(&mut self.vertex_generations, d.into(), v.generation);
+        v
+    }
+
+    fn take_edge(&mut self, d: EdgeDescriptor) -> Edge<EP> {
+        let e = self.edges.remove(d.into());
+        retire(&mut self.edge_generations, d.into(), e.generation);
+        e
+    }
+
+    fn head_mut(&mut self, d: VertexDescriptor, direction: Direction) -> &mut Option<EdgeDescriptor> {
+        let incidence = &mut self.vertices[d.into()].incidence;
+        match direction {
+            Direction::Outgoing => &mut incidence.2,
+            Direction::Incoming => &mut incidence.0,
+        }
+    }
+
+    fn next_mut(&mut self, d: EdgeDescriptor, direction: Direction) -> &mut Option<EdgeDescriptor> {
+        let next = &mut self.edges[d.into()].next;
+        match direction {
+            Direction::Outgoing => &mut next.1,
+            Direction::Incoming => &mut next.0,
+        }
+    }
+
+    fn unlink_edge(&mut self, d: EdgeDescriptor, vd: VertexDescriptor, direction: Direction) {
+        let next = *self.next_mut(d, direction);
+        let mut current = *self.head_mut(vd, direction);
+        if current == Some(d) {
+            *self.head_mut(vd, direction) = next;
+            return;
+        }
+        while let Some(ed) = current {
+            current = *self.next_mut(ed, direction);
+            if current == Some(d) {
+                *self.next_mut(ed, direction) = next;
+                return;
+            }
+        }
+    }
+
+    fn unlink_edges<F>(&mut self, is_removed: F)
+    where
+        F: Fn(EdgeDescriptor) -> bool,
+    {
+        let vds = self.vertices
             .iter()
-            .find(|&(_,
-               &Edge {
-                   incidence: (s, _, t),
-                   next: _,
-               })| {
-                (s == Some(source) && t == Some(target)) ||
-                    (!D::is_directed() && s == Some(target) && t == Some(source))
-            })
-            .and_then(|(k, _)| Some(EdgeDescriptor::from_usize(k)))
+            .map(|(k, v)| VertexDescriptor::with_generation(k, v.generation))
+            .collect::<Vec<_>>();
+        let mut kept = Vec::new();
+        for vd in vds {
+            for &direction in &[Direction::Outgoing, Direction::Incoming] {
+                kept.clear();
+                let mut current = *self.head_mut(vd, direction);
+                while let Some(ed) = current {
+                    current = *self.next_mut(ed, direction);
+                    if !is_removed(ed) {
+                        kept.push(ed);
+                    }
+                }
+
+                self.link_edges(vd, direction, &kept);
+            }
+        }
+    }
+
+    fn link_edges(&mut self, vd: VertexDescriptor, direction: Direction, eds: &[EdgeDescriptor]) {
+        *self.head_mut(vd, direction) = eds.first().cloned();
+        for pair in eds.windows(2) {
+            *self.next_mut(pair[0], direction) = Some(pair[1]);
+        }
+        if let Some(&last) = eds.last() {
+            *self.next_mut(last, direction) = None;
+        }
+    }
+
+    fn incident_edges(&self, vd: VertexDescriptor, direction: Direction) -> Vec<EdgeDescriptor> {
+        let mut eds = Vec::new();
+        let mut current = *match direction {
+            Direction::Outgoing => &self.vertices[vd.into()].incidence.2,
+            Direction::Incoming => &self.vertices[vd.into()].incidence.0,
+        };
+        while let Some(ed) = current {
+            eds.push(ed);
+            current = match direction {
+                Direction::Outgoing => self.edges[ed.into()].next.1,
+                Direction::Incoming => self.edges[ed.into()].next.0,
+            };
+        }
+        eds
     }
 }
 
 impl<D, VP, EP> MutableGraph for IncidenceList<D, VP, EP> {
     fn add_vertex(&mut self, property: Self::VertexProperty) -> VertexDescriptor {
-        let k = self.vertices.insert(
-            Vertex { incidence: (None, property, None) },
-        );
-        VertexDescriptor::from_usize(k)
+        let entry = self.vertices.vacant_entry();
+        let generation = generation_at(&self.vertex_generations, entry.key());
+        let vd = VertexDescriptor::with_generation(entry.key(), generation);
+        entry.insert(Vertex {
+            incidence: (None, property, None),
+            generation,
+        });
+        vd
     }
 
     fn add_edge(
@@ -341,57 +1090,37 @@ impl<D, VP, EP> MutableGraph for IncidenceList<D, VP, EP> {
         source: VertexDescriptor,
         target: VertexDescriptor,
         property: Self::EdgeProperty,
-    ) -> Option<EdgeDescriptor> {
-        let entry = self.edges.vacant_entry();
-        let key = entry.key();
-        let oe = self.vertices.get_mut(source.into()).and_then(
-            |&mut Vertex {
-                 incidence: (_, _, ref mut oe),
-             }| {
-                let next_oe = *oe;
-                *oe = Some(EdgeDescriptor::from_usize(key));
-                Some(next_oe)
-            },
-        );
-        let ie = match oe {
-            None => None,
-            Some(_) => {
-                self.vertices.get_mut(target.into()).and_then(
-                    |&mut Vertex {
-                         incidence: (ref mut ie, _, _),
-                     }| {
-                        let next_ie = *ie;
-                        *ie = Some(EdgeDescriptor::from_usize(key));
-                        Some(next_ie)
-                    },
-                )
-            }
-        };
-
-        if oe.is_some() && ie.is_some() {
-            let edge = Edge {
-                incidence: (Some(source.into()), property, Some(target.into())),
-                next: (ie.unwrap(), oe.unwrap()),
-            };
-            entry.insert(edge);
-            Some(EdgeDescriptor::from_usize(key))
-        } else {
-            None
+    ) -> Result<EdgeDescriptor, GraphError> {
+        if !self.contains_vertex(source) {
+            return Err(GraphError::VertexNotFound(source));
+        }
+        if !self.contains_vertex(target) {
+            return Err(GraphError::VertexNotFound(target));
         }
+
+        let entry = self.edges.vacant_entry();
+        let generation = generation_at(&self.edge_generations, entry.key());
+        let ed = EdgeDescriptor::with_generation(entry.key(), generation);
+        let oe = self.vertices[source.into()].incidence.2.replace(ed);
+        let ie = self.vertices[target.into()].incidence.0.replace(ed);
+        entry.insert(Edge {
+            incidence: (Some(source), property, Some(target)),
+            next: (ie, oe),
+            generation,
+        });
+        Ok(ed)
     }
 
     fn remove_vertex(&mut self, d: VertexDescriptor) -> Option<Self::VertexProperty> {
-        if self.vertices.contains(d.into()) {
-            let eds = self.out_edges(d.into())
-                .chain(self.in_edges(d.into()))
-                .collect::<Vec<_>>();
+        if self.contains_vertex(d) {
+            let mut eds = self.out_edges(d).chain(self.in_edges(d)).collect::<Vec<_>>();
+            eds.sort();
+            eds.dedup();
             for ed in eds {
-                if self.remove_edge(ed).is_none() {
-                    return None;
-                }
+                self.remove_edge(ed)?;
             }
 
-            let Vertex { incidence: (_, vp, _) } = self.vertices.remove(d.into());
+            let Vertex { incidence: (_, vp, _), .. } = self.take_vertex(d);
             Some(vp)
         } else {
             None
@@ -399,79 +1128,94 @@ impl<D, VP, EP> MutableGraph for IncidenceList<D, VP, EP> {
     }
 
     fn remove_edge(&mut self, d: EdgeDescriptor) -> Option<Self::EdgeProperty> {
-        if let Some((s, t, ie, oe)) =
-            self.edges.get(d.into()).and_then(|&Edge {
-                 incidence: (s, _, t),
-                 next: (ie, oe),
-             }| Some((s, t, ie, oe)))
-        {
-            s.and_then(|vd| {
-                let done = {
-                    let &mut Vertex { incidence: (_, _, ref mut oe_to_check) } =
-                        self.vertices.get_mut(vd.into()).unwrap();
-                    oe_to_check.and_then(|x| {
-                        if x == d {
-                            *oe_to_check = oe;
-                        }
-                        Some(())
-                    })
-                };
-                done.or_else(|| {
-                    self.out_edges(vd).find(|&x| x == d).and_then(|ed| {
-                        let &mut Edge {
-                            incidence: _,
-                            next: (_, ref mut oe_to_change),
-                        } = &mut self.edges[ed.into()];
-                        *oe_to_change = oe;
-                        Some(())
-                    })
-                })
-            });
+        let (s, t) = self.edge_endpoints(d)?;
+        self.unlink_edge(d, s, Direction::Outgoing);
+        self.unlink_edge(d, t, Direction::Incoming);
 
-            t.and_then(|vd| {
-                let done = {
-                    let &mut Vertex { incidence: (ref mut ie_to_check, _, _) } =
-                        self.vertices.get_mut(vd.into()).unwrap();
-                    ie_to_check.and_then(|x| {
-                        if x == d {
-                            *ie_to_check = ie;
-                        }
-                        Some(())
-                    })
-                };
-                done.or_else(|| {
-                    self.in_edges(vd).find(|&x| x == d).and_then(|ed| {
-                        let &mut Edge {
-                            incidence: _,
-                            next: (ref mut ie_to_change, _),
-                        } = &mut self.edges[ed.into()];
-                        *ie_to_change = ie;
-                        Some(())
-                    })
-                })
-            });
+        let Edge { incidence: (_, ep, _), .. } = self.take_edge(d);
+        Some(ep)
+    }
 
-            let Edge {
-                incidence: (_, ep, _),
-                next: _,
-            } = self.edges.remove(d.into());
-            Some(ep)
-        } else {
-            None
+    fn retain_vertices<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&Self, VertexDescriptor) -> bool,
+    {
+        let removed = self.vertices
+            .iter()
+            .map(|(k, v)| VertexDescriptor::with_generation(k, v.generation))
+            .filter(|&vd| !f(self, vd))
+            .collect::<FnvHashSet<_>>();
+        if removed.is_empty() {
+            return;
+        }
+
+        let removed_edges = self.edges
+            .iter()
+            .filter(|&(_, e)| {
+                let &(s, _, t) = e.deref();
+                s.is_some_and(|s| removed.contains(&s)) ||
+                    t.is_some_and(|t| removed.contains(&t))
+            })
+            .map(|(k, e)| EdgeDescriptor::with_generation(k, e.generation))
+            .collect::<FnvHashSet<_>>();
+        self.unlink_edges(|ed| removed_edges.contains(&ed));
+        for ed in removed_edges {
+            self.take_edge(ed);
+        }
+        for vd in removed {
+            self.take_vertex(vd);
+        }
+    }
+
+    fn retain_edges<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&Self, EdgeDescriptor) -> bool,
+    {
+        let removed = self.edges
+            .iter()
+            .map(|(k, e)| EdgeDescriptor::with_generation(k, e.generation))
+            .filter(|&ed| !f(self, ed))
+            .collect::<FnvHashSet<_>>();
+        if removed.is_empty() {
+            return;
+        }
+
+        self.unlink_edges(|ed| removed.contains(&ed));
+        for ed in removed {
+            self.take_edge(ed);
         }
     }
 
     fn vertex_property_mut(&mut self, d: VertexDescriptor) -> Option<&mut Self::VertexProperty> {
-        self.vertices.get_mut(d.into()).and_then(|&mut Vertex {
-             incidence: (_, ref mut vp, _),
-         }| Some(vp))
+        self.vertex_mut(d).map(|v| &mut v.incidence.1)
     }
 
     fn edge_property_mut(&mut self, d: EdgeDescriptor) -> Option<&mut Self::EdgeProperty> {
-        self.edges.get_mut(d.into()).and_then(|&mut Edge {
-             incidence: (_, ref mut ep, _),
-             next: _,
-         }| Some(ep))
+        self.edge_entry_mut(d).map(|e| &mut e.incidence.1)
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<D, VP, EP> IncidenceList<D, VP, EP>
+where
+    D: Sync,
+    VP: Sync,
+    EP: Sync,
+{
+    pub fn par_vertices<'a>(&'a self) -> impl ParallelIterator<Item = VertexDescriptor> + 'a {
+        (0..self.vertices.capacity()).into_par_iter().filter_map(move |k| {
+            self.vertices.get(k).map(
+                |v| VertexDescriptor::with_generation(k, v.generation),
+            )
+        })
+    }
+
+    pub fn par_edges<'a>(&'a self) -> impl ParallelIterator<Item = EdgeDescriptor> + 'a {
+        (0..self.edges.capacity()).into_par_iter().filter_map(move |k| {
+            self.edges.get(k).map(
+                |e| EdgeDescriptor::with_generation(k, e.generation),
+            )
+        })
     }
 }
 
@@ -501,6 +1245,185 @@ mod tests {
         assert!(g.vertices().any(|x| g.vertex_property(x) != Some(&69)));
     }
 
+    #[test]
+    fn index() {
+        use graph::{Directed, MutableGraph};
+
+        let mut g = IncidenceList::<Directed, isize, String>::new();
+
+        let v1 = g.add_vertex(3);
+        let v2 = g.add_vertex(5);
+        let e12 = g.add_edge(v1, v2, "a".into()).unwrap();
+
+        assert_eq!(g[v1], 3);
+        assert_eq!(g[v2], 5);
+        assert_eq!(g[e12], "a");
+
+        g[v1] = 42;
+        g[e12].push('b');
+
+        assert_eq!(g[v1], 42);
+        assert_eq!(g[e12], "ab");
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_with_removed_vertex() {
+        use graph::{Directed, MutableGraph};
+
+        let mut g = IncidenceList::<Directed, isize, String>::new();
+
+        let v1 = g.add_vertex(3);
+        assert!(g.remove_vertex(v1).is_some());
+
+        let _ = g[v1];
+    }
+
+    #[test]
+    fn contains() {
+        use graph::{Directed, Graph, MutableGraph};
+
+        let mut g = IncidenceList::<Directed, isize, String>::new();
+
+        let v1 = g.add_vertex(3);
+        let v2 = g.add_vertex(5);
+        let e12 = g.add_edge(v1, v2, "a".into()).unwrap();
+
+        assert!(g.contains_vertex(v1));
+        assert!(g.contains_vertex(v2));
+        assert!(g.contains_edge(e12));
+
+        assert!(g.remove_vertex(v2).is_some());
+
+        assert!(g.contains_vertex(v1));
+        assert!(!g.contains_vertex(v2));
+        assert!(!g.contains_edge(e12));
+    }
+
+    #[test]
+    #[cfg(feature = "generational")]
+    fn stale_descriptor() {
+        use graph::{Directed, Graph, IncidenceGraph, MutableGraph};
+
+        let mut g = IncidenceList::<Directed, isize, String>::new();
+
+        let v1 = g.add_vertex(3);
+        let v2 = g.add_vertex(5);
+        let e12 = g.add_edge(v1, v2, "a".into()).unwrap();
+
+        assert!(g.remove_vertex(v2).is_some());
+        let v3 = g.add_vertex(7);
+        let e13 = g.add_edge(v1, v3, "b".into()).unwrap();
+
+        assert_eq!(usize::from(v2), usize::from(v3));
+        assert_eq!(usize::from(e12), usize::from(e13));
+        assert!(v2 != v3 && e12 != e13);
+
+        assert_eq!(g.vertex_property(v2), None);
+        assert_eq!(g.vertex_property(v3), Some(&7));
+        assert_eq!(g.edge_property(e12), None);
+        assert_eq!(g.edge_endpoints(e12), None);
+        assert_eq!(g.vertex_property_mut(v2), None);
+        assert_eq!(g.remove_edge(e12), None);
+        assert_eq!(g.remove_vertex(v2), None);
+        assert!(g.add_edge(v1, v2, "c".into()).is_err());
+        assert_eq!(g.out_edges(v1).collect::<Vec<_>>(), vec![e13]);
+
+        g.clear();
+        let v4 = g.add_vertex(11);
+        assert_eq!(usize::from(v1), usize::from(v4));
+        assert!(!g.contains_vertex(v1));
+    }
+
+    #[test]
+    fn format() {
+        use graph::{Directed, MutableGraph};
+
+        let mut g = IncidenceList::<Directed, isize, &str>::new();
+
+        let v0 = g.add_vertex(3);
+        let v1 = g.add_vertex(5);
+        let v2 = g.add_vertex(7);
+        let v3 = g.add_vertex(11);
+
+        g.add_edge(v0, v1, "a").unwrap();
+        g.add_edge(v1, v2, "b").unwrap();
+        g.add_edge(v1, v0, "c").unwrap();
+        g.add_edge(v0, v3, "d").unwrap();
+
+        assert_eq!(
+            format!("{}", g),
+            "v0(3) -> v1 [\"a\"], v3 [\"d\"]\n\
+             v1(5) -> v2 [\"b\"], v0 [\"c\"]\n\
+             v2(7)\n\
+             v3(11)"
+        );
+        assert_eq!(
+            format!("{:?}", g),
+            "IncidenceList { v0(3) -> v1 [\"a\"], v3 [\"d\"]; \
+             v1(5) -> v2 [\"b\"], v0 [\"c\"]; v2(7); v3(11) }"
+        );
+    }
+
+    #[test]
+    fn from_iter_and_extend() {
+        use graph::{Directed, EdgeListGraph, FromUsize, Graph, IncidenceGraph, MutableGraph,
+                    VertexDescriptor, VertexListGraph};
+
+        let v = |i| VertexDescriptor::from_usize(i);
+
+        let mut g = vec![(v(0), v(1), "a"), (v(1), v(3), "b")]
+            .into_iter()
+            .collect::<IncidenceList<Directed, isize, _>>();
+
+        assert_eq!(g.order(), 4);
+        assert_eq!(g.size(), 2);
+        assert_eq!(g.vertex_property(v(2)), Some(&0));
+        assert_eq!(g.out_degree(v(1)), 1);
+
+        g[v(2)] = 7;
+        assert!(g.remove_vertex(v(3)).is_some());
+
+        g.extend(vec![(v(2), v(0), "c"), (v(0), v(4), "d")]);
+
+        assert_eq!(g.order(), 5);
+        assert_eq!(g.size(), 3);
+        assert_eq!(g.vertex_property(v(2)), Some(&7));
+        assert_eq!(g.vertex_properties().filter(|&&p| p == 0).count(), 4);
+        assert_eq!(g.out_degree(v(0)), 2);
+
+        let g = IncidenceList::<Directed, isize, ()>::default();
+        assert_eq!(g.order(), 0);
+    }
+
+    #[test]
+    fn try_extend_with_removed_vertex() {
+        use error::GraphError;
+        use graph::{Directed, EdgeListGraph, FromUsize, Graph, MutableGraph, VertexDescriptor,
+                    VertexListGraph};
+
+        let v = |i| VertexDescriptor::from_usize(i);
+
+        let mut g = IncidenceList::<Directed, isize, _>::from_edges(vec![(v(0), v(2), "a")])
+            .unwrap();
+        assert_eq!((g.order(), g.size()), (3, 1));
+        assert_eq!(g.vertex_property(v(1)), Some(&0));
+
+        // the slot of a removed vertex is filled again, but by a new vertex that only has the
+        // same descriptor without the generational feature
+        let removed = g.add_vertex(5);
+        assert!(g.remove_vertex(removed).is_some());
+        let edges = vec![(v(1), v(0), "b"), (removed, v(0), "c"), (v(0), v(1), "d")];
+        if cfg!(feature = "generational") {
+            assert_eq!(g.try_extend(edges), Err(GraphError::VertexNotFound(removed)));
+            assert_eq!(g.size(), 2);
+        } else {
+            assert_eq!(g.try_extend(edges), Ok(()));
+            assert_eq!(g.size(), 4);
+        }
+        assert_eq!(g.order(), 4);
+    }
+
     #[test]
     fn general_usage() {
         use graph::{Directed, EdgeListGraph, Graph, IncidenceGraph, MutableGraph, VertexListGraph};
@@ -511,9 +1434,9 @@ mod tests {
         let v2 = g.add_vertex(5);
         let v3 = g.add_vertex(7);
 
-        let e12 = g.add_edge(v1, v2, "a".into());
-        let e23 = g.add_edge(v2, v3, "b".into());
-        let e31 = g.add_edge(v3, v1, "c".into());
+        let e12 = g.add_edge(v1, v2, "a".into()).ok();
+        let e23 = g.add_edge(v2, v3, "b".into()).ok();
+        let e31 = g.add_edge(v3, v1, "c".into()).ok();
 
         // V1 <--E31--- V3
         // |            ^
@@ -563,6 +1486,32 @@ mod tests {
         assert_eq!(g.size(), 0);
     }
 
+    #[test]
+    fn edge_endpoints() {
+        use graph::{Directed, IncidenceGraph, MutableGraph};
+
+        let mut g = IncidenceList::<Directed, isize, String>::new();
+
+        let v1 = g.add_vertex(3);
+        let v2 = g.add_vertex(5);
+
+        let e12 = g.add_edge(v1, v2, "a".into()).unwrap();
+        let e21 = g.add_edge(v2, v1, "b".into()).unwrap();
+
+        assert_eq!(g.edge_endpoints(e12), Some((v1, v2)));
+        assert_eq!(g.edge_endpoints(e21), Some((v2, v1)));
+
+        assert!(g.remove_edge(e12).is_some());
+
+        assert_eq!(g.edge_endpoints(e12), None);
+        assert_eq!(g.edge_endpoints(e21), Some((v2, v1)));
+
+        assert_eq!(g.try_source(e12), None);
+        assert_eq!(g.try_target(e12), None);
+        assert_eq!(g.try_source(e21), Some(v2));
+        assert_eq!(g.try_target(e21), Some(v1));
+    }
+
     #[test]
     fn degree() {
         use graph::{Directed, IncidenceGraph, BidirectionalGraph, EdgeListGraph, MutableGraph};
@@ -573,10 +1522,10 @@ mod tests {
         let v2 = g.add_vertex(None);
         let v3 = g.add_vertex(Some(3));
 
-        assert!(g.add_edge(v1, v2, "a".into()) != None);
-        let e23 = g.add_edge(v2, v3, "b".into());
+        assert!(g.add_edge(v1, v2, "a".into()).is_ok());
+        let e23 = g.add_edge(v2, v3, "b".into()).ok();
         assert!(e23.is_some());
-        assert!(g.add_edge(v3, v1, "c".into()) != None);
+        assert!(g.add_edge(v3, v1, "c".into()).is_ok());
 
         // V1 <------- V3
         // |           ^
@@ -593,7 +1542,7 @@ mod tests {
         assert_eq!(g.in_degree(v3), 1);
 
         let v4 = g.add_vertex(Some(3));
-        assert!(g.add_edge(v4, v1, "d".to_string()) != None);
+        assert!(g.add_edge(v4, v1, "d".to_string()).is_ok());
 
         // V4
         // |
@@ -616,11 +1565,11 @@ mod tests {
         assert_eq!(g.out_degree(v4), 1);
 
         assert!(g.remove_edge(e23.unwrap()).is_some());
-        g.add_edge(v3, v2, "d1".to_string());
+        g.add_edge(v3, v2, "d1".to_string()).unwrap();
         let v5 = g.add_vertex(None);
-        g.add_edge(v2, v5, "d2".to_string());
-        g.add_edge(v5, v3, "d3".to_string());
-        g.add_edge(v5, v4, "d4".to_string());
+        g.add_edge(v2, v5, "d2".to_string()).unwrap();
+        g.add_edge(v5, v3, "d3".to_string()).unwrap();
+        g.add_edge(v5, v4, "d4".to_string()).unwrap();
 
         // V4 <-------------------+
         // |                      |
@@ -635,19 +1584,61 @@ mod tests {
         // |                      |
         // +--------------------> V5
 
-        assert_eq!(g.in_degree(v1), 2);
-        assert_eq!(g.in_degree(v2), 2);
-        assert_eq!(g.in_degree(v3), 1);
-        assert_eq!(g.in_degree(v4), 1);
-        assert_eq!(g.in_degree(v5), 1);
+        assert_eq!(g.in_degree(v1), 2);
+        assert_eq!(g.in_degree(v2), 2);
+        assert_eq!(g.in_degree(v3), 1);
+        assert_eq!(g.in_degree(v4), 1);
+        assert_eq!(g.in_degree(v5), 1);
+
+        assert_eq!(g.out_degree(v1), 1);
+        assert_eq!(g.out_degree(v2), 1);
+        assert_eq!(g.out_degree(v3), 2);
+        assert_eq!(g.out_degree(v4), 1);
+        assert_eq!(g.out_degree(v5), 2);
+
+        assert_eq!(g.size(), 7);
+    }
+
+    #[test]
+    fn self_loops() {
+        use graph::{BidirectionalGraph, EdgeListGraph, IncidenceGraph, MutableGraph, Undirected};
+
+        let mut g = IncidenceList::<Undirected, isize, String>::new();
+
+        let v1 = g.add_vertex(3);
+        let v2 = g.add_vertex(5);
+
+        let e11 = g.add_edge(v1, v1, "a".into()).unwrap();
+        let e12 = g.add_edge(v1, v2, "b".into()).unwrap();
+        let e22 = g.add_edge(v2, v2, "c".into()).unwrap();
+
+        //   E11
+        //  /   \
+        //  \   /
+        //   V1 ---E12--- V2
+        //               /  \
+        //               \  /
+        //               E22
+
+        assert!(g.is_self_loop(e11));
+        assert!(!g.is_self_loop(e12));
+        assert!(g.is_self_loop(e22));
 
-        assert_eq!(g.out_degree(v1), 1);
-        assert_eq!(g.out_degree(v2), 1);
-        assert_eq!(g.out_degree(v3), 2);
-        assert_eq!(g.out_degree(v4), 1);
-        assert_eq!(g.out_degree(v5), 2);
+        let mut i = g.self_loops().collect::<Vec<_>>();
+        i.sort();
+        assert_eq!(i, vec![e11, e22]);
 
-        assert_eq!(g.size(), 7);
+        assert_eq!(g.degree(v1), 3);
+        assert_eq!(g.degree(v2), 3);
+
+        g.remove_self_loops();
+
+        assert_eq!(g.size(), 1);
+        assert_eq!(g.self_loops().next(), None);
+        assert!(!g.is_self_loop(e11));
+        assert_eq!(g.degree(v1), 1);
+        assert_eq!(g.degree(v2), 1);
+        assert_eq!(g.out_edges(v1).collect::<Vec<_>>(), vec![e12]);
     }
 
     #[test]
@@ -661,10 +1652,10 @@ mod tests {
         let v3 = g.add_vertex(7);
         let v4 = g.add_vertex(11);
 
-        let e12 = g.add_edge(v1, v2, "a".into());
-        let e23 = g.add_edge(v2, v3, "b".into());
-        let e21 = g.add_edge(v2, v1, "c".into());
-        let e14 = g.add_edge(v1, v4, "d".into());
+        let e12 = g.add_edge(v1, v2, "a".into()).ok();
+        let e23 = g.add_edge(v2, v3, "b".into()).ok();
+        let e21 = g.add_edge(v2, v1, "c".into()).ok();
+        let e14 = g.add_edge(v1, v4, "d".into()).ok();
 
         // +--> V1 ---E14--> V4
         // |    |
@@ -696,10 +1687,10 @@ mod tests {
         let v3 = g.add_vertex(7);
         let v4 = g.add_vertex(11);
 
-        let e12 = g.add_edge(v1, v2, "a".into());
-        let e23 = g.add_edge(v2, v3, "b".into());
-        let e21 = g.add_edge(v2, v1, "c".into());
-        let e14 = g.add_edge(v1, v4, "d".into());
+        let e12 = g.add_edge(v1, v2, "a".into()).ok();
+        let e23 = g.add_edge(v2, v3, "b".into()).ok();
+        let e21 = g.add_edge(v2, v1, "c".into()).ok();
+        let e14 = g.add_edge(v1, v4, "d".into()).ok();
 
         // +--> V1 ---E14--> V4
         // |    |
@@ -723,6 +1714,105 @@ mod tests {
         assert!(i == vec![e14.unwrap()]);
     }
 
+    #[test]
+    fn directed_iterators() {
+        use graph::{BidirectionalGraph, Directed, Direction, MutableGraph};
+
+        let mut g = IncidenceList::<Directed, isize, String>::new();
+
+        let v1 = g.add_vertex(3);
+        let v2 = g.add_vertex(5);
+        let v3 = g.add_vertex(7);
+        let v4 = g.add_vertex(11);
+
+        let e12 = g.add_edge(v1, v2, "a".into()).ok();
+        let e23 = g.add_edge(v2, v3, "b".into()).ok();
+        let e21 = g.add_edge(v2, v1, "c".into()).ok();
+        let e14 = g.add_edge(v1, v4, "d".into()).ok();
+
+        // +--> V1 ---E14--> V4
+        // |    |
+        // E21  E12
+        // |    |
+        // |    v
+        // +--- V2 ---E23--> V3
+
+        assert!(e12.is_some() && e23.is_some() && e21.is_some() && e14.is_some());
+
+        let mut i = g.edges_directed(v1, Direction::Outgoing).collect::<Vec<_>>();
+        i.sort();
+        assert_eq!(i, vec![e12.unwrap(), e14.unwrap()]);
+
+        let i = g.edges_directed(v1, Direction::Incoming).collect::<Vec<_>>();
+        assert_eq!(i, vec![e21.unwrap()]);
+
+        let mut i = g.neighbors_directed(v2, Direction::Outgoing).collect::<Vec<_>>();
+        i.sort();
+        assert_eq!(i, vec![v1, v3]);
+
+        let i = g.neighbors_directed(v2, Direction::Incoming).collect::<Vec<_>>();
+        assert_eq!(i, vec![v1]);
+
+        assert_eq!(g.neighbors_directed(v3, Direction::Outgoing).next(), None);
+        assert_eq!(
+            g.neighbors_directed(v4, Direction::Incoming).collect::<Vec<_>>(),
+            vec![v1]
+        );
+        assert_eq!(Direction::Outgoing.opposite(), Direction::Incoming);
+    }
+
+    #[test]
+    fn externals() {
+        use graph::{Directed, Direction, MutableGraph};
+
+        let mut g = IncidenceList::<Directed, isize, String>::new();
+
+        let v1 = g.add_vertex(3);
+        let v2 = g.add_vertex(5);
+        let v3 = g.add_vertex(7);
+        let v4 = g.add_vertex(11);
+        let v5 = g.add_vertex(13);
+
+        g.add_edge(v1, v2, "a".into()).unwrap();
+        g.add_edge(v2, v3, "b".into()).unwrap();
+        g.add_edge(v4, v2, "c".into()).unwrap();
+
+        // V1 --> V2 --> V3    V5
+        //        ^
+        //        |
+        //        V4
+
+        assert_eq!(g.sources().collect::<Vec<_>>(), vec![v1, v4, v5]);
+        assert_eq!(g.sinks().collect::<Vec<_>>(), vec![v3, v5]);
+        assert_eq!(
+            g.externals(Direction::Incoming).collect::<Vec<_>>(),
+            vec![v1, v4, v5]
+        );
+    }
+
+    #[test]
+    fn externals_on_undirected_graph() {
+        use graph::{Direction, MutableGraph, Undirected};
+
+        let mut g = IncidenceList::<Undirected, isize, String>::new();
+
+        let v1 = g.add_vertex(3);
+        let v2 = g.add_vertex(5);
+        let v3 = g.add_vertex(7);
+        let v4 = g.add_vertex(11);
+
+        g.add_edge(v1, v2, "a".into()).unwrap();
+        g.add_edge(v3, v3, "b".into()).unwrap();
+
+        // V1 --- V2    V3 --+    V4
+        //              ^    |
+        //              +----+
+
+        assert_eq!(g.sources().collect::<Vec<_>>(), vec![v4]);
+        assert_eq!(g.sinks().collect::<Vec<_>>(), vec![v4]);
+        assert_eq!(g.externals(Direction::Outgoing).collect::<Vec<_>>(), vec![v4]);
+    }
+
     #[test]
     fn adj_iterator_on_directed_graph() {
         use graph::{AdjacencyGraph, Directed, MutableGraph};
@@ -734,10 +1824,10 @@ mod tests {
         let v3 = g.add_vertex(7);
         let v4 = g.add_vertex(11);
 
-        g.add_edge(v1, v2, "a".into());
-        g.add_edge(v2, v3, "b".into());
-        g.add_edge(v2, v1, "c".into());
-        g.add_edge(v1, v4, "d".into());
+        g.add_edge(v1, v2, "a".into()).unwrap();
+        g.add_edge(v2, v3, "b".into()).unwrap();
+        g.add_edge(v2, v1, "c".into()).unwrap();
+        g.add_edge(v1, v4, "d".into()).unwrap();
 
         // +--> V1 ---E14--> V4
         // |    |
@@ -770,10 +1860,10 @@ mod tests {
         let v3 = g.add_vertex(7);
         let v4 = g.add_vertex(11);
 
-        g.add_edge(v1, v2, "a".into());
-        g.add_edge(v2, v3, "b".into());
-        g.add_edge(v2, v1, "c".into());
-        g.add_edge(v1, v4, "d".into());
+        g.add_edge(v1, v2, "a".into()).unwrap();
+        g.add_edge(v2, v3, "b".into()).unwrap();
+        g.add_edge(v2, v1, "c".into()).unwrap();
+        g.add_edge(v1, v4, "d".into()).unwrap();
 
         // +--- V1 ---E14--- V4
         // |    |
@@ -781,11 +1871,16 @@ mod tests {
         // |    |
         // +--- V2 ---E23--- V3
 
-        let i = g.adjacent_vertices(v1).collect::<Vec<_>>();
-        assert!(i == vec![v2, v4] || i == vec![v4, v2]);
+        // once for every edge, so V2 and V1 twice
+        let mut i = g.adjacent_vertices(v1).collect::<Vec<_>>();
+        i.sort();
+        assert_eq!(i, vec![v2, v2, v4]);
+        assert_eq!(g.unique_adjacent_vertices(v1).collect::<Vec<_>>(), vec![v2, v4]);
 
-        let i = g.adjacent_vertices(v2).collect::<Vec<_>>();
-        assert!(i == vec![v1, v3] || i == vec![v3, v1]);
+        let mut i = g.adjacent_vertices(v2).collect::<Vec<_>>();
+        i.sort();
+        assert_eq!(i, vec![v1, v1, v3]);
+        assert_eq!(g.unique_adjacent_vertices(v2).collect::<Vec<_>>(), vec![v1, v3]);
 
         let i = g.adjacent_vertices(v3).collect::<Vec<_>>();
         assert!(i == vec![v2]);
@@ -806,10 +1901,10 @@ mod tests {
         let v3 = g.add_vertex(7);
         let v4 = g.add_vertex(11);
 
-        let e12 = g.add_edge(v1, v2, "a".into());
-        let e23 = g.add_edge(v2, v3, "b".into());
-        let e21 = g.add_edge(v2, v1, "c".into());
-        let e14 = g.add_edge(v1, v4, "d".into());
+        let e12 = g.add_edge(v1, v2, "a".into()).ok();
+        let e23 = g.add_edge(v2, v3, "b".into()).ok();
+        let e21 = g.add_edge(v2, v1, "c".into()).ok();
+        let e14 = g.add_edge(v1, v4, "d".into()).ok();
 
         // +--> V1 ---E14--> V4
         // |    |
@@ -833,6 +1928,104 @@ mod tests {
         assert_eq!(es.len(), 4);
     }
 
+    #[test]
+    fn vertex_references() {
+        use graph::{Directed, MutableGraph};
+
+        let mut g = IncidenceList::<Directed, isize, String>::new();
+
+        let v1 = g.add_vertex(3);
+        let v2 = g.add_vertex(5);
+        let v3 = g.add_vertex(7);
+
+        g.add_edge(v1, v2, "a".into()).unwrap();
+        g.add_edge(v2, v3, "b".into()).unwrap();
+
+        let mut vs = g.vertex_references().collect::<Vec<_>>();
+        vs.sort();
+        assert_eq!(vs, vec![(v1, &3), (v2, &5), (v3, &7)]);
+
+        let mut vps = g.vertex_properties().cloned().collect::<Vec<_>>();
+        vps.sort();
+        assert_eq!(vps, vec![3, 5, 7]);
+
+        let mut eps = g.edge_properties().cloned().collect::<Vec<_>>();
+        eps.sort();
+        assert_eq!(eps, vec!["a".to_string(), "b".to_string()]);
+
+        assert!(g.remove_vertex(v2).is_some());
+
+        let mut vs = g.vertex_references().collect::<Vec<_>>();
+        vs.sort();
+        assert_eq!(vs, vec![(v1, &3), (v3, &7)]);
+        assert_eq!(g.edge_properties().next(), None);
+    }
+
+    #[test]
+    fn edge_references() {
+        use graph::{Directed, MutableGraph};
+
+        let mut g = IncidenceList::<Directed, isize, String>::new();
+
+        let v1 = g.add_vertex(3);
+        let v2 = g.add_vertex(5);
+        let v3 = g.add_vertex(7);
+
+        let e12 = g.add_edge(v1, v2, "a".into()).unwrap();
+        let e23 = g.add_edge(v2, v3, "b".into()).unwrap();
+        let e31 = g.add_edge(v3, v1, "c".into()).unwrap();
+
+        let mut es = g.edge_references().collect::<Vec<_>>();
+        es.sort();
+        assert_eq!(
+            es,
+            vec![
+                (e12, v1, v2, &"a".to_string()),
+                (e23, v2, v3, &"b".to_string()),
+                (e31, v3, v1, &"c".to_string()),
+            ]
+        );
+
+        assert!(g.remove_edge(e23).is_some());
+
+        let mut es = g.edge_references().map(|(e, _, _, _)| e).collect::<Vec<_>>();
+        es.sort();
+        assert_eq!(es, vec![e12, e31]);
+    }
+
+    #[test]
+    fn bounds() {
+        use graph::{Directed, EdgeListGraph, MutableGraph, VertexListGraph};
+
+        let mut g = IncidenceList::<Directed, isize, String>::new();
+
+        assert_eq!(g.vertex_bound(), 0);
+        assert_eq!(g.edge_bound(), 0);
+
+        let v1 = g.add_vertex(3);
+        let v2 = g.add_vertex(5);
+        let v3 = g.add_vertex(7);
+
+        let e12 = g.add_edge(v1, v2, "a".into()).unwrap();
+        let e23 = g.add_edge(v2, v3, "b".into()).unwrap();
+
+        assert_eq!(g.vertex_bound(), 3);
+        assert_eq!(g.edge_bound(), 2);
+        assert!(g.vertices().all(|v| usize::from(v) < g.vertex_bound()));
+
+        assert!(g.remove_edge(e12).is_some());
+        assert_eq!(g.edge_bound(), 2);
+
+        assert!(g.remove_edge(e23).is_some());
+        assert_eq!(g.edge_bound(), 0);
+
+        assert!(g.remove_vertex(v2).is_some());
+        assert_eq!(g.vertex_bound(), 3);
+
+        assert!(g.remove_vertex(v3).is_some());
+        assert_eq!(g.vertex_bound(), 1);
+    }
+
     #[test]
     fn duplicate_label() {
         use graph::{EdgeListGraph, Directed, MutableGraph, VertexListGraph};
@@ -843,8 +2036,8 @@ mod tests {
         let v2 = g.add_vertex(5);
         let v3 = g.add_vertex(7);
 
-        let e12 = g.add_edge(v1, v2, "a".into());
-        let e23 = g.add_edge(v2, v3, "b".into());
+        let e12 = g.add_edge(v1, v2, "a".into()).ok();
+        let e23 = g.add_edge(v2, v3, "b".into()).ok();
 
         // V1          V3
         // |           ^
@@ -868,8 +2061,8 @@ mod tests {
         let v2 = g.add_vertex(5);
         let v3 = g.add_vertex(7);
 
-        let e12 = g.add_edge(v1, v2, "a".into());
-        let e13 = g.add_edge(v1, v3, "b".into());
+        let e12 = g.add_edge(v1, v2, "a".into()).ok();
+        let e13 = g.add_edge(v1, v3, "b".into()).ok();
 
         // V1 -------> V3
         // |
@@ -895,6 +2088,290 @@ mod tests {
         assert_eq!(g.order(), 3);
     }
 
+    #[test]
+    fn remove_edge_from_middle_of_incidence_lists() {
+        use graph::{BidirectionalGraph, Directed, IncidenceGraph, MutableGraph};
+
+        let mut g = IncidenceList::<Directed, isize, String>::new();
+
+        let v1 = g.add_vertex(3);
+        let v2 = g.add_vertex(5);
+
+        let e1 = g.add_edge(v1, v2, "a".into()).unwrap();
+        let e2 = g.add_edge(v1, v2, "b".into()).unwrap();
+        let e3 = g.add_edge(v1, v2, "c".into()).unwrap();
+
+        assert!(g.remove_edge(e2).is_some());
+
+        let mut i = g.out_edges(v1).collect::<Vec<_>>();
+        i.sort();
+        assert_eq!(i, vec![e1, e3]);
+
+        let mut i = g.in_edges(v2).collect::<Vec<_>>();
+        i.sort();
+        assert_eq!(i, vec![e1, e3]);
+
+        let e4 = g.add_edge(v2, v1, "d".into()).unwrap();
+        assert_eq!(g.out_degree(v1), 2);
+        assert_eq!(g.in_degree(v2), 2);
+        assert_eq!(g.out_edges(v2).collect::<Vec<_>>(), vec![e4]);
+    }
+
+    #[test]
+    fn add_edge_to_missing_vertex() {
+        use error::GraphError;
+        use graph::{Directed, EdgeListGraph, IncidenceGraph, MutableGraph};
+
+        let mut g = IncidenceList::<Directed, isize, String>::new();
+
+        let v1 = g.add_vertex(3);
+        let v2 = g.add_vertex(5);
+        let e1 = g.add_edge(v1, v2, "a".into()).unwrap();
+
+        let v3 = g.add_vertex(7);
+        assert!(g.remove_vertex(v3).is_some());
+
+        assert_eq!(
+            g.add_edge(v1, v3, "b".into()),
+            Err(GraphError::VertexNotFound(v3))
+        );
+        assert_eq!(
+            g.add_edge(v3, v1, "c".into()),
+            Err(GraphError::VertexNotFound(v3))
+        );
+        assert_eq!(g.size(), 1);
+        assert_eq!(g.out_edges(v1).collect::<Vec<_>>(), vec![e1]);
+    }
+
+    #[test]
+    fn remove_vertex_with_self_loop() {
+        use graph::{Directed, EdgeListGraph, MutableGraph, VertexListGraph};
+
+        let mut g = IncidenceList::<Directed, isize, String>::new();
+
+        let v1 = g.add_vertex(3);
+        let v2 = g.add_vertex(5);
+
+        g.add_edge(v1, v1, "a".into()).unwrap();
+        g.add_edge(v1, v2, "b".into()).unwrap();
+
+        assert_eq!(g.remove_vertex(v1), Some(3));
+        assert_eq!(g.order(), 1);
+        assert_eq!(g.size(), 0);
+    }
+
+    #[test]
+    fn retain_edges() {
+        use graph::{BidirectionalGraph, Directed, EdgeListGraph, Graph, IncidenceGraph,
+                    MutableGraph};
+
+        let mut g = IncidenceList::<Directed, isize, isize>::new();
+
+        let v1 = g.add_vertex(3);
+        let v2 = g.add_vertex(5);
+        let v3 = g.add_vertex(7);
+
+        let e12 = g.add_edge(v1, v2, 1).unwrap();
+        let e13 = g.add_edge(v1, v3, 2).unwrap();
+        let e23 = g.add_edge(v2, v3, 3).unwrap();
+        let e31 = g.add_edge(v3, v1, 4).unwrap();
+        let e32 = g.add_edge(v3, v2, 5).unwrap();
+
+        g.retain_edges(|g, e| g.edge_property(e).unwrap() % 2 == 1);
+
+        assert_eq!(g.size(), 3);
+        assert_eq!(g.out_edges(v1).collect::<Vec<_>>(), vec![e12]);
+        assert_eq!(g.out_edges(v2).collect::<Vec<_>>(), vec![e23]);
+        assert_eq!(g.out_edges(v3).collect::<Vec<_>>(), vec![e32]);
+        assert_eq!(g.in_edges(v1).next(), None);
+        assert_eq!(g.in_edges(v3).collect::<Vec<_>>(), vec![e23]);
+        assert_eq!(g.edge_property(e13), None);
+        assert_eq!(g.edge_property(e31), None);
+
+        let mut i = g.in_edges(v2).collect::<Vec<_>>();
+        i.sort();
+        assert_eq!(i, vec![e12, e32]);
+    }
+
+    #[test]
+    fn retain_vertices() {
+        use graph::{BidirectionalGraph, Directed, EdgeListGraph, Graph, IncidenceGraph,
+                    MutableGraph, VertexListGraph};
+
+        let mut g = IncidenceList::<Directed, isize, isize>::new();
+
+        let v1 = g.add_vertex(3);
+        let v2 = g.add_vertex(4);
+        let v3 = g.add_vertex(7);
+        let v4 = g.add_vertex(9);
+
+        g.add_edge(v1, v2, 1).unwrap();
+        let e13 = g.add_edge(v1, v3, 2).unwrap();
+        g.add_edge(v2, v2, 3).unwrap();
+        g.add_edge(v2, v4, 4).unwrap();
+        let e43 = g.add_edge(v4, v3, 5).unwrap();
+
+        g.retain_vertices(|g, v| g.vertex_property(v).unwrap() % 2 == 1);
+
+        assert_eq!(g.order(), 3);
+        assert_eq!(g.size(), 2);
+        assert_eq!(g.vertex_property(v2), None);
+        assert_eq!(g.out_edges(v1).collect::<Vec<_>>(), vec![e13]);
+        assert_eq!(g.out_edges(v4).collect::<Vec<_>>(), vec![e43]);
+        assert_eq!(g.in_edges(v4).next(), None);
+
+        let mut i = g.in_edges(v3).collect::<Vec<_>>();
+        i.sort();
+        assert_eq!(i, vec![e13, e43]);
+    }
+
+    #[test]
+    fn relabel() {
+        use error::GraphError;
+        use graph::{BidirectionalGraph, Directed, EdgeListGraph, Graph, IncidenceGraph,
+                    MutableGraph, VertexListGraph};
+
+        let mut g = IncidenceList::<Directed, isize, isize>::new();
+
+        let vs = (0..6).map(|i| g.add_vertex(i)).collect::<Vec<_>>();
+        g.add_edge(vs[0], vs[5], 0).unwrap();
+        let e1 = g.add_edge(vs[1], vs[3], 1).unwrap();
+        let e2 = g.add_edge(vs[3], vs[3], 2).unwrap();
+        let e3 = g.add_edge(vs[5], vs[3], 3).unwrap();
+        let e4 = g.add_edge(vs[5], vs[1], 4).unwrap();
+        g.remove_vertex(vs[0]);
+        g.remove_vertex(vs[2]);
+        g.remove_vertex(vs[4]);
+        assert_eq!(g.vertex_bound(), 6);
+
+        assert_eq!(g.relabel(&[vs[0]]), Err(GraphError::VertexNotFound(vs[0])));
+        let r = g.relabel(&[vs[5]]).unwrap();
+        g.validate().unwrap();
+        assert_eq!((g.order(), g.size()), (3, 4));
+        assert_eq!((g.vertex_bound(), g.edge_bound()), (3, 4));
+        assert_eq!(r.vertex(vs[0]), None);
+
+        let v5 = r.vertex(vs[5]).unwrap();
+        let v1 = r.vertex(vs[1]).unwrap();
+        let v3 = r.vertex(vs[3]).unwrap();
+        assert_eq!(g.vertices().collect::<Vec<_>>(), vec![v5, v1, v3]);
+        assert_eq!(g.vertex_property(v3), Some(&3));
+        assert_eq!(g.vertex_property(vs[5]), None);
+        assert_eq!(
+            g.out_edges(v5).collect::<Vec<_>>(),
+            vec![r.edge(e4).unwrap(), r.edge(e3).unwrap()]
+        );
+        assert_eq!(
+            g.in_edges(v3).collect::<Vec<_>>(),
+            vec![r.edge(e3).unwrap(), r.edge(e2).unwrap(), r.edge(e1).unwrap()]
+        );
+        assert_eq!(g.edge_property(r.edge(e1).unwrap()), Some(&1));
+        assert_eq!(g.source(r.edge(e1).unwrap()), v1);
+
+        let r = g.compact();
+        let compacted = [v5, v1, v3].iter().map(|&v| r.vertex(v).unwrap()).collect::<Vec<_>>();
+        assert_eq!(g.vertices().collect::<Vec<_>>(), compacted);
+    }
+
+    #[test]
+    fn clear() {
+        use graph::{BidirectionalGraph, Directed, EdgeListGraph, Graph, IncidenceGraph,
+                    MutableGraph, VertexListGraph};
+
+        let mut g = IncidenceList::<Directed, isize, String>::new();
+
+        let v1 = g.add_vertex(3);
+        let v2 = g.add_vertex(5);
+
+        g.add_edge(v1, v2, "a".into()).unwrap();
+        g.add_edge(v2, v1, "b".into()).unwrap();
+
+        g.clear_edges();
+
+        assert_eq!(g.order(), 2);
+        assert_eq!(g.size(), 0);
+        assert_eq!(g.vertex_property(v1), Some(&3));
+        assert_eq!(g.out_degree(v1), 0);
+        assert_eq!(g.in_degree(v1), 0);
+        assert_eq!(g.degree(v2), 0);
+
+        let e = g.add_edge(v1, v2, "c".into()).unwrap();
+        assert_eq!(g.out_edges(v1).collect::<Vec<_>>(), vec![e]);
+
+        g.clear();
+
+        assert_eq!(g.order(), 0);
+        assert_eq!(g.size(), 0);
+        assert_eq!(g.vertex_property(v1), None);
+    }
+
+    #[test]
+    fn validate() {
+        use error::GraphError;
+        use graph::{Directed, MutableGraph};
+
+        let mut g = IncidenceList::<Directed, isize, String>::new();
+
+        let v1 = g.add_vertex(3);
+        let v2 = g.add_vertex(5);
+        let v3 = g.add_vertex(7);
+
+        let e12 = g.add_edge(v1, v2, "a".into()).unwrap();
+        g.add_edge(v1, v3, "b".into()).unwrap();
+        g.add_edge(v2, v2, "c".into()).unwrap();
+        let e31 = g.add_edge(v3, v1, "d".into()).unwrap();
+        assert_eq!(g.validate(), Ok(()));
+
+        assert!(g.remove_edge(e12).is_some());
+        assert!(g.remove_vertex(v2).is_some());
+        g.retain_edges(|_, e| e != e31);
+        assert_eq!(g.validate(), Ok(()));
+
+        g.vertices[v3.into()].incidence.2 = Some(e12);
+        assert_eq!(g.validate(), Err(GraphError::CorruptedVertex(v3)));
+
+        g.vertices[v3.into()].incidence.2 = None;
+        g.vertices[v1.into()].incidence.2 = None;
+        assert!(g.validate().is_err());
+    }
+
+    #[test]
+    fn update_edge() {
+        use error::GraphError;
+        use graph::{Directed, EdgeListGraph, Graph, MutableGraph, Undirected};
+
+        let mut g = IncidenceList::<Directed, isize, isize>::new();
+
+        let v1 = g.add_vertex(3);
+        let v2 = g.add_vertex(5);
+
+        let e12 = g.update_edge(v1, v2, 1).unwrap();
+        assert_eq!(g.update_edge(v1, v2, 2), Ok(e12));
+        assert_eq!(g.edge_property(e12), Some(&2));
+        assert_eq!(g.size(), 1);
+
+        let e21 = g.update_edge(v2, v1, 3).unwrap();
+        assert!(e21 != e12);
+        assert_eq!(g.size(), 2);
+
+        let v3 = g.add_vertex(7);
+        assert!(g.remove_vertex(v3).is_some());
+        assert_eq!(
+            g.update_edge(v1, v3, 4),
+            Err(GraphError::VertexNotFound(v3))
+        );
+
+        let mut g = IncidenceList::<Undirected, isize, isize>::new();
+
+        let v1 = g.add_vertex(3);
+        let v2 = g.add_vertex(5);
+
+        let e12 = g.update_edge(v1, v2, 1).unwrap();
+        assert_eq!(g.update_edge(v2, v1, 2), Ok(e12));
+        assert_eq!(g.edge_property(e12), Some(&2));
+        assert_eq!(g.size(), 1);
+    }
+
     #[test]
     fn edge_on_directed_graph() {
         use graph::{AdjacencyMatrixGraph, Directed, MutableGraph};
@@ -906,10 +2383,10 @@ mod tests {
         let v3 = g.add_vertex(7);
         let v4 = g.add_vertex(11);
 
-        let e12 = g.add_edge(v1, v2, "a".into());
-        let e23 = g.add_edge(v2, v3, "b".into());
-        let e21 = g.add_edge(v2, v1, "c".into());
-        let e14 = g.add_edge(v1, v4, "d".into());
+        let e12 = g.add_edge(v1, v2, "a".into()).ok();
+        let e23 = g.add_edge(v2, v3, "b".into()).ok();
+        let e21 = g.add_edge(v2, v1, "c".into()).ok();
+        let e14 = g.add_edge(v1, v4, "d".into()).ok();
 
         // +--> V1 ---E14--> V4
         // |    |
@@ -949,10 +2426,10 @@ mod tests {
         let v3 = g.add_vertex(7);
         let v4 = g.add_vertex(11);
 
-        let e12 = g.add_edge(v1, v2, "a".into());
-        let e23 = g.add_edge(v2, v3, "b".into());
-        let e22 = g.add_edge(v2, v2, "c".into());
-        let e14 = g.add_edge(v1, v4, "d".into());
+        let e12 = g.add_edge(v1, v2, "a".into()).ok();
+        let e23 = g.add_edge(v2, v3, "b".into()).ok();
+        let e22 = g.add_edge(v2, v2, "c".into()).ok();
+        let e14 = g.add_edge(v1, v4, "d".into()).ok();
 
         //   V1 ---E14--- V4
         //   |
@@ -982,5 +2459,45 @@ mod tests {
         assert_eq!(g.edge(v4, v2), None);
         assert_eq!(g.edge(v4, v3), None);
         assert_eq!(g.edge(v4, v4), None);
+
+        assert!(g.remove_vertex(v4).is_some());
+        assert_eq!(g.edge(v1, v4), None);
+        assert_eq!(g.edge(v4, v1), None);
+    }
+
+    #[test]
+    fn send_and_sync() {
+        use graph::{Directed, Undirected};
+
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<IncidenceList<Directed, String, f64>>();
+        assert_send_sync::<IncidenceList<Undirected, (), ()>>();
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_vertices_and_edges() {
+        use rayon::prelude::*;
+        use graph::{Directed, EdgeListGraph, Graph, IncidenceGraph, MutableGraph, VertexListGraph};
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+        let vs = (0..100).map(|i| g.add_vertex(i)).collect::<Vec<_>>();
+        for i in 1..100 {
+            g.add_edge(vs[i - 1], vs[i], i).unwrap();
+        }
+        g.remove_vertex(vs[50]);
+
+        let mut vertices = g.par_vertices().collect::<Vec<_>>();
+        vertices.sort();
+        assert_eq!(vertices, g.vertices().collect::<Vec<_>>());
+
+        let mut edges = g.par_edges().collect::<Vec<_>>();
+        edges.sort();
+        assert_eq!(edges, g.edges().collect::<Vec<_>>());
+
+        let degrees = g.par_vertices().map(|v| g.out_degree(v)).sum::<usize>();
+        assert_eq!(degrees, 97);
+        let total = g.par_vertices().map(|v| *g.vertex_property(v).unwrap()).sum::<i32>();
+        assert_eq!(total, 4950 - 50);
     }
 }
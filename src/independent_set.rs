@@ -0,0 +1,184 @@
+use std::fmt::Debug;
+use std::ops::Sub;
+
+use num_traits::Zero;
+
+use chordal::is_chordal;
+use coloring::symmetric_adjacency;
+use error::GraphError;
+use graph::{IncidenceGraph, VertexDescriptor, VertexListGraph};
+use property_map::vertex_index;
+
+/// Finds an independent set of maximum total weight in a forest, ignoring edge direction, by
+/// dynamic programming over each tree: the best weight of a subtree with and without its root.
+/// Vertices of negative weight are never chosen. Fails with `GraphError::Cyclic` naming a
+/// vertex on a cycle if the graph is not a forest.
+pub fn tree_maximum_independent_set<'a, G, C, F>(
+    graph: &'a G,
+    vertex_weight: F,
+) -> Result<(Vec<VertexDescriptor>, C), GraphError>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    C: Copy + Debug + Ord + Zero,
+    F: Fn(&VertexDescriptor, &G) -> C,
+{
+    let (vertices, neighbors) = symmetric_adjacency(graph);
+    let n = vertices.len();
+    let weights = vertices
+        .iter()
+        .map(|v| vertex_weight(v, graph))
+        .collect::<Vec<_>>();
+
+    let mut parents = vec![None; n];
+    let mut visited = vec![false; n];
+    let mut order = Vec::with_capacity(n);
+    for root in 0..n {
+        if visited[root] {
+            continue;
+        }
+        visited[root] = true;
+        let mut stack = vec![root];
+        while let Some(i) = stack.pop() {
+            order.push(i);
+            for &j in &neighbors[i] {
+                if parents[i] == Some(j) {
+                    continue;
+                }
+                if visited[j] {
+                    return Err(GraphError::Cyclic(vertices[j]));
+                }
+                visited[j] = true;
+                parents[j] = Some(i);
+                stack.push(j);
+            }
+        }
+    }
+
+    // children come after their parents in `order`, so go backwards
+    let mut with = weights.clone();
+    let mut without = vec![C::zero(); n];
+    for &i in order.iter().rev() {
+        if let Some(p) = parents[i] {
+            with[p] = with[p] + without[i];
+            without[p] = without[p] + with[i].max(without[i]);
+        }
+    }
+    let mut chosen = vec![false; n];
+    let mut total = C::zero();
+    for &i in &order {
+        let free = parents[i].is_none_or(|p| !chosen[p]);
+        if free && with[i] > without[i] {
+            chosen[i] = true;
+            total = total + weights[i];
+        }
+    }
+    let set = (0..n).filter(|&i| chosen[i]).map(|i| vertices[i]).collect();
+    Ok((set, total))
+}
+
+/// Finds an independent set of maximum total weight in a chordal graph, ignoring edge
+/// direction, with the algorithm of Frank: along a perfect elimination ordering, each vertex
+/// of positive remaining weight is marked and its weight taken off its later neighbors; the
+/// marked vertices are then taken greedily in reverse. Interval graphs are chordal, so this
+/// covers them too. Returns `None` if the graph is not chordal.
+pub fn chordal_maximum_independent_set<'a, G, C, F>(
+    graph: &'a G,
+    vertex_weight: F,
+) -> Option<(Vec<VertexDescriptor>, C)>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    C: Copy + Debug + Ord + Zero + Sub<Output = C>,
+    F: Fn(&VertexDescriptor, &G) -> C,
+{
+    let order = is_chordal(graph).ok()?;
+    let (vertices, neighbors) = symmetric_adjacency(graph);
+    let index = vertex_index(graph, &vertices);
+    let mut position = vec![0; vertices.len()];
+    for (k, &v) in order.iter().enumerate() {
+        position[index[v]] = k;
+    }
+
+    let mut remaining = vertices
+        .iter()
+        .map(|v| vertex_weight(v, graph))
+        .collect::<Vec<_>>();
+    let mut marked = Vec::new();
+    for &v in &order {
+        let i = index[v];
+        let r = remaining[i];
+        if r <= C::zero() {
+            continue;
+        }
+        marked.push(i);
+        for &j in &neighbors[i] {
+            if position[j] > position[i] {
+                remaining[j] = remaining[j] - r;
+            }
+        }
+    }
+
+    let mut chosen = vec![false; vertices.len()];
+    let mut set = Vec::new();
+    let mut total = C::zero();
+    for &i in marked.iter().rev() {
+        if neighbors[i].iter().all(|&j| !chosen[j]) {
+            chosen[i] = true;
+            set.push(vertices[i]);
+            total = total + vertex_weight(&vertices[i], graph);
+        }
+    }
+    Some((set, total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{chordal_maximum_independent_set, tree_maximum_independent_set};
+
+    #[test]
+    fn maximum_weight_independent_sets() {
+        use error::GraphError;
+        use graph::{Graph, MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        //      v0 (1)
+        //     /     \
+        // v1 (5)   v2 (2)
+        //    |     /    \
+        // v3 (1) v4 (3) v5 (3)
+
+        let mut g = IncidenceList::<Undirected, _, ()>::new();
+        let weights = [1, 5, 2, 1, 3, 3];
+        let vs = weights.iter().map(|&w| g.add_vertex(w)).collect::<Vec<_>>();
+        for &(s, t) in &[(0, 1), (0, 2), (1, 3), (2, 4), (2, 5)] {
+            g.add_edge(vs[s], vs[t], ()).unwrap();
+        }
+        let weight = |v: &_, g: &IncidenceList<Undirected, i32, ()>| {
+            *g.vertex_property(*v).unwrap()
+        };
+
+        let (mut set, total) = tree_maximum_independent_set(&g, weight).unwrap();
+        set.sort();
+        assert_eq!(total, 11);
+        assert_eq!(set, vec![vs[1], vs[4], vs[5]]);
+
+        // a tree is chordal, so both agree
+        let (_, total) = chordal_maximum_independent_set(&g, weight).unwrap();
+        assert_eq!(total, 11);
+
+        // closing the triangle v0 - v1 - v2 keeps it chordal but no longer a tree; v1 and v2
+        // cannot both be taken either way
+        g.add_edge(vs[1], vs[2], ()).unwrap();
+        match tree_maximum_independent_set(&g, weight) {
+            Err(GraphError::Cyclic(_)) => {}
+            result => panic!("unexpected {:?}", result),
+        }
+        let (mut set, total) = chordal_maximum_independent_set(&g, weight).unwrap();
+        set.sort();
+        assert_eq!(total, 11);
+        assert_eq!(set, vec![vs[1], vs[4], vs[5]]);
+
+        // a chordless square is neither
+        g.add_edge(vs[3], vs[4], ()).unwrap();
+        assert!(chordal_maximum_independent_set(&g, weight).is_none());
+    }
+}
@@ -0,0 +1,874 @@
+use std::cmp::Ordering;
+
+use fnv::FnvHashMap;
+
+use bipartite::kuhn_matching;
+use graph::{Directivity, EdgeDescriptor, Graph, IncidenceGraph, VertexDescriptor,
+            VertexListGraph};
+use property_map::{vertex_index, PropertyMap};
+
+struct Side<'a, G>
+where
+    G: 'a,
+{
+    graph: &'a G,
+    directed: bool,
+    vertices: Vec<VertexDescriptor>,
+    successors: Vec<Vec<usize>>,
+    predecessors: Vec<Vec<usize>>,
+    edges: FnvHashMap<(usize, usize), Vec<EdgeDescriptor>>,
+    mapping: Vec<Option<usize>>,
+    out_depth: Vec<usize>,
+    in_depth: Vec<usize>,
+}
+
+impl<'a, G> Side<'a, G>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+{
+    fn new(graph: &'a G) -> Self {
+        let directed = G::Directivity::is_directed();
+        let vertices = graph.vertices().collect::<Vec<_>>();
+        let index = vertex_index(graph, &vertices);
+
+        let order = vertices.len();
+        let mut successors = vec![Vec::new(); order];
+        let mut predecessors = vec![Vec::new(); order];
+        let mut edges = FnvHashMap::default();
+        for (i, &v) in vertices.iter().enumerate() {
+            for e in graph.out_edges(v) {
+                let j = match graph.opposite(e, v).and_then(|t| index.get(t)) {
+                    Some(&j) => j,
+                    None => continue,
+                };
+                successors[i].push(j);
+                predecessors[j].push(i);
+                if !directed {
+                    successors[j].push(i);
+                    predecessors[i].push(j);
+                }
+                let key = if directed || i <= j { (i, j) } else { (j, i) };
+                edges.entry(key).or_insert_with(Vec::new).push(e);
+            }
+        }
+        for vs in successors.iter_mut().chain(predecessors.iter_mut()) {
+            vs.sort();
+            vs.dedup();
+        }
+
+        Side {
+            graph,
+            directed,
+            vertices,
+            successors,
+            predecessors,
+            edges,
+            mapping: vec![None; order],
+            out_depth: vec![0; order],
+            in_depth: vec![0; order],
+        }
+    }
+
+    fn order(&self) -> usize {
+        self.vertices.len()
+    }
+
+    fn size(&self) -> usize {
+        self.edges.values().map(|es| es.len()).sum()
+    }
+
+    fn edges_between(&self, i: usize, j: usize) -> &[EdgeDescriptor] {
+        let key = if self.directed || i <= j { (i, j) } else { (j, i) };
+        self.edges.get(&key).map_or(&[], |es| es.as_slice())
+    }
+
+    fn push(&mut self, i: usize, j: usize, depth: usize) {
+        self.mapping[i] = Some(j);
+        if self.out_depth[i] == 0 {
+            self.out_depth[i] = depth;
+        }
+        if self.in_depth[i] == 0 {
+            self.in_depth[i] = depth;
+        }
+        for &k in &self.successors[i] {
+            if self.out_depth[k] == 0 {
+                self.out_depth[k] = depth;
+            }
+        }
+        for &k in &self.predecessors[i] {
+            if self.in_depth[k] == 0 {
+                self.in_depth[k] = depth;
+            }
+        }
+    }
+
+    fn pop(&mut self, i: usize, depth: usize) {
+        self.mapping[i] = None;
+        for d in self.out_depth.iter_mut().chain(self.in_depth.iter_mut()) {
+            if *d == depth {
+                *d = 0;
+            }
+        }
+    }
+
+    fn unmapped<'b>(&'b self, depths: &'b [usize]) -> impl Iterator<Item = usize> + 'b {
+        (0..self.order()).filter(move |&k| self.mapping[k].is_none() && depths[k] > 0)
+    }
+
+    fn lookahead(&self, i: usize) -> [usize; 6] {
+        let mut counts = [0; 6];
+        for (offset, neighbors) in [&self.successors[i], &self.predecessors[i]]
+            .iter()
+            .enumerate()
+        {
+            for &k in neighbors.iter() {
+                if self.mapping[k].is_some() {
+                    continue;
+                }
+                if self.out_depth[k] > 0 {
+                    counts[offset * 3] += 1;
+                }
+                if self.in_depth[k] > 0 {
+                    counts[offset * 3 + 1] += 1;
+                }
+                if self.out_depth[k] == 0 && self.in_depth[k] == 0 {
+                    counts[offset * 3 + 2] += 1;
+                }
+            }
+        }
+        counts
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Mode {
+    Isomorphism,
+    InducedSubgraph,
+    Monomorphism,
+}
+
+struct Frame {
+    vertex: usize,
+    candidates: Vec<usize>,
+    next: usize,
+}
+
+pub struct Matches<'a, 'b, G0, G1, VM, EM>
+where
+    G0: 'a,
+    G1: 'b,
+{
+    mode: Mode,
+    first: Side<'a, G0>,
+    second: Side<'b, G1>,
+    vertex_match: VM,
+    edge_match: EM,
+    fixed: FnvHashMap<usize, usize>,
+    fixed_targets: FnvHashMap<usize, usize>,
+    stack: Vec<Frame>,
+    started: bool,
+    exhausted: bool,
+}
+
+impl<'a, 'b, G0, G1, VM, EM> Matches<'a, 'b, G0, G1, VM, EM>
+where
+    G0: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G0::Directivity: Directivity,
+    G1: IncidenceGraph<'b> + VertexListGraph<'b>,
+    G1::Directivity: Directivity,
+    VM: FnMut(&G0::VertexProperty, &G1::VertexProperty) -> bool,
+    EM: FnMut(&G0::EdgeProperty, &G1::EdgeProperty) -> bool,
+{
+    fn new(mode: Mode, g0: &'a G0, g1: &'b G1, vertex_match: VM, edge_match: EM) -> Self {
+        Matches {
+            mode,
+            first: Side::new(g0),
+            second: Side::new(g1),
+            vertex_match,
+            edge_match,
+            fixed: FnvHashMap::default(),
+            fixed_targets: FnvHashMap::default(),
+            stack: Vec::new(),
+            started: false,
+            exhausted: false,
+        }
+    }
+
+    fn next_mapping(&mut self) -> Option<FnvHashMap<VertexDescriptor, VertexDescriptor>> {
+        if self.exhausted {
+            return None;
+        }
+        if self.started {
+            self.undo_top();
+        } else {
+            self.started = true;
+            let (order, size) = (
+                self.first.order().cmp(&self.second.order()),
+                self.first.size().cmp(&self.second.size()),
+            );
+            let fits = match self.mode {
+                Mode::Isomorphism => order == Ordering::Equal && size == Ordering::Equal,
+                Mode::InducedSubgraph | Mode::Monomorphism => {
+                    order != Ordering::Greater && size != Ordering::Greater
+                }
+            };
+            if self.first.directed != self.second.directed || !fits {
+                self.exhausted = true;
+                return None;
+            }
+            if self.first.order() == 0 {
+                self.exhausted = true;
+                return Some(FnvHashMap::default());
+            }
+            self.push_frame();
+        }
+
+        loop {
+            let (n, mut idx) = {
+                let frame = self.stack.last().unwrap();
+                (frame.vertex, frame.next)
+            };
+            let mut found = None;
+            while idx < self.stack.last().unwrap().candidates.len() {
+                let m = self.stack.last().unwrap().candidates[idx];
+                idx += 1;
+                if self.is_feasible(n, m) {
+                    found = Some(m);
+                    break;
+                }
+            }
+            self.stack.last_mut().unwrap().next = idx;
+
+            match found {
+                Some(m) => {
+                    let depth = self.stack.len();
+                    self.first.push(n, m, depth);
+                    self.second.push(m, n, depth);
+                    if depth == self.first.order() {
+                        return Some(self.mapping());
+                    }
+                    self.push_frame();
+                }
+                None => {
+                    self.stack.pop();
+                    if self.stack.is_empty() {
+                        self.exhausted = true;
+                        return None;
+                    }
+                    self.undo_top();
+                }
+            }
+        }
+    }
+
+    fn mapping(&self) -> FnvHashMap<VertexDescriptor, VertexDescriptor> {
+        self.first
+            .mapping
+            .iter()
+            .enumerate()
+            .filter_map(|(i, m)| {
+                m.map(|j| (self.first.vertices[i], self.second.vertices[j]))
+            })
+            .collect()
+    }
+
+    fn undo_top(&mut self) {
+        let depth = self.stack.len();
+        let n = self.stack.last().unwrap().vertex;
+        if let Some(m) = self.first.mapping[n] {
+            self.first.pop(n, depth);
+            self.second.pop(m, depth);
+        }
+    }
+
+    fn push_frame(&mut self) {
+        let (vertex, candidates) = {
+            let first = &self.first;
+            let second = &self.second;
+            let pick = |d0: &[usize], d1: &[usize]| {
+                first.unmapped(d0).next().and_then(|n| {
+                    let ms = second.unmapped(d1).collect::<Vec<_>>();
+                    if ms.is_empty() { None } else { Some((n, ms)) }
+                })
+            };
+            pick(&first.out_depth, &second.out_depth)
+                .or_else(|| pick(&first.in_depth, &second.in_depth))
+                .unwrap_or_else(|| {
+                    let n = (0..first.order())
+                        .find(|&k| first.mapping[k].is_none())
+                        .unwrap();
+                    let ms = (0..second.order())
+                        .filter(|&k| second.mapping[k].is_none())
+                        .collect();
+                    (n, ms)
+                })
+        };
+        self.stack.push(Frame {
+            vertex,
+            candidates,
+            next: 0,
+        });
+    }
+
+    fn fix(&mut self, n: usize, m: usize) {
+        self.fixed.insert(n, m);
+        self.fixed_targets.insert(m, n);
+    }
+
+    fn is_feasible(&mut self, n: usize, m: usize) -> bool {
+        match self.fixed.get(&n) {
+            Some(&f) => {
+                if f != m {
+                    return false;
+                }
+            }
+            None => {
+                if self.fixed_targets.contains_key(&m) {
+                    return false;
+                }
+            }
+        }
+
+        {
+            let p0 = self.first.graph.vertex_property(self.first.vertices[n]);
+            let p1 = self.second.graph.vertex_property(self.second.vertices[m]);
+            match (p0, p1) {
+                (Some(p0), Some(p1)) => {
+                    if !(self.vertex_match)(p0, p1) {
+                        return false;
+                    }
+                }
+                _ => return false,
+            }
+        }
+
+        for k in 0..self.first.successors[n].len() {
+            let n2 = self.first.successors[n][k];
+            let m2 = match if n2 == n { Some(m) } else { self.first.mapping[n2] } {
+                Some(m2) => m2,
+                None => continue,
+            };
+            if !self.edges_match(n, n2, m, m2) {
+                return false;
+            }
+        }
+        if self.mode == Mode::Monomorphism {
+            if self.first.directed {
+                for k in 0..self.first.predecessors[n].len() {
+                    let n2 = self.first.predecessors[n][k];
+                    let m2 = match if n2 == n { Some(m) } else { self.first.mapping[n2] } {
+                        Some(m2) => m2,
+                        None => continue,
+                    };
+                    if !self.edges_match(n2, n, m2, m) {
+                        return false;
+                    }
+                }
+            }
+            let c0 = self.first.lookahead(n);
+            let c1 = self.second.lookahead(m);
+            return self.first.successors[n].len() <= self.second.successors[m].len() &&
+                self.first.predecessors[n].len() <= self.second.predecessors[m].len() &&
+                c0[0] + c0[1] + c0[2] <= c1[0] + c1[1] + c1[2] &&
+                c0[3] + c0[4] + c0[5] <= c1[3] + c1[4] + c1[5];
+        }
+
+        for &m2 in &self.second.successors[m] {
+            let n2 = match if m2 == m { Some(n) } else { self.second.mapping[m2] } {
+                Some(n2) => n2,
+                None => continue,
+            };
+            if self.first.edges_between(n, n2).len() != self.second.edges_between(m, m2).len() {
+                return false;
+            }
+        }
+
+        if self.first.directed {
+            for k in 0..self.first.predecessors[n].len() {
+                let n2 = self.first.predecessors[n][k];
+                let m2 = match if n2 == n { Some(m) } else { self.first.mapping[n2] } {
+                    Some(m2) => m2,
+                    None => continue,
+                };
+                if !self.edges_match(n2, n, m2, m) {
+                    return false;
+                }
+            }
+            for &m2 in &self.second.predecessors[m] {
+                let n2 = match if m2 == m { Some(n) } else { self.second.mapping[m2] } {
+                    Some(n2) => n2,
+                    None => continue,
+                };
+                if self.first.edges_between(n2, n).len() !=
+                    self.second.edges_between(m2, m).len()
+                {
+                    return false;
+                }
+            }
+        }
+
+        let c0 = self.first.lookahead(n);
+        let c1 = self.second.lookahead(m);
+        match self.mode {
+            Mode::Isomorphism => c0 == c1,
+            _ => c0.iter().zip(c1.iter()).all(|(x, y)| x <= y),
+        }
+    }
+
+    fn edges_match(&mut self, s0: usize, t0: usize, s1: usize, t1: usize) -> bool {
+        let es0 = self.first.edges_between(s0, t0);
+        let es1 = self.second.edges_between(s1, t1);
+        let counts_fit = match self.mode {
+            Mode::Monomorphism => es0.len() <= es1.len(),
+            _ => es0.len() == es1.len(),
+        };
+        if !counts_fit {
+            return false;
+        }
+
+        let g0 = self.first.graph;
+        let g1 = self.second.graph;
+        let edge_match = &mut self.edge_match;
+        let compatible = es0.iter()
+            .map(|&e0| {
+                (0..es1.len())
+                    .filter(|&j| match (g0.edge_property(e0), g1.edge_property(es1[j])) {
+                        (Some(p0), Some(p1)) => edge_match(p0, p1),
+                        _ => false,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let (partners, _) = kuhn_matching(&compatible, es1.len());
+        partners.iter().all(Option::is_some)
+    }
+}
+
+impl<'a, 'b, G0, G1, VM, EM> Iterator for Matches<'a, 'b, G0, G1, VM, EM>
+where
+    G0: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G0::Directivity: Directivity,
+    G1: IncidenceGraph<'b> + VertexListGraph<'b>,
+    G1::Directivity: Directivity,
+    VM: FnMut(&G0::VertexProperty, &G1::VertexProperty) -> bool,
+    EM: FnMut(&G0::EdgeProperty, &G1::EdgeProperty) -> bool,
+{
+    type Item = FnvHashMap<VertexDescriptor, VertexDescriptor>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_mapping()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AutomorphismGroup {
+    generators: Vec<FnvHashMap<VertexDescriptor, VertexDescriptor>>,
+    orbits: Vec<Vec<VertexDescriptor>>,
+}
+
+impl AutomorphismGroup {
+    pub fn generators(&self) -> &[FnvHashMap<VertexDescriptor, VertexDescriptor>] {
+        &self.generators
+    }
+
+    pub fn orbits(&self) -> &[Vec<VertexDescriptor>] {
+        &self.orbits
+    }
+}
+
+fn orbit_of(i: usize, generators: &[Vec<usize>], order: usize) -> Vec<bool> {
+    let mut orbit = vec![false; order];
+    let mut stack = vec![i];
+    orbit[i] = true;
+    while let Some(k) = stack.pop() {
+        for g in generators {
+            if !orbit[g[k]] {
+                orbit[g[k]] = true;
+                stack.push(g[k]);
+            }
+        }
+    }
+    orbit
+}
+
+pub fn automorphisms<'a, G>(graph: &'a G) -> AutomorphismGroup
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+{
+    automorphisms_matching(graph, |_, _| true, |_, _| true)
+}
+
+/// Computes a strong generating set along the stabilizer chain of the vertices in iteration
+/// order: for each vertex, deepest first, one automorphism is searched per image not already
+/// reachable by the generators found so far.
+pub fn automorphisms_matching<'a, G, VM, EM>(
+    graph: &'a G,
+    mut vertex_match: VM,
+    mut edge_match: EM,
+) -> AutomorphismGroup
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+    VM: FnMut(&G::VertexProperty, &G::VertexProperty) -> bool,
+    EM: FnMut(&G::EdgeProperty, &G::EdgeProperty) -> bool,
+{
+    let vertices = graph.vertices().collect::<Vec<_>>();
+    let order = vertices.len();
+    let index = vertex_index(graph, &vertices);
+
+    let mut generators: Vec<Vec<usize>> = Vec::new();
+    for i in (0..order).rev() {
+        let mut orbit = orbit_of(i, &generators, order);
+        for x in i + 1..order {
+            if orbit[x] {
+                continue;
+            }
+            let mut matches = Matches::new(
+                Mode::Isomorphism,
+                graph,
+                graph,
+                &mut vertex_match,
+                &mut edge_match,
+            );
+            for j in 0..i {
+                matches.fix(j, j);
+            }
+            matches.fix(i, x);
+            if let Some(mapping) = matches.next_mapping() {
+                let mut permutation = vec![0; order];
+                for (v, w) in mapping {
+                    permutation[index[v]] = index[w];
+                }
+                generators.push(permutation);
+                orbit = orbit_of(i, &generators, order);
+            }
+        }
+    }
+
+    let mut seen = vec![false; order];
+    let mut orbits = Vec::new();
+    for i in 0..order {
+        if seen[i] {
+            continue;
+        }
+        let orbit = orbit_of(i, &generators, order);
+        let mut members = Vec::new();
+        for (k, &member) in orbit.iter().enumerate() {
+            if member {
+                seen[k] = true;
+                members.push(vertices[k]);
+            }
+        }
+        orbits.push(members);
+    }
+
+    AutomorphismGroup {
+        generators: generators
+            .into_iter()
+            .map(|p| {
+                p.into_iter()
+                    .enumerate()
+                    .map(|(i, j)| (vertices[i], vertices[j]))
+                    .collect()
+            })
+            .collect(),
+        orbits,
+    }
+}
+
+pub fn is_isomorphic<'a, 'b, G0, G1>(g0: &'a G0, g1: &'b G1) -> bool
+where
+    G0: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G0::Directivity: Directivity,
+    G1: IncidenceGraph<'b> + VertexListGraph<'b>,
+    G1::Directivity: Directivity,
+{
+    isomorphism(g0, g1).is_some()
+}
+
+pub fn isomorphism<'a, 'b, G0, G1>(
+    g0: &'a G0,
+    g1: &'b G1,
+) -> Option<FnvHashMap<VertexDescriptor, VertexDescriptor>>
+where
+    G0: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G0::Directivity: Directivity,
+    G1: IncidenceGraph<'b> + VertexListGraph<'b>,
+    G1::Directivity: Directivity,
+{
+    isomorphism_matching(g0, g1, |_, _| true, |_, _| true)
+}
+
+pub fn isomorphism_matching<'a, 'b, G0, G1, VM, EM>(
+    g0: &'a G0,
+    g1: &'b G1,
+    vertex_match: VM,
+    edge_match: EM,
+) -> Option<FnvHashMap<VertexDescriptor, VertexDescriptor>>
+where
+    G0: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G0::Directivity: Directivity,
+    G1: IncidenceGraph<'b> + VertexListGraph<'b>,
+    G1::Directivity: Directivity,
+    VM: FnMut(&G0::VertexProperty, &G1::VertexProperty) -> bool,
+    EM: FnMut(&G0::EdgeProperty, &G1::EdgeProperty) -> bool,
+{
+    Matches::new(Mode::Isomorphism, g0, g1, vertex_match, edge_match).next_mapping()
+}
+
+fn any<A, B>(_: &A, _: &B) -> bool {
+    true
+}
+
+type Unconstrained<'a, 'b, G0, G1> = Matches<
+    'a,
+    'b,
+    G0,
+    G1,
+    fn(&<G0 as Graph>::VertexProperty, &<G1 as Graph>::VertexProperty) -> bool,
+    fn(&<G0 as Graph>::EdgeProperty, &<G1 as Graph>::EdgeProperty) -> bool,
+>;
+
+pub fn subgraph_isomorphisms<'a, 'b, G0, G1>(
+    pattern: &'a G0,
+    target: &'b G1,
+) -> Unconstrained<'a, 'b, G0, G1>
+where
+    G0: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G0::Directivity: Directivity,
+    G1: IncidenceGraph<'b> + VertexListGraph<'b>,
+    G1::Directivity: Directivity,
+{
+    subgraph_isomorphisms_matching(pattern, target, any, any)
+}
+
+pub fn subgraph_isomorphisms_matching<'a, 'b, G0, G1, VM, EM>(
+    pattern: &'a G0,
+    target: &'b G1,
+    vertex_match: VM,
+    edge_match: EM,
+) -> Matches<'a, 'b, G0, G1, VM, EM>
+where
+    G0: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G0::Directivity: Directivity,
+    G1: IncidenceGraph<'b> + VertexListGraph<'b>,
+    G1::Directivity: Directivity,
+    VM: FnMut(&G0::VertexProperty, &G1::VertexProperty) -> bool,
+    EM: FnMut(&G0::EdgeProperty, &G1::EdgeProperty) -> bool,
+{
+    Matches::new(Mode::InducedSubgraph, pattern, target, vertex_match, edge_match)
+}
+
+pub fn subgraph_monomorphisms<'a, 'b, G0, G1>(
+    pattern: &'a G0,
+    target: &'b G1,
+) -> Unconstrained<'a, 'b, G0, G1>
+where
+    G0: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G0::Directivity: Directivity,
+    G1: IncidenceGraph<'b> + VertexListGraph<'b>,
+    G1::Directivity: Directivity,
+{
+    subgraph_monomorphisms_matching(pattern, target, any, any)
+}
+
+pub fn subgraph_monomorphisms_matching<'a, 'b, G0, G1, VM, EM>(
+    pattern: &'a G0,
+    target: &'b G1,
+    vertex_match: VM,
+    edge_match: EM,
+) -> Matches<'a, 'b, G0, G1, VM, EM>
+where
+    G0: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G0::Directivity: Directivity,
+    G1: IncidenceGraph<'b> + VertexListGraph<'b>,
+    G1::Directivity: Directivity,
+    VM: FnMut(&G0::VertexProperty, &G1::VertexProperty) -> bool,
+    EM: FnMut(&G0::EdgeProperty, &G1::EdgeProperty) -> bool,
+{
+    Matches::new(Mode::Monomorphism, pattern, target, vertex_match, edge_match)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{automorphisms, automorphisms_matching, is_isomorphic, isomorphism, isomorphism_matching, subgraph_isomorphisms,
+                subgraph_monomorphisms, subgraph_monomorphisms_matching};
+
+    #[test]
+    fn directed() {
+        use graph::{AdjacencyMatrixGraph, Directed, MutableGraph};
+        use incidence_list::IncidenceList;
+
+        // 0 --> 1 --> 2        a <-- b <-- c
+        // ^     |     |        |     ^     ^
+        // |     v     |        v     |     |
+        // +---- 3 <---+        d ----+---- +
+
+        let mut g0 = IncidenceList::<Directed, _, _>::new();
+        let v0 = g0.add_vertex(0);
+        let v1 = g0.add_vertex(1);
+        let v2 = g0.add_vertex(2);
+        let v3 = g0.add_vertex(3);
+        g0.add_edge(v0, v1, ()).unwrap();
+        g0.add_edge(v1, v2, ()).unwrap();
+        g0.add_edge(v1, v3, ()).unwrap();
+        g0.add_edge(v2, v3, ()).unwrap();
+        g0.add_edge(v3, v0, ()).unwrap();
+
+        let mut g1 = IncidenceList::<Directed, _, _>::new();
+        let va = g1.add_vertex(0);
+        let vb = g1.add_vertex(1);
+        let vc = g1.add_vertex(2);
+        let vd = g1.add_vertex(3);
+        g1.add_edge(vb, va, ()).unwrap();
+        g1.add_edge(vc, vb, ()).unwrap();
+        g1.add_edge(va, vd, ()).unwrap();
+        g1.add_edge(vd, vb, ()).unwrap();
+        g1.add_edge(vd, vc, ()).unwrap();
+
+        let mapping = isomorphism(&g0, &g1).unwrap();
+        assert_eq!(mapping.len(), 4);
+        for (&u, &v) in &mapping {
+            for (&x, &y) in &mapping {
+                assert_eq!(g0.edge(u, x).is_some(), g1.edge(v, y).is_some());
+            }
+        }
+        assert_eq!(mapping[&v0], va);
+        assert_eq!(mapping[&v1], vd);
+
+        let e = g1.edge(vd, vc).unwrap();
+        g1.remove_edge(e).unwrap();
+        g1.add_edge(vc, vd, ()).unwrap();
+        assert!(!is_isomorphic(&g0, &g1));
+    }
+
+    #[test]
+    fn undirected_with_properties() {
+        use graph::{MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        // a - b        x - y
+        // |   |        |   |
+        // c - d        z - w
+
+        let mut g0 = IncidenceList::<Undirected, _, _>::new();
+        let a = g0.add_vertex("red");
+        let b = g0.add_vertex("blue");
+        let c = g0.add_vertex("blue");
+        let d = g0.add_vertex("red");
+        g0.add_edge(a, b, 1).unwrap();
+        g0.add_edge(b, d, 2).unwrap();
+        g0.add_edge(d, c, 1).unwrap();
+        g0.add_edge(c, a, 2).unwrap();
+
+        let mut g1 = IncidenceList::<Undirected, _, _>::new();
+        let x = g1.add_vertex("red");
+        let y = g1.add_vertex("blue");
+        let z = g1.add_vertex("blue");
+        let w = g1.add_vertex("red");
+        g1.add_edge(y, x, 2).unwrap();
+        g1.add_edge(w, y, 1).unwrap();
+        g1.add_edge(z, w, 2).unwrap();
+        g1.add_edge(x, z, 1).unwrap();
+
+        assert!(is_isomorphic(&g0, &g1));
+
+        let mapping = isomorphism_matching(&g0, &g1, |p0, p1| p0 == p1, |e0, e1| e0 == e1);
+        let mapping = mapping.unwrap();
+        assert_eq!(mapping[&a], x);
+        assert_eq!(mapping[&b], z);
+        assert_eq!(mapping[&c], y);
+        assert_eq!(mapping[&d], w);
+
+        assert!(isomorphism_matching(&g0, &g1, |_, _| true, |_, &e1| e1 == 1).is_none());
+    }
+
+    #[test]
+    fn subgraphs() {
+        use graph::{MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        // k4:            triangle:     path:
+        // 0 --- 1        a             a --- b --- c
+        // | \ / |        | \
+        // | / \ |        b - c
+        // 2 --- 3
+
+        let mut k4 = IncidenceList::<Undirected, _, _>::new();
+        let vs = (0..4).map(|i| k4.add_vertex(i)).collect::<Vec<_>>();
+        for i in 0..4 {
+            for j in i + 1..4 {
+                k4.add_edge(vs[i], vs[j], ()).unwrap();
+            }
+        }
+
+        let mut triangle = IncidenceList::<Undirected, _, _>::new();
+        let a = triangle.add_vertex(0);
+        let b = triangle.add_vertex(1);
+        let c = triangle.add_vertex(2);
+        triangle.add_edge(a, b, ()).unwrap();
+        triangle.add_edge(b, c, ()).unwrap();
+        triangle.add_edge(c, a, ()).unwrap();
+
+        let mut path = IncidenceList::<Undirected, _, _>::new();
+        let a = path.add_vertex(0);
+        let b = path.add_vertex(1);
+        let c = path.add_vertex(2);
+        path.add_edge(a, b, ()).unwrap();
+        path.add_edge(b, c, ()).unwrap();
+
+        assert_eq!(subgraph_isomorphisms(&triangle, &k4).count(), 24);
+        assert_eq!(subgraph_monomorphisms(&triangle, &k4).count(), 24);
+        assert_eq!(subgraph_isomorphisms(&path, &k4).count(), 0);
+        assert_eq!(subgraph_monomorphisms(&path, &k4).count(), 24);
+        assert_eq!(subgraph_monomorphisms(&k4, &triangle).count(), 0);
+
+        let mapping = subgraph_isomorphisms(&path, &triangle).next();
+        assert!(mapping.is_none());
+        let mapping = subgraph_monomorphisms_matching(&path, &k4, |p0, p1| p0 == p1, |_, _| true)
+            .collect::<Vec<_>>();
+        assert_eq!(mapping.len(), 1);
+        assert_eq!(mapping[0][&b], vs[1]);
+    }
+
+    #[test]
+    fn automorphism_group() {
+        use graph::{AdjacencyMatrixGraph, MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        // a --- b
+        // |     |
+        // d --- c
+
+        let mut g = IncidenceList::<Undirected, _, _>::new();
+        let a = g.add_vertex("red");
+        let b = g.add_vertex("blue");
+        let c = g.add_vertex("red");
+        let d = g.add_vertex("blue");
+        g.add_edge(a, b, ()).unwrap();
+        g.add_edge(b, c, ()).unwrap();
+        g.add_edge(c, d, ()).unwrap();
+        g.add_edge(d, a, ()).unwrap();
+
+        let group = automorphisms(&g);
+        assert_eq!(group.orbits(), &[vec![a, b, c, d]]);
+        assert!(!group.generators().is_empty());
+        for p in group.generators() {
+            for (&u, &v) in p {
+                for (&x, &y) in p {
+                    assert_eq!(g.edge(u, x).is_some(), g.edge(v, y).is_some());
+                }
+            }
+        }
+
+        let group = automorphisms_matching(&g, |p0, p1| p0 == p1, |_, _| true);
+        assert_eq!(group.orbits(), &[vec![a, c], vec![b, d]]);
+
+        g.add_edge(a, c, ()).unwrap();
+        let group = automorphisms(&g);
+        assert_eq!(group.orbits(), &[vec![a, c], vec![b, d]]);
+    }
+}
@@ -0,0 +1,105 @@
+use fnv::FnvHashMap;
+
+use error::GraphError;
+use graph::{Directivity, IncidenceGraph, VertexDescriptor, VertexListGraph};
+use topological_sort::topological_sort;
+
+/// Assigns every vertex of a DAG the length of the longest path reaching it, so sources are in
+/// layer 0 and every edge points to a higher layer. This uses as few layers as possible, though
+/// edges spanning several layers are not avoided. Fails with `GraphError::Undirected` on an
+/// undirected graph and with `GraphError::Cyclic` on a cyclic one.
+pub fn longest_path_layering<'a, G>(
+    graph: &'a G,
+) -> Result<FnvHashMap<VertexDescriptor, usize>, GraphError>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+{
+    if !G::Directivity::is_directed() {
+        return Err(GraphError::Undirected);
+    }
+    let order = topological_sort(graph)?;
+    let mut layers = order
+        .iter()
+        .map(|&v| (v, 0))
+        .collect::<FnvHashMap<_, _>>();
+    for &u in &order {
+        let next = layers[&u] + 1;
+        for e in graph.out_edges(u) {
+            if let Some(v) = graph.opposite(e, u) {
+                let layer = layers.get_mut(&v).unwrap();
+                if *layer < next {
+                    *layer = next;
+                }
+            }
+        }
+    }
+    Ok(layers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::longest_path_layering;
+
+    #[test]
+    fn layering() {
+        use graph::{Directed, MutableGraph};
+        use incidence_list::IncidenceList;
+
+        // v0 --> v1 --> v2
+        //  |             ^
+        //  +-------------+
+        // v3 --> v4
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+        let vs = (0..5).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t) in &[(0, 1), (1, 2), (0, 2), (3, 4)] {
+            g.add_edge(vs[s], vs[t], ()).unwrap();
+        }
+
+        let layers = longest_path_layering(&g).unwrap();
+        let ranks = vs.iter().map(|v| layers[v]).collect::<Vec<_>>();
+        assert_eq!(ranks, vec![0, 1, 2, 0, 1]);
+    }
+
+    #[test]
+    fn layering_empty() {
+        use graph::Directed;
+        use incidence_list::IncidenceList;
+
+        let g = IncidenceList::<Directed, (), ()>::new();
+        assert!(longest_path_layering(&g).unwrap().is_empty());
+    }
+
+    #[test]
+    fn layering_cyclic() {
+        use error::GraphError;
+        use graph::{Directed, MutableGraph};
+        use incidence_list::IncidenceList;
+
+        // v0 --> v1 <-> v2
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+        let vs = (0..3).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t) in &[(0, 1), (1, 2), (2, 1)] {
+            g.add_edge(vs[s], vs[t], ()).unwrap();
+        }
+
+        match longest_path_layering(&g) {
+            Err(GraphError::Cyclic(v)) => assert!(v == vs[1] || v == vs[2]),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn layering_undirected() {
+        use error::GraphError;
+        use graph::{MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        let mut g = IncidenceList::<Undirected, _, _>::new();
+        let vs = (0..2).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        g.add_edge(vs[0], vs[1], ()).unwrap();
+        assert_eq!(longest_path_layering(&g), Err(GraphError::Undirected));
+    }
+}
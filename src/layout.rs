@@ -0,0 +1,267 @@
+use std::collections::VecDeque;
+
+use fnv::{FnvHashMap, FnvHashSet};
+
+use error::GraphError;
+use feedback_arc_set::feedback_arc_set;
+use graph::{Directivity, EdgeDescriptor, IncidenceGraph, VertexDescriptor, VertexListGraph};
+use property_map::{vertex_index, PropertyMap};
+
+/// Rounds of barycenter sweeps, each going down and then up the layers.
+const SWEEPS: usize = 8;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Layout {
+    positions: FnvHashMap<VertexDescriptor, (f64, f64)>,
+    bends: FnvHashMap<EdgeDescriptor, Vec<(f64, f64)>>,
+}
+
+impl Layout {
+    /// The x coordinate orders a vertex within its layer and the y coordinate is its layer.
+    pub fn position(&self, v: VertexDescriptor) -> Option<(f64, f64)> {
+        self.positions.get(&v).cloned()
+    }
+
+    /// The points an edge passes through between its endpoints, from source to target. Empty
+    /// for edges between adjacent layers.
+    pub fn bends(&self, e: EdgeDescriptor) -> &[(f64, f64)] {
+        self.bends.get(&e).map_or(&[], |b| b.as_slice())
+    }
+}
+
+fn crossings(upper: &[usize], position: &[usize], down: &[Vec<usize>]) -> usize {
+    let mut segments = Vec::new();
+    for &u in upper {
+        for &w in &down[u] {
+            segments.push((position[u], position[w]));
+        }
+    }
+    let mut count = 0;
+    for (k, &(a, b)) in segments.iter().enumerate() {
+        for &(c, d) in &segments[k + 1..] {
+            if (a < c && b > d) || (a > c && b < d) {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+/// Lays out a graph in horizontal layers in the manner of Sugiyama, Tagawa and Toda. Cycles
+/// are broken by reversing a feedback arc set, vertices are layered by longest path, edges
+/// spanning several layers are routed through a bend point on each layer they cross, and
+/// crossings are reduced by barycenter ordering. Self-loops are not drawn. Fails with
+/// `GraphError::Undirected` on an undirected graph.
+pub fn sugiyama_layout<'a, G>(graph: &'a G) -> Result<Layout, GraphError>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+{
+    if !G::Directivity::is_directed() {
+        return Err(GraphError::Undirected);
+    }
+    let vertices = graph.vertices().collect::<Vec<_>>();
+    let index = vertex_index(graph, &vertices);
+    let n = vertices.len();
+
+    let reversed = feedback_arc_set(graph)?.into_iter().collect::<FnvHashSet<_>>();
+    let mut arcs = Vec::new();
+    for (i, &v) in vertices.iter().enumerate() {
+        for e in graph.out_edges(v) {
+            if let Some(&j) = graph.opposite(e, v).and_then(|t| index.get(t)) {
+                if i == j {
+                    continue;
+                }
+                if reversed.contains(&e) {
+                    arcs.push((j, i, e, true));
+                } else {
+                    arcs.push((i, j, e, false));
+                }
+            }
+        }
+    }
+
+    // longest path layering of the now acyclic arcs
+    let mut successors = vec![Vec::new(); n];
+    let mut in_degree = vec![0; n];
+    for &(s, t, _, _) in &arcs {
+        successors[s].push(t);
+        in_degree[t] += 1;
+    }
+    let mut layer = vec![0; n];
+    let mut ready = (0..n).filter(|&i| in_degree[i] == 0).collect::<VecDeque<_>>();
+    while let Some(i) = ready.pop_front() {
+        for &j in &successors[i] {
+            layer[j] = layer[j].max(layer[i] + 1);
+            in_degree[j] -= 1;
+            if in_degree[j] == 0 {
+                ready.push_back(j);
+            }
+        }
+    }
+
+    // split long arcs with a dummy node on every layer in between
+    let mut down = vec![Vec::new(); n];
+    let mut up = vec![Vec::new(); n];
+    let mut chains = Vec::new();
+    for &(s, t, e, flipped) in &arcs {
+        let mut previous = s;
+        let mut dummies = Vec::new();
+        for l in layer[s] + 1..layer[t] {
+            let d = layer.len();
+            layer.push(l);
+            down.push(Vec::new());
+            up.push(Vec::new());
+            down[previous].push(d);
+            up[d].push(previous);
+            dummies.push(d);
+            previous = d;
+        }
+        down[previous].push(t);
+        up[t].push(previous);
+        if !dummies.is_empty() {
+            if flipped {
+                dummies.reverse();
+            }
+            chains.push((e, dummies));
+        }
+    }
+
+    let depth = layer.iter().cloned().max().map_or(0, |l| l + 1);
+    let mut layers = vec![Vec::new(); depth];
+    for (node, &l) in layer.iter().enumerate() {
+        layers[l].push(node);
+    }
+    let mut position = vec![0; layer.len()];
+    let place = |layers: &[Vec<usize>], position: &mut [usize]| {
+        for nodes in layers {
+            for (k, &node) in nodes.iter().enumerate() {
+                position[node] = k;
+            }
+        }
+    };
+    place(&layers, &mut position);
+    let total = |layers: &[Vec<usize>], position: &[usize]| {
+        (1..layers.len())
+            .map(|l| crossings(&layers[l - 1], position, &down))
+            .sum::<usize>()
+    };
+
+    let mut best = (total(&layers, &position), layers.clone());
+    for _ in 0..SWEEPS {
+        let downward = (1..depth).map(|l| (l, &up)).collect::<Vec<_>>();
+        let upward = (0..depth.saturating_sub(1)).rev().map(|l| (l, &down));
+        for (l, neighbors) in downward.into_iter().chain(upward) {
+            let mut keyed = layers[l]
+                .iter()
+                .map(|&node| {
+                    let ns = &neighbors[node];
+                    let key = if ns.is_empty() {
+                        position[node] as f64
+                    } else {
+                        ns.iter().map(|&m| position[m] as f64).sum::<f64>() / ns.len() as f64
+                    };
+                    (key, node)
+                })
+                .collect::<Vec<_>>();
+            keyed.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+            layers[l] = keyed.into_iter().map(|(_, node)| node).collect();
+            for (k, &node) in layers[l].iter().enumerate() {
+                position[node] = k;
+            }
+        }
+        let count = total(&layers, &position);
+        if count < best.0 {
+            best = (count, layers.clone());
+        }
+    }
+    let layers = best.1;
+    place(&layers, &mut position);
+
+    let coordinate = |node: usize| {
+        let width = layers[layer[node]].len() as f64;
+        (position[node] as f64 - (width - 1.0) / 2.0, layer[node] as f64)
+    };
+    Ok(Layout {
+        positions: (0..n).map(|i| (vertices[i], coordinate(i))).collect(),
+        bends: chains
+            .into_iter()
+            .map(|(e, dummies)| (e, dummies.into_iter().map(&coordinate).collect()))
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sugiyama_layout;
+
+    #[test]
+    fn layered_layout() {
+        use graph::{Directed, MutableGraph};
+        use incidence_list::IncidenceList;
+
+        // v0     v1
+        //  | \   / |
+        //  |  \ /  |
+        //  |   X   |
+        //  |  / \  |
+        //  v v   v v
+        // v3      v2 <-- v4
+        //  |              ^
+        //  +--------------+
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+        assert_eq!(sugiyama_layout(&g).unwrap().positions.len(), 0);
+
+        let vs = (0..5).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        let e02 = g.add_edge(vs[0], vs[2], ()).unwrap();
+        g.add_edge(vs[1], vs[2], ()).unwrap();
+        g.add_edge(vs[0], vs[3], ()).unwrap();
+        g.add_edge(vs[1], vs[3], ()).unwrap();
+        g.add_edge(vs[3], vs[4], ()).unwrap();
+        let e42 = g.add_edge(vs[4], vs[2], ()).unwrap();
+
+        let layout = sugiyama_layout(&g).unwrap();
+        let at = |i: usize| layout.position(vs[i]).unwrap();
+        let layers = vs.iter().enumerate().map(|(i, _)| at(i).1).collect::<Vec<_>>();
+        assert_eq!(layers, vec![0.0, 0.0, 3.0, 1.0, 2.0]);
+        assert!(at(0).0 != at(1).0);
+
+        assert!(layout.bends(e42).is_empty());
+        let bends = layout.bends(e02);
+        assert_eq!(bends.iter().map(|b| b.1).collect::<Vec<_>>(), vec![1.0, 2.0]);
+        // the two long edges run side by side with v3 and v4 without crossing each other
+        assert!(bends.iter().all(|b| b.0 != at(3).0 && b.0 != at(4).0));
+
+        // an isolated vertex joins the sources on the top layer, and its self-loop is not drawn
+        let v5 = g.add_vertex(());
+        let e55 = g.add_edge(v5, v5, ()).unwrap();
+        let layout = sugiyama_layout(&g).unwrap();
+        let at = |v| layout.position(v).unwrap();
+        let mut top = [at(vs[0]).0, at(vs[1]).0, at(v5).0];
+        top.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(top, [-1.0, 0.0, 1.0]);
+        assert_eq!(at(v5).1, 0.0);
+        assert!(layout.bends(e55).is_empty());
+
+        // reversing v4 --> v2 into v2 --> v4 creates a cycle, which is broken for layering
+        g.remove_edge(e42);
+        let e24 = g.add_edge(vs[2], vs[4], ()).unwrap();
+        g.add_edge(vs[4], vs[0], ()).unwrap();
+        let layout = sugiyama_layout(&g).unwrap();
+        assert_eq!(layout.positions.len(), 6);
+        assert!(layout.bends(e24).len() <= 2);
+    }
+
+    #[test]
+    fn layered_layout_undirected() {
+        use error::GraphError;
+        use graph::{MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        let mut g = IncidenceList::<Undirected, _, _>::new();
+        let vs = (0..2).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        g.add_edge(vs[0], vs[1], ()).unwrap();
+        assert_eq!(sugiyama_layout(&g), Err(GraphError::Undirected));
+    }
+}
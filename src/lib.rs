@@ -1,22 +1,174 @@
 extern crate fnv;
+#[cfg(feature = "log")]
+#[macro_use]
+extern crate log;
+#[cfg(feature = "memmap2")]
+extern crate memmap2;
 extern crate num_traits;
+#[cfg(feature = "rand")]
+extern crate rand;
+#[cfg(feature = "rayon")]
+extern crate rayon;
 extern crate slab;
+#[cfg(feature = "tracing")]
+extern crate tracing;
 
+mod attributes;
+mod bellman_ford;
+mod bidirectional_search;
+mod bipartite;
+mod bottleneck;
+mod builder;
+mod centrality;
+mod chordal;
+mod clique;
+mod coloring;
+mod concurrent_graph;
+mod connectivity;
+mod critical_path;
+mod csr_graph;
+mod cycle;
+mod dense_subgraph;
+mod dominating_set;
+mod dynamic_shortest_paths;
+mod edge_stream;
+mod edit_distance;
+mod error;
+mod feedback_arc_set;
+mod floyd_warshall;
 mod graph;
+mod grid;
+mod hamiltonian;
+mod history;
+mod implicit_search;
 mod incidence_list;
+mod independent_set;
+mod isomorphism;
+mod layering;
+mod layout;
+mod lowest_common_ancestor;
+mod minor;
+mod ordered_float;
+mod ordering;
+mod partition;
 mod path;
+mod path_cover;
+mod persistent_graph;
+mod postman;
+mod property_map;
+mod reachability;
+mod resource_constrained;
+mod rewrite;
+mod spanning_trees;
+mod spectral;
+mod stochastic;
+mod strongly_connected_components;
+mod topological_sort;
+mod transaction;
+mod transitive_reduction;
+mod tsp;
+mod turn_expansion;
+mod two_sat;
+mod vertex_cover;
 mod visitor;
+mod weisfeiler_lehman;
+#[cfg(feature = "log")]
+mod log_visitor;
+#[cfg(feature = "tracing")]
+mod tracing_visitor;
+#[cfg(feature = "rand")]
+mod generator;
+#[cfg(feature = "rand")]
+mod perturbation;
 
 mod astar_search;
 mod breadth_first_search;
 mod depth_first_search;
+mod dijkstra_search;
+mod ida_star_search;
+mod weighted_astar_search;
 
+pub use attributes::{read_csv, AttributeError, Attributes, FromAttributes};
+pub use bellman_ford::{spfa, BellmanFord, ShortestPathTree};
+pub use bidirectional_search::{bidirectional_bfs, bidirectional_dijkstra};
+pub use bipartite::is_bipartite;
+pub use bottleneck::{widest_path, MinimaxPaths};
+pub use builder::GraphBuilder;
+pub use centrality::{hits, katz_centrality, pagerank};
+pub use chordal::{chordal_maximal_cliques, is_chordal, lex_bfs};
+pub use clique::{maximal_cliques, maximum_clique};
+pub use coloring::{dsatur_coloring, greedy_coloring, Coloring, ColoringOrder};
+pub use concurrent_graph::ConcurrentGraph;
+pub use connectivity::{edge_connectivity, is_k_connected, is_k_edge_connected,
+                       vertex_connectivity};
+pub use critical_path::{critical_path, CriticalPath};
+pub use csr_graph::{write_csr, CsrGraph, CsrIncidences};
+pub use cycle::{find_cycle, girth, minimum_cycle_basis};
+pub use dense_subgraph::{approximate_densest_subgraph, densest_subgraph};
+pub use dominating_set::greedy_dominating_set;
+pub use dynamic_shortest_paths::DynamicShortestPaths;
+pub use edge_stream::{Degrees, EdgeStream, StreamedEdges};
+pub use edit_distance::{approximate_graph_edit_distance, graph_edit_distance, EditCosts, EditPath,
+                        UnitCosts};
+pub use error::GraphError;
+pub use feedback_arc_set::feedback_arc_set;
+pub use floyd_warshall::{floyd_warshall, floyd_warshall_with_paths, AllPairsShortestPaths};
 pub use graph::{Graph, AdjacencyGraph, AdjacencyMatrixGraph, BidirectionalGraph, EdgeListGraph,
-                IncidenceGraph, MutableGraph, VertexListGraph, EdgeDescriptor, VertexDescriptor,
-                Directivity, Directed, Undirected};
-pub use incidence_list::{Edge, IncidenceList, IncidentEdges, IncidentVertices, Vertex};
+                IncidenceGraph, MutableGraph, VertexListGraph, EdgeDescriptor, Generation,
+                VertexDescriptor, Direction, Directivity, Directed, Undirected};
+pub use grid::{Connectivity, Grid};
+pub use hamiltonian::{hamiltonian_cycle, hamiltonian_path, HamiltonianSearch};
+pub use history::History;
+pub use implicit_search::{implicit_astar, implicit_bfs};
+pub use incidence_list::{AdjacentVertices, Edge, EdgeProperties, EdgeReferences, Externals,
+                         IncidenceList, IncidentEdges, IncidentVertices, SelfLoops, Vertex,
+                         Relabeling, VertexProperties, VertexReferences};
+pub use independent_set::{chordal_maximum_independent_set, tree_maximum_independent_set};
+pub use isomorphism::{automorphisms, automorphisms_matching, is_isomorphic, isomorphism,
+                      isomorphism_matching, subgraph_isomorphisms,
+                      subgraph_isomorphisms_matching, subgraph_monomorphisms,
+                      subgraph_monomorphisms_matching, AutomorphismGroup, Matches};
+pub use layering::longest_path_layering;
+pub use layout::{sugiyama_layout, Layout};
+pub use lowest_common_ancestor::{dag_lowest_common_ancestors, LowestCommonAncestor};
+pub use minor::{minor, topological_minor, Subdivision};
+pub use ordered_float::OrderedFloat;
+pub use ordering::{vertex_order, VertexOrder};
+pub use partition::{partition, spectral_bisection, Bisection, Partition};
+pub use path::Path;
+pub use path_cover::minimum_path_cover;
+pub use persistent_graph::PersistentGraph;
+pub use postman::{chinese_postman, PostmanTour};
+pub use property_map::{Color, HashPropertyMap, PropertyMap, VecPropertyMap};
+pub use reachability::ReachabilityIndex;
+pub use resource_constrained::constrained_shortest_path;
+pub use rewrite::{Replacement, Rewrite, Rule};
+pub use spanning_trees::{arborescence_count, arborescences, spanning_tree_count, spanning_trees};
+pub use spectral::{adjacency_matrix, fiedler_vector, laplacian_matrix, SparseMatrix};
+pub use stochastic::{stochastic_shortest_path, StochasticPath};
+pub use strongly_connected_components::strongly_connected_components;
+pub use topological_sort::{topological_orderings, topological_sort, TopologicalOrderings};
+pub use transaction::{Commit, Endpoint, NewEdge, NewVertex, Transaction};
+pub use transitive_reduction::transitive_reduction;
+pub use tsp::{christofides, held_karp};
+pub use turn_expansion::TurnExpansion;
+pub use two_sat::{Literal, TwoSat};
+pub use vertex_cover::{approximate_vertex_cover, bipartite_vertex_cover};
 pub use visitor::{Event, Visitor, DefaultVisitor};
+pub use weisfeiler_lehman::{weisfeiler_lehman_hash, weisfeiler_lehman_labels};
+#[cfg(feature = "log")]
+pub use log_visitor::LogVisitor;
+#[cfg(feature = "tracing")]
+pub use tracing_visitor::TracingVisitor;
+#[cfg(feature = "rand")]
+pub use generator::{gnm_random_graph, gnp_random_graph};
+#[cfg(feature = "rand")]
+pub use perturbation::{add_random_edges, double_edge_swap, remove_random_edges,
+                   rewire_random_edges};
 
-pub use astar_search::Astar;
+pub use astar_search::{Astar, GreedyBestFirst, UniformCost};
 pub use breadth_first_search::Bfs;
 pub use depth_first_search::Dfs;
+pub use dijkstra_search::Dijkstra;
+pub use ida_star_search::IdaStar;
+pub use weighted_astar_search::WeightedAstar;
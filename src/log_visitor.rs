@@ -0,0 +1,106 @@
+use std::fmt::Debug;
+
+use log::Level;
+
+use graph::Graph;
+use visitor::{describe, Event, Visitor};
+
+pub struct LogVisitor {
+    level: Level,
+}
+
+impl LogVisitor {
+    pub fn new() -> Self {
+        Self::with_level(Level::Debug)
+    }
+
+    pub fn with_level(level: Level) -> Self {
+        Self { level }
+    }
+}
+
+impl Default for LogVisitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<G> Visitor<G, Event> for LogVisitor
+where
+    G: Graph,
+    G::VertexProperty: Debug,
+    G::EdgeProperty: Debug,
+{
+    fn visit(&mut self, e: &Event, graph: &G) {
+        if !log_enabled!(target: "graph", self.level) {
+            return;
+        }
+
+        let (name, label) = describe(e, graph);
+        log!(target: "graph", self.level, "{} {}", name, label);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LogVisitor;
+
+    #[test]
+    fn log_visitor() {
+        use std::sync::Mutex;
+        use log::{self, Log, Metadata, Record};
+        use breadth_first_search::Bfs;
+        use graph::{Directed, MutableGraph};
+        use incidence_list::IncidenceList;
+
+        struct Recorder {
+            records: Mutex<Vec<String>>,
+        }
+
+        impl Log for Recorder {
+            fn enabled(&self, metadata: &Metadata) -> bool {
+                metadata.target() == "graph"
+            }
+
+            fn log(&self, record: &Record) {
+                if self.enabled(record.metadata()) {
+                    self.records.lock().unwrap().push(format!("{}", record.args()));
+                }
+            }
+
+            fn flush(&self) {}
+        }
+
+        let recorder: &'static Recorder = Box::leak(Box::new(Recorder {
+            records: Mutex::new(Vec::new()),
+        }));
+        log::set_logger(recorder).unwrap();
+        log::set_max_level(log::LevelFilter::Trace);
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+
+        let v0 = g.add_vertex("a");
+        let v1 = g.add_vertex("b");
+
+        g.add_edge(v0, v1, 3).unwrap();
+
+        let mut bfs = Bfs::with_visitor(LogVisitor::new());
+        assert_eq!(bfs.run(&v0, |&v| v == v1, &g), Ok(Some(vec![v0, v1])));
+
+        let records = recorder.records.lock().unwrap();
+        assert_eq!(
+            *records,
+            vec![
+                "initialize_vertex v0(\"a\")",
+                "initialize_vertex v1(\"b\")",
+                "discover_vertex v0(\"a\")",
+                "examine_vertex v0(\"a\")",
+                "examine_edge e0[3]",
+                "tree_edge e0[3]",
+                "discover_vertex v1(\"b\")",
+                "finish_vertex v0(\"a\")",
+                "examine_vertex v1(\"b\")",
+            ]
+        );
+    }
+}
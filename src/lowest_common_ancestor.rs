@@ -0,0 +1,234 @@
+use std::collections::VecDeque;
+
+use fnv::{FnvHashMap, FnvHashSet};
+
+use error::GraphError;
+use graph::{BidirectionalGraph, Directivity, EdgeDescriptor, VertexDescriptor, VertexListGraph};
+use topological_sort::topological_sort;
+
+fn children<'a, G>(graph: &'a G, v: VertexDescriptor) -> Vec<EdgeDescriptor>
+where
+    G: BidirectionalGraph<'a>,
+    G::Directivity: Directivity,
+{
+    let mut edges = graph.out_edges(v).collect::<Vec<_>>();
+    if !G::Directivity::is_directed() {
+        edges.extend(graph.in_edges(v));
+    }
+    edges
+}
+
+/// Answers lowest common ancestor queries on a rooted tree in O(log n) after O(n log n)
+/// preprocessing by binary lifting.
+pub struct LowestCommonAncestor {
+    index: FnvHashMap<VertexDescriptor, usize>,
+    vertices: Vec<VertexDescriptor>,
+    depth: Vec<usize>,
+    // ancestors[k][i] is the 2^k-th ancestor of i, the root being its own parent
+    ancestors: Vec<Vec<usize>>,
+}
+
+impl LowestCommonAncestor {
+    /// Roots the tree at `root`, following edges away from it in a directed graph and in both
+    /// directions in an undirected one. Vertices not reachable from `root` are left out. If
+    /// the graph is not a tree, its breadth-first tree from `root` is used.
+    pub fn new<'a, G>(graph: &'a G, root: VertexDescriptor) -> Result<Self, GraphError>
+    where
+        G: BidirectionalGraph<'a>,
+        G::Directivity: Directivity,
+    {
+        if !graph.contains_vertex(root) {
+            return Err(GraphError::VertexNotFound(root));
+        }
+
+        let mut index = FnvHashMap::default();
+        let mut vertices = vec![root];
+        let mut depth = vec![0];
+        let mut parents = vec![0];
+        index.insert(root, 0);
+        let mut fringe = VecDeque::new();
+        fringe.push_back(0);
+        while let Some(i) = fringe.pop_front() {
+            let v = vertices[i];
+            for e in children(graph, v) {
+                if let Some(w) = graph.opposite(e, v) {
+                    if index.contains_key(&w) {
+                        continue;
+                    }
+                    index.insert(w, vertices.len());
+                    fringe.push_back(vertices.len());
+                    vertices.push(w);
+                    depth.push(depth[i] + 1);
+                    parents.push(i);
+                }
+            }
+        }
+
+        let mut ancestors = vec![parents];
+        while 1 << ancestors.len() < vertices.len() {
+            let next = {
+                let last = ancestors.last().unwrap();
+                last.iter().map(|&p| last[p]).collect()
+            };
+            ancestors.push(next);
+        }
+
+        Ok(LowestCommonAncestor {
+            index,
+            vertices,
+            depth,
+            ancestors,
+        })
+    }
+
+    pub fn depth(&self, v: VertexDescriptor) -> Option<usize> {
+        self.index.get(&v).map(|&i| self.depth[i])
+    }
+
+    /// Returns `None` if either vertex is not in the tree.
+    pub fn lca(&self, u: VertexDescriptor, v: VertexDescriptor) -> Option<VertexDescriptor> {
+        let mut a = *self.index.get(&u)?;
+        let mut b = *self.index.get(&v)?;
+        if self.depth[a] < self.depth[b] {
+            ::std::mem::swap(&mut a, &mut b);
+        }
+        let gap = self.depth[a] - self.depth[b];
+        for (k, level) in self.ancestors.iter().enumerate() {
+            if gap & (1 << k) != 0 {
+                a = level[a];
+            }
+        }
+        if a == b {
+            return Some(self.vertices[a]);
+        }
+        for level in self.ancestors.iter().rev() {
+            if level[a] != level[b] {
+                a = level[a];
+                b = level[b];
+            }
+        }
+        Some(self.vertices[self.ancestors[0][a]])
+    }
+}
+
+fn ancestors_of<'a, G>(graph: &'a G, v: VertexDescriptor) -> FnvHashSet<VertexDescriptor>
+where
+    G: BidirectionalGraph<'a>,
+{
+    let mut seen = FnvHashSet::default();
+    seen.insert(v);
+    let mut stack = vec![v];
+    while let Some(u) = stack.pop() {
+        for e in graph.in_edges(u) {
+            if let Some(p) = graph.opposite(e, u) {
+                if seen.insert(p) {
+                    stack.push(p);
+                }
+            }
+        }
+    }
+    seen
+}
+
+/// Returns the lowest common ancestors of `u` and `v` in a directed acyclic graph: the common
+/// ancestors, each vertex counting as its own, from which no other common ancestor can be
+/// reached. Unlike in a tree there may be several, or none. Fails with `GraphError::Cyclic`
+/// if the graph has a cycle.
+pub fn dag_lowest_common_ancestors<'a, G>(
+    graph: &'a G,
+    u: VertexDescriptor,
+    v: VertexDescriptor,
+) -> Result<Vec<VertexDescriptor>, GraphError>
+where
+    G: BidirectionalGraph<'a> + VertexListGraph<'a>,
+{
+    for &w in &[u, v] {
+        if !graph.contains_vertex(w) {
+            return Err(GraphError::VertexNotFound(w));
+        }
+    }
+    topological_sort(graph)?;
+    let common = ancestors_of(graph, u)
+        .intersection(&ancestors_of(graph, v))
+        .cloned()
+        .collect::<FnvHashSet<_>>();
+    let mut lowest = common
+        .iter()
+        .cloned()
+        .filter(|&c| {
+            graph
+                .out_edges(c)
+                .filter_map(|e| graph.opposite(e, c))
+                .all(|w| !common.contains(&w))
+        })
+        .collect::<Vec<_>>();
+    lowest.sort();
+    Ok(lowest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dag_lowest_common_ancestors, LowestCommonAncestor};
+
+    #[test]
+    fn lca_in_tree() {
+        use graph::{MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        //          v0
+        //        /    \
+        //      v1      v2
+        //     /  \       \
+        //   v3    v4      v5
+        //   |
+        //   v6
+
+        let mut g = IncidenceList::<Undirected, _, _>::new();
+        let vs = (0..7).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t) in &[(0, 1), (0, 2), (1, 3), (4, 1), (2, 5), (3, 6)] {
+            g.add_edge(vs[s], vs[t], ()).unwrap();
+        }
+
+        let lca = LowestCommonAncestor::new(&g, vs[0]).unwrap();
+        assert_eq!(lca.lca(vs[6], vs[4]), Some(vs[1]));
+        assert_eq!(lca.lca(vs[6], vs[5]), Some(vs[0]));
+        assert_eq!(lca.lca(vs[3], vs[6]), Some(vs[3]));
+        assert_eq!(lca.lca(vs[2], vs[2]), Some(vs[2]));
+        assert_eq!(lca.depth(vs[6]), Some(3));
+
+        let lca = LowestCommonAncestor::new(&g, vs[3]).unwrap();
+        assert_eq!(lca.lca(vs[4], vs[5]), Some(vs[1]));
+    }
+
+    #[test]
+    fn lca_in_dag() {
+        use error::GraphError;
+        use graph::{Directed, MutableGraph};
+        use incidence_list::IncidenceList;
+
+        // v0     v1
+        //  | \  / |
+        //  |  \/  |
+        //  |  /\  |
+        //  v v  v v
+        // v2     v3 --> v4
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+        let vs = (0..5).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t) in &[(0, 2), (0, 3), (1, 2), (1, 3), (3, 4)] {
+            g.add_edge(vs[s], vs[t], ()).unwrap();
+        }
+
+        assert_eq!(dag_lowest_common_ancestors(&g, vs[2], vs[4]), Ok(vec![vs[0], vs[1]]));
+        assert_eq!(dag_lowest_common_ancestors(&g, vs[3], vs[4]), Ok(vec![vs[3]]));
+        assert_eq!(dag_lowest_common_ancestors(&g, vs[0], vs[1]), Ok(vec![]));
+
+        // with v4 --> v1 closing a cycle, every common ancestor of v3 and v4 reaches another,
+        // which is not to be mistaken for their having none
+        g.add_edge(vs[4], vs[1], ()).unwrap();
+        match dag_lowest_common_ancestors(&g, vs[3], vs[4]) {
+            Err(GraphError::Cyclic(v)) => assert!([1, 3, 4].iter().any(|&i| v == vs[i])),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,403 @@
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+
+use fnv::{FnvHashMap, FnvHashSet};
+
+use coloring::symmetric_adjacency;
+use graph::{EdgeDescriptor, IncidenceGraph, VertexDescriptor, VertexListGraph};
+use property_map::{vertex_index, PropertyMap, VecPropertyMap};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Subdivision {
+    vertices: FnvHashMap<VertexDescriptor, VertexDescriptor>,
+    paths: FnvHashMap<EdgeDescriptor, Vec<VertexDescriptor>>,
+}
+
+impl Subdivision {
+    /// The host vertex a pattern vertex is mapped to.
+    pub fn vertex(&self, v: VertexDescriptor) -> Option<VertexDescriptor> {
+        self.vertices.get(&v).cloned()
+    }
+
+    /// The host path a pattern edge is subdivided into, from the image of its source to the
+    /// image of its target.
+    pub fn path(&self, e: EdgeDescriptor) -> Option<&[VertexDescriptor]> {
+        self.paths.get(&e).map(|p| p.as_slice())
+    }
+}
+
+// The edges of the pattern between distinct vertices, one per pair of adjacent vertices.
+fn simple_edges<'a, G>(
+    graph: &'a G,
+    index: &VecPropertyMap<VertexDescriptor, usize>,
+) -> Vec<(EdgeDescriptor, usize, usize)>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+{
+    let mut seen = FnvHashSet::default();
+    let mut edges = Vec::new();
+    for v in graph.vertices() {
+        for e in graph.out_edges(v) {
+            if let Some(&j) = graph.opposite(e, v).and_then(|t| index.get(t)) {
+                let i = index[v];
+                if i != j && seen.insert((i.min(j), i.max(j))) {
+                    edges.push((e, i, j));
+                }
+            }
+        }
+    }
+    edges
+}
+
+struct MinorSearch<'s> {
+    host: &'s [Vec<usize>],
+    edges: &'s [(usize, usize)],
+    order: Vec<usize>,
+    position: Vec<usize>,
+    labels: Vec<Option<usize>>,
+    sizes: Vec<usize>,
+}
+
+impl<'s> MinorSearch<'s> {
+    fn open(&self, v: usize, done: usize, label: usize) -> bool {
+        self.position[v] >= done || self.labels[v] == Some(label)
+    }
+
+    // Whether the assignment of the first `done` vertices in order can still be completed:
+    // each branch set must lie within one component of itself plus the unassigned vertices,
+    // and each pattern edge must still be able to join its two branch sets.
+    fn viable(&self, done: usize) -> bool {
+        let empty = self.sizes.iter().filter(|&&s| s == 0).count();
+        if empty > self.order.len() - done {
+            return false;
+        }
+        for label in 0..self.sizes.len() {
+            let start = match (0..self.host.len()).find(|&v| self.labels[v] == Some(label)) {
+                Some(v) => v,
+                None => continue,
+            };
+            let mut seen = vec![false; self.host.len()];
+            let mut reached = 0;
+            let mut queue = VecDeque::new();
+            seen[start] = true;
+            queue.push_back(start);
+            while let Some(v) = queue.pop_front() {
+                if self.labels[v] == Some(label) {
+                    reached += 1;
+                }
+                for &w in &self.host[v] {
+                    if !seen[w] && self.open(w, done, label) {
+                        seen[w] = true;
+                        queue.push_back(w);
+                    }
+                }
+            }
+            if reached < self.sizes[label] {
+                return false;
+            }
+        }
+        self.edges.iter().all(|&(p, q)| {
+            (0..self.host.len()).any(|v| {
+                self.open(v, done, p) && self.host[v].iter().any(|&w| self.open(w, done, q))
+            })
+        })
+    }
+
+    fn assign(&mut self, done: usize) -> bool {
+        if !self.viable(done) {
+            return false;
+        }
+        if done == self.order.len() {
+            return true;
+        }
+        let v = self.order[done];
+        for label in (0..self.sizes.len()).map(Some).chain(Some(None)) {
+            self.labels[v] = label;
+            if let Some(l) = label {
+                self.sizes[l] += 1;
+            }
+            if self.assign(done + 1) {
+                return true;
+            }
+            if let Some(l) = label {
+                self.sizes[l] -= 1;
+            }
+        }
+        self.labels[v] = None;
+        false
+    }
+}
+
+/// Decides whether `pattern` is a minor of `host`, that is whether it can be obtained from the
+/// host by deleting vertices and edges and contracting edges, and if so returns the branch set
+/// of each pattern vertex: disjoint connected sets of host vertices with a host edge between
+/// the sets of every pair of adjacent pattern vertices. Both graphs are taken as undirected
+/// and simple. The search backtracks over the branch set of each host vertex, so it is meant
+/// for patterns of a handful of vertices, such as K5 and K3,3, in hosts that are not large.
+pub fn minor<'a, 'b, G0, G1>(
+    pattern: &'a G0,
+    host: &'b G1,
+) -> Option<FnvHashMap<VertexDescriptor, Vec<VertexDescriptor>>>
+where
+    G0: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G1: IncidenceGraph<'b> + VertexListGraph<'b>,
+{
+    let (pattern_vertices, pattern_neighbors) = symmetric_adjacency(pattern);
+    let (host_vertices, host_neighbors) = symmetric_adjacency(host);
+    let edges = (0..pattern_vertices.len())
+        .flat_map(|p| pattern_neighbors[p].iter().filter(move |&&q| p < q).map(move |&q| (p, q)))
+        .collect::<Vec<_>>();
+
+    // breadth-first order keeps the vertices of a branch set close together in the search
+    let mut order = Vec::with_capacity(host_vertices.len());
+    let mut position = vec![usize::MAX; host_vertices.len()];
+    for root in 0..host_vertices.len() {
+        if position[root] != usize::MAX {
+            continue;
+        }
+        position[root] = order.len();
+        order.push(root);
+        let mut k = order.len() - 1;
+        while k < order.len() {
+            for &w in &host_neighbors[order[k]] {
+                if position[w] == usize::MAX {
+                    position[w] = order.len();
+                    order.push(w);
+                }
+            }
+            k += 1;
+        }
+    }
+
+    let mut search = MinorSearch {
+        host: &host_neighbors,
+        edges: &edges,
+        order,
+        position,
+        labels: vec![None; host_vertices.len()],
+        sizes: vec![0; pattern_vertices.len()],
+    };
+    if !search.assign(0) {
+        return None;
+    }
+    let mut branch_sets = pattern_vertices
+        .iter()
+        .map(|&p| (p, Vec::new()))
+        .collect::<FnvHashMap<_, _>>();
+    for (v, label) in search.labels.iter().enumerate() {
+        if let Some(p) = *label {
+            branch_sets.get_mut(&pattern_vertices[p]).unwrap().push(host_vertices[v]);
+        }
+    }
+    Some(branch_sets)
+}
+
+struct SubdivisionSearch<'s> {
+    host: &'s [Vec<usize>],
+    pattern_degrees: Vec<usize>,
+    // pattern vertices by decreasing degree, the order in which they are placed
+    order: Vec<usize>,
+    edges: &'s [(usize, usize)],
+    images: Vec<usize>,
+    used: Vec<bool>,
+    paths: Vec<Vec<usize>>,
+}
+
+impl<'s> SubdivisionSearch<'s> {
+    fn place(&mut self, k: usize) -> bool {
+        if k == self.order.len() {
+            return self.route(0);
+        }
+        let p = self.order[k];
+        for v in 0..self.host.len() {
+            if self.used[v] || self.host[v].len() < self.pattern_degrees[p] {
+                continue;
+            }
+            self.used[v] = true;
+            self.images[p] = v;
+            if self.place(k + 1) {
+                return true;
+            }
+            self.used[v] = false;
+        }
+        false
+    }
+
+    fn route(&mut self, k: usize) -> bool {
+        if k == self.edges.len() {
+            return true;
+        }
+        let mut path = vec![self.images[self.edges[k].0]];
+        self.extend(k, &mut path)
+    }
+
+    // Extends a path for the k-th pattern edge through unused vertices by depth-first search,
+    // routing the remaining edges whenever it reaches its end.
+    fn extend(&mut self, k: usize, path: &mut Vec<usize>) -> bool {
+        let last = *path.last().unwrap();
+        let target = self.images[self.edges[k].1];
+        let neighbors = self.host[last].clone();
+        if neighbors.contains(&target) {
+            path.push(target);
+            self.paths[k] = path.clone();
+            if self.route(k + 1) {
+                return true;
+            }
+            path.pop();
+        }
+        for w in neighbors {
+            if self.used[w] {
+                continue;
+            }
+            self.used[w] = true;
+            path.push(w);
+            if self.extend(k, path) {
+                return true;
+            }
+            path.pop();
+            self.used[w] = false;
+        }
+        false
+    }
+}
+
+/// Decides whether a subdivision of `pattern` is a subgraph of `host`, that is whether its
+/// vertices can be mapped to distinct host vertices and its edges to host paths between their
+/// images that share no vertices but their ends. Both graphs are taken as undirected and
+/// simple, and of parallel pattern edges only one is given a path. Every topological minor is
+/// a minor, but not the other way round. The search backtracks over the images and the paths,
+/// so it is meant for small patterns in hosts that are not large.
+pub fn topological_minor<'a, 'b, G0, G1>(pattern: &'a G0, host: &'b G1) -> Option<Subdivision>
+where
+    G0: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G1: IncidenceGraph<'b> + VertexListGraph<'b>,
+{
+    let pattern_vertices = pattern.vertices().collect::<Vec<_>>();
+    let index = vertex_index(pattern, &pattern_vertices);
+    let edges = simple_edges(pattern, &index);
+    let (host_vertices, host_neighbors) = symmetric_adjacency(host);
+    if pattern_vertices.len() > host_vertices.len() {
+        return None;
+    }
+
+    let mut pattern_degrees = vec![0; pattern_vertices.len()];
+    for &(_, p, q) in &edges {
+        pattern_degrees[p] += 1;
+        pattern_degrees[q] += 1;
+    }
+    let mut order = (0..pattern_vertices.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&p| Reverse(pattern_degrees[p]));
+    let pairs = edges.iter().map(|&(_, p, q)| (p, q)).collect::<Vec<_>>();
+    let mut search = SubdivisionSearch {
+        host: &host_neighbors,
+        pattern_degrees,
+        order,
+        edges: &pairs,
+        images: vec![0; pattern_vertices.len()],
+        used: vec![false; host_vertices.len()],
+        paths: vec![Vec::new(); pairs.len()],
+    };
+    if !search.place(0) {
+        return None;
+    }
+    Some(Subdivision {
+        vertices: (0..pattern_vertices.len())
+            .map(|p| (pattern_vertices[p], host_vertices[search.images[p]]))
+            .collect(),
+        paths: edges
+            .iter()
+            .zip(&search.paths)
+            .map(|(&(e, _, _), path)| (e, path.iter().map(|&v| host_vertices[v]).collect()))
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{minor, topological_minor};
+
+    #[test]
+    fn minors_and_topological_minors() {
+        use graph::{AdjacencyMatrixGraph, MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        // the wheel: a hub h joined to every vertex of the cycle r0 - r1 - r2 - r3 - r4 - r0
+
+        let mut wheel = IncidenceList::<Undirected, (), ()>::new();
+        let hub = wheel.add_vertex(());
+        let rim = (0..5).map(|_| wheel.add_vertex(())).collect::<Vec<_>>();
+        for i in 0..5 {
+            wheel.add_edge(hub, rim[i], ()).unwrap();
+            wheel.add_edge(rim[i], rim[(i + 1) % 5], ()).unwrap();
+        }
+        let mut k4 = IncidenceList::<Undirected, (), ()>::new();
+        let ks = (0..4).map(|_| k4.add_vertex(())).collect::<Vec<_>>();
+        for i in 0..4 {
+            for j in 0..i {
+                k4.add_edge(ks[i], ks[j], ()).unwrap();
+            }
+        }
+
+        let sets = minor(&k4, &wheel).unwrap();
+        let mut all = sets.values().flat_map(|s| s.iter().cloned()).collect::<Vec<_>>();
+        let total = all.len();
+        all.sort();
+        all.dedup();
+        assert_eq!(all.len(), total);
+        for i in 0..4 {
+            for j in 0..i {
+                assert!(sets[&ks[i]].iter().any(|&u| {
+                    sets[&ks[j]].iter().any(|&w| wheel.edge(u, w).is_some())
+                }));
+            }
+        }
+
+        let subdivision = topological_minor(&k4, &wheel).unwrap();
+        let mut inner = Vec::new();
+        for i in 0..4 {
+            for j in 0..i {
+                let e = k4.edge(ks[i], ks[j]).unwrap();
+                let path = subdivision.path(e).unwrap();
+                assert!(path.windows(2).all(|w| wheel.edge(w[0], w[1]).is_some()));
+                inner.extend_from_slice(&path[1..path.len() - 1]);
+            }
+        }
+        let count = inner.len();
+        inner.sort();
+        inner.dedup();
+        assert_eq!(inner.len(), count);
+        assert!(inner.iter().all(|&v| ks.iter().all(|&p| subdivision.vertex(p) != Some(v))));
+
+        // a cycle has no K4 minor
+        let mut cycle = IncidenceList::<Undirected, (), ()>::new();
+        let cs = (0..6).map(|_| cycle.add_vertex(())).collect::<Vec<_>>();
+        for i in 0..6 {
+            cycle.add_edge(cs[i], cs[(i + 1) % 6], ()).unwrap();
+        }
+        assert!(minor(&k4, &cycle).is_none());
+        assert!(topological_minor(&k4, &cycle).is_none());
+
+        // contracting a - b makes a star with four leaves, which no subdivision can, as no
+        // vertex has four neighbors
+
+        let mut host = IncidenceList::<Undirected, (), ()>::new();
+        let a = host.add_vertex(());
+        let b = host.add_vertex(());
+        host.add_edge(a, b, ()).unwrap();
+        for &center in &[a, a, b, b] {
+            let leaf = host.add_vertex(());
+            host.add_edge(center, leaf, ()).unwrap();
+        }
+        let mut star = IncidenceList::<Undirected, (), ()>::new();
+        let center = star.add_vertex(());
+        for _ in 0..4 {
+            let leaf = star.add_vertex(());
+            star.add_edge(center, leaf, ()).unwrap();
+        }
+        let sets = minor(&star, &host).unwrap();
+        let mut contracted = sets[&center].clone();
+        contracted.sort();
+        assert_eq!(contracted, vec![a, b]);
+        assert!(topological_minor(&star, &host).is_none());
+    }
+}
@@ -0,0 +1,152 @@
+use std::cmp::Ordering;
+use std::ops::{Add, Sub};
+
+use num_traits::{Bounded, Float, NumCast, ToPrimitive, Zero};
+
+/// A floating-point number that is totally ordered, so that `f32` or `f64` weights can be the
+/// costs of `Astar`, `Dijkstra` and the other searches that require `Ord`.
+///
+/// Numbers compare as usual, with `-0.0` equal to `0.0`. NaN equals itself and is greater than
+/// every other number, infinity included, so that a search treats an edge of cost NaN as if it
+/// could never be taken; a path through one still costs NaN if nothing better is found.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OrderedFloat<F>(pub F);
+
+impl<F: Float> OrderedFloat<F> {
+    pub fn into_inner(self) -> F {
+        self.0
+    }
+}
+
+impl<F: Float> From<F> for OrderedFloat<F> {
+    fn from(f: F) -> Self {
+        OrderedFloat(f)
+    }
+}
+
+impl<F: Float> PartialEq for OrderedFloat<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<F: Float> Eq for OrderedFloat<F> {}
+
+impl<F: Float> PartialOrd for OrderedFloat<F> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<F: Float> Ord for OrderedFloat<F> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self.0.partial_cmp(&other.0) {
+            Some(ordering) => ordering,
+            None => self.0.is_nan().cmp(&other.0.is_nan()),
+        }
+    }
+}
+
+impl<F: Float> Add for OrderedFloat<F> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        OrderedFloat(self.0 + other.0)
+    }
+}
+
+impl<F: Float> Sub for OrderedFloat<F> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        OrderedFloat(self.0 - other.0)
+    }
+}
+
+impl<F: Float> Zero for OrderedFloat<F> {
+    fn zero() -> Self {
+        OrderedFloat(F::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+}
+
+impl<F: Float> Bounded for OrderedFloat<F> {
+    fn min_value() -> Self {
+        OrderedFloat(F::min_value())
+    }
+
+    fn max_value() -> Self {
+        OrderedFloat(F::max_value())
+    }
+}
+
+impl<F: Float> ToPrimitive for OrderedFloat<F> {
+    fn to_i64(&self) -> Option<i64> {
+        self.0.to_i64()
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        self.0.to_u64()
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        self.0.to_f64()
+    }
+}
+
+impl<F: Float> NumCast for OrderedFloat<F> {
+    fn from<T: ToPrimitive>(n: T) -> Option<Self> {
+        <F as NumCast>::from(n).map(OrderedFloat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OrderedFloat;
+
+    #[test]
+    fn float_costs() {
+        use std::f64;
+        use astar_search::Astar;
+        use dijkstra_search::Dijkstra;
+        use graph::{Directed, MutableGraph, VertexDescriptor};
+        use incidence_list::IncidenceList;
+        use path::Path;
+
+        let nan = OrderedFloat(f64::NAN);
+        let infinity = OrderedFloat(f64::INFINITY);
+        assert!(OrderedFloat(-1.5) < OrderedFloat(0.0) && OrderedFloat(0.0) < infinity);
+        assert!(infinity < nan && nan == nan);
+        assert_eq!(OrderedFloat(-0.0), OrderedFloat(0.0));
+
+        // (0, 0) ---1.5---> (1, 1) ---1.5---> (2, 0)
+        //    |                                  ^
+        //    +---------------2.5----------------+
+
+        let mut g = IncidenceList::<Directed, (f64, f64), f64>::new();
+        let a = g.add_vertex((0.0, 0.0));
+        let b = g.add_vertex((1.0, 1.0));
+        let c = g.add_vertex((2.0, 0.0));
+        g.add_edge(a, b, 1.5).unwrap();
+        g.add_edge(b, c, 1.5).unwrap();
+        let ac = g.add_edge(a, c, 2.5).unwrap();
+
+        let straight = |v: &VertexDescriptor, g: &IncidenceList<Directed, (f64, f64), f64>| {
+            let ((x, y), (gx, gy)) = (g[*v], g[c]);
+            OrderedFloat(((gx - x).powi(2) + (gy - y).powi(2)).sqrt())
+        };
+        assert_eq!(
+            Astar::new().run_to_vertex(&a, &c, |&e, g| OrderedFloat(g[e]), straight, &g),
+            Ok(Some(Path::new(vec![a, c], OrderedFloat(2.5))))
+        );
+
+        g[ac] = f64::NAN;
+        assert_eq!(
+            Dijkstra::new().run_to_vertex(&a, &c, |&e, g| OrderedFloat(g[e]), &g),
+            Ok(Some(Path::new(vec![a, b, c], OrderedFloat(3.0))))
+        );
+    }
+}
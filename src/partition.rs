@@ -0,0 +1,452 @@
+use std::collections::VecDeque;
+
+use fnv::FnvHashMap;
+
+use graph::{EdgeDescriptor, IncidenceGraph, VertexDescriptor, VertexListGraph};
+use property_map::{vertex_index, PropertyMap};
+use spectral::fiedler_vector;
+
+/// Coarsening stops once there are at most this many vertices per part.
+const COARSEST_PER_PART: usize = 8;
+/// Rounds of Kernighan-Lin passes over every pair of parts at each level.
+const REFINEMENT_ROUNDS: usize = 4;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Partition {
+    parts: FnvHashMap<VertexDescriptor, usize>,
+    cut_size: usize,
+}
+
+impl Partition {
+    pub fn part(&self, v: VertexDescriptor) -> Option<usize> {
+        self.parts.get(&v).cloned()
+    }
+
+    pub fn parts(&self) -> &FnvHashMap<VertexDescriptor, usize> {
+        &self.parts
+    }
+
+    /// The number of edges between vertices in different parts.
+    pub fn cut_size(&self) -> usize {
+        self.cut_size
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bisection {
+    halves: (Vec<VertexDescriptor>, Vec<VertexDescriptor>),
+    cut_edges: Vec<EdgeDescriptor>,
+}
+
+impl Bisection {
+    pub fn halves(&self) -> (&[VertexDescriptor], &[VertexDescriptor]) {
+        (&self.halves.0, &self.halves.1)
+    }
+
+    pub fn cut_edges(&self) -> &[EdgeDescriptor] {
+        &self.cut_edges
+    }
+}
+
+// A graph whose vertices and edges carry weights: the number of original vertices merged into
+// each vertex and of original edges between them.
+struct Level {
+    weights: Vec<usize>,
+    edges: Vec<FnvHashMap<usize, usize>>,
+}
+
+impl Level {
+    // Merges each vertex with its unmatched neighbor along the heaviest edge, keeping merged
+    // vertices no heavier than `limit`. Returns the coarser level and where each vertex went.
+    fn coarsen(&self, limit: usize) -> (Level, Vec<usize>) {
+        let n = self.weights.len();
+        let mut coarse = vec![usize::MAX; n];
+        let mut count = 0;
+        for v in 0..n {
+            if coarse[v] != usize::MAX {
+                continue;
+            }
+            let mate = self.edges[v]
+                .iter()
+                .filter(|&(&w, _)| coarse[w] == usize::MAX && w != v)
+                .filter(|&(&w, _)| self.weights[v] + self.weights[w] <= limit)
+                .max_by_key(|&(&w, &weight)| (weight, usize::MAX - w))
+                .map(|(&w, _)| w);
+            coarse[v] = count;
+            if let Some(w) = mate {
+                coarse[w] = count;
+            }
+            count += 1;
+        }
+
+        let mut weights = vec![0; count];
+        let mut edges = vec![FnvHashMap::default(); count];
+        for v in 0..n {
+            weights[coarse[v]] += self.weights[v];
+            for (&w, &weight) in &self.edges[v] {
+                if coarse[v] != coarse[w] {
+                    *edges[coarse[v]].entry(coarse[w]).or_insert(0) += weight;
+                }
+            }
+        }
+        (Level { weights, edges }, coarse)
+    }
+
+    fn weight(&self, v: usize, w: usize) -> isize {
+        self.edges[v].get(&w).cloned().unwrap_or(0) as isize
+    }
+
+    // Grows the parts one after another breadth-first, each up to its share of the weight.
+    fn initial(&self, k: usize) -> Vec<usize> {
+        let n = self.weights.len();
+        let total = self.weights.iter().sum::<usize>();
+        let mut parts = vec![usize::MAX; n];
+        let mut part = 0;
+        let mut filled = 0;
+        for root in 0..n {
+            if parts[root] != usize::MAX {
+                continue;
+            }
+            let mut queue = VecDeque::new();
+            queue.push_back(root);
+            parts[root] = part;
+            while let Some(v) = queue.pop_front() {
+                parts[v] = part;
+                filled += self.weights[v];
+                if filled * k >= total * (part + 1) && part + 1 < k {
+                    part += 1;
+                }
+                let mut next = self.edges[v]
+                    .keys()
+                    .cloned()
+                    .filter(|&w| parts[w] == usize::MAX)
+                    .collect::<Vec<_>>();
+                next.sort();
+                for w in next {
+                    parts[w] = part;
+                    queue.push_back(w);
+                }
+            }
+        }
+        parts
+    }
+
+    // Moves vertices out of parts heavier than `limit` into lighter ones, the cheapest first.
+    fn rebalance(&self, parts: &mut [usize], k: usize, limit: usize) {
+        let mut loads = vec![0; k];
+        for (v, &p) in parts.iter().enumerate() {
+            loads[p] += self.weights[v];
+        }
+        while let Some(p) = (0..k).find(|&p| loads[p] > limit) {
+            let mut best = None;
+            for v in (0..parts.len()).filter(|&v| parts[v] == p) {
+                let internal = self.edges[v]
+                    .iter()
+                    .filter(|&(&w, _)| parts[w] == p)
+                    .map(|(_, &weight)| weight as isize)
+                    .sum::<isize>();
+                for q in (0..k).filter(|&q| q != p && loads[q] + self.weights[v] <= limit) {
+                    let external = self.edges[v]
+                        .iter()
+                        .filter(|&(&w, _)| parts[w] == q)
+                        .map(|(_, &weight)| weight as isize)
+                        .sum::<isize>();
+                    if best.is_none_or(|(gain, _, _)| external - internal > gain) {
+                        best = Some((external - internal, v, q));
+                    }
+                }
+            }
+            match best {
+                Some((_, v, q)) => {
+                    parts[v] = q;
+                    loads[p] -= self.weights[v];
+                    loads[q] += self.weights[v];
+                }
+                None => break,
+            }
+        }
+    }
+
+    // One pass of Kernighan and Lin between parts p and q: tentatively swap the pair of
+    // unlocked vertices that gains most, lock them, and repeat; then keep the best prefix of
+    // swaps if it gains anything. Swaps that would overload a part are skipped.
+    fn kernighan_lin(&self, parts: &mut [usize], p: usize, q: usize, limit: usize) -> bool {
+        let mut loads = (0, 0);
+        let mut sides = (Vec::new(), Vec::new());
+        for (v, &part) in parts.iter().enumerate() {
+            if part == p {
+                sides.0.push(v);
+                loads.0 += self.weights[v];
+            } else if part == q {
+                sides.1.push(v);
+                loads.1 += self.weights[v];
+            }
+        }
+        // external minus internal cost of each vertex with respect to the pair
+        let mut gains = vec![0isize; parts.len()];
+        for &v in sides.0.iter().chain(&sides.1) {
+            let (own, other) = if parts[v] == p { (p, q) } else { (q, p) };
+            for (&w, &weight) in &self.edges[v] {
+                if parts[w] == other {
+                    gains[v] += weight as isize;
+                } else if parts[w] == own {
+                    gains[v] -= weight as isize;
+                }
+            }
+        }
+
+        let mut locked = vec![false; parts.len()];
+        let mut swaps = Vec::new();
+        let mut total = 0;
+        let mut best = (0, 0);
+        for _ in 0..sides.0.len().min(sides.1.len()) {
+            let mut a_side = sides.0.iter().cloned().filter(|&v| !locked[v]).collect::<Vec<_>>();
+            let mut b_side = sides.1.iter().cloned().filter(|&v| !locked[v]).collect::<Vec<_>>();
+            a_side.sort_by_key(|&v| -gains[v]);
+            b_side.sort_by_key(|&v| -gains[v]);
+            let mut choice: Option<(isize, usize, usize)> = None;
+            for &a in &a_side {
+                for &b in &b_side {
+                    if choice.is_some_and(|(gain, _, _)| gains[a] + gains[b] <= gain) {
+                        break;
+                    }
+                    let (wa, wb) = (self.weights[a], self.weights[b]);
+                    if loads.0 + wb > limit + wa || loads.1 + wa > limit + wb {
+                        continue;
+                    }
+                    let gain = gains[a] + gains[b] - 2 * self.weight(a, b);
+                    if choice.is_none_or(|(g, _, _)| gain > g) {
+                        choice = Some((gain, a, b));
+                    }
+                }
+            }
+            let (gain, a, b) = match choice {
+                Some(choice) => choice,
+                None => break,
+            };
+            locked[a] = true;
+            locked[b] = true;
+            loads.0 = loads.0 + self.weights[b] - self.weights[a];
+            loads.1 = loads.1 + self.weights[a] - self.weights[b];
+            for &x in sides.0.iter().filter(|&&x| !locked[x]) {
+                gains[x] += 2 * self.weight(x, a) - 2 * self.weight(x, b);
+            }
+            for &y in sides.1.iter().filter(|&&y| !locked[y]) {
+                gains[y] += 2 * self.weight(y, b) - 2 * self.weight(y, a);
+            }
+            swaps.push((a, b));
+            total += gain;
+            if total > best.0 {
+                best = (total, swaps.len());
+            }
+        }
+
+        for &(a, b) in &swaps[..best.1] {
+            parts[a] = q;
+            parts[b] = p;
+        }
+        best.1 > 0
+    }
+
+    fn refine(&self, parts: &mut [usize], k: usize, limit: usize) {
+        self.rebalance(parts, k, limit);
+        for _ in 0..REFINEMENT_ROUNDS {
+            let mut improved = false;
+            for p in 0..k {
+                for q in p + 1..k {
+                    improved |= self.kernighan_lin(parts, p, q, limit);
+                }
+            }
+            if !improved {
+                break;
+            }
+        }
+    }
+}
+
+/// Splits the vertices into `k` parts of as equal a size as possible while cutting few edges,
+/// ignoring edge direction, in the multilevel manner of Karypis and Kumar: the graph is
+/// coarsened by merging vertices along heavy edges, the coarsest graph is split by growing
+/// parts breadth-first, and the split is carried back level by level, rebalanced and refined
+/// with passes of Kernighan and Lin between every pair of parts. Part sizes differ by at most
+/// one unless the graph is too tangled for swaps to even them out.
+pub fn partition<'a, G>(graph: &'a G, k: usize) -> Partition
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+{
+    assert!(k > 0, "at least one part is needed");
+    let vertices = graph.vertices().collect::<Vec<_>>();
+    let index = vertex_index(graph, &vertices);
+    let n = vertices.len();
+
+    let mut edges = vec![FnvHashMap::default(); n];
+    let mut cut_edges = Vec::new();
+    for (i, &v) in vertices.iter().enumerate() {
+        for e in graph.out_edges(v) {
+            if let Some(&j) = graph.opposite(e, v).and_then(|t| index.get(t)) {
+                if i != j {
+                    *edges[i].entry(j).or_insert(0) += 1;
+                    *edges[j].entry(i).or_insert(0) += 1;
+                    cut_edges.push((i, j));
+                }
+            }
+        }
+    }
+    let share = n.div_ceil(k);
+
+    let mut levels = vec![Level {
+        weights: vec![1; n],
+        edges,
+    }];
+    let mut maps = Vec::new();
+    while levels.last().unwrap().weights.len() > COARSEST_PER_PART * k {
+        let (coarse, map) = levels.last().unwrap().coarsen(share);
+        if coarse.weights.len() * 10 > levels.last().unwrap().weights.len() * 9 {
+            break;
+        }
+        levels.push(coarse);
+        maps.push(map);
+    }
+
+    let coarsest = levels.last().unwrap();
+    let mut parts = coarsest.initial(k);
+    let heaviest = coarsest.weights.iter().cloned().max().unwrap_or(1);
+    coarsest.refine(&mut parts, k, share + heaviest - 1);
+    for (level, map) in levels.iter().rev().skip(1).zip(maps.iter().rev()) {
+        parts = map.iter().map(|&c| parts[c]).collect();
+        let heaviest = level.weights.iter().cloned().max().unwrap_or(1);
+        level.refine(&mut parts, k, share + heaviest - 1);
+    }
+
+    Partition {
+        cut_size: cut_edges.iter().filter(|&&(i, j)| parts[i] != parts[j]).count(),
+        parts: (0..n).map(|i| (vertices[i], parts[i])).collect(),
+    }
+}
+
+/// Splits the vertices into two halves, the first taking the odd one out, by sorting them along
+/// the Fiedler vector found with `iterations` steps of power iteration and cutting at the
+/// median. Edge direction is ignored and `weight` is as for `fiedler_vector`.
+pub fn spectral_bisection<'a, G, F>(graph: &'a G, weight: F, iterations: usize) -> Bisection
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    F: Fn(&EdgeDescriptor, &G) -> f64,
+{
+    let fiedler = fiedler_vector(graph, weight, iterations);
+    let mut vertices = graph.vertices().collect::<Vec<_>>();
+    vertices.sort_by(|a, b| fiedler[a].partial_cmp(&fiedler[b]).unwrap().then(a.cmp(b)));
+    let second = vertices.split_off(vertices.len().div_ceil(2));
+    let first = vertices;
+
+    let side = first
+        .iter()
+        .map(|&v| (v, false))
+        .chain(second.iter().map(|&v| (v, true)))
+        .collect::<FnvHashMap<_, _>>();
+    let mut cut_edges = Vec::new();
+    for (&v, &s) in &side {
+        for e in graph.out_edges(v) {
+            if graph.opposite(e, v).and_then(|t| side.get(&t)).is_some_and(|&t| t != s) {
+                cut_edges.push(e);
+            }
+        }
+    }
+    cut_edges.sort();
+    Bisection {
+        halves: (first, second),
+        cut_edges,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{partition, spectral_bisection};
+
+    #[test]
+    fn balanced_partitions() {
+        use graph::{Directed, MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        // two cliques of four joined by a single edge
+
+        let mut g = IncidenceList::<Undirected, (), ()>::new();
+        assert!(partition(&g, 2).parts().is_empty());
+        let empty = spectral_bisection(&g, |_, _| 1.0, 10);
+        assert_eq!(empty.halves(), (&[][..], &[][..]));
+        let vs = (0..8).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for i in 0..8 {
+            for j in 0..i {
+                if i / 4 == j / 4 {
+                    g.add_edge(vs[i], vs[j], ()).unwrap();
+                }
+            }
+        }
+        g.add_edge(vs[3], vs[4], ()).unwrap();
+
+        let halves = partition(&g, 2);
+        assert_eq!(halves.cut_size(), 1);
+        for i in 0..8 {
+            assert_eq!(halves.part(vs[i]) == halves.part(vs[0]), i < 4);
+        }
+        // one part cuts nothing, and a part for every vertex cuts all 13 edges
+        assert_eq!(partition(&g, 1).cut_size(), 0);
+        let singletons = partition(&g, 8);
+        assert_eq!(singletons.cut_size(), 13);
+        let mut parts = singletons.parts().values().cloned().collect::<Vec<_>>();
+        parts.sort();
+        assert_eq!(parts, (0..8).collect::<Vec<_>>());
+
+        // a 12 by 12 grid in four parts; the best cut is 24 edges
+        let mut grid = IncidenceList::<Undirected, (), ()>::new();
+        let cells = (0..144).map(|_| grid.add_vertex(())).collect::<Vec<_>>();
+        for y in 0..12 {
+            for x in 0..12 {
+                if x + 1 < 12 {
+                    grid.add_edge(cells[y * 12 + x], cells[y * 12 + x + 1], ()).unwrap();
+                }
+                if y + 1 < 12 {
+                    grid.add_edge(cells[y * 12 + x], cells[y * 12 + x + 12], ()).unwrap();
+                }
+            }
+        }
+        let quarters = partition(&grid, 4);
+        let mut sizes = vec![0; 4];
+        for &p in quarters.parts().values() {
+            sizes[p] += 1;
+        }
+        assert_eq!(sizes, vec![36; 4]);
+        assert!(quarters.cut_size() >= 24 && quarters.cut_size() <= 40);
+
+        let bisection = spectral_bisection(&g, |_, _| 1.0, 500);
+        let (first, second) = bisection.halves();
+        assert_eq!((first.len(), second.len()), (4, 4));
+        let mut first = first.to_vec();
+        first.sort();
+        assert!(first == vs[..4].to_vec() || first == vs[4..].to_vec());
+        assert_eq!(bisection.cut_edges().len(), 1);
+
+        // the odd one out goes to the first half
+        g.add_vertex(());
+        let bisection = spectral_bisection(&g, |_, _| 1.0, 500);
+        assert_eq!((bisection.halves().0.len(), bisection.halves().1.len()), (5, 4));
+
+        // v0 --> v1    v3 --> v4
+        //  ^     |      ^     |
+        //  |     v      |     v
+        //  +---- v2     +---- v5
+        //
+        // edge direction is ignored, so the two triangles are split apart
+
+        let mut d = IncidenceList::<Directed, (), ()>::new();
+        let ws = (0..6).map(|_| d.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t) in &[(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3)] {
+            d.add_edge(ws[s], ws[t], ()).unwrap();
+        }
+        let halves = partition(&d, 2);
+        assert_eq!(halves.cut_size(), 0);
+        for i in 0..6 {
+            assert_eq!(halves.part(ws[i]) == halves.part(ws[0]), i < 3);
+        }
+        assert!(spectral_bisection(&d, |_, _| 1.0, 100).cut_edges().is_empty());
+    }
+}
@@ -1,13 +1,40 @@
-use fnv::FnvHashMap;
-
 use graph::VertexDescriptor;
+use property_map::PropertyMap;
+
+/// A path found by a search, along with its cost.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Path<C> {
+    vertices: Vec<VertexDescriptor>,
+    cost: C,
+}
+
+impl<C> Path<C>
+where
+    C: Copy,
+{
+    pub(crate) fn new(vertices: Vec<VertexDescriptor>, cost: C) -> Self {
+        Path { vertices, cost }
+    }
+
+    pub fn vertices(&self) -> &[VertexDescriptor] {
+        &self.vertices
+    }
+
+    pub fn cost(&self) -> C {
+        self.cost
+    }
+
+    pub fn into_vertices(self) -> Vec<VertexDescriptor> {
+        self.vertices
+    }
+}
 
-pub fn reverse_path(
-    parents: &FnvHashMap<VertexDescriptor, VertexDescriptor>,
-    goal: VertexDescriptor,
-) -> Vec<VertexDescriptor> {
+pub fn reverse_path<P>(parents: &P, goal: VertexDescriptor) -> Vec<VertexDescriptor>
+where
+    P: PropertyMap<VertexDescriptor, VertexDescriptor>,
+{
     let mut path = vec![goal];
-    while let Some(parent) = parents.get(path.last().unwrap()) {
+    while let Some(parent) = parents.get(*path.last().unwrap()) {
         path.push(*parent);
     }
     path.reverse();
@@ -0,0 +1,89 @@
+use bipartite::kuhn_matching;
+use error::GraphError;
+use graph::{IncidenceGraph, VertexDescriptor, VertexListGraph};
+use property_map::{vertex_index, PropertyMap};
+use topological_sort::topological_sort;
+
+/// Covers a DAG with as few vertex-disjoint paths as possible, each path following edges and
+/// every vertex on exactly one path. Splitting each vertex into a left and a right copy with
+/// an edge from u to v joining left u to right v, a maximum matching links the paths
+/// together and leaves n minus its size of them. Paths are listed in topological order of
+/// their first vertices. Fails with `GraphError::Cyclic` on a cyclic graph.
+pub fn minimum_path_cover<'a, G>(graph: &'a G) -> Result<Vec<Vec<VertexDescriptor>>, GraphError>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+{
+    let order = topological_sort(graph)?;
+    let index = vertex_index(graph, &order);
+    let n = order.len();
+    let mut successors = vec![Vec::new(); n];
+    for (i, &v) in order.iter().enumerate() {
+        for e in graph.out_edges(v) {
+            if let Some(&j) = graph.opposite(e, v).and_then(|t| index.get(t)) {
+                successors[i].push(j);
+            }
+        }
+        successors[i].sort();
+        successors[i].dedup();
+    }
+
+    let (next, previous) = kuhn_matching(&successors, n);
+
+    Ok((0..n)
+        .filter(|&i| previous[i].is_none())
+        .map(|i| {
+            let mut path = vec![order[i]];
+            let mut i = i;
+            while let Some(j) = next[i] {
+                path.push(order[j]);
+                i = j;
+            }
+            path
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::minimum_path_cover;
+
+    #[test]
+    fn path_cover() {
+        use error::GraphError;
+        use graph::{Directed, MutableGraph};
+        use incidence_list::IncidenceList;
+
+        // v0 --> v1 --> v2
+        //  |      |
+        //  v      v
+        // v3 --> v4 --> v5
+        //         ^
+        //         |
+        //        v6
+
+        let mut g = IncidenceList::<Directed, (), ()>::new();
+        let vs = (0..7).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t) in &[(0, 1), (1, 2), (0, 3), (1, 4), (3, 4), (4, 5), (6, 4)] {
+            g.add_edge(vs[s], vs[t], ()).unwrap();
+        }
+
+        let paths = minimum_path_cover(&g).unwrap();
+        assert_eq!(paths.len(), 3);
+        let mut covered = paths.iter().flat_map(|p| p.iter().cloned()).collect::<Vec<_>>();
+        covered.sort();
+        assert_eq!(covered, vs);
+        for path in &paths {
+            for pair in path.windows(2) {
+                let i = vs.iter().position(|&v| v == pair[0]).unwrap();
+                let j = vs.iter().position(|&v| v == pair[1]).unwrap();
+                assert!([(0, 1), (1, 2), (0, 3), (1, 4), (3, 4), (4, 5), (6, 4)].contains(&(i, j)));
+            }
+        }
+
+        g.add_edge(vs[5], vs[0], ()).unwrap();
+        match minimum_path_cover(&g) {
+            Err(GraphError::Cyclic(_)) => {}
+            result => panic!("unexpected {:?}", result),
+        }
+    }
+}
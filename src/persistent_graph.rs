@@ -0,0 +1,461 @@
+use std::iter::Cloned;
+use std::marker::PhantomData;
+use std::ops::Index;
+use std::slice;
+use std::sync::Arc;
+
+use error::GraphError;
+use graph::{AdjacencyGraph, AdjacencyMatrixGraph, BidirectionalGraph, Direction, Directivity,
+            EdgeDescriptor, EdgeListGraph, Generation, Graph, IncidenceGraph, MutableGraph,
+            VertexDescriptor, VertexListGraph};
+
+/// Slots per chunk: a write after a snapshot copies the chunk it lands in and the list of
+/// chunks, and nothing else.
+const CHUNK_SIZE: usize = 64;
+
+// Slots that are never reused, in chunks shared between snapshots until written to.
+struct Chunks<T> {
+    chunks: Arc<Vec<Arc<Vec<Option<T>>>>>,
+    slots: usize,
+    len: usize,
+}
+
+impl<T> Clone for Chunks<T> {
+    fn clone(&self) -> Self {
+        Chunks {
+            chunks: Arc::clone(&self.chunks),
+            slots: self.slots,
+            len: self.len,
+        }
+    }
+}
+
+impl<T> Chunks<T> {
+    fn new() -> Self {
+        Chunks {
+            chunks: Arc::new(Vec::new()),
+            slots: 0,
+            len: 0,
+        }
+    }
+
+    fn get(&self, k: usize) -> Option<&T> {
+        self.chunks
+            .get(k / CHUNK_SIZE)
+            .and_then(|chunk| chunk[k % CHUNK_SIZE].as_ref())
+    }
+
+    fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = (usize, &'a T)> + 'a> {
+        Box::new(self.chunks.iter().enumerate().flat_map(|(c, chunk)| {
+            chunk
+                .iter()
+                .enumerate()
+                .filter_map(move |(i, slot)| slot.as_ref().map(|t| (c * CHUNK_SIZE + i, t)))
+        }))
+    }
+}
+
+impl<T> Chunks<T>
+where
+    T: Clone,
+{
+    fn slot_mut(&mut self, k: usize) -> Option<&mut Option<T>> {
+        if k >= self.slots {
+            return None;
+        }
+        let chunk = Arc::make_mut(&mut self.chunks).get_mut(k / CHUNK_SIZE)?;
+        Arc::make_mut(chunk).get_mut(k % CHUNK_SIZE)
+    }
+
+    fn get_mut(&mut self, k: usize) -> Option<&mut T> {
+        self.slot_mut(k).and_then(|slot| slot.as_mut())
+    }
+
+    fn push(&mut self, t: T) -> usize {
+        let k = self.slots;
+        let chunks = Arc::make_mut(&mut self.chunks);
+        if k.is_multiple_of(CHUNK_SIZE) {
+            chunks.push(Arc::new(Vec::with_capacity(CHUNK_SIZE)));
+        }
+        Arc::make_mut(chunks.last_mut().unwrap()).push(Some(t));
+        self.slots += 1;
+        self.len += 1;
+        k
+    }
+
+    fn take(&mut self, k: usize) -> Option<T> {
+        let t = self.slot_mut(k)?.take();
+        if t.is_some() {
+            self.len -= 1;
+        }
+        t
+    }
+}
+
+#[derive(Clone)]
+struct Vertex<VP> {
+    property: VP,
+    in_edges: Arc<Vec<EdgeDescriptor>>,
+    out_edges: Arc<Vec<EdgeDescriptor>>,
+}
+
+#[derive(Clone)]
+struct Edge<EP> {
+    source: VertexDescriptor,
+    target: VertexDescriptor,
+    property: EP,
+}
+
+/// A graph whose copies share their structure, so that `snapshot` takes constant time and a
+/// snapshot is unaffected by later changes to the graph it was taken from, or the other way
+/// round. Vertices and edges are kept in chunks, and the first change to a chunk after a
+/// snapshot copies it. Descriptors are never reused, so the slots of removed vertices and
+/// edges are not reclaimed. Like `IncidenceList`, an undirected edge is listed among the
+/// out-edges of its source and the in-edges of its target.
+pub struct PersistentGraph<D, VP, EP> {
+    vertices: Chunks<Vertex<VP>>,
+    edges: Chunks<Edge<EP>>,
+    phantom: PhantomData<D>,
+}
+
+impl<D, VP, EP> Clone for PersistentGraph<D, VP, EP> {
+    fn clone(&self) -> Self {
+        PersistentGraph {
+            vertices: self.vertices.clone(),
+            edges: self.edges.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<D, VP, EP> PersistentGraph<D, VP, EP> {
+    pub fn new() -> Self {
+        PersistentGraph {
+            vertices: Chunks::new(),
+            edges: Chunks::new(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns a view of the graph as it is now, sharing its storage.
+    pub fn snapshot(&self) -> Self {
+        self.clone()
+    }
+
+    fn vertex(&self, d: VertexDescriptor) -> Option<&Vertex<VP>> {
+        self.vertices.get(d.into())
+    }
+
+    fn edge_entry(&self, d: EdgeDescriptor) -> Option<&Edge<EP>> {
+        self.edges.get(d.into())
+    }
+
+    fn incidences(&self, d: VertexDescriptor, direction: Direction) -> &[EdgeDescriptor] {
+        self.vertex(d).map_or(&[], |v| match direction {
+            Direction::Outgoing => &v.out_edges[..],
+            Direction::Incoming => &v.in_edges[..],
+        })
+    }
+}
+
+impl<D, VP, EP> Default for PersistentGraph<D, VP, EP> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn vertex_descriptor(k: usize) -> VertexDescriptor {
+    VertexDescriptor::with_generation(k, Generation::default())
+}
+
+fn edge_descriptor(k: usize) -> EdgeDescriptor {
+    EdgeDescriptor::with_generation(k, Generation::default())
+}
+
+impl<D, VP, EP> Graph for PersistentGraph<D, VP, EP> {
+    type Directivity = D;
+    type VertexProperty = VP;
+    type EdgeProperty = EP;
+
+    fn vertex_property(&self, d: VertexDescriptor) -> Option<&Self::VertexProperty> {
+        self.vertex(d).map(|v| &v.property)
+    }
+
+    fn edge_property(&self, d: EdgeDescriptor) -> Option<&Self::EdgeProperty> {
+        self.edge_entry(d).map(|e| &e.property)
+    }
+}
+
+impl<D, VP, EP> Index<VertexDescriptor> for PersistentGraph<D, VP, EP> {
+    type Output = VP;
+
+    fn index(&self, d: VertexDescriptor) -> &Self::Output {
+        self.vertex_property(d).unwrap()
+    }
+}
+
+impl<D, VP, EP> Index<EdgeDescriptor> for PersistentGraph<D, VP, EP> {
+    type Output = EP;
+
+    fn index(&self, d: EdgeDescriptor) -> &Self::Output {
+        self.edge_property(d).unwrap()
+    }
+}
+
+impl<'a, D, VP, EP> IncidenceGraph<'a> for PersistentGraph<D, VP, EP> {
+    type Incidences = Cloned<slice::Iter<'a, EdgeDescriptor>>;
+
+    fn out_degree(&self, d: VertexDescriptor) -> usize {
+        self.incidences(d, Direction::Outgoing).len()
+    }
+
+    fn out_edges(&'a self, d: VertexDescriptor) -> Self::Incidences {
+        self.incidences(d, Direction::Outgoing).iter().cloned()
+    }
+
+    fn source(&self, d: EdgeDescriptor) -> VertexDescriptor {
+        self.edge_entry(d).unwrap().source
+    }
+
+    fn target(&self, d: EdgeDescriptor) -> VertexDescriptor {
+        self.edge_entry(d).unwrap().target
+    }
+
+    fn edge_endpoints(&self, d: EdgeDescriptor) -> Option<(VertexDescriptor, VertexDescriptor)> {
+        self.edge_entry(d).map(|e| (e.source, e.target))
+    }
+}
+
+impl<'a, D, VP, EP> BidirectionalGraph<'a> for PersistentGraph<D, VP, EP>
+where
+    D: 'a,
+    VP: 'a,
+    EP: 'a,
+{
+    type Neighbors = Box<dyn Iterator<Item = VertexDescriptor> + 'a>;
+
+    fn degree(&self, d: VertexDescriptor) -> usize {
+        self.out_degree(d) + self.in_degree(d)
+    }
+
+    fn in_degree(&self, d: VertexDescriptor) -> usize {
+        self.incidences(d, Direction::Incoming).len()
+    }
+
+    fn in_edges(&'a self, d: VertexDescriptor) -> Self::Incidences {
+        self.incidences(d, Direction::Incoming).iter().cloned()
+    }
+
+    fn neighbors_directed(&'a self, d: VertexDescriptor, direction: Direction) -> Self::Neighbors {
+        Box::new(self.incidences(d, direction).iter().map(move |&e| {
+            let edge = self.edge_entry(e).unwrap();
+            match direction {
+                Direction::Outgoing => edge.target,
+                Direction::Incoming => edge.source,
+            }
+        }))
+    }
+}
+
+impl<'a, D, VP, EP> AdjacencyGraph<'a> for PersistentGraph<D, VP, EP>
+where
+    D: Directivity + 'a,
+    VP: 'a,
+    EP: 'a,
+{
+    type Adjacencies = Box<dyn Iterator<Item = VertexDescriptor> + 'a>;
+
+    fn adjacent_vertices(&'a self, d: VertexDescriptor) -> Self::Adjacencies {
+        let successors = self.neighbors_directed(d, Direction::Outgoing);
+        if D::is_directed() {
+            successors
+        } else {
+            Box::new(successors.chain(self.neighbors_directed(d, Direction::Incoming)))
+        }
+    }
+}
+
+impl<'a, D, VP, EP> VertexListGraph<'a> for PersistentGraph<D, VP, EP>
+where
+    VP: 'a,
+{
+    type Vertices = Box<dyn Iterator<Item = VertexDescriptor> + 'a>;
+
+    fn order(&self) -> usize {
+        self.vertices.len
+    }
+
+    fn vertex_bound(&self) -> usize {
+        self.vertices.slots
+    }
+
+    fn vertices(&'a self) -> Self::Vertices {
+        Box::new(self.vertices.iter().map(|(k, _)| vertex_descriptor(k)))
+    }
+}
+
+impl<'a, D, VP, EP> EdgeListGraph<'a> for PersistentGraph<D, VP, EP>
+where
+    EP: 'a,
+{
+    type Edges = Box<dyn Iterator<Item = EdgeDescriptor> + 'a>;
+
+    fn size(&self) -> usize {
+        self.edges.len
+    }
+
+    fn edge_bound(&self) -> usize {
+        self.edges.slots
+    }
+
+    fn edges(&'a self) -> Self::Edges {
+        Box::new(self.edges.iter().map(|(k, _)| edge_descriptor(k)))
+    }
+}
+
+impl<D, VP, EP> AdjacencyMatrixGraph for PersistentGraph<D, VP, EP>
+where
+    D: Directivity,
+{
+    fn edge(&self, source: VertexDescriptor, target: VertexDescriptor) -> Option<EdgeDescriptor> {
+        let forward = self.incidences(source, Direction::Outgoing)
+            .iter()
+            .find(|&&e| self.edge_entry(e).unwrap().target == target);
+        let backward = || {
+            self.incidences(target, Direction::Outgoing)
+                .iter()
+                .find(|&&e| self.edge_entry(e).unwrap().target == source)
+        };
+        match forward {
+            Some(&e) => Some(e),
+            None if !D::is_directed() => backward().cloned(),
+            None => None,
+        }
+    }
+}
+
+impl<D, VP, EP> MutableGraph for PersistentGraph<D, VP, EP>
+where
+    VP: Clone,
+    EP: Clone,
+{
+    fn add_vertex(&mut self, property: Self::VertexProperty) -> VertexDescriptor {
+        vertex_descriptor(self.vertices.push(Vertex {
+            property,
+            in_edges: Arc::new(Vec::new()),
+            out_edges: Arc::new(Vec::new()),
+        }))
+    }
+
+    fn add_edge(
+        &mut self,
+        source: VertexDescriptor,
+        target: VertexDescriptor,
+        property: Self::EdgeProperty,
+    ) -> Result<EdgeDescriptor, GraphError> {
+        for &v in &[source, target] {
+            if !self.contains_vertex(v) {
+                return Err(GraphError::VertexNotFound(v));
+            }
+        }
+        let e = edge_descriptor(self.edges.push(Edge {
+            source,
+            target,
+            property,
+        }));
+        let s = self.vertices.get_mut(source.into()).unwrap();
+        Arc::make_mut(&mut s.out_edges).push(e);
+        let t = self.vertices.get_mut(target.into()).unwrap();
+        Arc::make_mut(&mut t.in_edges).push(e);
+        Ok(e)
+    }
+
+    fn remove_vertex(&mut self, d: VertexDescriptor) -> Option<Self::VertexProperty> {
+        let mut eds = self.out_edges(d).chain(self.in_edges(d)).collect::<Vec<_>>();
+        eds.sort();
+        eds.dedup();
+        for ed in eds {
+            self.remove_edge(ed)?;
+        }
+        self.vertices.take(d.into()).map(|v| v.property)
+    }
+
+    fn remove_edge(&mut self, d: EdgeDescriptor) -> Option<Self::EdgeProperty> {
+        let edge = self.edges.take(d.into())?;
+        let s = self.vertices.get_mut(edge.source.into()).unwrap();
+        Arc::make_mut(&mut s.out_edges).retain(|&e| e != d);
+        let t = self.vertices.get_mut(edge.target.into()).unwrap();
+        Arc::make_mut(&mut t.in_edges).retain(|&e| e != d);
+        Some(edge.property)
+    }
+
+    fn retain_vertices<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&Self, VertexDescriptor) -> bool,
+    {
+        let removed = self.vertices()
+            .filter(|&v| !f(self, v))
+            .collect::<Vec<_>>();
+        for v in removed {
+            self.remove_vertex(v);
+        }
+    }
+
+    fn retain_edges<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&Self, EdgeDescriptor) -> bool,
+    {
+        let removed = self.edges().filter(|&e| !f(self, e)).collect::<Vec<_>>();
+        for e in removed {
+            self.remove_edge(e);
+        }
+    }
+
+    fn vertex_property_mut(&mut self, d: VertexDescriptor) -> Option<&mut Self::VertexProperty> {
+        self.vertices.get_mut(d.into()).map(|v| &mut v.property)
+    }
+
+    fn edge_property_mut(&mut self, d: EdgeDescriptor) -> Option<&mut Self::EdgeProperty> {
+        self.edges.get_mut(d.into()).map(|e| &mut e.property)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PersistentGraph;
+
+    #[test]
+    fn snapshots() {
+        use std::thread;
+        use graph::{AdjacencyMatrixGraph, BidirectionalGraph, Directed, EdgeListGraph, Graph,
+                    IncidenceGraph, MutableGraph, VertexListGraph};
+
+        let mut g = PersistentGraph::<Directed, _, _>::new();
+        let vs = (0..200).map(|i| g.add_vertex(i)).collect::<Vec<_>>();
+        for i in 1..200 {
+            g.add_edge(vs[i - 1], vs[i], i).unwrap();
+        }
+
+        let before = g.snapshot();
+        let e = g.edge(vs[99], vs[100]).unwrap();
+        g.remove_vertex(vs[0]).unwrap();
+        g.remove_edge(e).unwrap();
+        *g.vertex_property_mut(vs[150]).unwrap() = 0;
+        let v = g.add_vertex(200);
+        g.add_edge(vs[199], v, 200).unwrap();
+
+        let reader = thread::spawn(move || {
+            let sum = before.vertices().map(|v| before[v]).sum::<usize>();
+            let first = before.vertices().next().unwrap();
+            (before.order(), before.size(), sum, before.out_degree(first))
+        });
+        assert_eq!(reader.join().unwrap(), (200, 199, 199 * 100, 1));
+
+        assert_eq!((g.order(), g.size()), (200, 198));
+        assert!(!g.contains_vertex(vs[0]) && !g.contains_edge(e));
+        assert_eq!(g.vertex_property(vs[150]), Some(&0));
+        assert_eq!(g.in_degree(vs[100]), 0);
+        assert_eq!(g.neighbors_directed(vs[199], ::graph::Direction::Outgoing).next(), Some(v));
+        assert_eq!(g.edges().count(), 198);
+    }
+}
@@ -0,0 +1,258 @@
+use std::marker::PhantomData;
+use std::mem;
+
+use fnv::FnvHashSet;
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+use graph::{Directivity, EdgeListGraph, IncidenceGraph, MutableGraph, VertexDescriptor,
+            VertexListGraph};
+use incidence_list::IncidenceList;
+
+// Tries per change before giving up, for changes that may be impossible.
+const TRIES: usize = 100;
+
+fn count(fraction: f64, size: usize) -> usize {
+    assert!(fraction >= 0.0, "fraction must not be negative");
+    (fraction * size as f64).round() as usize
+}
+
+// The vertex pairs joined by an edge, unordered if the graph is undirected.
+struct Adjacency<D> {
+    pairs: FnvHashSet<(VertexDescriptor, VertexDescriptor)>,
+    directivity: PhantomData<D>,
+}
+
+impl<D> Adjacency<D>
+where
+    D: Directivity,
+{
+    fn new<VP, EP>(graph: &IncidenceList<D, VP, EP>) -> Self {
+        let mut adjacency = Adjacency {
+            pairs: FnvHashSet::default(),
+            directivity: PhantomData,
+        };
+        for e in graph.edges() {
+            let (s, t) = graph.edge_endpoints(e).unwrap();
+            adjacency.insert(s, t);
+        }
+        adjacency
+    }
+
+    fn key(s: VertexDescriptor, t: VertexDescriptor) -> (VertexDescriptor, VertexDescriptor) {
+        if D::is_directed() || s <= t {
+            (s, t)
+        } else {
+            (t, s)
+        }
+    }
+
+    fn contains(&self, s: VertexDescriptor, t: VertexDescriptor) -> bool {
+        self.pairs.contains(&Self::key(s, t))
+    }
+
+    fn insert(&mut self, s: VertexDescriptor, t: VertexDescriptor) {
+        self.pairs.insert(Self::key(s, t));
+    }
+
+    fn remove(&mut self, s: VertexDescriptor, t: VertexDescriptor) {
+        self.pairs.remove(&Self::key(s, t));
+    }
+}
+
+/// Removes `fraction` of the edges, chosen uniformly at random. Returns the number removed.
+pub fn remove_random_edges<D, VP, EP, R>(
+    graph: &mut IncidenceList<D, VP, EP>,
+    fraction: f64,
+    rng: &mut R,
+) -> usize
+where
+    R: Rng,
+{
+    let mut edges = graph.edges().collect::<Vec<_>>();
+    let n = count(fraction, edges.len()).min(edges.len());
+    edges.shuffle(rng);
+    for &e in &edges[..n] {
+        graph.remove_edge(e);
+    }
+    n
+}
+
+/// Adds `fraction` times as many edges as there are, between distinct vertices chosen at
+/// random that are not yet adjacent, with properties drawn by `edge_property`. Returns the
+/// number added, which falls short only when the graph is close to complete.
+pub fn add_random_edges<D, VP, EP, R, F>(
+    graph: &mut IncidenceList<D, VP, EP>,
+    fraction: f64,
+    rng: &mut R,
+    mut edge_property: F,
+) -> usize
+where
+    D: Directivity,
+    R: Rng,
+    F: FnMut(&mut R) -> EP,
+{
+    let vertices = graph.vertices().collect::<Vec<_>>();
+    let n = count(fraction, graph.size());
+    if vertices.len() < 2 {
+        return 0;
+    }
+    let mut adjacency = Adjacency::new(graph);
+    let mut added = 0;
+    for _ in 0..n * TRIES {
+        if added == n {
+            break;
+        }
+        let (s, t) = (*vertices.choose(rng).unwrap(), *vertices.choose(rng).unwrap());
+        if s == t || adjacency.contains(s, t) {
+            continue;
+        }
+        let property = edge_property(rng);
+        graph.add_edge(s, t, property).unwrap();
+        adjacency.insert(s, t);
+        added += 1;
+    }
+    added
+}
+
+/// Moves the target of `fraction` of the edges, chosen uniformly at random, to a random vertex,
+/// keeping their sources and properties and creating no self-loops or parallel edges, so that
+/// out-degrees are preserved. Returns the number of edges rewired.
+pub fn rewire_random_edges<D, VP, EP, R>(
+    graph: &mut IncidenceList<D, VP, EP>,
+    fraction: f64,
+    rng: &mut R,
+) -> usize
+where
+    D: Directivity,
+    R: Rng,
+{
+    let vertices = graph.vertices().collect::<Vec<_>>();
+    let mut edges = graph.edges().collect::<Vec<_>>();
+    let n = count(fraction, edges.len()).min(edges.len());
+    edges.shuffle(rng);
+    let mut adjacency = Adjacency::new(graph);
+    let mut rewired = 0;
+    for &e in &edges[..n] {
+        let (s, t) = graph.edge_endpoints(e).unwrap();
+        let target = (0..TRIES)
+            .map(|_| *vertices.choose(rng).unwrap())
+            .find(|&w| w != s && w != t && !adjacency.contains(s, w));
+        if let Some(w) = target {
+            let property = graph.remove_edge(e).unwrap();
+            graph.add_edge(s, w, property).unwrap();
+            adjacency.remove(s, t);
+            adjacency.insert(s, w);
+            rewired += 1;
+        }
+    }
+    rewired
+}
+
+/// Makes `fraction` times as many double-edge swaps as there are edges: two edges `(a, b)` and
+/// `(c, d)` chosen at random are replaced by `(a, d)` and `(c, b)`, which keep their
+/// properties, so that the degree of every vertex is preserved, and the in- and out-degrees in
+/// a directed graph. A swap that would create a self-loop or parallel edges is drawn again.
+/// Repeated swaps randomize a graph while preserving its degree sequence, as a null model.
+/// Returns the number of swaps made, which falls short if too many draws fail.
+pub fn double_edge_swap<D, VP, EP, R>(
+    graph: &mut IncidenceList<D, VP, EP>,
+    fraction: f64,
+    rng: &mut R,
+) -> usize
+where
+    D: Directivity,
+    R: Rng,
+{
+    let mut edges = graph.edges().collect::<Vec<_>>();
+    let n = count(fraction, edges.len());
+    if edges.len() < 2 {
+        return 0;
+    }
+    let mut adjacency = Adjacency::new(graph);
+    let mut swapped = 0;
+    for _ in 0..n * TRIES {
+        if swapped == n {
+            break;
+        }
+        let (i, j) = (rng.gen_range(0..edges.len()), rng.gen_range(0..edges.len()));
+        if i == j {
+            continue;
+        }
+        let (a, b) = graph.edge_endpoints(edges[i]).unwrap();
+        let (mut c, mut d) = graph.edge_endpoints(edges[j]).unwrap();
+        // an undirected edge can be swapped either way round
+        if !D::is_directed() && rng.gen_bool(0.5) {
+            mem::swap(&mut c, &mut d);
+        }
+        if a == c || b == d || a == d || c == b || adjacency.contains(a, d) ||
+            adjacency.contains(c, b)
+        {
+            continue;
+        }
+        let p = graph.remove_edge(edges[i]).unwrap();
+        let q = graph.remove_edge(edges[j]).unwrap();
+        edges[i] = graph.add_edge(a, d, p).unwrap();
+        edges[j] = graph.add_edge(c, b, q).unwrap();
+        adjacency.remove(a, b);
+        adjacency.remove(c, d);
+        adjacency.insert(a, d);
+        adjacency.insert(c, b);
+        swapped += 1;
+    }
+    swapped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{add_random_edges, double_edge_swap, remove_random_edges, rewire_random_edges};
+
+    #[test]
+    fn perturbations() {
+        use rand::SeedableRng;
+        use rand::rngs::StdRng;
+        use generator::gnm_random_graph;
+        use graph::{BidirectionalGraph, Directed, EdgeListGraph, IncidenceGraph, Undirected,
+                    VertexListGraph};
+        use incidence_list::IncidenceList;
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut g: IncidenceList<Directed, (), u32> =
+            gnm_random_graph(40, 120, &mut rng, |_| (), |_| 1);
+        let degrees = |g: &IncidenceList<Directed, (), u32>| {
+            g.vertices()
+                .map(|v| (g.out_degree(v), g.in_degree(v)))
+                .collect::<Vec<_>>()
+        };
+        let simple = |g: &IncidenceList<Directed, (), u32>| {
+            let mut pairs = g.edges()
+                .map(|e| g.edge_endpoints(e).unwrap())
+                .collect::<Vec<_>>();
+            pairs.sort();
+            pairs.dedup();
+            pairs.len() == g.size() && pairs.iter().all(|&(s, t)| s != t)
+        };
+
+        let before = degrees(&g);
+        assert_eq!(double_edge_swap(&mut g, 2.0, &mut rng), 240);
+        assert_eq!(degrees(&g), before);
+        assert!(simple(&g));
+
+        let out_degrees = before.iter().map(|d| d.0).collect::<Vec<_>>();
+        assert_eq!(rewire_random_edges(&mut g, 0.5, &mut rng), 60);
+        assert_eq!(degrees(&g).iter().map(|d| d.0).collect::<Vec<_>>(), out_degrees);
+        assert!(simple(&g));
+
+        assert_eq!(remove_random_edges(&mut g, 0.25, &mut rng), 30);
+        assert_eq!(g.size(), 90);
+        assert_eq!(add_random_edges(&mut g, 1.0, &mut rng, |_| 2), 90);
+        assert_eq!((g.size(), g.edges().filter(|&e| g[e] == 2).count()), (180, 90));
+        assert!(simple(&g));
+
+        let mut u: IncidenceList<Undirected, (), ()> =
+            gnm_random_graph(30, 60, &mut rng, |_| (), |_| ());
+        let before = u.vertices().map(|v| u.degree(v)).collect::<Vec<_>>();
+        assert_eq!(double_edge_swap(&mut u, 1.0, &mut rng), 60);
+        assert_eq!(u.vertices().map(|v| u.degree(v)).collect::<Vec<_>>(), before);
+    }
+}
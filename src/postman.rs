@@ -0,0 +1,347 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt::Debug;
+use std::ops::Sub;
+
+use num_traits::Zero;
+
+use graph::{Directivity, EdgeDescriptor, IncidenceGraph, VertexDescriptor, VertexListGraph};
+use property_map::{vertex_index, PropertyMap};
+use tsp::{eulerian_circuit, minimum_perfect_matching};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PostmanTour<C> {
+    walk: Vec<VertexDescriptor>,
+    edges: Vec<EdgeDescriptor>,
+    cost: C,
+}
+
+impl<C> PostmanTour<C>
+where
+    C: Copy,
+{
+    /// The vertices in the order visited, the first not repeated at the end.
+    pub fn walk(&self) -> &[VertexDescriptor] {
+        &self.walk
+    }
+
+    /// The edges in the order traversed, the k-th leading from the k-th vertex of the walk to
+    /// the next and the last one back to the first. Edges traversed more than once repeat.
+    pub fn edges(&self) -> &[EdgeDescriptor] {
+        &self.edges
+    }
+
+    pub fn cost(&self) -> C {
+        self.cost
+    }
+}
+
+type ShortestPaths<C> = (Vec<Option<C>>, Vec<Option<(usize, usize)>>);
+
+// Dijkstra where `leaving[u]` lists the (vertex, arc) pairs reachable from u in one step,
+// returning the distance to each vertex and the arc and vertex it is reached through.
+fn shortest_paths<C>(
+    from: usize,
+    leaving: &[Vec<(usize, usize)>],
+    costs: &[C],
+) -> ShortestPaths<C>
+where
+    C: Copy + Debug + Ord + Zero,
+{
+    let mut distance = vec![None; leaving.len()];
+    let mut via = vec![None; leaving.len()];
+    let mut fringe = BinaryHeap::new();
+    distance[from] = Some(C::zero());
+    fringe.push(Reverse((C::zero(), from)));
+    while let Some(Reverse((d, u))) = fringe.pop() {
+        if distance[u].is_some_and(|best| d > best) {
+            continue;
+        }
+        for &(w, k) in &leaving[u] {
+            let next = d + costs[k];
+            if distance[w].is_none_or(|best| next < best) {
+                distance[w] = Some(next);
+                via[w] = Some((k, u));
+                fringe.push(Reverse((next, w)));
+            }
+        }
+    }
+    (distance, via)
+}
+
+// Balances a directed graph at least cost by successive shortest paths: `surplus` lists the
+// vertices entered more often than left and by how much, `deficit` the reverse, and the result
+// says how many extra times to walk from each of the former to each of the latter.
+fn balance<C>(
+    surplus: &[(usize, usize)],
+    deficit: &[(usize, usize)],
+    distance: &[Vec<Option<C>>],
+) -> Option<Vec<Vec<usize>>>
+where
+    C: Copy + Debug + Ord + Zero + Sub<Output = C>,
+{
+    let (s, t) = (surplus.len(), deficit.len());
+    let mut supply = surplus.iter().map(|&(_, n)| n).collect::<Vec<_>>();
+    let mut demand = deficit.iter().map(|&(_, n)| n).collect::<Vec<_>>();
+    let mut flow = vec![vec![0; t]; s];
+    while supply.iter().any(|&n| n > 0) {
+        // nodes 0..s are the surplus vertices and s..s + t the deficit ones
+        let mut reach: Vec<Option<C>> = vec![None; s + t];
+        let mut previous = vec![None; s + t];
+        for a in (0..s).filter(|&a| supply[a] > 0) {
+            reach[a] = Some(C::zero());
+        }
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for a in 0..s {
+                for b in 0..t {
+                    let d = match distance[a][b] {
+                        Some(d) => d,
+                        None => continue,
+                    };
+                    if let Some(r) = reach[a] {
+                        if reach[s + b].is_none_or(|old| r + d < old) {
+                            reach[s + b] = Some(r + d);
+                            previous[s + b] = Some(a);
+                            changed = true;
+                        }
+                    }
+                    if let Some(r) = reach[s + b] {
+                        if flow[a][b] > 0 && reach[a].is_none_or(|old| r - d < old) {
+                            reach[a] = Some(r - d);
+                            previous[a] = Some(s + b);
+                            changed = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        let b = (0..t)
+            .filter(|&b| demand[b] > 0 && reach[s + b].is_some())
+            .min_by_key(|&b| reach[s + b])?;
+        let mut steps = Vec::new();
+        let mut node = s + b;
+        while let Some(p) = previous[node] {
+            steps.push((p, node));
+            node = p;
+        }
+        let mut amount = supply[node].min(demand[b]);
+        for &(p, q) in &steps {
+            if q < s {
+                amount = amount.min(flow[q][p - s]);
+            }
+        }
+        for &(p, q) in &steps {
+            if q < s {
+                flow[q][p - s] -= amount;
+            } else {
+                flow[p][q - s] += amount;
+            }
+        }
+        supply[node] -= amount;
+        demand[b] -= amount;
+    }
+    Some(flow)
+}
+
+/// Solves the Chinese postman problem: finds a cheapest closed walk traversing every edge at
+/// least once, following edge directions in a directed graph. The edges walked twice are
+/// shortest paths between vertices of odd degree paired by a minimum weight perfect matching,
+/// or in a directed graph between vertices entered more often than left and the reverse,
+/// paired by a minimum cost flow; the walk is then an Eulerian circuit. Costs must not be
+/// negative. With more than twenty odd-degree vertices the matching is built greedily and the
+/// walk may not be the cheapest. Returns `None` if no such walk exists because the edges are
+/// not connected, or not strongly connected in a directed graph.
+pub fn chinese_postman<'a, G, C, F>(graph: &'a G, edge_cost: F) -> Option<PostmanTour<C>>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+    C: Copy + Debug + Ord + Zero + Sub<Output = C>,
+    F: Fn(&EdgeDescriptor, &G) -> C,
+{
+    let directed = G::Directivity::is_directed();
+    let vertices = graph.vertices().collect::<Vec<_>>();
+    let index = vertex_index(graph, &vertices);
+    let n = vertices.len();
+
+    let mut arcs = Vec::new();
+    let mut costs = Vec::new();
+    let mut leaving = vec![Vec::new(); n];
+    let mut in_degree = vec![0; n];
+    let mut out_degree = vec![0; n];
+    for (i, &v) in vertices.iter().enumerate() {
+        for e in graph.out_edges(v) {
+            if let Some(&j) = graph.opposite(e, v).and_then(|t| index.get(t)) {
+                leaving[i].push((j, arcs.len()));
+                if !directed {
+                    leaving[j].push((i, arcs.len()));
+                }
+                out_degree[i] += 1;
+                in_degree[j] += 1;
+                arcs.push((e, i, j));
+                costs.push(edge_cost(&e, graph));
+            }
+        }
+    }
+    let start = match arcs.first() {
+        Some(&(_, i, _)) => i,
+        None => {
+            return Some(PostmanTour {
+                walk: Vec::new(),
+                edges: Vec::new(),
+                cost: C::zero(),
+            })
+        }
+    };
+
+    // pairs of vertices to add a shortest path between, as often as they are listed
+    let mut extra = Vec::new();
+    if directed {
+        let surplus = (0..n)
+            .filter(|&i| in_degree[i] > out_degree[i])
+            .map(|i| (i, in_degree[i] - out_degree[i]))
+            .collect::<Vec<_>>();
+        let deficit = (0..n)
+            .filter(|&i| out_degree[i] > in_degree[i])
+            .map(|i| (i, out_degree[i] - in_degree[i]))
+            .collect::<Vec<_>>();
+        let distance = surplus
+            .iter()
+            .map(|&(i, _)| {
+                let reach = shortest_paths(i, &leaving, &costs).0;
+                deficit.iter().map(|&(j, _)| reach[j]).collect()
+            })
+            .collect::<Vec<Vec<_>>>();
+        let flow = balance(&surplus, &deficit, &distance)?;
+        for (a, &(i, _)) in surplus.iter().enumerate() {
+            for (b, &(j, _)) in deficit.iter().enumerate() {
+                for _ in 0..flow[a][b] {
+                    extra.push((i, j));
+                }
+            }
+        }
+    } else {
+        let odd = (0..n)
+            .filter(|&i| (in_degree[i] + out_degree[i]) % 2 == 1)
+            .collect::<Vec<_>>();
+        let mut distance = vec![vec![C::zero(); n]; n];
+        for &i in &odd {
+            let reach = shortest_paths(i, &leaving, &costs).0;
+            for &j in &odd {
+                distance[i][j] = reach[j]?;
+            }
+        }
+        extra = minimum_perfect_matching(&odd, &distance);
+    }
+
+    let mut copies = (0..arcs.len()).collect::<Vec<_>>();
+    let mut ends = arcs.iter().map(|&(_, i, j)| (i, j)).collect::<Vec<_>>();
+    for (i, j) in extra {
+        let via = shortest_paths(i, &leaving, &costs).1;
+        let mut w = j;
+        while let Some((k, u)) = via[w] {
+            copies.push(k);
+            ends.push((u, w));
+            w = u;
+        }
+    }
+
+    let (mut walk, taken) = eulerian_circuit(n, &ends, directed, start);
+    if taken.len() < ends.len() {
+        return None;
+    }
+    walk.pop();
+    Some(PostmanTour {
+        walk: walk.into_iter().map(|i| vertices[i]).collect(),
+        edges: taken.iter().map(|&k| arcs[copies[k]].0).collect(),
+        cost: taken.iter().fold(C::zero(), |c, &k| c + costs[copies[k]]),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::chinese_postman;
+
+    #[test]
+    fn chinese_postman_tours() {
+        use graph::{Directed, Graph, IncidenceGraph, MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        let empty = IncidenceList::<Undirected, (), i32>::new();
+        let tour = chinese_postman(&empty, |&e, g| g[e]).unwrap();
+        assert!(tour.walk().is_empty() && tour.edges().is_empty());
+        assert_eq!(tour.cost(), 0);
+
+        // v0 --(1)-- v1 --(2)-- v2
+        //  |          |          |
+        // (1)        (5)        (1)
+        //  |          |          |
+        // v3 --(1)-- v4 --(2)-- v5
+        //
+        // v1 and v4 are odd; walking v1 - v0 - v3 - v4 again is cheaper than v1 - v4
+
+        let mut g = IncidenceList::<Undirected, _, _>::new();
+        let vs = (0..6).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        let edges = [(0, 1, 1), (1, 2, 2), (0, 3, 1), (1, 4, 5), (2, 5, 1), (3, 4, 1), (4, 5, 2)];
+        for &(s, t, c) in &edges {
+            g.add_edge(vs[s], vs[t], c).unwrap();
+        }
+        let cost = |e: &_, g: &IncidenceList<Undirected, (), i32>| *g.edge_property(*e).unwrap();
+
+        let tour = chinese_postman(&g, cost).unwrap();
+        assert_eq!(tour.cost(), 13 + 3);
+        assert_eq!(tour.edges().len(), 10);
+        assert_eq!(tour.walk().len(), 10);
+        for (k, &e) in tour.edges().iter().enumerate() {
+            let (s, t) = (tour.walk()[k], tour.walk()[(k + 1) % 10]);
+            let ends = (g.source(e), g.target(e));
+            assert!(ends == (s, t) || ends == (t, s));
+        }
+
+        // a lone path is walked there and back
+        let mut p = IncidenceList::<Undirected, (), i32>::new();
+        let ps = (0..3).map(|_| p.add_vertex(())).collect::<Vec<_>>();
+        p.add_edge(ps[0], ps[1], 1).unwrap();
+        p.add_edge(ps[1], ps[2], 1).unwrap();
+        let tour = chinese_postman(&p, |&e, g| g[e]).unwrap();
+        assert_eq!((tour.cost(), tour.edges().len()), (4, 4));
+        let (p3, p4) = (p.add_vertex(()), p.add_vertex(()));
+        p.add_edge(p3, p4, 1).unwrap();
+        assert!(chinese_postman(&p, |&e, g| g[e]).is_none());
+
+        // v0 --> v1 --> v2
+        //  ^     |      |
+        //  |     v      |
+        //  +---- v3 <---+
+        //
+        // v1 is left more often than entered and v3 the reverse, so v3 --> v0 --> v1 repeats
+
+        let mut d = IncidenceList::<Directed, _, _>::new();
+        assert_eq!(chinese_postman(&d, |&e, g| g[e]).map(|t| t.cost()), Some(0));
+        let ws = (0..4).map(|_| d.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t) in &[(0, 1), (1, 2), (1, 3), (2, 3), (3, 0)] {
+            d.add_edge(ws[s], ws[t], 1).unwrap();
+        }
+        let cost = |e: &_, g: &IncidenceList<Directed, (), i32>| *g.edge_property(*e).unwrap();
+        let tour = chinese_postman(&d, cost).unwrap();
+        assert_eq!(tour.cost(), 7);
+        for (k, &e) in tour.edges().iter().enumerate() {
+            assert_eq!(d.source(e), tour.walk()[k]);
+        }
+
+        d.add_vertex(());
+        let isolated = d.add_vertex(());
+        assert!(chinese_postman(&d, cost).is_some());
+        d.add_edge(ws[0], isolated, 1).unwrap();
+        assert!(chinese_postman(&d, cost).is_none());
+
+        // a directed path cannot be walked back
+        let mut q = IncidenceList::<Directed, (), i32>::new();
+        let qs = (0..3).map(|_| q.add_vertex(())).collect::<Vec<_>>();
+        q.add_edge(qs[0], qs[1], 1).unwrap();
+        q.add_edge(qs[1], qs[2], 1).unwrap();
+        assert!(chinese_postman(&q, |&e, g| g[e]).is_none());
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+use std::ops::Index;
+
+use fnv::FnvHashMap;
+
+use graph::{VertexDescriptor, VertexListGraph};
+
+pub trait PropertyMap<K, V> {
+    fn get(&self, key: K) -> Option<&V>;
+    fn get_mut(&mut self, key: K) -> Option<&mut V>;
+    fn insert(&mut self, key: K, value: V) -> Option<V>;
+    fn remove(&mut self, key: K) -> Option<V>;
+    fn clear(&mut self);
+
+    fn contains_key(&self, key: K) -> bool {
+        self.get(key).is_some()
+    }
+}
+
+pub type HashPropertyMap<K, V> = FnvHashMap<K, V>;
+
+/// The progress of a search through a vertex. A vertex without a color has not been
+/// discovered yet, the same as a `White` one.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Color {
+    White,
+    Gray,
+    Black,
+}
+
+impl<K, V, S> PropertyMap<K, V> for HashMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    fn get(&self, key: K) -> Option<&V> {
+        HashMap::get(self, &key)
+    }
+
+    fn get_mut(&mut self, key: K) -> Option<&mut V> {
+        HashMap::get_mut(self, &key)
+    }
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        HashMap::insert(self, key, value)
+    }
+
+    fn remove(&mut self, key: K) -> Option<V> {
+        HashMap::remove(self, &key)
+    }
+
+    fn clear(&mut self) {
+        HashMap::clear(self)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct VecPropertyMap<K, V> {
+    values: Vec<Option<V>>,
+    phantom: PhantomData<K>,
+}
+
+impl<K, V> VecPropertyMap<K, V> {
+    pub fn new() -> Self {
+        Self {
+            values: Vec::new(),
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn with_bound(bound: usize) -> Self {
+        let mut values = Vec::with_capacity(bound);
+        values.resize_with(bound, || None);
+        Self {
+            values,
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn bound(&self) -> usize {
+        self.values.len()
+    }
+}
+
+impl<K, V> Default for VecPropertyMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> PropertyMap<K, V> for VecPropertyMap<K, V>
+where
+    usize: From<K>,
+{
+    fn get(&self, key: K) -> Option<&V> {
+        self.values.get(usize::from(key)).and_then(|v| v.as_ref())
+    }
+
+    fn get_mut(&mut self, key: K) -> Option<&mut V> {
+        self.values.get_mut(usize::from(key)).and_then(|v| v.as_mut())
+    }
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let i = usize::from(key);
+        if i >= self.values.len() {
+            self.values.resize_with(i + 1, || None);
+        }
+        self.values[i].replace(value)
+    }
+
+    fn remove(&mut self, key: K) -> Option<V> {
+        self.values.get_mut(usize::from(key)).and_then(|v| v.take())
+    }
+
+    fn clear(&mut self) {
+        for v in &mut self.values {
+            *v = None;
+        }
+    }
+}
+
+impl<K, V> Index<K> for VecPropertyMap<K, V>
+where
+    usize: From<K>,
+{
+    type Output = V;
+
+    fn index(&self, key: K) -> &V {
+        self.get(key).expect("no value for the key")
+    }
+}
+
+/// Numbers `vertices`, which must be vertices of `graph`, by their positions in the slice, so
+/// that an algorithm can keep its state for them in vectors.
+pub fn vertex_index<'a, G>(
+    graph: &'a G,
+    vertices: &[VertexDescriptor],
+) -> VecPropertyMap<VertexDescriptor, usize>
+where
+    G: VertexListGraph<'a>,
+{
+    let mut index = VecPropertyMap::with_bound(graph.vertex_bound());
+    for (i, &v) in vertices.iter().enumerate() {
+        index.insert(v, i);
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{vertex_index, HashPropertyMap, PropertyMap, VecPropertyMap};
+
+    fn exercise<P>(mut map: P)
+    where
+        P: PropertyMap<::graph::VertexDescriptor, usize>,
+    {
+        use graph::{FromUsize, VertexDescriptor};
+
+        let v0 = VertexDescriptor::from_usize(0);
+        let v3 = VertexDescriptor::from_usize(3);
+        let v7 = VertexDescriptor::from_usize(7);
+
+        assert_eq!(map.get(v3), None);
+        assert_eq!(map.insert(v3, 30), None);
+        assert_eq!(map.insert(v7, 70), None);
+        assert_eq!(map.insert(v3, 31), Some(30));
+
+        assert_eq!(map.get(v3), Some(&31));
+        assert!(!map.contains_key(v0));
+        assert!(map.contains_key(v7));
+
+        *map.get_mut(v7).unwrap() += 1;
+        assert_eq!(map.get(v7), Some(&71));
+
+        assert_eq!(map.remove(v7), Some(71));
+        assert_eq!(map.remove(v7), None);
+        assert!(!map.contains_key(v7));
+
+        map.clear();
+        assert!(!map.contains_key(v3));
+    }
+
+    #[test]
+    fn vec_property_map() {
+        exercise(VecPropertyMap::new());
+        exercise(VecPropertyMap::with_bound(4));
+
+        let map = VecPropertyMap::<::graph::VertexDescriptor, usize>::with_bound(4);
+        assert_eq!(map.bound(), 4);
+    }
+
+    #[test]
+    fn vertex_indices() {
+        use graph::{Directed, MutableGraph};
+        use incidence_list::IncidenceList;
+
+        let mut g = IncidenceList::<Directed, (), ()>::new();
+        let v0 = g.add_vertex(());
+        let v1 = g.add_vertex(());
+        let v2 = g.add_vertex(());
+        g.remove_vertex(v1).unwrap();
+
+        let index = vertex_index(&g, &[v2, v0]);
+        assert_eq!((index[v2], index[v0]), (0, 1));
+        assert!(!index.contains_key(v1));
+    }
+
+    #[test]
+    fn hash_property_map() {
+        exercise(HashPropertyMap::default());
+    }
+}
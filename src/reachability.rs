@@ -0,0 +1,195 @@
+use bipartite::kuhn_matching;
+use error::GraphError;
+use graph::{Directivity, IncidenceGraph, VertexDescriptor, VertexListGraph};
+use property_map::{vertex_index, PropertyMap, VecPropertyMap};
+use topological_sort::topological_sort;
+
+/// Answers reachability queries on a DAG in O(log k) time for k chains. The vertices are
+/// covered with as few vertex-disjoint chains of edges as a maximum matching of the edges
+/// allows, and every vertex records the earliest position it reaches on each chain it reaches
+/// at all. Building the index takes O(nm) time for the matching at worst, and space for the
+/// pairs of a vertex and a chain it reaches, which is linear on most graphs.
+pub struct ReachabilityIndex {
+    index: VecPropertyMap<VertexDescriptor, usize>,
+    chain: Vec<usize>,
+    position: Vec<usize>,
+    chains: usize,
+    // earliest[i] holds the first position reachable from i on every chain it reaches, sorted
+    // by chain
+    earliest: Vec<Vec<(usize, usize)>>,
+}
+
+impl ReachabilityIndex {
+    /// Fails with `GraphError::Undirected` on an undirected graph and with
+    /// `GraphError::Cyclic` if the graph has a cycle.
+    pub fn new<'a, G>(graph: &'a G) -> Result<Self, GraphError>
+    where
+        G: IncidenceGraph<'a> + VertexListGraph<'a>,
+        G::Directivity: Directivity,
+    {
+        if !G::Directivity::is_directed() {
+            return Err(GraphError::Undirected);
+        }
+        let order = topological_sort(graph)?;
+        let index = vertex_index(graph, &order);
+        let n = order.len();
+        let successors = order
+            .iter()
+            .map(|&v| {
+                let mut successors = graph
+                    .out_edges(v)
+                    .filter_map(|e| graph.opposite(e, v))
+                    .map(|w| index[w])
+                    .collect::<Vec<_>>();
+                successors.sort();
+                successors.dedup();
+                successors
+            })
+            .collect::<Vec<_>>();
+
+        // a chain starts at every vertex that no matched edge leads to
+        let (next, previous) = kuhn_matching(&successors, n);
+        let mut chain = vec![0; n];
+        let mut position = vec![0; n];
+        let mut chains = 0;
+        for i in (0..n).filter(|&i| previous[i].is_none()) {
+            let mut j = Some(i);
+            let mut p = 0;
+            while let Some(k) = j {
+                chain[k] = chains;
+                position[k] = p;
+                p += 1;
+                j = next[k];
+            }
+            chains += 1;
+        }
+
+        let mut earliest = vec![Vec::new(); n];
+        for i in (0..n).rev() {
+            let mut reach = vec![(chain[i], position[i])];
+            for &j in &successors[i] {
+                reach.extend_from_slice(&earliest[j]);
+            }
+            reach.sort();
+            reach.dedup_by_key(|&mut (c, _)| c);
+            earliest[i] = reach;
+        }
+
+        Ok(ReachabilityIndex {
+            index,
+            chain,
+            position,
+            chains,
+            earliest,
+        })
+    }
+
+    pub fn chain_count(&self) -> usize {
+        self.chains
+    }
+
+    /// Every vertex reaches itself. Vertices unknown to the index reach nothing.
+    pub fn reaches(&self, u: VertexDescriptor, v: VertexDescriptor) -> bool {
+        match (self.index.get(u), self.index.get(v)) {
+            (Some(&i), Some(&j)) => {
+                let earliest = &self.earliest[i];
+                earliest
+                    .binary_search_by_key(&self.chain[j], |&(c, _)| c)
+                    .is_ok_and(|k| earliest[k].1 <= self.position[j])
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReachabilityIndex;
+
+    #[test]
+    fn reachability_index() {
+        use graph::{Directed, MutableGraph};
+        use incidence_list::IncidenceList;
+
+        // v0 --> v1 --> v2 --> v3
+        //  |             ^
+        //  v             |
+        // v4 --> v5 -----+     v6
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+        let vs = (0..7).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t) in &[(0, 1), (1, 2), (2, 3), (0, 4), (4, 5), (5, 2)] {
+            g.add_edge(vs[s], vs[t], ()).unwrap();
+        }
+
+        let index = ReachabilityIndex::new(&g).unwrap();
+        let expected = [
+            vec![0, 1, 2, 3, 4, 5],
+            vec![1, 2, 3],
+            vec![2, 3],
+            vec![3],
+            vec![2, 3, 4, 5],
+            vec![2, 3, 5],
+            vec![6],
+        ];
+        for (u, reachable) in expected.iter().enumerate() {
+            for v in 0..7 {
+                assert_eq!(index.reaches(vs[u], vs[v]), reachable.contains(&v));
+            }
+        }
+        assert_eq!(index.chain_count(), 3);
+
+        // the leaves of a star are chains of their own, each reached only from the center
+        let mut star = IncidenceList::<Directed, (), ()>::new();
+        let center = star.add_vertex(());
+        let leaves = (0..5).map(|_| star.add_vertex(())).collect::<Vec<_>>();
+        for &leaf in &leaves {
+            star.add_edge(center, leaf, ()).unwrap();
+        }
+        let index = ReachabilityIndex::new(&star).unwrap();
+        assert_eq!(index.chain_count(), 5);
+        assert!(leaves.iter().all(|&leaf| index.reaches(center, leaf)));
+        assert!(!index.reaches(leaves[0], leaves[1]) && !index.reaches(leaves[0], center));
+    }
+
+    #[test]
+    fn reachability_index_empty() {
+        use graph::{Directed, MutableGraph};
+        use incidence_list::IncidenceList;
+
+        let mut g = IncidenceList::<Directed, (), ()>::new();
+        let index = ReachabilityIndex::new(&g).unwrap();
+        assert_eq!(index.chain_count(), 0);
+
+        // vertices added afterwards are unknown to the index
+        let v = g.add_vertex(());
+        assert!(!index.reaches(v, v));
+    }
+
+    #[test]
+    fn reachability_index_cyclic() {
+        use error::GraphError;
+        use graph::{Directed, MutableGraph};
+        use incidence_list::IncidenceList;
+
+        let mut g = IncidenceList::<Directed, (), ()>::new();
+        let v = g.add_vertex(());
+        g.add_edge(v, v, ()).unwrap();
+        assert!(match ReachabilityIndex::new(&g) {
+            Err(GraphError::Cyclic(u)) => u == v,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn reachability_index_undirected() {
+        use error::GraphError;
+        use graph::{MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        let mut g = IncidenceList::<Undirected, (), ()>::new();
+        let vs = (0..2).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        g.add_edge(vs[0], vs[1], ()).unwrap();
+        assert_eq!(ReachabilityIndex::new(&g).err(), Some(GraphError::Undirected));
+    }
+}
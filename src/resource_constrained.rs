@@ -0,0 +1,199 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt::Debug;
+use std::ops::Add;
+
+use num_traits::Zero;
+
+use error::GraphError;
+use graph::{Directivity, EdgeDescriptor, IncidenceGraph, VertexDescriptor, VertexListGraph};
+use path::Path;
+use property_map::{vertex_index, PropertyMap};
+
+type Constrained<C, Q> = (Path<C>, Vec<Q>);
+
+// A partial path ending at `vertex`, reached from the label `parent`.
+struct Label<C, Q> {
+    cost: C,
+    resources: Vec<Q>,
+    vertex: usize,
+    parent: Option<usize>,
+    dominated: bool,
+}
+
+fn dominates<C: Ord, Q: Ord>(a: (&C, &[Q]), b: (&C, &[Q])) -> bool {
+    a.0 <= b.0 && a.1.iter().zip(b.1).all(|(x, y)| x <= y)
+}
+
+/// Finds a cheapest path from `source` to `target` among those that consume no more of each
+/// resource than its limit, along with the resources it consumes. Every edge consumes the
+/// amounts of the resources given by `edge_resources`, in the order of `limits`; a cheapest
+/// route whose total time is at most T, for example, has the time of each edge as its only
+/// resource and T as its limit.
+///
+/// The labels of partial paths are extended in order of cost, and a label is discarded once
+/// another label at the same vertex costs no more and consumes no more of any resource. Costs
+/// and resources must not be negative. The problem is NP-hard, so the number of labels may
+/// grow exponentially with many resources. Returns `Ok(None)` if no path is within the limits.
+pub fn constrained_shortest_path<'a, G, C, Q, F, R>(
+    graph: &'a G,
+    source: VertexDescriptor,
+    target: VertexDescriptor,
+    edge_cost: F,
+    edge_resources: R,
+    limits: &[Q],
+) -> Result<Option<Constrained<C, Q>>, GraphError>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+    C: Copy + Debug + Ord + Zero,
+    Q: Copy + Debug + Ord + Zero + Add<Output = Q>,
+    F: Fn(&EdgeDescriptor, &G) -> C,
+    R: Fn(&EdgeDescriptor, &G) -> Vec<Q>,
+{
+    for &v in &[source, target] {
+        if !graph.contains_vertex(v) {
+            return Err(GraphError::VertexNotFound(v));
+        }
+    }
+    let vertices = graph.vertices().collect::<Vec<_>>();
+    let index = vertex_index(graph, &vertices);
+    let n = vertices.len();
+
+    let mut leaving = (0..n).map(|_| Vec::new()).collect::<Vec<_>>();
+    for (i, &v) in vertices.iter().enumerate() {
+        for e in graph.out_edges(v) {
+            if let Some(&j) = graph.opposite(e, v).and_then(|t| index.get(t)) {
+                let c = edge_cost(&e, graph);
+                if c < C::zero() {
+                    return Err(GraphError::NegativeEdgeCost(e));
+                }
+                let r = edge_resources(&e, graph);
+                assert_eq!(r.len(), limits.len(), "one amount is needed for every resource");
+                assert!(r.iter().all(|&q| q >= Q::zero()), "resources must not be negative");
+                if !G::Directivity::is_directed() && i != j {
+                    leaving[j].push((i, c, r.clone()));
+                }
+                leaving[i].push((j, c, r));
+            }
+        }
+    }
+
+    let (s, t) = (index[source], index[target]);
+    let mut labels = vec![Label {
+        cost: C::zero(),
+        resources: vec![Q::zero(); limits.len()],
+        vertex: s,
+        parent: None,
+        dominated: false,
+    }];
+    let mut at = vec![Vec::new(); n];
+    at[s].push(0);
+    let mut fringe = BinaryHeap::new();
+    fringe.push(Reverse((C::zero(), 0)));
+
+    while let Some(Reverse((cost, k))) = fringe.pop() {
+        if labels[k].dominated {
+            continue;
+        }
+        let i = labels[k].vertex;
+        if i == t {
+            let mut path = Vec::new();
+            let mut l = Some(k);
+            while let Some(x) = l {
+                path.push(vertices[labels[x].vertex]);
+                l = labels[x].parent;
+            }
+            path.reverse();
+            let resources = labels[k].resources.clone();
+            return Ok(Some((Path::new(path, cost), resources)));
+        }
+        for &(j, c, ref r) in &leaving[i] {
+            let resources = labels[k]
+                .resources
+                .iter()
+                .zip(r)
+                .map(|(&x, &y)| x + y)
+                .collect::<Vec<_>>();
+            if resources.iter().zip(limits).any(|(x, limit)| x > limit) {
+                continue;
+            }
+            let cost = cost + c;
+            if at[j]
+                .iter()
+                .any(|&l| dominates((&labels[l].cost, &labels[l].resources), (&cost, &resources)))
+            {
+                continue;
+            }
+            for &l in &at[j] {
+                if dominates((&cost, &resources), (&labels[l].cost, &labels[l].resources)) {
+                    labels[l].dominated = true;
+                }
+            }
+            at[j].retain(|&l| !labels[l].dominated);
+            at[j].push(labels.len());
+            fringe.push(Reverse((cost, labels.len())));
+            labels.push(Label {
+                cost,
+                resources,
+                vertex: j,
+                parent: Some(k),
+                dominated: false,
+            });
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::constrained_shortest_path;
+
+    #[test]
+    fn cheapest_within_limits() {
+        use graph::{Directed, MutableGraph};
+        use incidence_list::IncidenceList;
+        use path::Path;
+
+        //   +--(1, 10)--> a --(1, 10)--+
+        //   |                          v
+        //   s --(3, 2)--> b --(3, 2)-->t
+        //   |                          ^
+        //   +----------(10, 1)---------+
+        //
+        // (cost, time) on each edge; b also has a loop
+
+        let mut g = IncidenceList::<Directed, (), (u32, u32)>::new();
+        let s = g.add_vertex(());
+        let a = g.add_vertex(());
+        let b = g.add_vertex(());
+        let t = g.add_vertex(());
+        g.add_edge(s, a, (1, 10)).unwrap();
+        g.add_edge(a, t, (1, 10)).unwrap();
+        g.add_edge(s, b, (3, 2)).unwrap();
+        g.add_edge(b, b, (0, 0)).unwrap();
+        g.add_edge(b, t, (3, 2)).unwrap();
+        g.add_edge(s, t, (10, 1)).unwrap();
+
+        let within = |limit: u32| {
+            constrained_shortest_path(&g, s, t, |&e, g| g[e].0, |&e, g| vec![g[e].1], &[limit])
+        };
+        assert_eq!(within(25), Ok(Some((Path::new(vec![s, a, t], 2), vec![20]))));
+        assert_eq!(within(10), Ok(Some((Path::new(vec![s, b, t], 6), vec![4]))));
+        assert_eq!(within(3), Ok(Some((Path::new(vec![s, t], 10), vec![1]))));
+        assert_eq!(within(0), Ok(None));
+
+        // the number of edges as a second resource
+        assert_eq!(
+            constrained_shortest_path(
+                &g,
+                s,
+                t,
+                |&e, g| g[e].0,
+                |&e, g| vec![g[e].1, 1],
+                &[25, 1],
+            ),
+            Ok(Some((Path::new(vec![s, t], 10), vec![1, 1])))
+        );
+    }
+}
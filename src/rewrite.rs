@@ -0,0 +1,269 @@
+use fnv::{FnvHashMap, FnvHashSet};
+
+use error::GraphError;
+use graph::{BidirectionalGraph, Directivity, EdgeDescriptor, Graph, IncidenceGraph, MutableGraph,
+            VertexDescriptor};
+use incidence_list::IncidenceList;
+use isomorphism::subgraph_monomorphisms_matching;
+
+/// What a rule does to the matched vertices once its builder has added the new ones.
+#[derive(Clone, Debug, Default)]
+pub struct Replacement {
+    replaced: Vec<(VertexDescriptor, VertexDescriptor)>,
+    removed: Vec<VertexDescriptor>,
+}
+
+impl Replacement {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Removes `old`, moving its edges to vertices outside the match over to `new`. Edges to
+    /// other matched vertices go with it.
+    pub fn replace(mut self, old: VertexDescriptor, new: VertexDescriptor) -> Self {
+        self.replaced.push((old, new));
+        self
+    }
+
+    /// Removes `old` along with all its edges.
+    pub fn remove(mut self, old: VertexDescriptor) -> Self {
+        self.removed.push(old);
+        self
+    }
+}
+
+/// A rule applied at one site of the graph.
+#[derive(Clone, Debug)]
+pub struct Rewrite {
+    matched: FnvHashMap<VertexDescriptor, VertexDescriptor>,
+    edges: FnvHashMap<EdgeDescriptor, EdgeDescriptor>,
+}
+
+impl Rewrite {
+    /// Returns the vertex of the graph each vertex of the pattern was matched to.
+    pub fn matched(&self) -> &FnvHashMap<VertexDescriptor, VertexDescriptor> {
+        &self.matched
+    }
+
+    /// Returns the descriptor an edge moved by a replacement has now.
+    pub fn edge(&self, old: EdgeDescriptor) -> Option<EdgeDescriptor> {
+        self.edges.get(&old).cloned()
+    }
+
+    pub fn edges(&self) -> &FnvHashMap<EdgeDescriptor, EdgeDescriptor> {
+        &self.edges
+    }
+}
+
+/// A rewriting rule: wherever `pattern` occurs in a graph, not necessarily induced, `builder`
+/// is called with the graph and the match to add whatever replaces it, and returns the
+/// `Replacement` saying which matched vertices go.
+pub struct Rule<D, PV, PE, VM, EM, B> {
+    pattern: IncidenceList<D, PV, PE>,
+    vertex_match: VM,
+    edge_match: EM,
+    builder: B,
+}
+
+impl<D, PV, PE, VM, EM, B> Rule<D, PV, PE, VM, EM, B>
+where
+    D: Directivity,
+{
+    pub fn new(
+        pattern: IncidenceList<D, PV, PE>,
+        vertex_match: VM,
+        edge_match: EM,
+        builder: B,
+    ) -> Self {
+        Rule {
+            pattern,
+            vertex_match,
+            edge_match,
+            builder,
+        }
+    }
+
+    pub fn pattern(&self) -> &IncidenceList<D, PV, PE> {
+        &self.pattern
+    }
+
+    /// Applies the rule at the first match found, if any.
+    pub fn apply_first<VP, EP>(
+        &mut self,
+        graph: &mut IncidenceList<D, VP, EP>,
+    ) -> Result<Option<Rewrite>, GraphError>
+    where
+        VM: FnMut(&PV, &VP) -> bool,
+        EM: FnMut(&PE, &EP) -> bool,
+        B: FnMut(&mut IncidenceList<D, VP, EP>, &FnvHashMap<VertexDescriptor, VertexDescriptor>)
+            -> Replacement,
+    {
+        let matched = subgraph_monomorphisms_matching(
+            &self.pattern,
+            &*graph,
+            &mut self.vertex_match,
+            &mut self.edge_match,
+        ).next();
+        match matched {
+            Some(matched) => self.rewrite(graph, matched).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Applies the rule at every match that shares no vertex with one applied before it. The
+    /// matches are all found first, so the rule is not applied again to what it builds, and a
+    /// match whose vertices the builder has removed in the meantime is skipped.
+    pub fn apply_all<VP, EP>(
+        &mut self,
+        graph: &mut IncidenceList<D, VP, EP>,
+    ) -> Result<Vec<Rewrite>, GraphError>
+    where
+        VM: FnMut(&PV, &VP) -> bool,
+        EM: FnMut(&PE, &EP) -> bool,
+        B: FnMut(&mut IncidenceList<D, VP, EP>, &FnvHashMap<VertexDescriptor, VertexDescriptor>)
+            -> Replacement,
+    {
+        let matches = subgraph_monomorphisms_matching(
+            &self.pattern,
+            &*graph,
+            &mut self.vertex_match,
+            &mut self.edge_match,
+        ).collect::<Vec<_>>();
+
+        let mut used = FnvHashSet::default();
+        let mut rewrites = Vec::new();
+        for matched in matches {
+            if matched
+                .values()
+                .any(|v| used.contains(v) || !graph.contains_vertex(*v))
+            {
+                continue;
+            }
+            used.extend(matched.values().cloned());
+            rewrites.push(self.rewrite(graph, matched)?);
+        }
+        Ok(rewrites)
+    }
+
+    fn rewrite<VP, EP>(
+        &mut self,
+        graph: &mut IncidenceList<D, VP, EP>,
+        matched: FnvHashMap<VertexDescriptor, VertexDescriptor>,
+    ) -> Result<Rewrite, GraphError>
+    where
+        B: FnMut(&mut IncidenceList<D, VP, EP>, &FnvHashMap<VertexDescriptor, VertexDescriptor>)
+            -> Replacement,
+    {
+        let replacement = (self.builder)(graph, &matched);
+        let gone = replacement
+            .replaced
+            .iter()
+            .filter(|&&(old, new)| old != new)
+            .map(|&(old, _)| old)
+            .chain(replacement.removed.iter().cloned())
+            .collect::<Vec<_>>();
+        for &(old, new) in &replacement.replaced {
+            for &v in &[old, new] {
+                if !graph.contains_vertex(v) {
+                    return Err(GraphError::VertexNotFound(v));
+                }
+            }
+        }
+        if let Some(&v) = replacement.removed.iter().find(|&&v| !graph.contains_vertex(v)) {
+            return Err(GraphError::VertexNotFound(v));
+        }
+
+        let inside = matched.values().cloned().collect::<FnvHashSet<_>>();
+        let mut edges = FnvHashMap::default();
+        for &(old, new) in &replacement.replaced {
+            if old == new {
+                continue;
+            }
+            let mut incident = graph
+                .out_edges(old)
+                .chain(graph.in_edges(old))
+                .collect::<Vec<_>>();
+            incident.sort();
+            incident.dedup();
+            for e in incident {
+                let (s, t) = graph.edge_endpoints(e).ok_or(GraphError::EdgeNotFound(e))?;
+                if inside.contains(&s) && inside.contains(&t) {
+                    continue;
+                }
+                let s = if s == old { new } else { s };
+                let t = if t == old { new } else { t };
+                let property = graph.remove_edge(e).ok_or(GraphError::EdgeNotFound(e))?;
+                edges.insert(e, graph.add_edge(s, t, property)?);
+            }
+        }
+        for v in gone {
+            graph.remove_vertex(v);
+        }
+        Ok(Rewrite { matched, edges })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Replacement, Rule};
+
+    #[test]
+    fn constant_folding() {
+        use fnv::FnvHashMap;
+        use graph::{AdjacencyMatrixGraph, Directed, Graph, IncidenceGraph, MutableGraph,
+                    VertexDescriptor, VertexListGraph};
+        use incidence_list::IncidenceList;
+
+        // const 2 --+                   const 4 --+
+        //           +--> add --> neg              +--> add --> out
+        // const 3 --+                   const 1 --+
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+        let c2 = g.add_vertex(("const", 2));
+        let c3 = g.add_vertex(("const", 3));
+        let a0 = g.add_vertex(("add", 0));
+        let neg = g.add_vertex(("neg", 0));
+        let c4 = g.add_vertex(("const", 4));
+        let c1 = g.add_vertex(("const", 1));
+        let a1 = g.add_vertex(("add", 0));
+        let out = g.add_vertex(("out", 0));
+        for &(s, t) in &[(c2, a0), (c3, a0), (a0, neg), (c4, a1), (c1, a1), (a1, out)] {
+            g.add_edge(s, t, ()).unwrap();
+        }
+        let e = g.edge(a0, neg).unwrap();
+
+        let mut pattern = IncidenceList::<Directed, _, _>::new();
+        let x = pattern.add_vertex("const");
+        let y = pattern.add_vertex("const");
+        let sum = pattern.add_vertex("add");
+        pattern.add_edge(x, sum, ()).unwrap();
+        pattern.add_edge(y, sum, ()).unwrap();
+
+        let mut fold = Rule::new(
+            pattern,
+            |p: &&str, v: &(&str, i32)| *p == v.0,
+            |_: &(), _: &()| true,
+            |g: &mut IncidenceList<Directed, (&str, i32), ()>,
+             m: &FnvHashMap<VertexDescriptor, VertexDescriptor>| {
+                let value = g[m[&x]].1 + g[m[&y]].1;
+                let folded = g.add_vertex(("const", value));
+                Replacement::new()
+                    .replace(m[&sum], folded)
+                    .remove(m[&x])
+                    .remove(m[&y])
+            },
+        );
+
+        let rewrites = fold.apply_all(&mut g).unwrap();
+        assert_eq!(rewrites.len(), 2);
+        assert_eq!(g.order(), 4);
+        let moved = rewrites[0].edge(e).or(rewrites[1].edge(e)).unwrap();
+        assert_eq!(g.target(moved), neg);
+        assert_eq!(g[g.source(moved)], ("const", 5));
+        let into_out = g.vertices().find(|&v| g.edge(v, out).is_some()).unwrap();
+        assert_eq!(g[into_out], ("const", 5));
+        assert!(!g.contains_vertex(a0) && !g.contains_vertex(c1));
+
+        assert!(fold.apply_first(&mut g).unwrap().is_none());
+    }
+}
@@ -0,0 +1,286 @@
+use graph::{Directivity, EdgeDescriptor, IncidenceGraph, VertexDescriptor, VertexListGraph};
+use property_map::{vertex_index, PropertyMap};
+
+type Arcs = (Vec<VertexDescriptor>, Vec<(EdgeDescriptor, usize, usize)>);
+
+// The edges between distinct vertices as (edge, from, to), both ways round for an undirected
+// graph when `symmetric`.
+fn arcs<'a, G>(graph: &'a G, symmetric: bool) -> Arcs
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+{
+    let vertices = graph.vertices().collect::<Vec<_>>();
+    let index = vertex_index(graph, &vertices);
+    let mut arcs = Vec::new();
+    for (i, &v) in vertices.iter().enumerate() {
+        for e in graph.out_edges(v) {
+            if let Some(&j) = graph.opposite(e, v).and_then(|t| index.get(t)) {
+                if i != j {
+                    arcs.push((e, i, j));
+                    if symmetric {
+                        arcs.push((e, j, i));
+                    }
+                }
+            }
+        }
+    }
+    (vertices, arcs)
+}
+
+// Bareiss' fraction-free elimination, exact as long as no intermediate minor overflows.
+fn determinant(mut a: Vec<Vec<i128>>) -> Option<i128> {
+    let n = a.len();
+    let mut sign = 1;
+    let mut previous = 1;
+    for k in 0..n {
+        if a[k][k] == 0 {
+            match (k + 1..n).find(|&r| a[r][k] != 0) {
+                Some(r) => {
+                    a.swap(k, r);
+                    sign = -sign;
+                }
+                None => return Some(0),
+            }
+        }
+        for i in k + 1..n {
+            for j in k + 1..n {
+                let x = a[i][j].checked_mul(a[k][k])?;
+                let y = a[i][k].checked_mul(a[k][j])?;
+                a[i][j] = x.checked_sub(y)? / previous;
+            }
+        }
+        previous = a[k][k];
+    }
+    Some(if n == 0 { 1 } else { sign * a[n - 1][n - 1] })
+}
+
+// The Laplacian with in-degrees on the diagonal, without the row and column of `root`.
+fn reduced_laplacian(
+    n: usize,
+    arcs: &[(EdgeDescriptor, usize, usize)],
+    root: usize,
+) -> Vec<Vec<i128>> {
+    let mut laplacian = vec![vec![0; n]; n];
+    for &(_, i, j) in arcs {
+        laplacian[j][j] += 1;
+        laplacian[i][j] -= 1;
+    }
+    laplacian.remove(root);
+    for row in &mut laplacian {
+        row.remove(root);
+    }
+    laplacian
+}
+
+/// Counts the spanning trees, ignoring edge direction, by Kirchhoff's matrix-tree theorem: the
+/// count is any cofactor of the Laplacian. Parallel edges make distinct trees and self-loops
+/// are ignored. A disconnected graph has none, and a graph of at most one vertex has one.
+/// Returns `None` if the count does not fit in a `u128`.
+pub fn spanning_tree_count<'a, G>(graph: &'a G) -> Option<u128>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+{
+    let (vertices, arcs) = arcs(graph, true);
+    if vertices.is_empty() {
+        return Some(1);
+    }
+    determinant(reduced_laplacian(vertices.len(), &arcs, 0)).map(|d| d as u128)
+}
+
+/// Counts the spanning arborescences rooted at `root`, the trees in which every other vertex
+/// is reached from the root along the edges, by Tutte's directed matrix-tree theorem. In an
+/// undirected graph these are the spanning trees. Returns `None` if `root` is not in the graph
+/// or the count does not fit in a `u128`.
+pub fn arborescence_count<'a, G>(graph: &'a G, root: VertexDescriptor) -> Option<u128>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+{
+    let (vertices, arcs) = arcs(graph, !G::Directivity::is_directed());
+    let r = vertices.iter().position(|&v| v == root)?;
+    determinant(reduced_laplacian(vertices.len(), &arcs, r)).map(|d| d as u128)
+}
+
+fn find(parents: &[usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        i = parents[i];
+    }
+    i
+}
+
+fn grow_trees(
+    n: usize,
+    arcs: &[(EdgeDescriptor, usize, usize)],
+    parents: &mut Vec<usize>,
+    chosen: &mut Vec<EdgeDescriptor>,
+    trees: &mut Vec<Vec<EdgeDescriptor>>,
+) {
+    if chosen.len() + 1 == n {
+        trees.push(chosen.clone());
+        return;
+    }
+    let (&(e, i, j), rest) = match arcs.split_first() {
+        Some(split) if chosen.len() + split.1.len() + 2 >= n => split,
+        _ => return,
+    };
+    let (a, b) = (find(parents, i), find(parents, j));
+    if a != b {
+        parents[a] = b;
+        chosen.push(e);
+        grow_trees(n, rest, parents, chosen, trees);
+        chosen.pop();
+        parents[a] = a;
+    }
+    grow_trees(n, rest, parents, chosen, trees);
+}
+
+/// Lists the edges of every spanning tree, ignoring edge direction, by choosing each edge in
+/// or out in turn. There are exponentially many, so this is only for small graphs.
+pub fn spanning_trees<'a, G>(graph: &'a G) -> Vec<Vec<EdgeDescriptor>>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+{
+    let (vertices, arcs) = arcs(graph, false);
+    let n = vertices.len();
+    let mut trees = Vec::new();
+    if n == 0 {
+        trees.push(Vec::new());
+    } else {
+        let mut parents = (0..n).collect();
+        grow_trees(n, &arcs, &mut parents, &mut Vec::new(), &mut trees);
+    }
+    trees
+}
+
+/// Lists the edges of every spanning arborescence rooted at `root` by choosing an entering
+/// edge for each other vertex in turn and dropping choices that close a cycle. Only for small
+/// graphs. Lists nothing if `root` is not in the graph.
+pub fn arborescences<'a, G>(graph: &'a G, root: VertexDescriptor) -> Vec<Vec<EdgeDescriptor>>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+{
+    let (vertices, arcs) = arcs(graph, !G::Directivity::is_directed());
+    let r = match vertices.iter().position(|&v| v == root) {
+        Some(r) => r,
+        None => return Vec::new(),
+    };
+    let n = vertices.len();
+    let mut entering = vec![Vec::new(); n];
+    for &(e, i, j) in &arcs {
+        entering[j].push((e, i));
+    }
+    let others = (0..n).filter(|&i| i != r).collect::<Vec<_>>();
+
+    let mut found = Vec::new();
+    let mut parents = vec![None::<(EdgeDescriptor, usize)>; n];
+    let mut choices = vec![0; others.len()];
+    let mut k = 0;
+    // an explicit stack of choices: `choices[k]` is the next entering edge to try for others[k]
+    loop {
+        if k == others.len() {
+            found.push(others.iter().map(|&v| parents[v].unwrap().0).collect());
+            if k == 0 {
+                break;
+            }
+            k -= 1;
+            continue;
+        }
+        let v = others[k];
+        parents[v] = None;
+        match entering[v].get(choices[k]) {
+            Some(&(e, u)) => {
+                choices[k] += 1;
+                // the edge closes a cycle if following parents from u comes back to v
+                let mut w = Some(u);
+                while let Some(x) = w {
+                    if x == v {
+                        break;
+                    }
+                    w = parents[x].map(|(_, p)| p);
+                }
+                if w.is_none() {
+                    parents[v] = Some((e, u));
+                    k += 1;
+                    if k < others.len() {
+                        choices[k] = 0;
+                    }
+                }
+            }
+            None => {
+                if k == 0 {
+                    break;
+                }
+                k -= 1;
+            }
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{arborescence_count, arborescences, spanning_tree_count, spanning_trees};
+
+    #[test]
+    fn spanning_trees_and_arborescences() {
+        use graph::{Directed, MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        // K4 has 4^2 spanning trees by Cayley's formula
+        let mut k4 = IncidenceList::<Undirected, (), ()>::new();
+        let ks = (0..4).map(|_| k4.add_vertex(())).collect::<Vec<_>>();
+        for i in 0..4 {
+            for j in 0..i {
+                k4.add_edge(ks[i], ks[j], ()).unwrap();
+            }
+        }
+        assert_eq!(spanning_tree_count(&k4), Some(16));
+        assert_eq!(arborescence_count(&k4, ks[0]), Some(16));
+        let trees = spanning_trees(&k4);
+        assert_eq!(trees.len(), 16);
+        assert!(trees.iter().all(|t| t.len() == 3));
+        // self-loops are in no tree
+        k4.add_edge(ks[0], ks[0], ()).unwrap();
+        assert_eq!(spanning_tree_count(&k4), Some(16));
+        assert_eq!(spanning_trees(&k4).len(), 16);
+
+        //  +=====> v1 ---> v2
+        //  |               ^ |
+        // v0 --------------+ |
+        //  ^                 |
+        //  +-----------------+
+        //
+        // v0 reaches v1 by either of two edges
+
+        let mut g = IncidenceList::<Directed, (), ()>::new();
+        let vs = (0..3).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t) in &[(0, 1), (0, 1), (1, 2), (0, 2), (2, 0)] {
+            g.add_edge(vs[s], vs[t], ()).unwrap();
+        }
+        assert_eq!(arborescence_count(&g, vs[0]), Some(4));
+        assert_eq!(arborescences(&g, vs[0]).len(), 4);
+        assert_eq!(arborescence_count(&g, vs[1]), Some(1));
+        assert_eq!(arborescences(&g, vs[1]).len(), 1);
+        assert_eq!(arborescence_count(&g, vs[2]), Some(2));
+        assert_eq!(spanning_tree_count(&g), Some(8));
+        assert_eq!(spanning_trees(&g).len(), 8);
+
+        g.add_vertex(());
+        assert_eq!(spanning_tree_count(&g), Some(0));
+        assert!(spanning_trees(&g).is_empty());
+        assert_eq!(arborescence_count(&g, vs[0]), Some(0));
+
+        // the empty graph and a lone vertex have a single tree without edges, and a vertex
+        // not in the graph roots nothing
+        let mut t = IncidenceList::<Directed, (), ()>::new();
+        assert_eq!(spanning_tree_count(&t), Some(1));
+        assert_eq!(spanning_trees(&t), vec![vec![]]);
+        let v = t.add_vertex(());
+        assert_eq!(arborescence_count(&t, v), Some(1));
+        assert_eq!(arborescences(&t, v), vec![vec![]]);
+        t.remove_vertex(v).unwrap();
+        assert_eq!(arborescence_count(&t, v), None);
+        assert!(arborescences(&t, v).is_empty());
+    }
+}
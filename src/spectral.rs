@@ -0,0 +1,248 @@
+use fnv::FnvHashMap;
+
+use graph::{Directivity, EdgeDescriptor, IncidenceGraph, VertexDescriptor, VertexListGraph};
+use property_map::{vertex_index, PropertyMap};
+
+/// A square matrix indexed by vertex, as (row, column, value) triplets sorted by row and then
+/// column, with no duplicate positions and no zeros.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SparseMatrix {
+    vertices: Vec<VertexDescriptor>,
+    triplets: Vec<(usize, usize, f64)>,
+}
+
+impl SparseMatrix {
+    fn from_entries(
+        vertices: Vec<VertexDescriptor>,
+        entries: FnvHashMap<(usize, usize), f64>,
+    ) -> Self {
+        let mut triplets = entries
+            .into_iter()
+            .filter(|&(_, x)| x != 0.0)
+            .map(|((i, j), x)| (i, j, x))
+            .collect::<Vec<_>>();
+        triplets.sort_by_key(|&(i, j, _)| (i, j));
+        SparseMatrix { vertices, triplets }
+    }
+
+    /// The vertex of each row and column.
+    pub fn vertices(&self) -> &[VertexDescriptor] {
+        &self.vertices
+    }
+
+    pub fn triplets(&self) -> &[(usize, usize, f64)] {
+        &self.triplets
+    }
+
+    pub fn order(&self) -> usize {
+        self.vertices.len()
+    }
+
+    fn multiply(&self, x: &[f64]) -> Vec<f64> {
+        let mut y = vec![0.0; x.len()];
+        for &(i, j, a) in &self.triplets {
+            y[i] += a * x[j];
+        }
+        y
+    }
+}
+
+fn weighted_edges<'a, G, F>(
+    graph: &'a G,
+    weight: F,
+) -> (Vec<VertexDescriptor>, Vec<(usize, usize, f64)>)
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    F: Fn(&EdgeDescriptor, &G) -> f64,
+{
+    let vertices = graph.vertices().collect::<Vec<_>>();
+    let index = vertex_index(graph, &vertices);
+    let mut edges = Vec::new();
+    for (i, &v) in vertices.iter().enumerate() {
+        for e in graph.out_edges(v) {
+            if let Some(&j) = graph.opposite(e, v).and_then(|t| index.get(t)) {
+                edges.push((i, j, weight(&e, graph)));
+            }
+        }
+    }
+    (vertices, edges)
+}
+
+/// Sums the weights of parallel edges. An undirected graph gives a symmetric matrix.
+pub fn adjacency_matrix<'a, G, F>(graph: &'a G, weight: F) -> SparseMatrix
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+    F: Fn(&EdgeDescriptor, &G) -> f64,
+{
+    let (vertices, edges) = weighted_edges(graph, weight);
+    let mut entries = FnvHashMap::default();
+    for (i, j, w) in edges {
+        *entries.entry((i, j)).or_insert(0.0) += w;
+        if !G::Directivity::is_directed() && i != j {
+            *entries.entry((j, i)).or_insert(0.0) += w;
+        }
+    }
+    SparseMatrix::from_entries(vertices, entries)
+}
+
+/// Builds `D - A`, or `I - D^-1/2 A D^-1/2` when `normalized`, with edge direction ignored
+/// and self-loops left out. Isolated vertices get a zero row in the normalized form.
+pub fn laplacian_matrix<'a, G, F>(graph: &'a G, weight: F, normalized: bool) -> SparseMatrix
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    F: Fn(&EdgeDescriptor, &G) -> f64,
+{
+    let (vertices, edges) = weighted_edges(graph, weight);
+    let mut degree = vec![0.0; vertices.len()];
+    let mut entries = FnvHashMap::default();
+    for (i, j, w) in edges {
+        if i == j {
+            continue;
+        }
+        degree[i] += w;
+        degree[j] += w;
+        *entries.entry((i, j)).or_insert(0.0) -= w;
+        *entries.entry((j, i)).or_insert(0.0) -= w;
+    }
+    if normalized {
+        for (&(i, j), x) in &mut entries {
+            *x /= (degree[i] * degree[j]).sqrt();
+        }
+    }
+    for (i, &d) in degree.iter().enumerate() {
+        if d != 0.0 {
+            entries.insert((i, i), if normalized { 1.0 } else { d });
+        }
+    }
+    SparseMatrix::from_entries(vertices, entries)
+}
+
+/// Approximates the eigenvector of the second smallest eigenvalue of the Laplacian by power
+/// iteration on `cI - L`, keeping the iterate orthogonal to the constant vector. The signs of
+/// its entries split a connected graph into two loosely linked parts.
+pub fn fiedler_vector<'a, G, F>(
+    graph: &'a G,
+    weight: F,
+    iterations: usize,
+) -> FnvHashMap<VertexDescriptor, f64>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    F: Fn(&EdgeDescriptor, &G) -> f64,
+{
+    let laplacian = laplacian_matrix(graph, weight, false);
+    let n = laplacian.order();
+    // Gershgorin: no eigenvalue of L exceeds twice the largest degree
+    let shift = 2.0 *
+        laplacian
+            .triplets()
+            .iter()
+            .filter(|&&(i, j, _)| i == j)
+            .map(|&(_, _, d)| d)
+            .fold(0.0, f64::max);
+
+    let mut x = (0..n).map(|i| i as f64 - (n as f64 - 1.0) / 2.0).collect::<Vec<_>>();
+    for _ in 0..iterations {
+        let lx = laplacian.multiply(&x);
+        let mut y = x.iter().zip(lx).map(|(a, b)| shift * a - b).collect::<Vec<_>>();
+        let mean = y.iter().sum::<f64>() / n as f64;
+        for v in &mut y {
+            *v -= mean;
+        }
+        let norm = y.iter().map(|v| v * v).sum::<f64>().sqrt();
+        if norm == 0.0 {
+            break;
+        }
+        x = y.into_iter().map(|v| v / norm).collect();
+    }
+    laplacian.vertices.iter().cloned().zip(x).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{adjacency_matrix, fiedler_vector, laplacian_matrix};
+
+    #[test]
+    fn spectral() {
+        use graph::{Directed, MutableGraph, VertexListGraph};
+        use incidence_list::IncidenceList;
+
+        // v0 --> v1      v3 --> v4
+        //  ^     |        ^     |
+        //  |     v        |     v
+        //  +---- v2 ----> v5 ---+
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+        let unit = |_: &_, _: &_| 1.0;
+        assert_eq!(laplacian_matrix(&g, unit, true).triplets(), &[]);
+        assert!(fiedler_vector(&g, unit, 10).is_empty());
+
+        let vs = (0..6).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t) in &[(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 5)] {
+            g.add_edge(vs[s], vs[t], ()).unwrap();
+        }
+
+        let a = adjacency_matrix(&g, unit);
+        assert_eq!(a.order(), 6);
+        assert_eq!(a.triplets().len(), 7);
+        assert_eq!(a.triplets()[0], (0, 1, 1.0));
+
+        let l = laplacian_matrix(&g, unit, false);
+        assert_eq!(l.triplets().len(), 6 + 14);
+        assert!(l.triplets().contains(&(2, 2, 3.0)));
+        assert!(l.triplets().contains(&(5, 2, -1.0)));
+
+        let n = laplacian_matrix(&g, unit, true);
+        assert!(n.triplets().contains(&(0, 0, 1.0)));
+        assert!(n.triplets().iter().any(|&(i, j, x)| {
+            i == 2 && j == 5 && (x + 1.0 / 3.0).abs() < 1e-12
+        }));
+
+        let fiedler = fiedler_vector(&g, unit, 500);
+        let side = |i: usize| fiedler[&vs[i]] > 0.0;
+        assert_eq!(side(0), side(1));
+        assert_eq!(side(1), side(2));
+        assert_eq!(side(3), side(4));
+        assert_eq!(side(4), side(5));
+        assert!(side(0) != side(3));
+
+        // a self-loop is left out, and a vertex without other edges has a zero row when
+        // normalized and a zero entry in the Fiedler vector
+        let v = g.add_vertex(());
+        g.add_edge(v, v, ()).unwrap();
+        assert_eq!(laplacian_matrix(&g, unit, false).triplets(), l.triplets());
+        let n = laplacian_matrix(&g, unit, true);
+        assert!(n.triplets().iter().all(|&(i, j, _)| i != 6 && j != 6));
+        for v in g.vertices().collect::<Vec<_>>() {
+            if v != vs[5] {
+                g.remove_vertex(v).unwrap();
+            }
+        }
+        assert_eq!(fiedler_vector(&g, unit, 10)[&vs[5]], 0.0);
+    }
+
+    #[test]
+    fn adjacency_matrix_undirected() {
+        use graph::{MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        // v0 === v1 --- v2    v3
+        //
+        // v0 and v1 are joined by two parallel edges, and v2 has a self-loop
+
+        let mut g = IncidenceList::<Undirected, _, _>::new();
+        let unit = |_: &_, _: &_| 1.0;
+        assert_eq!(adjacency_matrix(&g, unit).order(), 0);
+
+        let vs = (0..4).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t) in &[(0, 1), (1, 0), (1, 2), (2, 2)] {
+            g.add_edge(vs[s], vs[t], ()).unwrap();
+        }
+        let a = adjacency_matrix(&g, unit);
+        assert_eq!(a.vertices(), &vs[..]);
+        assert_eq!(
+            a.triplets(),
+            &[(0, 1, 2.0), (1, 0, 2.0), (1, 2, 1.0), (2, 1, 1.0), (2, 2, 1.0)]
+        );
+    }
+}
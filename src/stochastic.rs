@@ -0,0 +1,202 @@
+use std::collections::VecDeque;
+
+use error::GraphError;
+use graph::{Directivity, EdgeDescriptor, IncidenceGraph, VertexDescriptor, VertexListGraph};
+use property_map::{vertex_index, PropertyMap};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StochasticPath {
+    vertices: Vec<VertexDescriptor>,
+    mean: f64,
+    variance: f64,
+}
+
+impl StochasticPath {
+    pub fn vertices(&self) -> &[VertexDescriptor] {
+        &self.vertices
+    }
+
+    /// The expected cost of the path.
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    pub fn variance(&self) -> f64 {
+        self.variance
+    }
+
+    pub fn std_dev(&self) -> f64 {
+        self.variance.sqrt()
+    }
+}
+
+// A partial path ending at `vertex`, reached from the label `parent`.
+struct Label {
+    mean: f64,
+    variance: f64,
+    vertex: usize,
+    parent: Option<usize>,
+    dominated: bool,
+}
+
+/// Finds a path from `source` to `target` minimizing `mean + risk * std_dev` when the cost of
+/// every edge is an independent random variable with the mean and variance given by
+/// `edge_distribution`, so that the costs of a path have the sums of them as their mean and
+/// variance. A `risk` of zero minimizes the expected cost; a larger one prefers paths whose cost
+/// is more predictable.
+///
+/// The objective is not additive, so the paths to every vertex that no other path beats in
+/// both mean and variance are kept, by label correcting, and the best of those reaching
+/// `target` is returned; their number may grow large on big graphs. Means and variances must
+/// not be negative, nor `risk`. Returns `Ok(None)` if `target` cannot be reached.
+pub fn stochastic_shortest_path<'a, G, F>(
+    graph: &'a G,
+    source: VertexDescriptor,
+    target: VertexDescriptor,
+    edge_distribution: F,
+    risk: f64,
+) -> Result<Option<StochasticPath>, GraphError>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+    F: Fn(&EdgeDescriptor, &G) -> (f64, f64),
+{
+    assert!(risk >= 0.0, "risk must not be negative");
+    for &v in &[source, target] {
+        if !graph.contains_vertex(v) {
+            return Err(GraphError::VertexNotFound(v));
+        }
+    }
+    let vertices = graph.vertices().collect::<Vec<_>>();
+    let index = vertex_index(graph, &vertices);
+    let n = vertices.len();
+
+    let mut leaving = vec![Vec::new(); n];
+    for (i, &v) in vertices.iter().enumerate() {
+        for e in graph.out_edges(v) {
+            if let Some(&j) = graph.opposite(e, v).and_then(|t| index.get(t)) {
+                let (mean, variance) = edge_distribution(&e, graph);
+                if mean < 0.0 {
+                    return Err(GraphError::NegativeEdgeCost(e));
+                }
+                assert!(variance >= 0.0, "variances must not be negative");
+                leaving[i].push((j, mean, variance));
+                if !G::Directivity::is_directed() && i != j {
+                    leaving[j].push((i, mean, variance));
+                }
+            }
+        }
+    }
+
+    let (s, t) = (index[source], index[target]);
+    let mut labels = vec![Label {
+        mean: 0.0,
+        variance: 0.0,
+        vertex: s,
+        parent: None,
+        dominated: false,
+    }];
+    let mut at = vec![Vec::new(); n];
+    at[s].push(0);
+    let mut queue = VecDeque::new();
+    queue.push_back(0);
+
+    while let Some(k) = queue.pop_front() {
+        if labels[k].dominated {
+            continue;
+        }
+        let i = labels[k].vertex;
+        for &(j, mean, variance) in &leaving[i] {
+            let mean = labels[k].mean + mean;
+            let variance = labels[k].variance + variance;
+            if at[j]
+                .iter()
+                .any(|&l| labels[l].mean <= mean && labels[l].variance <= variance)
+            {
+                continue;
+            }
+            for &l in &at[j] {
+                if mean <= labels[l].mean && variance <= labels[l].variance {
+                    labels[l].dominated = true;
+                }
+            }
+            at[j].retain(|&l| !labels[l].dominated);
+            at[j].push(labels.len());
+            queue.push_back(labels.len());
+            labels.push(Label {
+                mean,
+                variance,
+                vertex: j,
+                parent: Some(k),
+                dominated: false,
+            });
+        }
+    }
+
+    let objective = |l: &Label| l.mean + risk * l.variance.sqrt();
+    let best = at[t].iter().cloned().fold(None, |best: Option<usize>, l| match best {
+        Some(b) if objective(&labels[b]) <= objective(&labels[l]) => Some(b),
+        _ => Some(l),
+    });
+    Ok(best.map(|k| {
+        let mut path = Vec::new();
+        let mut l = Some(k);
+        while let Some(x) = l {
+            path.push(vertices[labels[x].vertex]);
+            l = labels[x].parent;
+        }
+        path.reverse();
+        StochasticPath {
+            vertices: path,
+            mean: labels[k].mean,
+            variance: labels[k].variance,
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::stochastic_shortest_path;
+
+    #[test]
+    fn risk_averse_paths() {
+        use graph::{MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        //   +--(3, 1)--- a ---(9, 1)--+
+        //   |                         |
+        //   s ---------(10, 100)----- t
+        //   |                         |
+        //   +--(5, 16)-- b --(5.5, 16)+
+        //
+        // (mean, variance) on each edge
+
+        let mut g = IncidenceList::<Undirected, (), (f64, f64)>::new();
+        let s = g.add_vertex(());
+        let a = g.add_vertex(());
+        let b = g.add_vertex(());
+        let t = g.add_vertex(());
+        g.add_edge(s, a, (3.0, 1.0)).unwrap();
+        g.add_edge(t, a, (9.0, 1.0)).unwrap();
+        g.add_edge(s, t, (10.0, 100.0)).unwrap();
+        g.add_edge(s, b, (5.0, 16.0)).unwrap();
+        g.add_edge(b, t, (5.5, 16.0)).unwrap();
+
+        let path = |risk| {
+            stochastic_shortest_path(&g, s, t, |&e, g| g[e], risk)
+                .unwrap()
+                .unwrap()
+        };
+        // expected costs 10, 10.5 and 12 with standard deviations 10, 5.66 and 1.41
+        let expected = path(0.0);
+        assert_eq!(expected.vertices(), &[s, t]);
+        assert_eq!((expected.mean(), expected.std_dev()), (10.0, 10.0));
+        assert_eq!(path(0.25).vertices(), &[s, b, t]);
+        let cautious = path(1.0);
+        assert_eq!(cautious.vertices(), &[s, a, t]);
+        assert_eq!((cautious.mean(), cautious.variance()), (12.0, 2.0));
+
+        let x = g.add_vertex(());
+        assert_eq!(stochastic_shortest_path(&g, s, x, |&e, g| g[e], 1.0), Ok(None));
+    }
+}
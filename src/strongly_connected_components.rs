@@ -0,0 +1,117 @@
+use graph::{Directivity, IncidenceGraph, VertexDescriptor, VertexListGraph};
+use property_map::{vertex_index, PropertyMap};
+
+/// Splits the vertices into strongly connected components with Tarjan's algorithm, run without
+/// recursion. Components come in reverse topological order of the condensation: no edge leads
+/// from a component to an earlier one. In an undirected graph these are the connected
+/// components.
+pub fn strongly_connected_components<'a, G>(graph: &'a G) -> Vec<Vec<VertexDescriptor>>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+{
+    let vertices = graph.vertices().collect::<Vec<_>>();
+    let index = vertex_index(graph, &vertices);
+    let n = vertices.len();
+    let mut successors = vec![Vec::new(); n];
+    for (i, &v) in vertices.iter().enumerate() {
+        for e in graph.out_edges(v) {
+            if let Some(&j) = graph.opposite(e, v).and_then(|t| index.get(t)) {
+                successors[i].push(j);
+                if !G::Directivity::is_directed() {
+                    successors[j].push(i);
+                }
+            }
+        }
+    }
+
+    let mut discovered = vec![None; n];
+    let mut lowlinks = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = Vec::new();
+    let mut components = Vec::new();
+    let mut count = 0;
+    for root in 0..n {
+        if discovered[root].is_some() {
+            continue;
+        }
+        let mut calls = vec![(root, 0)];
+        discovered[root] = Some(count);
+        lowlinks[root] = count;
+        count += 1;
+        stack.push(root);
+        on_stack[root] = true;
+        while let Some(&mut (i, ref mut k)) = calls.last_mut() {
+            if let Some(&j) = successors[i].get(*k) {
+                *k += 1;
+                match discovered[j] {
+                    None => {
+                        discovered[j] = Some(count);
+                        lowlinks[j] = count;
+                        count += 1;
+                        stack.push(j);
+                        on_stack[j] = true;
+                        calls.push((j, 0));
+                    }
+                    Some(d) if on_stack[j] => lowlinks[i] = lowlinks[i].min(d),
+                    Some(_) => {}
+                }
+                continue;
+            }
+
+            calls.pop();
+            if let Some(&(p, _)) = calls.last() {
+                lowlinks[p] = lowlinks[p].min(lowlinks[i]);
+            }
+            if Some(lowlinks[i]) == discovered[i] {
+                let mut component = Vec::new();
+                loop {
+                    let j = stack.pop().unwrap();
+                    on_stack[j] = false;
+                    component.push(vertices[j]);
+                    if j == i {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::strongly_connected_components;
+
+    #[test]
+    fn strongly_connected_components_in_reverse_topological_order() {
+        use graph::{Directed, MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        // v0 --> v1 --> v3 --> v4
+        //  ^     |       ^     |
+        //  |     v       |     v
+        //  +---- v2      +---- v5     v6
+
+        let mut g = IncidenceList::<Directed, (), ()>::new();
+        let vs = (0..7).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t) in &[(0, 1), (1, 2), (2, 0), (1, 3), (3, 4), (4, 5), (5, 3)] {
+            g.add_edge(vs[s], vs[t], ()).unwrap();
+        }
+
+        let mut components = strongly_connected_components(&g);
+        for c in &mut components {
+            c.sort();
+        }
+        assert_eq!(
+            components,
+            vec![vec![vs[3], vs[4], vs[5]], vec![vs[0], vs[1], vs[2]], vec![vs[6]]]
+        );
+
+        let mut u = IncidenceList::<Undirected, (), ()>::new();
+        let us = (0..3).map(|_| u.add_vertex(())).collect::<Vec<_>>();
+        u.add_edge(us[1], us[0], ()).unwrap();
+        assert_eq!(strongly_connected_components(&u).len(), 2);
+    }
+}
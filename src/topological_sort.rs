@@ -0,0 +1,200 @@
+use std::collections::VecDeque;
+
+use error::GraphError;
+use graph::{IncidenceGraph, VertexDescriptor, VertexListGraph};
+use property_map::{vertex_index, PropertyMap};
+
+type Adjacency = (Vec<VertexDescriptor>, Vec<Vec<usize>>, Vec<Vec<usize>>);
+
+fn adjacency<'a, G>(graph: &'a G) -> Adjacency
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+{
+    let vertices = graph.vertices().collect::<Vec<_>>();
+    let index = vertex_index(graph, &vertices);
+
+    let mut successors = vec![Vec::new(); vertices.len()];
+    let mut predecessors = vec![Vec::new(); vertices.len()];
+    for (i, &v) in vertices.iter().enumerate() {
+        for e in graph.out_edges(v) {
+            if let Some(&j) = graph.opposite(e, v).and_then(|t| index.get(t)) {
+                successors[i].push(j);
+                predecessors[j].push(i);
+            }
+        }
+    }
+    (vertices, successors, predecessors)
+}
+
+/// Orders the vertices so that every edge points forward, treating the graph as directed.
+/// Fails with `GraphError::Cyclic` naming a vertex on some cycle if there is no such order.
+pub fn topological_sort<'a, G>(graph: &'a G) -> Result<Vec<VertexDescriptor>, GraphError>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+{
+    let (vertices, successors, predecessors) = adjacency(graph);
+    let mut in_degree = predecessors.iter().map(|p| p.len()).collect::<Vec<_>>();
+    let mut ready = (0..vertices.len())
+        .filter(|&i| in_degree[i] == 0)
+        .collect::<VecDeque<_>>();
+    let mut order = Vec::with_capacity(vertices.len());
+    while let Some(i) = ready.pop_front() {
+        order.push(vertices[i]);
+        for &j in &successors[i] {
+            in_degree[j] -= 1;
+            if in_degree[j] == 0 {
+                ready.push_back(j);
+            }
+        }
+    }
+
+    if order.len() < vertices.len() {
+        // every vertex left over has a predecessor left over, so walking backwards along them
+        // must eventually come round to a vertex already seen
+        let mut seen = vec![false; vertices.len()];
+        let mut i = (0..vertices.len()).find(|&i| in_degree[i] > 0).unwrap();
+        while !seen[i] {
+            seen[i] = true;
+            i = *predecessors[i].iter().find(|&&j| in_degree[j] > 0).unwrap();
+        }
+        return Err(GraphError::Cyclic(vertices[i]));
+    }
+    Ok(order)
+}
+
+pub struct TopologicalOrderings {
+    vertices: Vec<VertexDescriptor>,
+    successors: Vec<Vec<usize>>,
+    in_degree: Vec<usize>,
+    placed: Vec<bool>,
+    order: Vec<usize>,
+    // the first vertex to try at the next level, or `None` once exhausted
+    resume: Option<usize>,
+}
+
+impl TopologicalOrderings {
+    fn place(&mut self, i: usize) {
+        self.placed[i] = true;
+        self.order.push(i);
+        for &j in &self.successors[i] {
+            self.in_degree[j] -= 1;
+        }
+    }
+
+    fn unplace(&mut self) -> Option<usize> {
+        let i = self.order.pop()?;
+        self.placed[i] = false;
+        for &j in &self.successors[i] {
+            self.in_degree[j] += 1;
+        }
+        Some(i)
+    }
+}
+
+impl Iterator for TopologicalOrderings {
+    type Item = Vec<VertexDescriptor>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut from = self.resume?;
+        while self.order.len() < self.vertices.len() {
+            match (from..self.vertices.len()).find(|&i| !self.placed[i] && self.in_degree[i] == 0) {
+                Some(i) => {
+                    self.place(i);
+                    from = 0;
+                }
+                None => match self.unplace() {
+                    Some(i) => from = i + 1,
+                    None => {
+                        self.resume = None;
+                        return None;
+                    }
+                },
+            }
+        }
+
+        let ordering = self.order.iter().map(|&i| self.vertices[i]).collect();
+        self.resume = self.unplace().map(|i| i + 1);
+        Some(ordering)
+    }
+}
+
+/// Lazily enumerates every topological ordering of the graph, treating it as directed, by
+/// backtracking over the choice of vertex at each position. A cyclic graph has none. There can
+/// be factorially many, so stop early with the usual iterator adapters when exploring.
+pub fn topological_orderings<'a, G>(graph: &'a G) -> TopologicalOrderings
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+{
+    let (vertices, successors, predecessors) = adjacency(graph);
+    let in_degree = predecessors.iter().map(|p| p.len()).collect();
+    TopologicalOrderings {
+        placed: vec![false; vertices.len()],
+        order: Vec::with_capacity(vertices.len()),
+        resume: Some(0),
+        vertices,
+        successors,
+        in_degree,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{topological_orderings, topological_sort};
+
+    #[test]
+    fn topological_sort_of_dag_and_cycle() {
+        use error::GraphError;
+        use graph::{Directed, MutableGraph};
+        use incidence_list::IncidenceList;
+
+        // v0 --> v1 --> v3 <-- v4
+        //  |            ^
+        //  v            |
+        // v2 -----------+
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+        let vs = (0..5).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        let edges = [(0, 1), (1, 3), (4, 3), (0, 2), (2, 3)];
+        for &(s, t) in &edges {
+            g.add_edge(vs[s], vs[t], ()).unwrap();
+        }
+
+        let order = topological_sort(&g).unwrap();
+        let position = |v| order.iter().position(|&u| u == v).unwrap();
+        assert_eq!(order.len(), 5);
+        for &(s, t) in &edges {
+            assert!(position(vs[s]) < position(vs[t]));
+        }
+
+        g.add_edge(vs[3], vs[2], ()).unwrap();
+        match topological_sort(&g) {
+            Err(GraphError::Cyclic(v)) => assert!(v == vs[2] || v == vs[3]),
+            result => panic!("unexpected {:?}", result),
+        }
+    }
+
+    #[test]
+    fn all_topological_orderings() {
+        use graph::{Directed, MutableGraph};
+        use incidence_list::IncidenceList;
+
+        // v0 --> v1    v3
+        //  |
+        //  v
+        // v2
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+        let vs = (0..4).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        g.add_edge(vs[0], vs[1], ()).unwrap();
+        g.add_edge(vs[0], vs[2], ()).unwrap();
+
+        let orderings = topological_orderings(&g).collect::<Vec<_>>();
+        assert_eq!(orderings.len(), 8);
+        assert_eq!(orderings[0], vec![vs[0], vs[1], vs[2], vs[3]]);
+        assert_eq!(orderings[7], vec![vs[3], vs[0], vs[2], vs[1]]);
+        assert_eq!(topological_orderings(&g).take(3).count(), 3);
+
+        g.add_edge(vs[2], vs[0], ()).unwrap();
+        assert_eq!(topological_orderings(&g).next(), None);
+    }
+}
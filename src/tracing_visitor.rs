@@ -0,0 +1,141 @@
+use std::fmt::Debug;
+
+use tracing::{enabled, event, Level};
+
+use graph::Graph;
+use visitor::{describe, Event, Visitor};
+
+pub struct TracingVisitor {
+    level: Level,
+}
+
+impl TracingVisitor {
+    pub fn new() -> Self {
+        Self::with_level(Level::DEBUG)
+    }
+
+    pub fn with_level(level: Level) -> Self {
+        Self { level }
+    }
+}
+
+impl Default for TracingVisitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// The level of a tracing event must be a constant, so every level has an arm of its own.
+macro_rules! emit {
+    ($level:expr, $e:expr, $graph:expr) => {
+        if enabled!(target: "graph", $level) {
+            let (name, label) = describe($e, $graph);
+            event!(target: "graph", $level, event = name, "{} {}", name, label);
+        }
+    };
+}
+
+impl<G> Visitor<G, Event> for TracingVisitor
+where
+    G: Graph,
+    G::VertexProperty: Debug,
+    G::EdgeProperty: Debug,
+{
+    fn visit(&mut self, e: &Event, graph: &G) {
+        match self.level {
+            Level::TRACE => emit!(Level::TRACE, e, graph),
+            Level::DEBUG => emit!(Level::DEBUG, e, graph),
+            Level::INFO => emit!(Level::INFO, e, graph),
+            Level::WARN => emit!(Level::WARN, e, graph),
+            _ => emit!(Level::ERROR, e, graph),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TracingVisitor;
+
+    #[test]
+    fn tracing_visitor() {
+        use std::fmt;
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{subscriber, Event, Level, Metadata, Subscriber};
+        use breadth_first_search::Bfs;
+        use graph::{Directed, MutableGraph};
+        use incidence_list::IncidenceList;
+
+        struct Recorder {
+            records: Arc<Mutex<Vec<String>>>,
+        }
+
+        struct Message(String);
+
+        impl Visit for Message {
+            fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+                if field.name() == "message" {
+                    self.0 = format!("{:?}", value);
+                }
+            }
+        }
+
+        impl Subscriber for Recorder {
+            fn enabled(&self, metadata: &Metadata) -> bool {
+                metadata.target() == "graph" && *metadata.level() <= Level::INFO
+            }
+
+            fn new_span(&self, _: &Attributes) -> Id {
+                Id::from_u64(1)
+            }
+
+            fn record(&self, _: &Id, _: &Record) {}
+
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+            fn event(&self, event: &Event) {
+                let mut message = Message(String::new());
+                event.record(&mut message);
+                self.records.lock().unwrap().push(message.0);
+            }
+
+            fn enter(&self, _: &Id) {}
+
+            fn exit(&self, _: &Id) {}
+        }
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+
+        let v0 = g.add_vertex("a");
+        let v1 = g.add_vertex("b");
+
+        g.add_edge(v0, v1, 3).unwrap();
+
+        let records = Arc::new(Mutex::new(Vec::new()));
+        let recorder = Recorder { records: records.clone() };
+        subscriber::with_default(recorder, || {
+            let mut bfs = Bfs::with_visitor(TracingVisitor::new());
+            assert_eq!(bfs.run(&v0, |&v| v == v1, &g), Ok(Some(vec![v0, v1])));
+            assert!(records.lock().unwrap().is_empty());
+
+            let mut bfs = Bfs::with_visitor(TracingVisitor::with_level(Level::INFO));
+            assert_eq!(bfs.run(&v0, |&v| v == v1, &g), Ok(Some(vec![v0, v1])));
+        });
+
+        assert_eq!(
+            *records.lock().unwrap(),
+            vec![
+                "initialize_vertex v0(\"a\")",
+                "initialize_vertex v1(\"b\")",
+                "discover_vertex v0(\"a\")",
+                "examine_vertex v0(\"a\")",
+                "examine_edge e0[3]",
+                "tree_edge e0[3]",
+                "discover_vertex v1(\"b\")",
+                "finish_vertex v0(\"a\")",
+                "examine_vertex v1(\"b\")",
+            ]
+        );
+    }
+}
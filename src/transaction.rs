@@ -0,0 +1,238 @@
+use fnv::FnvHashSet;
+
+use error::GraphError;
+use graph::{EdgeDescriptor, Graph, IncidenceGraph, MutableGraph, VertexDescriptor};
+use incidence_list::IncidenceList;
+
+/// A vertex added in a transaction, which gets its descriptor when the transaction commits.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct NewVertex(usize);
+
+/// An edge added in a transaction, which gets its descriptor when the transaction commits.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct NewEdge(usize);
+
+/// An end of an edge added in a transaction: a vertex of the graph or one added before it.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Endpoint {
+    Existing(VertexDescriptor),
+    New(NewVertex),
+}
+
+impl From<VertexDescriptor> for Endpoint {
+    fn from(v: VertexDescriptor) -> Self {
+        Endpoint::Existing(v)
+    }
+}
+
+impl From<NewVertex> for Endpoint {
+    fn from(v: NewVertex) -> Self {
+        Endpoint::New(v)
+    }
+}
+
+enum Operation<VP, EP> {
+    AddVertex(VP),
+    AddEdge(Endpoint, Endpoint, EP),
+    RemoveVertex(VertexDescriptor),
+    RemoveEdge(EdgeDescriptor),
+}
+
+/// A batch of changes to an `IncidenceList`, checked as they are made against the graph as it
+/// would be after the changes before them, and applied only when the whole batch succeeds.
+pub struct Transaction<'a, D, VP, EP>
+where
+    D: 'a,
+    VP: 'a,
+    EP: 'a,
+{
+    graph: &'a IncidenceList<D, VP, EP>,
+    operations: Vec<Operation<VP, EP>>,
+    vertices: usize,
+    edges: usize,
+    removed_vertices: FnvHashSet<VertexDescriptor>,
+    removed_edges: FnvHashSet<EdgeDescriptor>,
+}
+
+impl<'a, D, VP, EP> Transaction<'a, D, VP, EP> {
+    /// Returns the graph as it was before the transaction.
+    pub fn graph(&self) -> &IncidenceList<D, VP, EP> {
+        self.graph
+    }
+
+    pub fn add_vertex(&mut self, property: VP) -> NewVertex {
+        self.operations.push(Operation::AddVertex(property));
+        self.vertices += 1;
+        NewVertex(self.vertices - 1)
+    }
+
+    pub fn add_edge<S, T>(
+        &mut self,
+        source: S,
+        target: T,
+        property: EP,
+    ) -> Result<NewEdge, GraphError>
+    where
+        S: Into<Endpoint>,
+        T: Into<Endpoint>,
+    {
+        let (source, target) = (source.into(), target.into());
+        for &endpoint in &[source, target] {
+            self.check(endpoint)?;
+        }
+        self.operations.push(Operation::AddEdge(source, target, property));
+        self.edges += 1;
+        Ok(NewEdge(self.edges - 1))
+    }
+
+    /// Removes a vertex of the graph along with its edges, including those added to it earlier
+    /// in the transaction.
+    pub fn remove_vertex(&mut self, d: VertexDescriptor) -> Result<(), GraphError> {
+        self.check(Endpoint::Existing(d))?;
+        self.removed_vertices.insert(d);
+        self.operations.push(Operation::RemoveVertex(d));
+        Ok(())
+    }
+
+    pub fn remove_edge(&mut self, d: EdgeDescriptor) -> Result<(), GraphError> {
+        match self.graph.edge_endpoints(d) {
+            Some((s, t))
+                if !self.removed_edges.contains(&d) && !self.removed_vertices.contains(&s) &&
+                       !self.removed_vertices.contains(&t) => {}
+            _ => return Err(GraphError::EdgeNotFound(d)),
+        }
+        self.removed_edges.insert(d);
+        self.operations.push(Operation::RemoveEdge(d));
+        Ok(())
+    }
+
+    fn check(&self, endpoint: Endpoint) -> Result<(), GraphError> {
+        match endpoint {
+            Endpoint::Existing(v) => {
+                if !self.graph.contains_vertex(v) || self.removed_vertices.contains(&v) {
+                    return Err(GraphError::VertexNotFound(v));
+                }
+            }
+            Endpoint::New(NewVertex(i)) => assert!(i < self.vertices),
+        }
+        Ok(())
+    }
+}
+
+/// The descriptors given to what a transaction added.
+#[derive(Clone, Debug, Default)]
+pub struct Commit {
+    vertices: Vec<VertexDescriptor>,
+    edges: Vec<EdgeDescriptor>,
+}
+
+impl Commit {
+    pub fn vertex(&self, v: NewVertex) -> VertexDescriptor {
+        self.vertices[v.0]
+    }
+
+    pub fn edge(&self, e: NewEdge) -> EdgeDescriptor {
+        self.edges[e.0]
+    }
+
+    pub fn vertices(&self) -> &[VertexDescriptor] {
+        &self.vertices
+    }
+
+    pub fn edges(&self) -> &[EdgeDescriptor] {
+        &self.edges
+    }
+}
+
+impl<D, VP, EP> IncidenceList<D, VP, EP> {
+    /// Runs `f` to build a batch of changes and applies them all if it succeeds. If `f` fails,
+    /// the graph is left untouched and its error is returned. Panics if `f` hands the
+    /// transaction a `NewVertex` from another one.
+    pub fn transaction<F>(&mut self, f: F) -> Result<Commit, GraphError>
+    where
+        F: FnOnce(&mut Transaction<D, VP, EP>) -> Result<(), GraphError>,
+    {
+        let operations = {
+            let mut transaction = Transaction {
+                graph: &*self,
+                operations: Vec::new(),
+                vertices: 0,
+                edges: 0,
+                removed_vertices: FnvHashSet::default(),
+                removed_edges: FnvHashSet::default(),
+            };
+            f(&mut transaction)?;
+            transaction.operations
+        };
+
+        // every operation was checked, so none of these fail
+        let mut commit = Commit::default();
+        for operation in operations {
+            match operation {
+                Operation::AddVertex(property) => commit.vertices.push(self.add_vertex(property)),
+                Operation::AddEdge(source, target, property) => {
+                    let vertex = |endpoint| match endpoint {
+                        Endpoint::Existing(v) => v,
+                        Endpoint::New(NewVertex(i)) => commit.vertices[i],
+                    };
+                    let (s, t) = (vertex(source), vertex(target));
+                    commit.edges.push(self.add_edge(s, t, property)?);
+                }
+                Operation::RemoveVertex(v) => {
+                    self.remove_vertex(v);
+                }
+                Operation::RemoveEdge(e) => {
+                    self.remove_edge(e);
+                }
+            }
+        }
+        Ok(commit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn transaction() {
+        use error::GraphError;
+        use graph::{Directed, EdgeListGraph, Graph, IncidenceGraph, MutableGraph,
+                    VertexListGraph};
+        use incidence_list::IncidenceList;
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+        let v0 = g.add_vertex("a");
+        let v1 = g.add_vertex("b");
+        let e = g.add_edge(v0, v1, 1).unwrap();
+
+        // an import that fails halfway through changes nothing
+        let result = g.transaction(|txn| {
+            let c = txn.add_vertex("c");
+            txn.add_edge(v1, c, 2)?;
+            txn.remove_vertex(v0)?;
+            txn.remove_edge(e)?;
+            Ok(())
+        });
+        assert_eq!(result.unwrap_err(), GraphError::EdgeNotFound(e));
+        assert_eq!((g.order(), g.size()), (2, 1));
+        assert!(g.contains_edge(e));
+
+        let mut new = None;
+        let commit = g
+            .transaction(|txn| {
+                let c = txn.add_vertex("c");
+                let d = txn.add_vertex("d");
+                txn.add_edge(v1, c, 2)?;
+                new = Some((c, txn.add_edge(c, d, 3)?));
+                txn.remove_edge(e)?;
+                Ok(())
+            })
+            .unwrap();
+        let (c, cd) = new.unwrap();
+        assert_eq!((g.order(), g.size()), (4, 2));
+        assert!(!g.contains_edge(e));
+        assert_eq!(g[commit.vertex(c)], "c");
+        assert_eq!(g.source(commit.edge(cd)), commit.vertex(c));
+        assert_eq!(g.edge_property(commit.edge(cd)), Some(&3));
+        assert_eq!(commit.edges().len(), 2);
+    }
+}
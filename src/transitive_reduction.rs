@@ -0,0 +1,87 @@
+use error::GraphError;
+use graph::{EdgeDescriptor, IncidenceGraph, MutableGraph, VertexListGraph};
+use property_map::vertex_index;
+use topological_sort::topological_sort;
+
+/// Returns a copy of the DAG without the edges implied by others, along with the removed
+/// edges. An edge from u to v is redundant if v is also reachable through another successor
+/// of u, or if it duplicates an earlier edge from u to v. Descriptors of the kept vertices and
+/// edges stay valid in the copy. Fails with `GraphError::Cyclic` if the graph is not acyclic.
+pub fn transitive_reduction<'a, G>(graph: &'a G) -> Result<(G, Vec<EdgeDescriptor>), GraphError>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a> + MutableGraph + Clone,
+{
+    let order = topological_sort(graph)?;
+    let index = vertex_index(graph, &order);
+
+    let words = order.len().div_ceil(64);
+    let contains = |set: &[u64], i: usize| set[i / 64] & (1 << (i % 64)) != 0;
+    let mut reachable = vec![vec![0u64; words]; order.len()];
+    let mut removed = Vec::new();
+    for (i, &v) in order.iter().enumerate().rev() {
+        let mut successors = graph
+            .out_edges(v)
+            .filter_map(|e| graph.opposite(e, v).map(|t| (index[t], e)))
+            .collect::<Vec<_>>();
+        successors.sort_by_key(|&(j, _)| j);
+
+        let mut below = vec![0u64; words];
+        for &(j, _) in &successors {
+            for (w, r) in below.iter_mut().zip(reachable[j].iter()) {
+                *w |= *r;
+            }
+        }
+        for &(j, e) in &successors {
+            if contains(&below, j) {
+                removed.push(e);
+            }
+            below[j / 64] |= 1 << (j % 64);
+        }
+        reachable[i] = below;
+    }
+
+    let mut reduced = graph.clone();
+    for &e in &removed {
+        reduced.remove_edge(e);
+    }
+    Ok((reduced, removed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::transitive_reduction;
+
+    #[test]
+    fn transitive_reduction_of_dag() {
+        use error::GraphError;
+        use graph::{Directed, EdgeListGraph, MutableGraph};
+        use incidence_list::IncidenceList;
+
+        // v0 --> v1 --> v2 --> v3
+        //  |             ^      ^
+        //  +-------------+      |
+        //  +--------------------+
+        //
+        // with v2 --> v3 doubled
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+        let vs = (0..4).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        g.add_edge(vs[0], vs[1], ()).unwrap();
+        g.add_edge(vs[1], vs[2], ()).unwrap();
+        let e23 = g.add_edge(vs[2], vs[3], ()).unwrap();
+        let e02 = g.add_edge(vs[0], vs[2], ()).unwrap();
+        let e03 = g.add_edge(vs[0], vs[3], ()).unwrap();
+        let duplicate = g.add_edge(vs[2], vs[3], ()).unwrap();
+
+        let (reduced, removed) = transitive_reduction(&g).unwrap();
+        assert_eq!(removed.len(), 3);
+        assert!(removed.contains(&e02));
+        assert!(removed.contains(&e03));
+        assert!(removed.contains(&e23) != removed.contains(&duplicate));
+        assert_eq!(reduced.edges().count(), 3);
+        assert!(reduced.edges().all(|e| !removed.contains(&e)));
+
+        g.add_edge(vs[3], vs[0], ()).unwrap();
+        assert!(matches!(transitive_reduction(&g), Err(GraphError::Cyclic(_))));
+    }
+}
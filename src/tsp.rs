@@ -0,0 +1,366 @@
+use std::fmt::Debug;
+
+use num_traits::Zero;
+
+use graph::{Directivity, EdgeDescriptor, IncidenceGraph, VertexDescriptor, VertexListGraph};
+use path::Path;
+use property_map::{vertex_index, PropertyMap};
+
+/// Odd vertex sets up to this size are matched exactly in `christofides`.
+const EXACT_MATCHING_LIMIT: usize = 20;
+
+/// The most vertices `held_karp` takes on. Its table has 2^(n-1) n entries, some ten million
+/// here, and every further vertex more than doubles it.
+const HELD_KARP_LIMIT: usize = 20;
+
+fn cost_matrix<'a, G, C, F>(
+    graph: &'a G,
+    edge_cost: F,
+) -> (Vec<VertexDescriptor>, Vec<Vec<Option<C>>>)
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+    C: Copy + Debug + Ord + Zero,
+    F: Fn(&EdgeDescriptor, &G) -> C,
+{
+    let vertices = graph.vertices().collect::<Vec<_>>();
+    let index = vertex_index(graph, &vertices);
+
+    let mut costs = vec![vec![None; vertices.len()]; vertices.len()];
+    for (i, &v) in vertices.iter().enumerate() {
+        for e in graph.out_edges(v) {
+            if let Some(&j) = graph.opposite(e, v).and_then(|t| index.get(t)) {
+                let c = edge_cost(&e, graph);
+                if costs[i][j].is_none_or(|old| c < old) {
+                    costs[i][j] = Some(c);
+                    if !G::Directivity::is_directed() {
+                        costs[j][i] = Some(c);
+                    }
+                }
+            }
+        }
+    }
+    (vertices, costs)
+}
+
+fn tour_length<C>(tour: &[usize], costs: &[Vec<Option<C>>]) -> Option<C>
+where
+    C: Copy + Debug + Ord + Zero,
+{
+    let mut length = C::zero();
+    for k in 0..tour.len() {
+        if tour.len() > 1 {
+            length = length + costs[tour[k]][tour[(k + 1) % tour.len()]]?;
+        }
+    }
+    Some(length)
+}
+
+/// Finds a shortest closed tour visiting every vertex exactly once by dynamic programming over
+/// vertex subsets, in O(2^n n^2) time and O(2^n n) space, so only for small graphs. Returns the
+/// tour, without repeating the first vertex at the end, with its length as the cost, or `None`
+/// if the graph has no Hamiltonian cycle or more than 20 vertices.
+pub fn held_karp<'a, G, C, F>(graph: &'a G, edge_cost: F) -> Option<Path<C>>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+    C: Copy + Debug + Ord + Zero,
+    F: Fn(&EdgeDescriptor, &G) -> C,
+{
+    let (vertices, costs) = cost_matrix(graph, edge_cost);
+    let n = vertices.len();
+    if n <= 1 {
+        return Some(Path::new(vertices, C::zero()));
+    }
+    if n > HELD_KARP_LIMIT {
+        return None;
+    }
+
+    // best[set * n + j]: cheapest path from vertex 0 through `set` (which excludes 0) ending
+    // in j
+    let full = 1 << (n - 1);
+    let mut best: Vec<Option<(C, usize)>> = vec![None; full * n];
+    for j in 1..n {
+        best[(1 << (j - 1)) * n + j] = costs[0][j].map(|c| (c, 0));
+    }
+    for set in 1..full {
+        for j in 1..n {
+            let (c, _) = match best[set * n + j] {
+                Some(entry) if set & (1 << (j - 1)) != 0 => entry,
+                _ => continue,
+            };
+            for k in 1..n {
+                if set & (1 << (k - 1)) != 0 {
+                    continue;
+                }
+                if let Some(step) = costs[j][k] {
+                    let next = set | (1 << (k - 1));
+                    if best[next * n + k].is_none_or(|(old, _)| c + step < old) {
+                        best[next * n + k] = Some((c + step, j));
+                    }
+                }
+            }
+        }
+    }
+
+    let (length, last) = (1..n)
+        .filter_map(|j| match (best[(full - 1) * n + j], costs[j][0]) {
+            (Some((c, _)), Some(back)) => Some((c + back, j)),
+            _ => None,
+        })
+        .min()?;
+
+    let mut tour = Vec::with_capacity(n);
+    let mut set = full - 1;
+    let mut j = last;
+    while j != 0 {
+        tour.push(vertices[j]);
+        let (_, previous) = best[set * n + j].unwrap();
+        set &= !(1 << (j - 1));
+        j = previous;
+    }
+    tour.push(vertices[0]);
+    tour.reverse();
+    Some(Path::new(tour, length))
+}
+
+fn minimum_spanning_tree<C>(costs: &[Vec<C>]) -> Vec<(usize, usize)>
+where
+    C: Copy + Debug + Ord + Zero,
+{
+    let n = costs.len();
+    let mut in_tree = vec![false; n];
+    let mut closest = vec![(None, 0); n];
+    let mut edges = Vec::new();
+    let mut next = 0;
+    for _ in 0..n {
+        in_tree[next] = true;
+        for j in 0..n {
+            if !in_tree[j] && closest[j].0.is_none_or(|c| costs[next][j] < c) {
+                closest[j] = (Some(costs[next][j]), next);
+            }
+        }
+        match (0..n).filter(|&j| !in_tree[j]).min_by_key(|&j| closest[j].0) {
+            Some(j) => {
+                edges.push((closest[j].1, j));
+                next = j;
+            }
+            None => break,
+        }
+    }
+    edges
+}
+
+/// Pairs up the vertices in `odd`, of which there must be an even number, at minimum total
+/// cost: exactly up to `EXACT_MATCHING_LIMIT` of them and greedily beyond.
+pub(crate) fn minimum_perfect_matching<C>(odd: &[usize], costs: &[Vec<C>]) -> Vec<(usize, usize)>
+where
+    C: Copy + Debug + Ord + Zero,
+{
+    if odd.len() > EXACT_MATCHING_LIMIT {
+        let mut pairs = Vec::new();
+        let mut left = odd.to_vec();
+        while let Some(i) = left.pop() {
+            let k = (0..left.len()).min_by_key(|&k| costs[i][left[k]]).unwrap();
+            pairs.push((i, left.swap_remove(k)));
+        }
+        return pairs;
+    }
+
+    // best[set]: cheapest perfect matching of the odd vertices in `set`, always pairing up the
+    // lowest one first
+    let full = 1usize << odd.len();
+    let mut best: Vec<Option<(C, usize, usize)>> = vec![None; full];
+    best[0] = Some((C::zero(), 0, 0));
+    for set in 0..full {
+        let c = match best[set] {
+            Some((c, _, _)) => c,
+            None => continue,
+        };
+        let a = match (0..odd.len()).find(|&a| set & (1 << a) == 0) {
+            Some(a) => a,
+            None => continue,
+        };
+        for b in a + 1..odd.len() {
+            if set & (1 << b) == 0 {
+                let next = set | (1 << a) | (1 << b);
+                let total = c + costs[odd[a]][odd[b]];
+                if best[next].is_none_or(|(old, _, _)| total < old) {
+                    best[next] = Some((total, a, b));
+                }
+            }
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let mut set = full - 1;
+    while set != 0 {
+        let (_, a, b) = best[set].unwrap();
+        pairs.push((odd[a], odd[b]));
+        set &= !((1 << a) | (1 << b));
+    }
+    pairs
+}
+
+/// Walks every edge once with the algorithm of Hierholzer, starting and ending at `start`, and
+/// returns the vertices passed, with `start` at both ends, and the indices of the edges taken.
+/// Edges are followed from first to second only if `directed`. Stops short of the edges that
+/// cannot be reached from `start`.
+pub(crate) fn eulerian_circuit(
+    n: usize,
+    edges: &[(usize, usize)],
+    directed: bool,
+    start: usize,
+) -> (Vec<usize>, Vec<usize>) {
+    let mut incident = vec![Vec::new(); n];
+    for (k, &(a, b)) in edges.iter().enumerate() {
+        incident[a].push(k);
+        if !directed {
+            incident[b].push(k);
+        }
+    }
+    let mut used = vec![false; edges.len()];
+    let mut circuit = Vec::new();
+    let mut stack = vec![(start, None)];
+    while let Some(&(v, _)) = stack.last() {
+        while incident[v].last().is_some_and(|&k| used[k]) {
+            incident[v].pop();
+        }
+        match incident[v].pop() {
+            Some(k) => {
+                used[k] = true;
+                let (a, b) = edges[k];
+                stack.push((if a == v { b } else { a }, Some(k)));
+            }
+            None => circuit.push(stack.pop().unwrap()),
+        }
+    }
+    circuit.reverse();
+    let taken = circuit.iter().filter_map(|&(_, k)| k).collect();
+    (circuit.into_iter().map(|(v, _)| v).collect(), taken)
+}
+
+/// Approximates a shortest closed tour with the algorithm of Christofides: a minimum spanning
+/// tree plus a minimum weight perfect matching of its odd-degree vertices, walked as an Eulerian
+/// circuit with repeated vertices skipped. The graph must be complete and its costs must satisfy
+/// the triangle inequality, in which case the tour is at most 3/2 times the optimum. With more
+/// than twenty odd-degree vertices the matching is built greedily and that guarantee is lost.
+/// Returns the tour with its length as the cost, or `None` if some pair of vertices is not
+/// connected by an edge.
+pub fn christofides<'a, G, C, F>(graph: &'a G, edge_cost: F) -> Option<Path<C>>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+    C: Copy + Debug + Ord + Zero,
+    F: Fn(&EdgeDescriptor, &G) -> C,
+{
+    let (vertices, partial) = cost_matrix(graph, edge_cost);
+    let n = vertices.len();
+    if n <= 1 {
+        return Some(Path::new(vertices, C::zero()));
+    }
+    let costs = (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| if i == j { Some(C::zero()) } else { partial[i][j] })
+                .collect::<Option<Vec<_>>>()
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let mut edges = minimum_spanning_tree(&costs);
+    let mut degree = vec![0; n];
+    for &(a, b) in &edges {
+        degree[a] += 1;
+        degree[b] += 1;
+    }
+    let odd = (0..n).filter(|&i| degree[i] % 2 == 1).collect::<Vec<_>>();
+    edges.extend(minimum_perfect_matching(&odd, &costs));
+
+    let mut visited = vec![false; n];
+    let mut tour = Vec::with_capacity(n);
+    for i in eulerian_circuit(n, &edges, false, 0).0 {
+        if !visited[i] {
+            visited[i] = true;
+            tour.push(i);
+        }
+    }
+    let length = tour_length(&tour, &partial)?;
+    Some(Path::new(tour.into_iter().map(|i| vertices[i]).collect(), length))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{christofides, held_karp};
+
+    #[test]
+    fn tsp() {
+        use graph::{AdjacencyMatrixGraph, Directed, Graph, MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+        use path::Path;
+
+        // five points on a line at 0, 1, 2, 3 and 4; the best tour goes out and back for a
+        // length of 8
+
+        let mut g = IncidenceList::<Undirected, _, _>::new();
+        let xs = [2, 0, 4, 1, 3];
+        let vs = xs.iter().map(|&x| g.add_vertex(x)).collect::<Vec<_>>();
+        for i in 0..vs.len() {
+            for j in 0..i {
+                let d: i32 = xs[i] - xs[j];
+                g.add_edge(vs[i], vs[j], d.abs()).unwrap();
+            }
+        }
+        let cost = |e: &_, g: &IncidenceList<Undirected, i32, i32>| *g.edge_property(*e).unwrap();
+
+        let tour = held_karp(&g, cost).unwrap();
+        assert_eq!(tour.cost(), 8);
+        assert_eq!(tour.vertices().len(), 5);
+        assert_eq!(tour.vertices()[0], vs[0]);
+
+        let tour = christofides(&g, cost).unwrap();
+        assert!((8..=12).contains(&tour.cost()));
+        let mut sorted = tour.into_vertices();
+        sorted.sort();
+        assert_eq!(sorted, vs);
+
+        g.remove_edge(g.edge(vs[1], vs[2]).unwrap());
+        assert!(christofides(&g, cost).is_none());
+        assert_eq!(held_karp(&g, cost).map(|t| t.cost()), Some(8));
+
+        // a vertex without edges leaves no tour at all
+        g.add_vertex(5);
+        assert_eq!(held_karp(&g, cost), None);
+        assert!(christofides(&g, cost).is_none());
+
+        // a --1--> b --1--> c --1--> a, each edge also reversed at a cost of 5
+        //
+        // a directed tour has to follow the cheap edges round
+
+        let mut d = IncidenceList::<Directed, (), u32>::new();
+        assert_eq!(held_karp(&d, |&e, g| g[e]), Some(Path::new(vec![], 0)));
+        assert_eq!(christofides(&d, |&e, g| g[e]), Some(Path::new(vec![], 0)));
+        let ws = (0..3).map(|_| d.add_vertex(())).collect::<Vec<_>>();
+        for i in 0..3 {
+            d.add_edge(ws[i], ws[(i + 1) % 3], 1).unwrap();
+            d.add_edge(ws[(i + 1) % 3], ws[i], 5).unwrap();
+        }
+        assert_eq!(held_karp(&d, |&e, g| g[e]), Some(Path::new(ws, 3)));
+    }
+
+    #[test]
+    fn held_karp_too_large() {
+        use graph::{MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+        use super::HELD_KARP_LIMIT;
+
+        // a cycle just beyond the limit is refused rather than filling memory with its table
+        let mut g = IncidenceList::<Undirected, (), u32>::new();
+        let n = HELD_KARP_LIMIT + 1;
+        let vs = (0..n).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for i in 0..n {
+            g.add_edge(vs[i], vs[(i + 1) % n], 1).unwrap();
+        }
+        assert_eq!(held_karp(&g, |&e, g| g[e]), None);
+        assert_eq!(christofides(&g, |&e, g| g[e]), None);
+    }
+}
@@ -0,0 +1,243 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt::Debug;
+
+use fnv::FnvHashMap;
+use num_traits::Zero;
+
+use error::GraphError;
+use graph::{Directed, Directivity, EdgeDescriptor, IncidenceGraph, MutableGraph,
+            VertexDescriptor, VertexListGraph};
+use incidence_list::IncidenceList;
+use path::{reverse_path, Path};
+
+type Traversal = (VertexDescriptor, EdgeDescriptor, VertexDescriptor);
+
+/// The edge-based expansion of a graph for routing with turn penalties and restrictions, as in
+/// road networks. Every edge traversed in one direction, from a source to a target, becomes a
+/// vertex of a directed line graph, and every permitted turn from one edge into another at
+/// the vertex between them becomes an edge whose cost is that of the turn plus that of the
+/// edge turned into. The edges of an undirected graph are expanded in both directions, so
+/// that a U-turn is a turn from an edge into itself.
+///
+/// The expanded graph can be searched directly; every vertex of it has the traversal
+/// `(source, edge, target)` it stands for as its property.
+#[derive(Clone, Debug)]
+pub struct TurnExpansion<C> {
+    expanded: IncidenceList<Directed, Traversal, C>,
+    costs: FnvHashMap<VertexDescriptor, C>,
+    leaving: FnvHashMap<VertexDescriptor, Vec<VertexDescriptor>>,
+}
+
+impl<C> TurnExpansion<C>
+where
+    C: Copy + Debug + Ord + Zero,
+{
+    /// Expands `graph`, where `turn_cost(from, via, into, graph)` is the cost of turning from
+    /// the edge `from` into the edge `into` at the vertex `via`, or `None` if the turn is
+    /// forbidden.
+    pub fn new<'a, G, F, T>(graph: &'a G, edge_cost: F, turn_cost: T) -> Self
+    where
+        G: IncidenceGraph<'a> + VertexListGraph<'a>,
+        G::Directivity: Directivity,
+        F: Fn(&EdgeDescriptor, &G) -> C,
+        T: Fn(&EdgeDescriptor, &VertexDescriptor, &EdgeDescriptor, &G) -> Option<C>,
+    {
+        let mut expansion = TurnExpansion {
+            expanded: IncidenceList::new(),
+            costs: FnvHashMap::default(),
+            leaving: FnvHashMap::default(),
+        };
+        for v in graph.vertices() {
+            expansion.leaving.insert(v, Vec::new());
+        }
+        for v in graph.vertices() {
+            for e in graph.out_edges(v) {
+                if let Some(w) = graph.opposite(e, v) {
+                    let cost = edge_cost(&e, graph);
+                    expansion.add_traversal((v, e, w), cost);
+                    if !G::Directivity::is_directed() && v != w {
+                        expansion.add_traversal((w, e, v), cost);
+                    }
+                }
+            }
+        }
+
+        let traversals = expansion.expanded.vertices().collect::<Vec<_>>();
+        for x in traversals {
+            let (_, from, via) = expansion.expanded[x];
+            for &y in &expansion.leaving[&via] {
+                let into = expansion.expanded[y].1;
+                if let Some(c) = turn_cost(&from, &via, &into, graph) {
+                    expansion.expanded.add_edge(x, y, c + expansion.costs[&y]).unwrap();
+                }
+            }
+        }
+        expansion
+    }
+
+    fn add_traversal(&mut self, traversal: Traversal, cost: C) {
+        let x = self.expanded.add_vertex(traversal);
+        self.costs.insert(x, cost);
+        self.leaving.get_mut(&traversal.0).unwrap().push(x);
+    }
+
+    pub fn expanded(&self) -> &IncidenceList<Directed, Traversal, C> {
+        &self.expanded
+    }
+
+    /// The vertices of the expanded graph standing for the edges leaving `v` in the original
+    /// graph, where a route from `v` starts.
+    pub fn leaving(&self, v: VertexDescriptor) -> &[VertexDescriptor] {
+        self.leaving.get(&v).map_or(&[], Vec::as_slice)
+    }
+
+    /// The cost of the edge that a vertex of the expanded graph stands for, which is not
+    /// included in the cost of any edge of the expanded graph leading to it.
+    pub fn cost(&self, x: VertexDescriptor) -> Option<C> {
+        self.costs.get(&x).cloned()
+    }
+
+    /// Maps a path in the expanded graph back to the vertices of the original graph.
+    pub fn to_original(&self, path: &[VertexDescriptor]) -> Vec<VertexDescriptor> {
+        let mut vertices = Vec::with_capacity(path.len() + 1);
+        if let Some(&first) = path.first() {
+            vertices.push(self.expanded[first].0);
+        }
+        vertices.extend(path.iter().map(|&x| self.expanded[x].2));
+        vertices
+    }
+
+    /// Finds a cheapest route from `source` to `target` of the original graph that makes no
+    /// forbidden turn, by Dijkstra's algorithm on the expanded graph, and returns it as a path
+    /// of the original vertices.
+    pub fn shortest_path(
+        &self,
+        source: VertexDescriptor,
+        target: VertexDescriptor,
+    ) -> Result<Option<Path<C>>, GraphError> {
+        for &v in &[source, target] {
+            if !self.leaving.contains_key(&v) {
+                return Err(GraphError::VertexNotFound(v));
+            }
+        }
+        if source == target {
+            return Ok(Some(Path::new(vec![source], C::zero())));
+        }
+
+        let mut distances = FnvHashMap::default();
+        let mut parents = FnvHashMap::default();
+        let mut fringe = BinaryHeap::new();
+        for &x in &self.leaving[&source] {
+            distances.insert(x, self.costs[&x]);
+            fringe.push(Reverse((self.costs[&x], x)));
+        }
+        while let Some(Reverse((cost, x))) = fringe.pop() {
+            if distances[&x] < cost {
+                continue;
+            }
+            if self.expanded[x].2 == target {
+                let path = reverse_path(&parents, x);
+                return Ok(Some(Path::new(self.to_original(&path), cost)));
+            }
+            for e in self.expanded.out_edges(x) {
+                let y = self.expanded.target(e);
+                let c = cost + self.expanded[e];
+                if distances.get(&y).is_none_or(|&d| c < d) {
+                    distances.insert(y, c);
+                    parents.insert(y, x);
+                    fringe.push(Reverse((c, y)));
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TurnExpansion;
+
+    #[test]
+    fn forbidden_turn() {
+        use graph::{Directed, EdgeListGraph, MutableGraph, VertexListGraph};
+        use incidence_list::IncidenceList;
+        use path::Path;
+
+        // a ---> b ---> c
+        //        |      |
+        //        v      v
+        //        d <--- e
+        //
+        // every edge costs 1; turning from a-b into b-d is forbidden
+
+        let mut g = IncidenceList::<Directed, (), u32>::new();
+        let a = g.add_vertex(());
+        let b = g.add_vertex(());
+        let c = g.add_vertex(());
+        let d = g.add_vertex(());
+        let e = g.add_vertex(());
+        let ab = g.add_edge(a, b, 1).unwrap();
+        let bd = g.add_edge(b, d, 1).unwrap();
+        g.add_edge(b, c, 1).unwrap();
+        g.add_edge(c, e, 1).unwrap();
+        g.add_edge(e, d, 1).unwrap();
+
+        let free = TurnExpansion::new(&g, |&x, g| g[x], |_, _, _, _| Some(0));
+        assert_eq!((free.expanded().order(), free.expanded().size()), (5, 4));
+        assert_eq!(free.shortest_path(a, d), Ok(Some(Path::new(vec![a, b, d], 2))));
+
+        let restricted = TurnExpansion::new(&g, |&x, g| g[x], |&from, _, &into, _| {
+            if (from, into) == (ab, bd) { None } else { Some(0) }
+        });
+        assert_eq!(
+            restricted.shortest_path(a, d),
+            Ok(Some(Path::new(vec![a, b, c, e, d], 4)))
+        );
+        assert_eq!(restricted.shortest_path(b, d), Ok(Some(Path::new(vec![b, d], 1))));
+        assert_eq!(restricted.shortest_path(d, a), Ok(None));
+    }
+
+    #[test]
+    fn u_turns() {
+        use graph::{MutableGraph, Undirected, VertexListGraph};
+        use incidence_list::IncidenceList;
+        use path::Path;
+
+        // a --- b --- c
+        //       |
+        //       d
+        //
+        // every edge costs 1; turning from a-b into b-d is forbidden, so that d can only be
+        // reached from a by making a U-turn at the dead end c
+
+        let mut g = IncidenceList::<Undirected, (), u32>::new();
+        let a = g.add_vertex(());
+        let b = g.add_vertex(());
+        let c = g.add_vertex(());
+        let d = g.add_vertex(());
+        let ab = g.add_edge(a, b, 1).unwrap();
+        g.add_edge(b, c, 1).unwrap();
+        let bd = g.add_edge(b, d, 1).unwrap();
+
+        let expand = |u_turn: Option<u32>| {
+            TurnExpansion::new(&g, |&x, g| g[x], move |&from, _, &into, _| {
+                if (from, into) == (ab, bd) {
+                    None
+                } else if from == into {
+                    u_turn
+                } else {
+                    Some(0)
+                }
+            })
+        };
+        let penalized = expand(Some(10));
+        assert_eq!(penalized.expanded().order(), 6);
+        assert_eq!(
+            penalized.shortest_path(a, d),
+            Ok(Some(Path::new(vec![a, b, c, b, d], 14)))
+        );
+        assert_eq!(expand(None).shortest_path(a, d), Ok(None));
+        assert_eq!(expand(None).shortest_path(d, a), Ok(Some(Path::new(vec![d, b, a], 2))));
+    }
+}
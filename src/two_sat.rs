@@ -0,0 +1,148 @@
+use std::ops::Not;
+
+use fnv::FnvHashMap;
+
+use graph::{Directed, MutableGraph};
+use incidence_list::IncidenceList;
+use strongly_connected_components::strongly_connected_components;
+
+/// A boolean variable, identified by its number, or its negation.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Literal {
+    variable: usize,
+    negated: bool,
+}
+
+impl Literal {
+    pub fn positive(variable: usize) -> Self {
+        Literal {
+            variable,
+            negated: false,
+        }
+    }
+
+    pub fn negative(variable: usize) -> Self {
+        Literal {
+            variable,
+            negated: true,
+        }
+    }
+
+    pub fn variable(&self) -> usize {
+        self.variable
+    }
+
+    pub fn is_negated(&self) -> bool {
+        self.negated
+    }
+}
+
+impl Not for Literal {
+    type Output = Literal;
+
+    fn not(self) -> Literal {
+        Literal {
+            variable: self.variable,
+            negated: !self.negated,
+        }
+    }
+}
+
+/// A formula in conjunctive normal form whose clauses have at most two literals each.
+#[derive(Clone, Debug, Default)]
+pub struct TwoSat {
+    variables: usize,
+    clauses: Vec<(Literal, Literal)>,
+}
+
+impl TwoSat {
+    pub fn new(variables: usize) -> Self {
+        TwoSat {
+            variables,
+            clauses: Vec::new(),
+        }
+    }
+
+    pub fn variables(&self) -> usize {
+        self.variables
+    }
+
+    /// Requires `a` or `b` to hold. Panics if either names a variable out of range.
+    pub fn add_clause(&mut self, a: Literal, b: Literal) {
+        assert!(a.variable < self.variables && b.variable < self.variables);
+        self.clauses.push((a, b));
+    }
+
+    /// Requires `a` to hold.
+    pub fn add_unit(&mut self, a: Literal) {
+        self.add_clause(a, a);
+    }
+
+    /// Builds the implication graph: a vertex for each literal, and for each clause `a or b`
+    /// the edges `not a -> b` and `not b -> a`.
+    pub fn implication_graph(&self) -> IncidenceList<Directed, Literal, ()> {
+        let mut graph = IncidenceList::with_order_size(2 * self.variables, 2 * self.clauses.len());
+        let mut vertices = FnvHashMap::default();
+        for variable in 0..self.variables {
+            for &literal in &[Literal::positive(variable), Literal::negative(variable)] {
+                vertices.insert(literal, graph.add_vertex(literal));
+            }
+        }
+        for &(a, b) in &self.clauses {
+            graph.add_edge(vertices[&!a], vertices[&b], ()).unwrap();
+            graph.add_edge(vertices[&!b], vertices[&a], ()).unwrap();
+        }
+        graph
+    }
+
+    /// Finds values for the variables that satisfy every clause, or `None` if there are none.
+    /// The formula is unsatisfiable exactly when some variable and its negation are strongly
+    /// connected in the implication graph; otherwise each variable is set to whichever of its
+    /// literals comes later in topological order of the components.
+    pub fn solve(&self) -> Option<Vec<bool>> {
+        let graph = self.implication_graph();
+        let mut components = vec![(0, 0); self.variables];
+        for (k, component) in strongly_connected_components(&graph).into_iter().enumerate() {
+            for v in component {
+                let literal = graph[v];
+                if literal.negated {
+                    components[literal.variable].1 = k;
+                } else {
+                    components[literal.variable].0 = k;
+                }
+            }
+        }
+        components
+            .into_iter()
+            .map(|(positive, negative)| if positive == negative {
+                None
+            } else {
+                Some(positive < negative)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Literal, TwoSat};
+
+    #[test]
+    fn two_sat() {
+        let (x, y, z) = (Literal::positive(0), Literal::positive(1), Literal::positive(2));
+
+        // (x or y) and (not x or z) and (not y or not z) and (not z or not x) forces x false
+        let mut formula = TwoSat::new(3);
+        formula.add_clause(x, y);
+        formula.add_clause(!x, z);
+        formula.add_clause(!y, !z);
+        formula.add_clause(!z, !x);
+        let values = formula.solve().unwrap();
+        assert_eq!(values, vec![false, true, false]);
+        let holds = |l: Literal| values[l.variable()] != l.is_negated();
+        assert!(holds(x) || holds(y));
+
+        formula.add_unit(x);
+        assert_eq!(formula.solve(), None);
+    }
+}
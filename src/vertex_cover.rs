@@ -0,0 +1,208 @@
+use fnv::FnvHashSet;
+
+use bipartite::{is_bipartite, kuhn_matching};
+use coloring::symmetric_adjacency;
+use graph::{IncidenceGraph, VertexDescriptor, VertexListGraph};
+use property_map::vertex_index;
+
+/// Returns a vertex cover at most twice the size of a minimum one, made of both endpoints of
+/// every edge in a greedily built maximal matching. A self-loop forces its vertex in.
+pub fn approximate_vertex_cover<'a, G>(graph: &'a G) -> Vec<VertexDescriptor>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+{
+    let mut covered = FnvHashSet::default();
+    let mut cover = Vec::new();
+    for v in graph.vertices() {
+        for e in graph.out_edges(v) {
+            if let Some(u) = graph.opposite(e, v) {
+                if covered.contains(&v) || covered.contains(&u) {
+                    continue;
+                }
+                covered.insert(v);
+                cover.push(v);
+                if u != v {
+                    covered.insert(u);
+                    cover.push(u);
+                }
+            }
+        }
+    }
+    cover
+}
+
+/// Returns a minimum vertex cover of a bipartite graph, ignoring edge direction, or `None` if
+/// the graph is not bipartite. By König's theorem the cover is as large as a maximum matching:
+/// of the vertices reachable from the unmatched ones on the left by paths alternating between
+/// unmatched and matched edges, it takes those on the right, and of the rest those on the left.
+/// The cover is listed in vertex order.
+pub fn bipartite_vertex_cover<'a, G>(graph: &'a G) -> Option<Vec<VertexDescriptor>>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+{
+    let (left, _) = is_bipartite(graph).ok()?;
+    let (vertices, neighbors) = symmetric_adjacency(graph);
+    let n = vertices.len();
+    let mut is_left = vec![false; n];
+    let index = vertex_index(graph, &vertices);
+    for v in left {
+        is_left[index[v]] = true;
+    }
+
+    let adjacent = (0..n)
+        .map(|i| if is_left[i] { neighbors[i].clone() } else { Vec::new() })
+        .collect::<Vec<_>>();
+    let (partners, owners) = kuhn_matching(&adjacent, n);
+
+    let mut reached = vec![false; n];
+    let mut stack = (0..n)
+        .filter(|&i| is_left[i] && partners[i].is_none())
+        .collect::<Vec<_>>();
+    for &i in &stack {
+        reached[i] = true;
+    }
+    while let Some(i) = stack.pop() {
+        for &j in &adjacent[i] {
+            if reached[j] || partners[i] == Some(j) {
+                continue;
+            }
+            reached[j] = true;
+            if let Some(k) = owners[j] {
+                if !reached[k] {
+                    reached[k] = true;
+                    stack.push(k);
+                }
+            }
+        }
+    }
+
+    Some(
+        (0..n)
+            .filter(|&i| is_left[i] != reached[i])
+            .map(|i| vertices[i])
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{approximate_vertex_cover, bipartite_vertex_cover};
+
+    #[test]
+    fn vertex_cover() {
+        use graph::{Directed, EdgeListGraph, IncidenceGraph, MutableGraph};
+        use incidence_list::IncidenceList;
+
+        // v0 --> v1 --> v2 --> v3
+        //        |
+        //        v
+        //       v4 --> v4
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+        let vs = (0..5).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t) in &[(0, 1), (1, 2), (2, 3), (1, 4), (4, 4)] {
+            g.add_edge(vs[s], vs[t], ()).unwrap();
+        }
+
+        let cover = approximate_vertex_cover(&g);
+        assert!(g.edges().all(|e| {
+            let (s, t) = g.edge_endpoints(e).unwrap();
+            cover.contains(&s) || cover.contains(&t)
+        }));
+        // a minimum cover is {v1, v2, v4}
+        assert!(cover.len() <= 6);
+        assert!(cover.contains(&vs[4]));
+    }
+
+    #[test]
+    fn approximate_vertex_cover_of_empty_graph() {
+        use graph::{MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        let mut g = IncidenceList::<Undirected, (), ()>::new();
+        assert!(approximate_vertex_cover(&g).is_empty());
+
+        g.add_vertex(());
+        g.add_vertex(());
+        assert!(approximate_vertex_cover(&g).is_empty());
+    }
+
+    #[test]
+    fn approximate_vertex_cover_of_undirected_graph() {
+        use graph::{EdgeListGraph, IncidenceGraph, MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        // v0 --- v1    v2 --- v3
+        //        |      |
+        //       v4     v5
+
+        let mut g = IncidenceList::<Undirected, (), ()>::new();
+        let vs = (0..6).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t) in &[(0, 1), (1, 4), (2, 3), (5, 2)] {
+            g.add_edge(vs[s], vs[t], ()).unwrap();
+        }
+
+        let cover = approximate_vertex_cover(&g);
+        assert!(g.edges().all(|e| {
+            let (s, t) = g.edge_endpoints(e).unwrap();
+            cover.contains(&s) || cover.contains(&t)
+        }));
+        assert_eq!(cover.len(), 4);
+    }
+
+    #[test]
+    fn bipartite_cover() {
+        use graph::{Directed, MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        // v0 --> v3
+        //   \
+        // v1 --> v4 <-- v2
+        //
+        // every edge touches v4 but v0 --> v3, so two vertices are needed
+
+        let mut g = IncidenceList::<Directed, (), ()>::new();
+        let vs = (0..5).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t) in &[(0, 3), (0, 4), (1, 4), (2, 4)] {
+            g.add_edge(vs[s], vs[t], ()).unwrap();
+        }
+        assert_eq!(bipartite_vertex_cover(&g), Some(vec![vs[0], vs[4]]));
+
+        // a path of four vertices, covered by its two inner ones
+        let mut h = IncidenceList::<Undirected, (), ()>::new();
+        let ws = (0..4).map(|_| h.add_vertex(())).collect::<Vec<_>>();
+        for i in 0..3 {
+            h.add_edge(ws[i], ws[i + 1], ()).unwrap();
+        }
+        let cover = bipartite_vertex_cover(&h).unwrap();
+        assert_eq!(cover.len(), 2);
+        assert!((0..3).all(|i| cover.contains(&ws[i]) || cover.contains(&ws[i + 1])));
+
+        // an odd cycle is not bipartite
+        h.add_edge(ws[3], ws[1], ()).unwrap();
+        assert_eq!(bipartite_vertex_cover(&h), None);
+    }
+
+    #[test]
+    fn bipartite_cover_of_empty_and_disconnected_graphs() {
+        use graph::{MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        let mut g = IncidenceList::<Undirected, (), ()>::new();
+        assert_eq!(bipartite_vertex_cover(&g), Some(vec![]));
+
+        // v0 --- v1    v2    v3 --- v4 --- v5
+
+        let vs = (0..6).map(|_| g.add_vertex(())).collect::<Vec<_>>();
+        for &(s, t) in &[(0, 1), (3, 4), (4, 5)] {
+            g.add_edge(vs[s], vs[t], ()).unwrap();
+        }
+        let cover = bipartite_vertex_cover(&g).unwrap();
+        assert_eq!(cover.len(), 2);
+        assert!(cover.contains(&vs[4]) && !cover.contains(&vs[2]));
+
+        // a self-loop cannot be two-colored
+        g.add_edge(vs[2], vs[2], ()).unwrap();
+        assert_eq!(bipartite_vertex_cover(&g), None);
+    }
+}
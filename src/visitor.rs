@@ -1,3 +1,6 @@
+#[cfg(any(feature = "log", feature = "tracing"))]
+use std::fmt::Debug;
+
 use graph::{Graph, EdgeDescriptor, VertexDescriptor};
 
 pub trait Visitor<G, T>
@@ -25,6 +28,7 @@ pub enum Event {
     EdgeNotRelaxed(EdgeDescriptor),
     EdgeMinimized(EdgeDescriptor),
     EdgeNotMinimized(EdgeDescriptor),
+    PathImproved(Vec<VertexDescriptor>),
 }
 
 pub struct DefaultVisitor;
@@ -35,3 +39,61 @@ where
 {
     fn visit(&mut self, _e: &Event, _g: &G) {}
 }
+
+#[cfg(any(feature = "log", feature = "tracing"))]
+fn vertex_label<G>(d: VertexDescriptor, graph: &G) -> String
+where
+    G: Graph,
+    G::VertexProperty: Debug,
+{
+    match graph.vertex_property(d) {
+        Some(p) => format!("{}({:?})", d, p),
+        None => format!("{}", d),
+    }
+}
+
+#[cfg(any(feature = "log", feature = "tracing"))]
+fn edge_label<G>(d: EdgeDescriptor, graph: &G) -> String
+where
+    G: Graph,
+    G::EdgeProperty: Debug,
+{
+    match graph.edge_property(d) {
+        Some(p) => format!("{}[{:?}]", d, p),
+        None => format!("{}", d),
+    }
+}
+
+// The name of an event and a label of the vertices or edge it concerns, as the visitors that
+// report events print them.
+#[cfg(any(feature = "log", feature = "tracing"))]
+pub(crate) fn describe<G>(e: &Event, graph: &G) -> (&'static str, String)
+where
+    G: Graph,
+    G::VertexProperty: Debug,
+    G::EdgeProperty: Debug,
+{
+    match *e {
+        Event::InitializeVertex(v) => ("initialize_vertex", vertex_label(v, graph)),
+        Event::StartVertex(v) => ("start_vertex", vertex_label(v, graph)),
+        Event::DiscoverVertex(v) => ("discover_vertex", vertex_label(v, graph)),
+        Event::FinishVertex(v) => ("finish_vertex", vertex_label(v, graph)),
+        Event::ExamineVertex(v) => ("examine_vertex", vertex_label(v, graph)),
+        Event::ExamineEdge(e) => ("examine_edge", edge_label(e, graph)),
+        Event::TreeEdge(e) => ("tree_edge", edge_label(e, graph)),
+        Event::NonTreeEdge(e) => ("non_tree_edge", edge_label(e, graph)),
+        Event::GrayTarget(e) => ("gray_target", edge_label(e, graph)),
+        Event::BlackTarget(e) => ("black_target", edge_label(e, graph)),
+        Event::ForwardOrCrossEdge(e) => ("forward_or_cross_edge", edge_label(e, graph)),
+        Event::BackEdge(e) => ("back_edge", edge_label(e, graph)),
+        Event::FinishEdge(e) => ("finish_edge", edge_label(e, graph)),
+        Event::EdgeRelaxed(e) => ("edge_relaxed", edge_label(e, graph)),
+        Event::EdgeNotRelaxed(e) => ("edge_not_relaxed", edge_label(e, graph)),
+        Event::EdgeMinimized(e) => ("edge_minimized", edge_label(e, graph)),
+        Event::EdgeNotMinimized(e) => ("edge_not_minimized", edge_label(e, graph)),
+        Event::PathImproved(ref path) => {
+            let labels = path.iter().map(|&v| vertex_label(v, graph)).collect::<Vec<_>>();
+            ("path_improved", labels.join(" -> "))
+        }
+    }
+}
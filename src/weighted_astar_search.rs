@@ -0,0 +1,282 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+use std::fmt::Debug;
+use std::hash::BuildHasher;
+use std::marker::PhantomData;
+
+use fnv::FnvHashMap;
+use num_traits::{NumCast, Zero};
+
+use graph::{Graph, BidirectionalGraph, Directivity, VertexListGraph,
+            EdgeDescriptor, VertexDescriptor};
+use error::GraphError;
+use path::{reverse_path, Path};
+use visitor::{Event, Visitor, DefaultVisitor};
+
+/// A* with the heuristic inflated by a factor `epsilon`, which usually reaches a goal after
+/// expanding far fewer vertices, along a path costing at most `epsilon` times the cheapest one
+/// when the heuristic is admissible. The inflated heuristic is converted back to `C` by
+/// truncation.
+///
+/// In anytime mode the search goes on after the first goal, pruning every vertex whose cost
+/// plus uninflated heuristic cannot beat the best path so far, and returns a cheapest path once
+/// nothing is left to expand. Every path that improves on the previous one is reported with
+/// `Event::PathImproved`, so that a visitor can make use of it before the search ends.
+pub struct WeightedAstar<C, T, V>
+where
+    C: Copy + Debug + NumCast + Ord + Zero,
+    T: Graph,
+    V: Visitor<T, Event>,
+{
+    fringe: BinaryHeap<Reverse<(C, C, VertexDescriptor)>>,
+    costs: FnvHashMap<VertexDescriptor, C>,
+    parents: FnvHashMap<VertexDescriptor, VertexDescriptor>,
+    visitor: V,
+    epsilon: f64,
+    anytime: bool,
+    phantom: PhantomData<T>,
+}
+
+impl<C, T> WeightedAstar<C, T, DefaultVisitor>
+where
+    C: Copy + Debug + NumCast + Ord + Zero,
+    T: Graph,
+{
+    pub fn new() -> Self {
+        Self::with_visitor(DefaultVisitor)
+    }
+}
+
+impl<C, T> Default for WeightedAstar<C, T, DefaultVisitor>
+where
+    C: Copy + Debug + NumCast + Ord + Zero,
+    T: Graph,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C, T, V> WeightedAstar<C, T, V>
+where
+    C: Copy + Debug + NumCast + Ord + Zero,
+    T: Graph,
+    V: Visitor<T, Event>,
+{
+    pub fn with_visitor(visitor: V) -> Self {
+        Self {
+            fringe: BinaryHeap::new(),
+            costs: FnvHashMap::default(),
+            parents: FnvHashMap::default(),
+            visitor,
+            epsilon: 1.0,
+            anytime: false,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Sets the factor the heuristic is multiplied by, 1 by default. Panics if it is less
+    /// than 1.
+    pub fn epsilon(mut self, epsilon: f64) -> Self {
+        assert!(epsilon >= 1.0, "epsilon must be at least 1");
+        self.epsilon = epsilon;
+        self
+    }
+
+    /// When enabled, keeps improving on the first path found until it is a cheapest one.
+    pub fn anytime(mut self, enabled: bool) -> Self {
+        self.anytime = enabled;
+        self
+    }
+
+    fn inflate(&self, h: C) -> C {
+        if self.epsilon == 1.0 {
+            return h;
+        }
+        h.to_f64()
+            .and_then(|h| NumCast::from(h * self.epsilon))
+            .unwrap_or(h)
+    }
+
+    pub fn run<'a, F, G, H>(
+        &mut self,
+        start: &VertexDescriptor,
+        edge_cost: G,
+        heuristic: H,
+        is_goal: F,
+        graph: &'a T,
+    ) -> Result<Option<Path<C>>, GraphError>
+    where
+        F: Fn(&VertexDescriptor) -> bool,
+        G: Fn(&EdgeDescriptor, &T) -> C,
+        H: Fn(&VertexDescriptor, &T) -> C,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        if !graph.contains_vertex(*start) {
+            return Err(GraphError::VertexNotFound(*start));
+        }
+
+        for vertex in graph.vertices() {
+            self.visitor.visit(&Event::InitializeVertex(vertex), graph)
+        }
+
+        self.fringe.clear();
+        self.costs.clear();
+        self.parents.clear();
+        self.visitor.visit(&Event::DiscoverVertex(*start), graph);
+        self.costs.insert(*start, C::zero());
+        self.fringe.push(Reverse((self.inflate(heuristic(start, graph)), C::zero(), *start)));
+
+        let mut best: Option<Path<C>> = None;
+        while let Some(Reverse((_, cost, vertex))) = self.fringe.pop() {
+            if self.costs[&vertex] < cost {
+                continue;
+            }
+            if best.as_ref().is_some_and(|p| cost + heuristic(&vertex, graph) >= p.cost()) {
+                continue;
+            }
+            self.visitor.visit(&Event::ExamineVertex(vertex), graph);
+            if is_goal(&vertex) {
+                let path = reverse_path(&self.parents, vertex);
+                self.visitor.visit(&Event::PathImproved(path.clone()), graph);
+                best = Some(Path::new(path, cost));
+                if !self.anytime {
+                    break;
+                }
+                continue;
+            }
+            let in_edges = if T::Directivity::is_directed() {
+                None
+            } else {
+                Some(graph.in_edges(vertex))
+            };
+            for edge in graph.out_edges(vertex).chain(in_edges.into_iter().flatten()) {
+                let adjacency = graph.opposite(edge, vertex).ok_or(
+                    GraphError::EdgeNotFound(edge),
+                )?;
+                self.visitor.visit(&Event::ExamineEdge(edge), graph);
+                let cost_of_edge = edge_cost(&edge, graph);
+                if cost_of_edge < C::zero() {
+                    return Err(GraphError::NegativeEdgeCost(edge));
+                }
+                let cost_to_adjacency = cost + cost_of_edge;
+                let h = heuristic(&adjacency, graph);
+                let improves = self.costs.get(&adjacency).is_none_or(|&c| cost_to_adjacency < c);
+                let promising = best.as_ref().is_none_or(|p| cost_to_adjacency + h < p.cost());
+                if !improves || !promising {
+                    self.visitor.visit(&Event::EdgeNotRelaxed(edge), graph);
+                    continue;
+                }
+                self.costs.insert(adjacency, cost_to_adjacency);
+                self.parents.insert(adjacency, vertex);
+                self.visitor.visit(&Event::EdgeRelaxed(edge), graph);
+                self.visitor.visit(&Event::DiscoverVertex(adjacency), graph);
+                let evaluation = cost_to_adjacency + self.inflate(h);
+                self.fringe.push(Reverse((evaluation, cost_to_adjacency, adjacency)));
+            }
+            self.visitor.visit(&Event::FinishVertex(vertex), graph);
+        }
+        Ok(best)
+    }
+
+    pub fn run_to_vertex<'a, G, H>(
+        &mut self,
+        start: &VertexDescriptor,
+        goal: &VertexDescriptor,
+        edge_cost: G,
+        heuristic: H,
+        graph: &'a T,
+    ) -> Result<Option<Path<C>>, GraphError>
+    where
+        G: Fn(&EdgeDescriptor, &T) -> C,
+        H: Fn(&VertexDescriptor, &T) -> C,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.run(start, edge_cost, heuristic, |v| v == goal, graph)
+    }
+
+    pub fn run_to_any<'a, S, G, H>(
+        &mut self,
+        start: &VertexDescriptor,
+        goals: &HashSet<VertexDescriptor, S>,
+        edge_cost: G,
+        heuristic: H,
+        graph: &'a T,
+    ) -> Result<Option<Path<C>>, GraphError>
+    where
+        S: BuildHasher,
+        G: Fn(&EdgeDescriptor, &T) -> C,
+        H: Fn(&VertexDescriptor, &T) -> C,
+        T: BidirectionalGraph<'a> + VertexListGraph<'a>,
+        T::Directivity: Directivity,
+    {
+        self.run(start, edge_cost, heuristic, |v| goals.contains(v), graph)
+    }
+
+    pub fn visitor_ref(&self) -> &V {
+        &self.visitor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WeightedAstar;
+    use path::Path;
+
+    #[test]
+    fn weighted_astar_anytime() {
+        use graph::{Directed, Graph, MutableGraph, VertexDescriptor};
+        use incidence_list::IncidenceList;
+        use visitor::{Event, Visitor};
+
+        struct Improvements(Vec<Vec<VertexDescriptor>>);
+
+        impl<G: Graph> Visitor<G, Event> for Improvements {
+            fn visit(&mut self, e: &Event, _g: &G) {
+                if let Event::PathImproved(ref path) = *e {
+                    self.0.push(path.clone());
+                }
+            }
+        }
+
+        //      +--1--> a --10--+
+        //      |               v
+        //      s               g
+        //      |               ^
+        //      +--2--> b --2--> c --2--+
+
+        let mut g = IncidenceList::<Directed, _, _>::new();
+        let s = g.add_vertex(5);
+        let a = g.add_vertex(0);
+        let b = g.add_vertex(4);
+        let c = g.add_vertex(2);
+        let goal = g.add_vertex(0);
+        g.add_edge(s, a, 1).unwrap();
+        g.add_edge(a, goal, 10).unwrap();
+        g.add_edge(s, b, 2).unwrap();
+        g.add_edge(b, c, 2).unwrap();
+        g.add_edge(c, goal, 2).unwrap();
+        let cost = |e: &_, g: &IncidenceList<Directed, i32, i32>| *g.edge_property(*e).unwrap();
+        let h = |v: &_, g: &IncidenceList<Directed, i32, i32>| *g.vertex_property(*v).unwrap();
+
+        assert_eq!(
+            WeightedAstar::new().run_to_vertex(&s, &goal, cost, h, &g),
+            Ok(Some(Path::new(vec![s, b, c, goal], 6)))
+        );
+        assert_eq!(
+            WeightedAstar::new().epsilon(3.0).run_to_vertex(&s, &goal, cost, h, &g),
+            Ok(Some(Path::new(vec![s, a, goal], 11)))
+        );
+
+        let mut search = WeightedAstar::with_visitor(Improvements(Vec::new()))
+            .epsilon(3.0)
+            .anytime(true);
+        assert_eq!(
+            search.run_to_vertex(&s, &goal, cost, h, &g),
+            Ok(Some(Path::new(vec![s, b, c, goal], 6)))
+        );
+        assert_eq!(search.visitor_ref().0, vec![vec![s, a, goal], vec![s, b, c, goal]]);
+    }
+}
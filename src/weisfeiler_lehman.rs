@@ -0,0 +1,146 @@
+use std::hash::{Hash, Hasher};
+
+use fnv::{FnvHashMap, FnvHasher};
+
+use graph::{Directivity, IncidenceGraph, VertexDescriptor, VertexListGraph};
+use property_map::{vertex_index, PropertyMap};
+
+fn hash_of<T>(value: &T) -> u64
+where
+    T: Hash,
+{
+    let mut hasher = FnvHasher::default();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+pub fn weisfeiler_lehman_labels<'a, G, F, L>(
+    graph: &'a G,
+    iterations: usize,
+    label: F,
+) -> Vec<FnvHashMap<VertexDescriptor, u64>>
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+    F: Fn(&G::VertexProperty) -> L,
+    L: Hash,
+{
+    let directed = G::Directivity::is_directed();
+    let vertices = graph.vertices().collect::<Vec<_>>();
+    let index = vertex_index(graph, &vertices);
+
+    let mut successors = vec![Vec::new(); vertices.len()];
+    let mut predecessors = vec![Vec::new(); vertices.len()];
+    for (i, &v) in vertices.iter().enumerate() {
+        for e in graph.out_edges(v) {
+            if let Some(&j) = graph.opposite(e, v).and_then(|t| index.get(t)) {
+                successors[i].push(j);
+                if directed {
+                    predecessors[j].push(i);
+                } else {
+                    successors[j].push(i);
+                }
+            }
+        }
+    }
+
+    let mut current = vertices
+        .iter()
+        .map(|&v| graph.vertex_property(v).map_or(0, |p| hash_of(&label(p))))
+        .collect::<Vec<_>>();
+    let to_map = |labels: &[u64]| {
+        vertices
+            .iter()
+            .cloned()
+            .zip(labels.iter().cloned())
+            .collect::<FnvHashMap<_, _>>()
+    };
+
+    let mut rounds = vec![to_map(&current)];
+    for _ in 0..iterations {
+        let next = (0..vertices.len())
+            .map(|i| {
+                let mut outs = successors[i].iter().map(|&j| current[j]).collect::<Vec<_>>();
+                let mut ins = predecessors[i].iter().map(|&j| current[j]).collect::<Vec<_>>();
+                outs.sort();
+                ins.sort();
+                hash_of(&(current[i], outs, ins))
+            })
+            .collect::<Vec<_>>();
+        current = next;
+        rounds.push(to_map(&current));
+    }
+    rounds
+}
+
+pub fn weisfeiler_lehman_hash<'a, G, F, L>(graph: &'a G, iterations: usize, label: F) -> u64
+where
+    G: IncidenceGraph<'a> + VertexListGraph<'a>,
+    G::Directivity: Directivity,
+    F: Fn(&G::VertexProperty) -> L,
+    L: Hash,
+{
+    let rounds = weisfeiler_lehman_labels(graph, iterations, label);
+    let mut hasher = FnvHasher::default();
+    G::Directivity::is_directed().hash(&mut hasher);
+    for round in rounds {
+        let mut labels = round.values().cloned().collect::<Vec<_>>();
+        labels.sort();
+        labels.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{weisfeiler_lehman_hash, weisfeiler_lehman_labels};
+
+    #[test]
+    fn labels_and_hash() {
+        use graph::{MutableGraph, Undirected};
+        use incidence_list::IncidenceList;
+
+        // path:                star:
+        // a --- b --- c --- d  a --- b --- c
+        //                            |
+        //                            d
+
+        let mut path = IncidenceList::<Undirected, _, _>::new();
+        let a = path.add_vertex(());
+        let b = path.add_vertex(());
+        let c = path.add_vertex(());
+        let d = path.add_vertex(());
+        path.add_edge(a, b, ()).unwrap();
+        path.add_edge(b, c, ()).unwrap();
+        path.add_edge(c, d, ()).unwrap();
+
+        let labels = weisfeiler_lehman_labels(&path, 2, |_| ());
+        assert_eq!(labels.len(), 3);
+        assert_eq!(labels[0][&a], labels[0][&b]);
+        assert_eq!(labels[1][&a], labels[1][&d]);
+        assert_eq!(labels[1][&b], labels[1][&c]);
+        assert!(labels[1][&a] != labels[1][&b]);
+
+        let mut reversed = IncidenceList::<Undirected, _, _>::new();
+        let d = reversed.add_vertex(());
+        let c = reversed.add_vertex(());
+        let b = reversed.add_vertex(());
+        let a = reversed.add_vertex(());
+        reversed.add_edge(c, d, ()).unwrap();
+        reversed.add_edge(a, b, ()).unwrap();
+        reversed.add_edge(c, b, ()).unwrap();
+
+        let mut star = IncidenceList::<Undirected, _, _>::new();
+        let a = star.add_vertex(());
+        let b = star.add_vertex(());
+        let c = star.add_vertex(());
+        let d = star.add_vertex(());
+        star.add_edge(a, b, ()).unwrap();
+        star.add_edge(b, c, ()).unwrap();
+        star.add_edge(b, d, ()).unwrap();
+
+        let hash = |g: &IncidenceList<Undirected, (), ()>| weisfeiler_lehman_hash(g, 3, |_| ());
+        assert_eq!(hash(&path), hash(&reversed));
+        assert!(hash(&path) != hash(&star));
+    }
+}